@@ -1,5 +1,7 @@
 extern crate exonum_build;
 
+use std::{env, fs, path::Path};
+
 use exonum_build::{get_exonum_protobuf_files_path, protobuf_generate};
 
 fn main() {
@@ -9,4 +11,36 @@ fn main() {
         &["src/proto", &exonum_protos],
         "protobuf_mod.rs",
     );
+    generate_error_codes();
+}
+
+/// Writes the codes, names and descriptions of the transaction errors declared
+/// in `src/transactions.rs` as a slice expression to `error_codes.rs`,
+/// which the OpenAPI specification includes.
+fn generate_error_codes() {
+    const FAIL_PREFIX: &str = "#[fail(display = \"";
+
+    let source = fs::read_to_string("src/transactions.rs").unwrap();
+    let start = source.find("pub enum Error {").unwrap();
+    let end = start + source[start..].find("\n}").unwrap();
+
+    let mut codes = String::from("&[\n");
+    let mut description = None;
+    for line in source[start..end].lines().map(str::trim) {
+        if line.starts_with("//") {
+            continue;
+        } else if line.starts_with(FAIL_PREFIX) {
+            let end = line.rfind('"').unwrap();
+            description = Some(line[FAIL_PREFIX.len()..end].to_owned());
+        } else if let Some(index) = line.find(" = ") {
+            let name = &line[..index];
+            let code = line[index + 3..].trim_end_matches(',');
+            let description = description.take().unwrap_or_default();
+            codes.push_str(&format!("    ({}, {:?}, {:?}),\n", code, name, description));
+        }
+    }
+    codes.push(']');
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("error_codes.rs"), codes).unwrap();
 }
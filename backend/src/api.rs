@@ -14,17 +14,110 @@
 
 //! Cryptocurrency API.
 
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    self,
+    http::{header, Method},
+    AsyncResponder, FromRequest, HttpMessage, HttpResponse, Query,
+};
+use base64;
 use exonum::{
-    api::{self, ServiceApiBuilder, ServiceApiState},
-    blockchain::{self, BlockProof, TransactionMessage},
-    crypto::{Hash, PublicKey},
-    explorer::BlockchainExplorer,
+    api::{
+        self,
+        backends::actix::{FutureResponse, HttpRequest, RequestHandler},
+        ServiceApiBuilder, ServiceApiState,
+    },
+    blockchain::{self, BlockProof, TransactionErrorType, TransactionMessage, TransactionSet},
+    crypto::{self, CryptoHash, Hash, PublicKey},
+    explorer::{BlockchainExplorer, TransactionInfo},
     helpers::Height,
-    storage::{ListProof, MapProof},
+    messages::{Message, ProtocolMessage, RawTransaction, Signed, SignedMessage},
+    proto::ProtobufConvert,
+    storage::{ListProof, MapProof, Snapshot},
 };
+use failure;
+use futures::{future, Future};
+use hex;
+use juniper::http::GraphQLRequest;
+use protobuf::{Message as ProtobufMessage, RepeatedField};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json;
 
+use auth::{authorized, ApiKeys};
+use config::NodeConfig;
+use cors::{preflight_endpoint, with_cors};
+use events::{WalletEvent, WalletEvents};
+use graphql;
+use health::{health_endpoint, readiness_endpoint, HealthMonitor};
+use idempotency::{IdempotencyCache, Lookup, IDEMPOTENCY_KEY_HEADER};
+use invoice::Invoice;
+use logging::{with_tracing, CorrelationId, Tracer};
+use openapi;
+use pending_transfer::PendingTransfer;
+use snapshot::StateSnapshot;
+use sse::transaction_feed_stream_endpoint;
+use proto;
+use rate_limit::{RateLimitStats, RateLimiter};
+use receipt::{EventType, ExecutionEvent};
+use transactions::{
+    DryRunContext, ExecuteTransaction, FaucetIssue, HaltableTransaction, WalletTransactions,
+};
 use wallet::Wallet;
-use {Schema, CRYPTOCURRENCY_SERVICE_ID};
+use websocket::wallet_socket_endpoint;
+use webhooks::{Webhook, Webhooks};
+use {Schema, CRYPTOCURRENCY_SERVICE_ID, SERVICE_NAME};
+
+/// Index of the total supply hash in the service `state_hash`.
+const TOTAL_SUPPLY_TABLE_INDEX: usize = 4;
+
+/// Index of the blacklist root hash in the service `state_hash`.
+const BLACKLIST_TABLE_INDEX: usize = 16;
+
+/// Content type of the responses encoded as protobuf messages.
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Fields serialized as decimal strings for the requests with `amounts_as_strings` set.
+const STRING_AMOUNT_FIELDS: &[&str] = &["balance", "amount", "seed"];
+
+/// Endpoints of the second API version, succeeding the same paths of the first one.
+pub const V2_ENDPOINTS: &[&str] = &[
+    "wallets",
+    "wallets/info",
+    "wallets/history",
+    "wallets/top",
+    "wallets/search",
+];
+
+/// Maximum number of items returned by the paginated endpoints at once.
+const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Maximum number of wallets returned by the `top_wallets` endpoint.
+const MAX_TOP_WALLETS: u64 = 100;
+
+/// Maximum number of the event log entries the `events` endpoint looks through at once.
+const MAX_SCANNED_EVENTS: u64 = 10_000;
+
+/// Number of the latest blocks the transaction throughput is averaged over.
+const STATS_BLOCKS_WINDOW: u64 = 100;
+
+/// Maximum number of blocks returned by the `transaction_feed` endpoint at once.
+const MAX_FEED_BLOCKS: u64 = 100;
+
+/// Default time to wait for a submitted transaction to be committed, in milliseconds.
+pub const DEFAULT_WAIT_TIMEOUT_MS: u64 = 10_000;
+
+/// Maximum time to wait for a submitted transaction to be committed, in milliseconds.
+pub const MAX_WAIT_TIMEOUT_MS: u64 = 30_000;
+
+/// Interval between the status checks of a submitted transaction, in milliseconds.
+const WAIT_POLL_INTERVAL_MS: u64 = 100;
 
 /// Describes the query parameters for the `get_wallet` endpoint.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -33,6 +126,262 @@ pub struct WalletQuery {
     pub pub_key: PublicKey,
 }
 
+/// Describes the formatting options of the JSON responses, accepted by the wallet, history,
+/// invoice and feed endpoints in addition to their query parameters.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FormatQuery {
+    /// Whether the `balance`, `amount` and `seed` fields are serialized as decimal strings
+    /// for the clients losing the precision of numbers above 2^53, `false` by default.
+    pub amounts_as_strings: Option<bool>,
+}
+
+/// Describes the query parameters for the `wallets` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WalletsQuery {
+    /// Number of wallets to skip, `0` by default.
+    pub offset: Option<u64>,
+    /// Maximum number of wallets to return, `MAX_PAGE_SIZE` by default.
+    pub limit: Option<u64>,
+}
+
+/// Describes the query parameters for the `subscribe_wallet` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WalletSubscribeQuery {
+    /// Public key of the watched wallet.
+    pub pub_key: PublicKey,
+    /// Height of the last block the client knows the changes of, `0` by default.
+    pub since: Option<u64>,
+    /// Maximum time to wait in milliseconds, `DEFAULT_WAIT_TIMEOUT_MS` by default
+    /// and at most `MAX_WAIT_TIMEOUT_MS`.
+    pub timeout: Option<u64>,
+}
+
+/// Describes the query parameters for the `v1/wallets/subscribe/ws` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WalletSocketQuery {
+    /// Public key of the watched wallet.
+    pub pub_key: PublicKey,
+    /// Height of the last block the client knows the changes of, `0` by default.
+    pub since: Option<u64>,
+}
+
+/// Describes the query parameters for the `v1/transactions/feed/stream` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FeedStreamQuery {
+    /// Height of the last block the client knows the transactions of, the `Last-Event-ID`
+    /// header or `0` by default.
+    pub since: Option<u64>,
+}
+
+/// Describes the query parameters for the `transaction_feed` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransactionFeedQuery {
+    /// Height of the last block the client knows the transactions of, `0` by default.
+    pub since: Option<u64>,
+    /// Maximum time to wait in milliseconds, `DEFAULT_WAIT_TIMEOUT_MS` by default
+    /// and at most `MAX_WAIT_TIMEOUT_MS`.
+    pub timeout: Option<u64>,
+}
+
+/// Describes the query parameters for the `search_wallets` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletSearchQuery {
+    /// Prefix of the searched wallet names.
+    pub name_prefix: String,
+    /// Maximum number of wallets to return, `MAX_PAGE_SIZE` by default.
+    pub limit: Option<u64>,
+}
+
+/// Describes the query parameters for the `top_wallets` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TopWalletsQuery {
+    /// Maximum number of wallets to return, `MAX_TOP_WALLETS` by default.
+    pub limit: Option<u64>,
+}
+
+/// Describes the query parameters for the `wallet_history` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletHistoryQuery {
+    /// Public key of the queried wallet.
+    pub pub_key: PublicKey,
+    /// Number of records to skip from the start of the history, or from its end
+    /// if `newest_first` is set, `0` by default.
+    pub from: Option<u64>,
+    /// Cursor of the page, returned as `next_cursor` of the previous one.
+    /// Takes precedence over `from`.
+    pub cursor: Option<String>,
+    /// Maximum number of records to return, `MAX_PAGE_SIZE` by default.
+    pub limit: Option<u64>,
+    /// Whether to return the newest records first.
+    pub newest_first: Option<bool>,
+    /// Type of the returned transactions.
+    #[serde(rename = "type")]
+    pub tx_type: Option<HistoryTxType>,
+    /// Key that must take part in the returned transactions.
+    pub counterparty: Option<PublicKey>,
+    /// Minimal height of the blocks with the returned transactions.
+    pub from_height: Option<u64>,
+    /// Maximal height of the blocks with the returned transactions.
+    pub to_height: Option<u64>,
+}
+
+/// Type of the transactions the wallet history is filtered by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryTxType {
+    /// Transfers between ordinary wallets.
+    Transfer,
+    /// Issuance of new currency.
+    Issue,
+    /// Multisign wallet management and transfers.
+    Multisig,
+}
+
+impl HistoryTxType {
+    /// Returns whether the transaction has this type.
+    fn matches(self, transaction: &WalletTransactions) -> bool {
+        match (self, transaction) {
+            (HistoryTxType::Transfer, WalletTransactions::Transfer(_))
+            | (HistoryTxType::Transfer, WalletTransactions::TransferByName(_))
+            | (HistoryTxType::Transfer, WalletTransactions::BatchTransfer(_))
+            | (HistoryTxType::Transfer, WalletTransactions::TransferFrom(_))
+            | (HistoryTxType::Transfer, WalletTransactions::PermitTransfer(_))
+            | (HistoryTxType::Transfer, WalletTransactions::CoSignedTransfer(_))
+            | (HistoryTxType::Transfer, WalletTransactions::DelegatedTransfer(_))
+            | (HistoryTxType::Transfer, WalletTransactions::SessionTransfer(_))
+            | (HistoryTxType::Issue, WalletTransactions::Issue(_))
+            | (HistoryTxType::Issue, WalletTransactions::FaucetIssue(_))
+            | (HistoryTxType::Multisig, WalletTransactions::CreateMultisigWallet(_))
+            | (HistoryTxType::Multisig, WalletTransactions::LinkWallet(_))
+            | (HistoryTxType::Multisig, WalletTransactions::ChangeApprovers(_))
+            | (HistoryTxType::Multisig, WalletTransactions::TransferMultisign(_))
+            | (HistoryTxType::Multisig, WalletTransactions::AcceptMultisign(_))
+            | (HistoryTxType::Multisig, WalletTransactions::CancelMultisign(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Format of the exported wallet history.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// List of the history records.
+    Json,
+    /// CSV table with a header row.
+    Csv,
+}
+
+/// Describes the query parameters for the `export_wallet_history` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HistoryExportQuery {
+    /// Public key of the exported wallet.
+    pub pub_key: PublicKey,
+    /// Format of the export, `Json` by default.
+    pub format: Option<ExportFormat>,
+}
+
+/// Describes the query parameters for the `get_invoice` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct InvoiceQuery {
+    /// Hash of the `CreateInvoice` transaction.
+    pub invoice: Hash,
+}
+
+/// Describes the query parameters for the `events` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct EventsQuery {
+    /// Type of the returned events.
+    #[serde(rename = "type")]
+    pub event_type: Option<EventType>,
+    /// Key the returned events concern.
+    pub wallet: Option<PublicKey>,
+    /// Minimal height of the blocks with the returned events.
+    pub from_height: Option<u64>,
+    /// Maximal height of the blocks with the returned events.
+    pub to_height: Option<u64>,
+    /// Position in the event log to continue from, returned as `next` of the previous page.
+    pub from: Option<u64>,
+    /// Maximum number of events to return, `MAX_PAGE_SIZE` by default.
+    pub limit: Option<u64>,
+}
+
+/// Describes the query parameters for the `transaction_status` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransactionStatusQuery {
+    /// Hash of the queried transaction.
+    pub hash: Hash,
+}
+
+/// Encoding of a signed transaction passed to the transaction endpoints.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionEncoding {
+    /// Hexadecimal encoding.
+    Hex,
+    /// Standard base64 encoding.
+    Base64,
+}
+
+/// Describes the request body of the `submit_transaction` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubmitTransactionQuery {
+    /// Encoded signed transaction.
+    pub tx_body: String,
+    /// Encoding of the transaction, `Hex` by default.
+    pub encoding: Option<TransactionEncoding>,
+    /// Whether to wait until the transaction is committed.
+    pub wait: Option<bool>,
+    /// Maximum time to wait in milliseconds, `DEFAULT_WAIT_TIMEOUT_MS` by default
+    /// and at most `MAX_WAIT_TIMEOUT_MS`.
+    pub timeout: Option<u64>,
+}
+
+/// Response of the `submit_transaction` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubmitTransactionResponse {
+    /// Hash of the submitted transaction.
+    pub tx_hash: Hash,
+    /// Last known status of the transaction if waiting was requested, `None` if the node
+    /// has not received the transaction yet.
+    pub status: Option<TransactionStatus>,
+}
+
+/// Describes the request body of the `validate_transaction` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValidateTransactionQuery {
+    /// Encoded signed transaction.
+    pub tx_body: String,
+    /// Encoding of the transaction, `Hex` by default.
+    pub encoding: Option<TransactionEncoding>,
+}
+
+/// Response of the `validate_transaction` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValidateTransactionResponse {
+    /// Hash of the validated transaction.
+    pub tx_hash: Hash,
+    /// Reason the transaction would be rejected or the error its execution would fail with,
+    /// `None` if it would be executed successfully.
+    pub error: Option<String>,
+}
+
+/// Describes the request body of the `faucet` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FaucetQuery {
+    /// Public key of the credited wallet.
+    pub pub_key: PublicKey,
+    /// Requested amount of currency.
+    pub amount: u64,
+}
+
+/// Response of the `faucet` endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FaucetResponse {
+    /// Hash of the submitted `FaucetIssue` transaction.
+    pub tx_hash: Hash,
+}
+
 /// Proof of existence for specific wallet.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletProof {
@@ -49,6 +398,10 @@ pub struct WalletHistory {
     pub proof: ListProof<Hash>,
     /// List of above transactions.
     pub transactions: Vec<TransactionMessage>,
+    /// Encrypted memos of the above transfers by transaction hashes.
+    pub encrypted_memos: BTreeMap<Hash, Vec<u8>>,
+    /// Hashes of the `Refund` transactions of the above transfers by transaction hashes.
+    pub refunds: BTreeMap<Hash, Hash>,
 }
 
 /// Wallet information.
@@ -58,8 +411,737 @@ pub struct WalletInfo {
     pub block_proof: BlockProof,
     /// Proof of the appropriate wallet.
     pub wallet_proof: WalletProof,
-    /// History of the appropriate wallet.
+    /// History of the appropriate wallet, `None` if the wallet is missing or its history is empty.
     pub wallet_history: Option<WalletHistory>,
+    /// Multisign wallets linked to the appropriate wallet.
+    pub linked_multisig_wallets: Vec<PublicKey>,
+    /// Hashes of the pending transactions of the appropriate wallet.
+    pub pending_txs: Vec<Hash>,
+    /// Balances of the appropriate wallet in assets other than the default one.
+    pub asset_balances: BTreeMap<String, u64>,
+    /// Metadata entries of the appropriate wallet.
+    pub metadata: BTreeMap<String, String>,
+    /// Loyalty points of the appropriate wallet.
+    pub loyalty_points: u64,
+    /// Fees paid to the appropriate wallet by heights of the blocks.
+    pub fee_payouts: BTreeMap<u64, u64>,
+    /// Staking rewards paid to the appropriate wallet by heights of the blocks.
+    pub staking_payouts: BTreeMap<u64, u64>,
+}
+
+/// Aggregated information about a watched key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchedWallet {
+    /// Watched public key.
+    pub pub_key: PublicKey,
+    /// Wallet of the key, if any.
+    pub wallet: Option<Wallet>,
+    /// Transactions of the wallet history.
+    pub history: Vec<TransactionMessage>,
+    /// Multisign transfers the wallet is waiting for.
+    pub pending_transfers: Vec<PendingTransfer>,
+    /// Balances of the wallet in assets other than the default one.
+    pub asset_balances: BTreeMap<String, u64>,
+}
+
+/// Page of the wallet history.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletHistoryPage {
+    /// Total number of records in the wallet history.
+    pub total: u64,
+    /// Proof of the range of the history the page covers, `None` for an empty page.
+    pub proof: Option<ListProof<Hash>>,
+    /// Transactions of the page in the requested order.
+    pub transactions: Vec<TransactionMessage>,
+    /// Cursor of the following page, `None` if the page reaches the end of the history.
+    pub next_cursor: Option<String>,
+}
+
+/// Position in the wallet history the next page starts at, passed to the clients
+/// as an opaque token.
+///
+/// The position is an absolute index in the history list, so that the pages do not shift
+/// as the history grows and are found without skipping the preceding records.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HistoryCursor {
+    /// Whether the pages go from the newest records to the oldest.
+    newest_first: bool,
+    /// Index the next page starts at, or ends before if `newest_first` is set.
+    position: u64,
+}
+
+impl HistoryCursor {
+    /// Encodes the cursor as a URL-safe token.
+    fn encode(self) -> String {
+        let mut bytes = vec![self.newest_first as u8];
+        bytes.extend_from_slice(&self.position.to_be_bytes());
+        base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Decodes the cursor from the token, if it is valid.
+    fn decode(token: &str) -> Option<Self> {
+        let bytes = base64::decode_config(token, base64::URL_SAFE_NO_PAD).ok()?;
+        if bytes.len() != 9 {
+            return None;
+        }
+        let newest_first = match bytes[0] {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let mut position = [0; 8];
+        position.copy_from_slice(&bytes[1..]);
+        Some(HistoryCursor {
+            newest_first,
+            position: u64::from_be_bytes(position),
+        })
+    }
+}
+
+/// Decoded record of the wallet history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryRecord {
+    /// Height of the block with the transaction.
+    pub height: u64,
+    /// Hash of the transaction.
+    pub tx_hash: Hash,
+    /// Type of the transaction.
+    #[serde(rename = "type")]
+    pub tx_type: String,
+    /// Other key taking part in the transaction, if any.
+    pub counterparty: Option<PublicKey>,
+    /// Amount of currency moved by the transaction, if any.
+    pub amount: Option<u64>,
+    /// Balance of the wallet after the transaction.
+    pub balance_after: Option<u64>,
+}
+
+impl HistoryRecord {
+    /// Decodes at most `limit` records of the wallet history starting from the index `from`.
+    ///
+    /// Records of the transactions unknown to the blockchain, such as the pending transfers
+    /// restored from a state snapshot, are skipped.
+    pub fn from_history(
+        snapshot: &dyn Snapshot,
+        pub_key: &PublicKey,
+        from: u64,
+        limit: u64,
+    ) -> Vec<Self> {
+        let general_schema = blockchain::Schema::new(snapshot);
+        let currency_schema = Schema::new(snapshot);
+
+        let history = currency_schema.wallet_history(pub_key);
+        let balances = currency_schema.wallet_history_balances(pub_key);
+        // Records appended before the balances were tracked have no balance.
+        let untracked = history.len().saturating_sub(balances.len());
+
+        history
+            .iter_from(from)
+            .take(limit as usize)
+            .zip(from..)
+            .filter_map(|(record, index)| {
+                let height = general_schema
+                    .transactions_locations()
+                    .get(&record)
+                    .map_or(0, |location| location.block_height().0);
+                let signed = general_schema.transactions().get(&record)?;
+                let decoded = WalletTransactions::tx_from_raw(signed.payload().clone()).ok();
+
+                let tx_type = decoded.as_ref().map_or_else(String::new, |decoded| {
+                    let debug = format!("{:?}", decoded);
+                    debug.split('(').next().unwrap_or_default().to_owned()
+                });
+                let counterparty = decoded.as_ref().and_then(|decoded| {
+                    decoded
+                        .parties(signed.author())
+                        .into_iter()
+                        .find(|key| key != pub_key)
+                });
+                let amount = decoded.as_ref().and_then(WalletTransactions::amount);
+                let balance_after = if index >= untracked {
+                    balances.get(index - untracked)
+                } else {
+                    None
+                };
+
+                Some(HistoryRecord {
+                    height,
+                    tx_hash: record,
+                    tx_type,
+                    counterparty,
+                    amount,
+                    balance_after,
+                })
+            })
+            .collect()
+    }
+
+    /// Formats the records as a CSV table with a header row.
+    pub fn to_csv(records: &[Self]) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let mut csv = "height,tx_hash,type,counterparty,amount,balance_after\n".to_owned();
+        for record in records {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                record.height,
+                hex::encode(record.tx_hash.as_ref()),
+                record.tx_type,
+                optional(record.counterparty.map(|key| hex::encode(key.as_ref()))),
+                optional(record.amount.map(|amount| amount.to_string())),
+                optional(record.balance_after.map(|balance| balance.to_string())),
+            ));
+        }
+        csv
+    }
+}
+
+/// Exported wallet history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum HistoryExport {
+    /// History records.
+    Json(Vec<HistoryRecord>),
+    /// History records formatted as a CSV table.
+    Csv(String),
+}
+
+/// Page of the wallets listing.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletsPage {
+    /// Total number of wallets.
+    pub total: u64,
+    /// Wallets of the page in the order of the wallets index.
+    pub wallets: Vec<Wallet>,
+}
+
+/// Information about the keys watched by a key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchedWalletsInfo {
+    /// Watched keys in the order of the watch list.
+    pub wallets: Vec<WatchedWallet>,
+    /// Sum of the balances of the watched wallets.
+    ///
+    /// Wider than the balances, since the sum of several ones may not fit into `u64`.
+    pub total_balance: u128,
+}
+
+/// Status of a service transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// Transaction is in the pool waiting to be committed.
+    InPool,
+    /// Transaction was committed and executed successfully.
+    Success {
+        /// Height of the block with the transaction.
+        height: Height,
+    },
+    /// Transaction was committed, but its execution failed with an error.
+    Error {
+        /// Height of the block with the transaction.
+        height: Height,
+        /// Code of the error.
+        code: u8,
+        /// Description of the error.
+        description: Option<String>,
+    },
+    /// Transaction was committed, but its execution panicked.
+    Panic {
+        /// Height of the block with the transaction.
+        height: Height,
+        /// Description of the panic.
+        description: Option<String>,
+    },
+}
+
+/// Status of a service transaction together with the events emitted by its execution.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionReceipt {
+    /// Status of the transaction.
+    #[serde(flatten)]
+    pub status: TransactionStatus,
+    /// Events emitted by the execution in order, empty unless it succeeded.
+    pub events: Vec<ExecutionEvent>,
+}
+
+/// Execution event with its place in the event log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedEvent {
+    /// Position of the event in the event log.
+    pub position: u64,
+    /// Height of the block with the transaction.
+    pub height: Height,
+    /// Hash of the transaction that emitted the event.
+    pub tx_hash: Hash,
+    /// The event itself.
+    #[serde(flatten)]
+    pub event: ExecutionEvent,
+}
+
+/// Page of the event log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventsPage {
+    /// Total number of events in the log.
+    pub total: u64,
+    /// Events of the page in the order of execution.
+    pub events: Vec<IndexedEvent>,
+    /// Position to continue from, `None` if the page reaches the end of the queried range.
+    pub next: Option<u64>,
+}
+
+/// Committed service transaction with its execution status.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedTransaction {
+    /// Hash of the transaction.
+    pub tx_hash: Hash,
+    /// The transaction.
+    pub transaction: TransactionMessage,
+    /// Execution status of the transaction.
+    pub status: TransactionStatus,
+}
+
+/// Service transactions committed in a range of blocks.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionFeed {
+    /// Height of the last block covered by the feed, to be passed as `since` to get
+    /// the next transactions.
+    pub height: Height,
+    /// Service transactions of the covered blocks in the order of their commitment.
+    pub transactions: Vec<FeedTransaction>,
+}
+
+/// Total supply information.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplyInfo {
+    /// Proof of the last block.
+    pub block_proof: BlockProof,
+    /// Proof of the total supply hash in the database table.
+    pub to_table: MapProof<Hash, Hash>,
+    /// Total amount of currency in circulation.
+    pub total_supply: u64,
+}
+
+/// Service statistics.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceStats {
+    /// Number of created wallets.
+    pub wallets: u64,
+    /// Total amount of currency in circulation.
+    pub total_supply: u64,
+    /// Number of executed transfers.
+    pub transfers: u64,
+    /// Cumulative amount of the default asset moved by transfers.
+    pub transfer_volume: u64,
+    /// Average number of transactions per block over the latest `STATS_BLOCKS_WINDOW` blocks.
+    pub transactions_per_block: f64,
+}
+
+/// Blacklist information.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlacklistInfo {
+    /// Proof of the last block.
+    pub block_proof: BlockProof,
+    /// Proof of the blacklist in the database table.
+    pub to_table: MapProof<Hash, Hash>,
+    /// Proof of the blacklisted keys with heights of the blocks they were blacklisted in.
+    pub to_blacklist: MapProof<PublicKey, u64>,
+    /// Currently blacklisted keys.
+    pub blacklist: Vec<PublicKey>,
+}
+
+/// Response that can be encoded as a protobuf message of the service.
+pub trait ProtobufResponse {
+    /// Encodes the response as a protobuf message.
+    fn to_protobuf(&self) -> Vec<u8>;
+}
+
+impl ProtobufResponse for Vec<Wallet> {
+    fn to_protobuf(&self) -> Vec<u8> {
+        let mut wallets = proto::WalletList::new();
+        wallets.set_wallets(RepeatedField::from_vec(
+            self.iter().map(ProtobufConvert::to_pb).collect(),
+        ));
+        wallets.write_to_bytes().unwrap()
+    }
+}
+
+impl ProtobufResponse for WalletsPage {
+    fn to_protobuf(&self) -> Vec<u8> {
+        let mut page = proto::WalletsPage::new();
+        page.set_total(self.total);
+        page.set_wallets(RepeatedField::from_vec(
+            self.wallets.iter().map(ProtobufConvert::to_pb).collect(),
+        ));
+        page.write_to_bytes().unwrap()
+    }
+}
+
+impl ProtobufResponse for WalletHistoryPage {
+    fn to_protobuf(&self) -> Vec<u8> {
+        let mut page = proto::WalletHistoryPage::new();
+        page.set_total(self.total);
+        page.set_transactions(RepeatedField::from_vec(
+            self.transactions
+                .iter()
+                .map(|tx| tx.signed_message().signed_message().raw().to_vec())
+                .collect(),
+        ));
+        page.set_next_cursor(self.next_cursor.clone().unwrap_or_default());
+        page.write_to_bytes().unwrap()
+    }
+}
+
+/// Version of the public API.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApiVersion {
+    /// Original API, answering with numeric amounts by default.
+    V1,
+    /// API answering with decimal string amounts by default.
+    V2,
+}
+
+impl ApiVersion {
+    /// Returns the path prefix of the version.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+}
+
+/// Encoding of the responses of a raw endpoint.
+struct Encoding<I> {
+    /// Encodes the response for the requests accepting `application/x-protobuf`.
+    to_protobuf: Option<fn(&I) -> Vec<u8>>,
+    /// Whether amounts are serialized as strings unless the request chooses otherwise.
+    amounts_as_strings: bool,
+    /// Path of the endpoint succeeding the deprecated one.
+    successor: Option<String>,
+}
+
+/// Creates a `GET` handler of the endpoint that answers with the protobuf encoding
+/// of the response if the request accepts `application/x-protobuf`, and with JSON otherwise.
+fn negotiated_endpoint<Q, I, F>(version: ApiVersion, path: &str, handler: F) -> RequestHandler
+where
+    Q: DeserializeOwned + 'static,
+    I: Serialize + ProtobufResponse + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I> + Send + Sync + 'static,
+{
+    raw_endpoint(version, path, handler, Some(I::to_protobuf))
+}
+
+/// Creates a `GET` handler of the endpoint that answers with JSON formatted
+/// according to the `FormatQuery` options of the request.
+fn formatted_endpoint<Q, I, F>(version: ApiVersion, path: &str, handler: F) -> RequestHandler
+where
+    Q: DeserializeOwned + 'static,
+    I: Serialize + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I> + Send + Sync + 'static,
+{
+    raw_endpoint(version, path, handler, None)
+}
+
+/// Creates a `GET` handler of the endpoint that answers with the protobuf encoding
+/// given by `to_protobuf` if the request accepts `application/x-protobuf`, and with JSON
+/// formatted according to the `FormatQuery` options of the request otherwise.
+///
+/// Endpoints of the first version succeeded by `V2_ENDPOINTS` mark their responses
+/// as deprecated with the `Deprecation` and `Link` headers.
+///
+/// The endpoints of `ServiceApiScope` always answer with plain JSON and do not see the request
+/// headers, so the handler is registered on the actix backend directly.
+fn raw_endpoint<Q, I, F>(
+    version: ApiVersion,
+    path: &str,
+    handler: F,
+    to_protobuf: Option<fn(&I) -> Vec<u8>>,
+) -> RequestHandler
+where
+    Q: DeserializeOwned + 'static,
+    I: Serialize + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I> + Send + Sync + 'static,
+{
+    let successor = if version == ApiVersion::V1 && V2_ENDPOINTS.contains(&path) {
+        let v2 = ApiVersion::V2.prefix();
+        Some(format!("/api/services/{}/{}/{}", SERVICE_NAME, v2, path))
+    } else {
+        None
+    };
+    let encoding = Encoding {
+        to_protobuf,
+        amounts_as_strings: version == ApiVersion::V2,
+        successor,
+    };
+    let index = move |request: HttpRequest| -> FutureResponse {
+        Box::new(future::result(respond(&request, &handler, &encoding)))
+    };
+    RequestHandler {
+        name: format!("{}/{}", version.prefix(), path),
+        method: Method::GET,
+        inner: Arc::new(index),
+    }
+}
+
+/// Answers the request with the response of the handler in the given encoding.
+fn respond<Q, I, F>(
+    request: &HttpRequest,
+    handler: &F,
+    encoding: &Encoding<I>,
+) -> actix_web::Result<HttpResponse>
+where
+    Q: DeserializeOwned + 'static,
+    I: Serialize + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I>,
+{
+    let format = Query::<FormatQuery>::from_request(request, &Default::default())?;
+    let query = Query::<Q>::from_request(request, &Default::default())?;
+    let value = handler(request.state(), query.into_inner())?;
+
+    let mut response = HttpResponse::Ok();
+    if let Some(ref successor) = encoding.successor {
+        response.header("Deprecation", "true").header(
+            header::LINK,
+            format!("<{}>; rel=\"successor-version\"", successor),
+        );
+    }
+
+    let amounts_as_strings = format
+        .amounts_as_strings
+        .unwrap_or(encoding.amounts_as_strings);
+    Ok(match encoding.to_protobuf {
+        Some(to_protobuf) if accepts_protobuf(request) => response
+            .content_type(PROTOBUF_CONTENT_TYPE)
+            .body(to_protobuf(&value)),
+        _ if amounts_as_strings => {
+            let mut value =
+                serde_json::to_value(&value).map_err(|e| api::Error::InternalError(e.into()))?;
+            stringify_amounts(&mut value);
+            response.json(value)
+        }
+        _ => response.json(value),
+    })
+}
+
+/// Returns whether the request accepts `application/x-protobuf` responses.
+fn accepts_protobuf(request: &HttpRequest) -> bool {
+    request
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.contains(PROTOBUF_CONTENT_TYPE))
+}
+
+/// Returns the ETag of the wallet given by the `pub_key` query parameter, if it exists.
+///
+/// The tag is derived from the hash of the wallet record, which covers its `history_hash`
+/// as well as its pending and frozen state. Protobuf representations get their own tags.
+fn wallet_etag(request: &HttpRequest) -> Option<String> {
+    let query = Query::<WalletQuery>::from_request(request, &Default::default()).ok()?;
+    let wallet = Schema::new(&request.state().snapshot()).wallet(&query.pub_key)?;
+    let suffix = if accepts_protobuf(request) { "-pb" } else { "" };
+    Some(format!("W/\"{}{}\"", hex::encode(wallet.hash().as_ref()), suffix))
+}
+
+/// Returns whether the `If-None-Match` header of the request matches the ETag.
+///
+/// Tags are compared weakly, i.e. ignoring the `W/` prefix.
+fn matches_etag(request: &HttpRequest, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+        })
+}
+
+/// Wraps the `GET` handler of a wallet endpoint so that its successful responses carry
+/// the ETag of the wallet, and the requests with a matching `If-None-Match` header
+/// are answered with `304 Not Modified` without running the handler.
+fn with_wallet_etag(handler: RequestHandler) -> RequestHandler {
+    let inner = handler.inner;
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let etag = match wallet_etag(&request) {
+            Some(etag) => etag,
+            None => return inner(request),
+        };
+        if matches_etag(&request, &etag) {
+            return Box::new(future::ok(
+                HttpResponse::NotModified()
+                    .header(header::ETAG, etag)
+                    .finish(),
+            ));
+        }
+        Box::new(inner(request).map(move |mut response| {
+            if response.status().is_success() {
+                if let Ok(value) = header::HeaderValue::from_str(&etag) {
+                    response.headers_mut().insert(header::ETAG, value);
+                }
+            }
+            response
+        }))
+    };
+    RequestHandler {
+        name: handler.name,
+        method: handler.method,
+        inner: Arc::new(index),
+    }
+}
+
+/// Replaces the numbers of the `STRING_AMOUNT_FIELDS` in the JSON value with decimal strings.
+fn stringify_amounts(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let number = match value {
+                    serde_json::Value::Number(number)
+                        if STRING_AMOUNT_FIELDS.contains(&key.as_str()) =>
+                    {
+                        Some(number.to_string())
+                    }
+                    _ => None,
+                };
+                match number {
+                    Some(number) => *value = serde_json::Value::String(number),
+                    None => stringify_amounts(value),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                stringify_amounts(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Creates a `POST` handler of the submission endpoint that answers with
+/// `429 Too Many Requests` once the client exceeds the rate limits of the node configuration.
+///
+/// Submissions are counted for the IP address of the peer and for the public key given
+/// by `key_of`. Submissions carrying an `Idempotency-Key` header already used with the same
+/// request get the cached response instead, marked by the `Idempotent-Replayed` header,
+/// and those reusing the key with another request are rejected with `422 Unprocessable Entity`.
+/// Hashes of the submitted transactions are registered in `tracer` with the correlation ID
+/// of the request, so that their execution is logged with it.
+///
+/// The endpoints of `ServiceApiScope` do not see the peer address and the headers,
+/// so the handler is registered on the actix backend directly.
+fn submission_endpoint<Q, I, K, F>(
+    name: &str,
+    limiter: Arc<RateLimiter>,
+    cache: Arc<IdempotencyCache>,
+    tracer: Arc<Tracer>,
+    key_of: K,
+    handler: F,
+) -> RequestHandler
+where
+    Q: Serialize + DeserializeOwned + 'static,
+    I: Serialize + 'static,
+    K: Fn(&Q) -> Option<PublicKey> + Send + Sync + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I> + Send + Sync + 'static,
+{
+    let endpoint = name.to_owned();
+    let key_of = Arc::new(key_of);
+    let handler = Arc::new(handler);
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let limiter = Arc::clone(&limiter);
+        let cache = Arc::clone(&cache);
+        let tracer = Arc::clone(&tracer);
+        let key_of = Arc::clone(&key_of);
+        let handler = Arc::clone(&handler);
+        let state = request.state().clone();
+        let ip = request.peer_addr().map(|addr| addr.ip());
+        let correlation_id = request.extensions().get::<CorrelationId>().cloned();
+        // Keys are scoped by the endpoint, so that a faucet request can't replay a submission.
+        let idempotency_key = request
+            .headers()
+            .get(IDEMPOTENCY_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|key| format!("{} {}", endpoint, key));
+        request
+            .json()
+            .from_err()
+            .and_then(move |query: Q| -> actix_web::Result<HttpResponse> {
+                let internal_error = |e: serde_json::Error| api::Error::InternalError(e.into());
+                let body = serde_json::to_vec(&query).map_err(internal_error)?;
+                let fingerprint = crypto::hash(&body);
+                if let Some(ref key) = idempotency_key {
+                    match cache.get(key, &fingerprint) {
+                        Lookup::Replay(response) => {
+                            return Ok(HttpResponse::Ok()
+                                .header("Idempotent-Replayed", "true")
+                                .json(response));
+                        }
+                        Lookup::Conflict => {
+                            return Ok(HttpResponse::UnprocessableEntity()
+                                .body("Idempotency key was used with another request"));
+                        }
+                        Lookup::Miss => {}
+                    }
+                }
+
+                if let Err(retry_after) = limiter.check(ip, key_of(&query)) {
+                    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                    return Ok(HttpResponse::TooManyRequests()
+                        .header(header::RETRY_AFTER, seconds.to_string())
+                        .body("Too many requests"));
+                }
+
+                let response =
+                    serde_json::to_value(handler(&state, query)?).map_err(internal_error)?;
+                let tx_hash = response
+                    .get("tx_hash")
+                    .and_then(|tx_hash| serde_json::from_value::<Hash>(tx_hash.clone()).ok());
+                if let (Some(tx_hash), Some(CorrelationId(id))) = (tx_hash, correlation_id) {
+                    tracer.register_transaction(tx_hash, id);
+                }
+                if let Some(key) = idempotency_key {
+                    cache.insert(key, fingerprint, response.clone());
+                }
+                Ok(HttpResponse::Ok().json(response))
+            })
+            .responder()
+    };
+    RequestHandler {
+        name: name.to_owned(),
+        method: Method::POST,
+        inner: Arc::new(index),
+    }
+}
+
+/// Creates a `POST` handler of the endpoint that answers with JSON.
+///
+/// The handler is registered on the actix backend directly, so that it can be wrapped
+/// like the other raw endpoints.
+fn json_endpoint<Q, I, F>(name: &str, handler: F) -> RequestHandler
+where
+    Q: DeserializeOwned + 'static,
+    I: Serialize + 'static,
+    F: Fn(&ServiceApiState, Q) -> api::Result<I> + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let handler = Arc::clone(&handler);
+        let state = request.state().clone();
+        request
+            .json()
+            .from_err()
+            .and_then(move |query: Q| -> actix_web::Result<HttpResponse> {
+                Ok(HttpResponse::Ok().json(handler(&state, query)?))
+            })
+            .responder()
+    };
+    RequestHandler {
+        name: name.to_owned(),
+        method: Method::POST,
+        inner: Arc::new(index),
+    }
 }
 
 /// Public service API description.
@@ -94,32 +1176,986 @@ impl PublicApi {
 
         let explorer = BlockchainExplorer::new(state.blockchain());
 
-        let wallet_history = wallet.map(|_| {
-            let history = currency_schema.wallet_history(&query.pub_key);
+        let history = currency_schema.wallet_history(&query.pub_key);
+        let wallet_history = wallet.filter(|_| !history.is_empty()).map(|_| {
             let proof = history.get_range_proof(0, history.len());
 
             let transactions = history
                 .iter()
-                .map(|record| explorer.transaction_without_proof(&record).unwrap())
+                .filter_map(|record| explorer.transaction_without_proof(&record))
                 .collect::<Vec<_>>();
 
+            let encrypted_memos = history
+                .iter()
+                .filter_map(|record| {
+                    let memo = currency_schema.encrypted_memos().get(&record)?;
+                    Some((record, memo))
+                })
+                .collect();
+
+            let refunds = history
+                .iter()
+                .filter_map(|record| {
+                    let refund = currency_schema.refunds().get(&record)?;
+                    Some((record, refund))
+                })
+                .collect();
+
             WalletHistory {
                 proof,
                 transactions,
+                encrypted_memos,
+                refunds,
             }
         });
 
+        let linked_multisig_wallets = currency_schema
+            .linked_multisig_wallets(&query.pub_key)
+            .iter()
+            .collect();
+
+        let pending_txs = currency_schema
+            .wallet_pending_txs(&query.pub_key)
+            .iter()
+            .collect();
+
+        let asset_balances = currency_schema
+            .asset_balances(&query.pub_key)
+            .iter()
+            .collect();
+
+        let metadata = currency_schema
+            .wallet_metadata(&query.pub_key)
+            .iter()
+            .collect();
+
+        let loyalty_points = currency_schema.loyalty_balance(&query.pub_key);
+
+        let fee_payouts = currency_schema.fee_payouts(&query.pub_key).iter().collect();
+
+        let staking_payouts = currency_schema
+            .staking_payouts(&query.pub_key)
+            .iter()
+            .collect();
+
         Ok(WalletInfo {
             block_proof,
             wallet_proof,
             wallet_history,
+            linked_multisig_wallets,
+            pending_txs,
+            asset_balances,
+            metadata,
+            loyalty_points,
+            fee_payouts,
+            staking_payouts,
+        })
+    }
+
+    /// Endpoint for getting the wallets watched by a key.
+    pub fn watched_wallets(
+        state: &ServiceApiState,
+        query: WalletQuery,
+    ) -> api::Result<WatchedWalletsInfo> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+        let explorer = BlockchainExplorer::new(state.blockchain());
+
+        let keys = currency_schema
+            .watch_lists()
+            .get(&query.pub_key)
+            .map_or_else(Vec::new, |watch_list| watch_list.keys);
+
+        let wallets = keys
+            .into_iter()
+            .map(|pub_key| {
+                let wallet = currency_schema.wallet(&pub_key);
+
+                let history = currency_schema
+                    .wallet_history(&pub_key)
+                    .iter()
+                    .filter_map(|record| explorer.transaction_without_proof(&record))
+                    .collect();
+
+                let pending_transfers = currency_schema
+                    .wallet_pending_txs(&pub_key)
+                    .iter()
+                    .filter_map(|tx_hash| currency_schema.pending_transfer(&tx_hash))
+                    .collect();
+
+                let asset_balances = currency_schema.asset_balances(&pub_key).iter().collect();
+
+                WatchedWallet {
+                    pub_key,
+                    wallet,
+                    history,
+                    pending_transfers,
+                    asset_balances,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let total_balance = wallets
+            .iter()
+            .filter_map(|watched| watched.wallet.as_ref())
+            .map(|wallet| u128::from(wallet.balance))
+            .sum();
+
+        Ok(WatchedWalletsInfo {
+            wallets,
+            total_balance,
+        })
+    }
+
+    /// Endpoint for getting the non-fungible tokens owned by a wallet.
+    pub fn wallet_nfts(state: &ServiceApiState, query: WalletQuery) -> api::Result<Vec<Hash>> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+        Ok(currency_schema.nfts_owned_by(&query.pub_key))
+    }
+
+    /// Endpoint for walking the wallet history page by page.
+    ///
+    /// Filters apply to the transactions of the page, while the proof covers the whole page,
+    /// so that the returned transactions can still be checked against the wallet history.
+    pub fn wallet_history(
+        state: &ServiceApiState,
+        query: WalletHistoryQuery,
+    ) -> api::Result<WalletHistoryPage> {
+        let snapshot = state.snapshot();
+        let general_schema = blockchain::Schema::new(&snapshot);
+        let currency_schema = Schema::new(&snapshot);
+        let explorer = BlockchainExplorer::new(state.blockchain());
+
+        if currency_schema.wallet(&query.pub_key).is_none() {
+            return Err(api::Error::NotFound("Wallet not found".to_owned()));
+        }
+
+        let history = currency_schema.wallet_history(&query.pub_key);
+        let total = history.len();
+        let limit = query.limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let newest_first = query.newest_first.unwrap_or(false);
+
+        // Both the offset and the cursor are turned into the position the page starts at,
+        // or ends before for the newest records first.
+        let position = match query.cursor {
+            Some(ref token) => {
+                let cursor = HistoryCursor::decode(token)
+                    .filter(|cursor| cursor.newest_first == newest_first)
+                    .filter(|cursor| cursor.position <= total);
+                cursor
+                    .ok_or_else(|| api::Error::BadRequest("Invalid history cursor".to_owned()))?
+                    .position
+            }
+            None if newest_first => total - query.from.unwrap_or(0).min(total),
+            None => query.from.unwrap_or(0).min(total),
+        };
+        let (start, end) = if newest_first {
+            (position.saturating_sub(limit), position)
+        } else {
+            (position, total.min(position.saturating_add(limit)))
+        };
+        if start == end {
+            return Ok(WalletHistoryPage {
+                total,
+                proof: None,
+                transactions: Vec::new(),
+                next_cursor: None,
+            });
+        }
+        let next_position = if newest_first { start } else { end };
+        let next_cursor = if (newest_first && start > 0) || (!newest_first && end < total) {
+            let cursor = HistoryCursor {
+                newest_first,
+                position: next_position,
+            };
+            Some(cursor.encode())
+        } else {
+            None
+        };
+
+        let proof = history.get_range_proof(start, end);
+        let mut transactions = (start..end)
+            .filter_map(|index| history.get(index))
+            .filter(|record| {
+                let height = general_schema
+                    .transactions_locations()
+                    .get(record)
+                    .map(|location| location.block_height().0);
+                let from_height = query.from_height.unwrap_or(0);
+                let to_height = query.to_height.unwrap_or(u64::max_value());
+                height.map_or(false, |height| height >= from_height && height <= to_height)
+            })
+            .filter_map(|record| explorer.transaction_without_proof(&record))
+            .filter(|transaction| Self::matches_history_query(&query, transaction))
+            .collect::<Vec<_>>();
+        if newest_first {
+            transactions.reverse();
+        }
+
+        Ok(WalletHistoryPage {
+            total,
+            proof: Some(proof),
+            transactions,
+            next_cursor,
+        })
+    }
+
+    /// Returns whether the transaction passes the type and counterparty filters of the query.
+    fn matches_history_query(query: &WalletHistoryQuery, transaction: &TransactionMessage) -> bool {
+        if query.tx_type.is_none() && query.counterparty.is_none() {
+            return true;
+        }
+        let signed = transaction.signed_message();
+        let decoded = match WalletTransactions::tx_from_raw(signed.payload().clone()) {
+            Ok(decoded) => decoded,
+            Err(_) => return false,
+        };
+
+        if let Some(tx_type) = query.tx_type {
+            if !tx_type.matches(&decoded) {
+                return false;
+            }
+        }
+        if let Some(counterparty) = query.counterparty {
+            if !decoded.parties(signed.author()).contains(&counterparty) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Endpoint for exporting the complete decoded wallet history, e.g. for bookkeeping tools.
+    ///
+    /// Blocks carry no time, so records are dated by the heights of their blocks.
+    pub fn export_wallet_history(
+        state: &ServiceApiState,
+        query: HistoryExportQuery,
+    ) -> api::Result<HistoryExport> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+
+        if currency_schema.wallet(&query.pub_key).is_none() {
+            return Err(api::Error::NotFound("Wallet not found".to_owned()));
+        }
+
+        let records = HistoryRecord::from_history(&*snapshot, &query.pub_key, 0, u64::max_value());
+
+        Ok(match query.format.unwrap_or(ExportFormat::Json) {
+            ExportFormat::Json => HistoryExport::Json(records),
+            ExportFormat::Csv => HistoryExport::Csv(HistoryRecord::to_csv(&records)),
+        })
+    }
+
+    /// Endpoint for listing the wallets page by page.
+    ///
+    /// Wallets are returned in the deterministic order of the wallets index.
+    pub fn wallets(state: &ServiceApiState, query: WalletsQuery) -> api::Result<WalletsPage> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+
+        let offset = query.offset.unwrap_or(0);
+        let limit = query.limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+        let index = currency_schema.wallets();
+        let total = index.keys().count() as u64;
+        let wallets = index
+            .values()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok(WalletsPage { total, wallets })
+    }
+
+    /// Endpoint for searching the wallets by name prefix, in the order of their names.
+    ///
+    /// Only the wallets owning their names are found, see `Schema::wallet_names`.
+    pub fn search_wallets(
+        state: &ServiceApiState,
+        query: WalletSearchQuery,
+    ) -> api::Result<Vec<Wallet>> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+
+        let limit = query.limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+        let wallets = currency_schema
+            .wallet_names()
+            .iter_from(&query.name_prefix)
+            .take_while(|(name, _)| name.starts_with(&query.name_prefix))
+            .filter_map(|(_, key)| currency_schema.wallet(&key))
+            .take(limit as usize)
+            .collect();
+        Ok(wallets)
+    }
+
+    /// Endpoint for getting the wallets with the largest balances, richest first.
+    ///
+    /// The list is computed on demand; wallets with equal balances keep the order of
+    /// the wallets index.
+    pub fn top_wallets(
+        state: &ServiceApiState,
+        query: TopWalletsQuery,
+    ) -> api::Result<Vec<Wallet>> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+
+        let limit = query.limit.unwrap_or(MAX_TOP_WALLETS).min(MAX_TOP_WALLETS);
+
+        let mut wallets = currency_schema.wallets().values().collect::<Vec<_>>();
+        wallets.sort_by(|a, b| b.balance.cmp(&a.balance));
+        wallets.truncate(limit as usize);
+        Ok(wallets)
+    }
+
+    /// Endpoint for getting an invoice, e.g. to reconcile its payment.
+    pub fn invoice(state: &ServiceApiState, query: InvoiceQuery) -> api::Result<Invoice> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+        currency_schema
+            .invoice(&query.invoice)
+            .ok_or_else(|| api::Error::NotFound("Invoice not found".to_owned()))
+    }
+
+    /// Endpoint for getting the total supply.
+    pub fn supply(state: &ServiceApiState, _query: ()) -> api::Result<SupplyInfo> {
+        let snapshot = state.snapshot();
+        let general_schema = blockchain::Schema::new(&snapshot);
+        let currency_schema = Schema::new(&snapshot);
+
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+
+        let to_table: MapProof<Hash, Hash> = general_schema
+            .get_proof_to_service_table(CRYPTOCURRENCY_SERVICE_ID, TOTAL_SUPPLY_TABLE_INDEX);
+
+        Ok(SupplyInfo {
+            block_proof,
+            to_table,
+            total_supply: currency_schema.total_supply(),
+        })
+    }
+
+    /// Endpoint for getting the service statistics.
+    pub fn stats(state: &ServiceApiState, _query: ()) -> api::Result<ServiceStats> {
+        let snapshot = state.snapshot();
+        let general_schema = blockchain::Schema::new(&snapshot);
+        let currency_schema = Schema::new(&snapshot);
+
+        // The genesis block is left out, as it carries no transactions.
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+        let first_height = max_height.saturating_sub(STATS_BLOCKS_WINDOW - 1).max(1);
+        let transactions = (first_height..=max_height)
+            .filter_map(|height| general_schema.block_hashes_by_height().get(height))
+            .filter_map(|hash| general_schema.blocks().get(&hash))
+            .map(|block| u64::from(block.tx_count()))
+            .sum::<u64>();
+        let blocks = (max_height + 1).saturating_sub(first_height);
+        let transactions_per_block = if blocks == 0 {
+            0.0
+        } else {
+            transactions as f64 / blocks as f64
+        };
+
+        Ok(ServiceStats {
+            wallets: currency_schema.wallet_count(),
+            total_supply: currency_schema.total_supply(),
+            transfers: currency_schema.transfer_count(),
+            transfer_volume: currency_schema.transfer_volume(),
+            transactions_per_block,
+        })
+    }
+
+    /// Endpoint for getting the blacklisted keys.
+    pub fn blacklist(state: &ServiceApiState, _query: ()) -> api::Result<BlacklistInfo> {
+        let snapshot = state.snapshot();
+        let general_schema = blockchain::Schema::new(&snapshot);
+        let currency_schema = Schema::new(&snapshot);
+
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+
+        let to_table: MapProof<Hash, Hash> = general_schema
+            .get_proof_to_service_table(CRYPTOCURRENCY_SERVICE_ID, BLACKLIST_TABLE_INDEX);
+
+        let blacklist = currency_schema.blacklist().keys().collect::<Vec<_>>();
+        let to_blacklist = currency_schema
+            .blacklist()
+            .get_multiproof(blacklist.clone());
+
+        Ok(BlacklistInfo {
+            block_proof,
+            to_table,
+            to_blacklist,
+            blacklist,
+        })
+    }
+
+    /// Endpoint for polling the status of a service transaction together with the events
+    /// emitted by its execution.
+    pub fn transaction_status(
+        state: &ServiceApiState,
+        query: TransactionStatusQuery,
+    ) -> api::Result<TransactionReceipt> {
+        let status = Self::find_transaction_status(state, &query.hash)
+            .ok_or_else(|| api::Error::NotFound("Transaction not found".to_owned()))?;
+        let snapshot = state.snapshot();
+        let events = Schema::new(&snapshot)
+            .execution_events(&query.hash)
+            .iter()
+            .filter_map(|record| record.event())
+            .collect();
+        Ok(TransactionReceipt { status, events })
+    }
+
+    /// Endpoint for querying the events of all the transactions by type, wallet
+    /// and height range.
+    ///
+    /// Pages start at the first event of `from_height` found by the height index
+    /// and look through at most `MAX_SCANNED_EVENTS` events of the log.
+    pub fn events(state: &ServiceApiState, query: EventsQuery) -> api::Result<EventsPage> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+        let log = currency_schema.event_log();
+        let total = log.len();
+        let limit = query.limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let to_height = query.to_height.unwrap_or(u64::max_value());
+
+        let first = match query.from_height {
+            Some(height) => currency_schema
+                .event_log_heights()
+                .iter_from(&height)
+                .next()
+                .map_or(total, |(_, position)| position),
+            None => 0,
+        };
+        let mut position = first.max(query.from.unwrap_or(0));
+        let scan_end = total.min(position.saturating_add(MAX_SCANNED_EVENTS));
+
+        let mut events = Vec::new();
+        let mut next = None;
+        while position < total {
+            if events.len() as u64 == limit || position == scan_end {
+                next = Some(position);
+                break;
+            }
+            let record = log.get(position).unwrap();
+            if record.height > to_height {
+                break;
+            }
+            let matches = |event: &ExecutionEvent| {
+                query.event_type.map_or(true, |kind| event.event_type() == kind)
+                    && query.wallet.map_or(true, |wallet| event.wallet() == wallet)
+            };
+            if let Some(event) = record.event().filter(matches) {
+                events.push(IndexedEvent {
+                    position,
+                    height: Height(record.height),
+                    tx_hash: record.tx_hash,
+                    event,
+                });
+            }
+            position += 1;
+        }
+
+        Ok(EventsPage {
+            total,
+            events,
+            next,
+        })
+    }
+
+    /// Returns the status of a service transaction, if the node knows about it.
+    fn find_transaction_status(state: &ServiceApiState, hash: &Hash) -> Option<TransactionStatus> {
+        let explorer = BlockchainExplorer::new(state.blockchain());
+        let info = explorer.transaction(hash)?;
+        if info.content().signed_message().payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+            return None;
+        }
+
+        let committed = match info {
+            TransactionInfo::InPool { .. } => return Some(TransactionStatus::InPool),
+            TransactionInfo::Committed(committed) => committed,
+        };
+        let height = committed.location().block_height();
+        Some(match committed.status() {
+            Ok(()) => TransactionStatus::Success { height },
+            Err(error) => {
+                let description = error.description().map(str::to_owned);
+                match error.error_type() {
+                    TransactionErrorType::Code(code) => TransactionStatus::Error {
+                        height,
+                        code,
+                        description,
+                    },
+                    TransactionErrorType::Panic => TransactionStatus::Panic {
+                        height,
+                        description,
+                    },
+                }
+            }
         })
     }
 
-    /// Wires the above endpoint to public scope of the given `ServiceApiBuilder`.
-    pub fn wire(builder: &mut ServiceApiBuilder) {
+    /// Endpoint signing and submitting a `FaucetIssue` transaction with the service key
+    /// of the node.
+    ///
+    /// Requests above the faucet limit or too frequent for the key are rejected right away.
+    pub fn faucet(state: &ServiceApiState, query: FaucetQuery) -> api::Result<FaucetResponse> {
+        let snapshot = state.snapshot();
+        let currency_schema = Schema::new(&snapshot);
+
+        let limit = currency_schema.config().faucet_limit;
+        if limit == 0 {
+            return Err(api::Error::NotFound("Faucet is disabled".to_owned()));
+        }
+        if query.amount == 0 || query.amount > limit {
+            let message = format!("Amount must be positive and not exceed {}", limit);
+            return Err(api::Error::BadRequest(message));
+        }
+        if !currency_schema.allows_faucet_claim(&query.pub_key) {
+            let message = "Faucet was used by the key recently".to_owned();
+            return Err(api::Error::BadRequest(message));
+        }
+
+        let seed = currency_schema.current_height().0;
+        let tx = FaucetIssue::sign(
+            state.public_key(),
+            &query.pub_key,
+            query.amount,
+            seed,
+            state.secret_key(),
+        );
+        let tx_hash = tx.hash();
+        state
+            .sender()
+            .broadcast_transaction(tx)
+            .map_err(api::Error::InternalError)?;
+        Ok(FaucetResponse { tx_hash })
+    }
+
+    /// Endpoint for submitting a transaction signed offline.
+    ///
+    /// The transaction is checked to be a valid transaction of the service before broadcasting,
+    /// so the secret key never leaves the client. If waiting is requested, the endpoint blocks
+    /// until the transaction is committed or the timeout expires.
+    pub fn submit_transaction(
+        state: &ServiceApiState,
+        query: SubmitTransactionQuery,
+    ) -> api::Result<SubmitTransactionResponse> {
+        let (tx, _) = Self::decode_transaction(&query.tx_body, query.encoding)?;
+
+        let tx_hash = tx.hash();
+        state
+            .sender()
+            .broadcast_transaction(tx)
+            .map_err(api::Error::InternalError)?;
+
+        if !query.wait.unwrap_or(false) {
+            return Ok(SubmitTransactionResponse {
+                tx_hash,
+                status: None,
+            });
+        }
+
+        let timeout = query
+            .timeout
+            .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+            .min(MAX_WAIT_TIMEOUT_MS);
+        let deadline = Instant::now() + Duration::from_millis(timeout);
+        loop {
+            let status = Self::find_transaction_status(state, &tx_hash);
+            let pending = match status {
+                None | Some(TransactionStatus::InPool) => true,
+                _ => false,
+            };
+            if !pending || Instant::now() >= deadline {
+                return Ok(SubmitTransactionResponse { tx_hash, status });
+            }
+            thread::sleep(Duration::from_millis(WAIT_POLL_INTERVAL_MS));
+        }
+    }
+
+    /// Endpoint for checking a transaction signed offline without broadcasting it.
+    ///
+    /// The transaction must be a valid signed transaction of the service, must not be known
+    /// to the node yet and is executed against a throwaway fork of the latest committed state.
+    /// The error it would fail with is returned; the result may still change if other
+    /// transactions are committed before it.
+    pub fn validate_transaction(
+        state: &ServiceApiState,
+        query: ValidateTransactionQuery,
+    ) -> api::Result<ValidateTransactionResponse> {
+        let (tx, transaction) = Self::decode_transaction(&query.tx_body, query.encoding)?;
+        let tx_hash = tx.hash();
+
+        if Self::find_transaction_status(state, &tx_hash).is_some() {
+            let error = Some("Transaction is already known to the node".to_owned());
+            return Ok(ValidateTransactionResponse { tx_hash, error });
+        }
+
+        let resumes_service = match transaction {
+            WalletTransactions::ResumeService(_) => true,
+            _ => false,
+        };
+        let transaction = HaltableTransaction::new(transaction, resumes_service);
+        let mut fork = state.blockchain().fork();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let context = DryRunContext::new(&mut fork, &tx);
+            ExecuteTransaction::execute(&transaction, context)
+        }));
+        let error = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(error)) => Some(
+                error
+                    .description
+                    .unwrap_or_else(|| format!("Execution error {}", error.code)),
+            ),
+            Err(_) => Some("Transaction execution panicked".to_owned()),
+        };
+        Ok(ValidateTransactionResponse { tx_hash, error })
+    }
+
+    /// Returns the author of the submitted transaction, if it can be decoded.
+    fn submission_author(query: &SubmitTransactionQuery) -> Option<PublicKey> {
+        Self::decode_transaction(&query.tx_body, query.encoding)
+            .ok()
+            .map(|(tx, _)| tx.author())
+    }
+
+    /// Decodes a signed transaction of the service.
+    fn decode_transaction(
+        tx_body: &str,
+        encoding: Option<TransactionEncoding>,
+    ) -> api::Result<(Signed<RawTransaction>, WalletTransactions)> {
+        let bad_request = |e: failure::Error| api::Error::BadRequest(e.to_string());
+
+        let bytes = match encoding.unwrap_or(TransactionEncoding::Hex) {
+            TransactionEncoding::Hex => hex::decode(tx_body).map_err(|e| e.to_string()),
+            TransactionEncoding::Base64 => base64::decode(tx_body).map_err(|e| e.to_string()),
+        }
+        .map_err(api::Error::BadRequest)?;
+
+        let signed = SignedMessage::from_raw_buffer(bytes).map_err(bad_request)?;
+        let message = Message::deserialize(signed).map_err(bad_request)?;
+        let tx = RawTransaction::try_from(message)
+            .map_err(|_| api::Error::BadRequest("Message is not a transaction".to_owned()))?;
+        if tx.payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+            let message = "Transaction belongs to another service".to_owned();
+            return Err(api::Error::BadRequest(message));
+        }
+        let transaction =
+            WalletTransactions::tx_from_raw(tx.payload().clone()).map_err(bad_request)?;
+        Ok((tx, transaction))
+    }
+
+    /// Endpoint streaming the committed service transactions by long polling.
+    ///
+    /// Waits for a block above `since` and returns the service transactions of up to
+    /// `MAX_FEED_BLOCKS` blocks following it.
+    pub fn transaction_feed(
+        events: &WalletEvents,
+        state: &ServiceApiState,
+        query: TransactionFeedQuery,
+    ) -> api::Result<TransactionFeed> {
+        let since = Height(query.since.unwrap_or(0));
+        let timeout = query
+            .timeout
+            .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+            .min(MAX_WAIT_TIMEOUT_MS);
+
+        let height = blockchain::Schema::new(&state.snapshot()).height();
+        if height <= since {
+            events.wait_for_block(since, Duration::from_millis(timeout));
+        }
+        Ok(Self::collect_feed(state, since))
+    }
+
+    /// Returns the service transactions of up to `MAX_FEED_BLOCKS` committed blocks
+    /// above `since`.
+    pub fn collect_feed(state: &ServiceApiState, since: Height) -> TransactionFeed {
+        let snapshot = state.snapshot();
+        let general_schema = blockchain::Schema::new(&snapshot);
+        let explorer = BlockchainExplorer::new(state.blockchain());
+
+        let height = general_schema.height();
+        if height <= since {
+            return TransactionFeed {
+                height,
+                transactions: Vec::new(),
+            };
+        }
+        let last = height.0.min(since.0 + MAX_FEED_BLOCKS);
+
+        let transactions = (since.0 + 1..=last)
+            .flat_map(|block| {
+                general_schema
+                    .block_transactions(Height(block))
+                    .iter()
+                    .collect::<Vec<_>>()
+            })
+            .filter_map(|tx_hash| {
+                let transaction = explorer.transaction_without_proof(&tx_hash)?;
+                let service_id = transaction.signed_message().payload().service_id();
+                if service_id != CRYPTOCURRENCY_SERVICE_ID {
+                    return None;
+                }
+                let status = Self::find_transaction_status(state, &tx_hash)?;
+                Some(FeedTransaction {
+                    tx_hash,
+                    transaction,
+                    status,
+                })
+            })
+            .collect();
+
+        TransactionFeed {
+            height: Height(last),
+            transactions,
+        }
+    }
+
+    /// Endpoint for waiting for the changes of the wallet made by the committed blocks.
+    ///
+    /// The first request subscribes to the wallet, so only the changes made after it
+    /// are reported. The changes are kept for ten minutes after the latest request;
+    /// subscribing to a new wallet fails while the limit of the subscribed wallets is reached.
+    pub fn subscribe_wallet(
+        events: &WalletEvents,
+        state: &ServiceApiState,
+        query: WalletSubscribeQuery,
+    ) -> api::Result<Vec<WalletEvent>> {
+        let snapshot = state.snapshot();
+        let timeout = query
+            .timeout
+            .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+            .min(MAX_WAIT_TIMEOUT_MS);
+        events
+            .wait(
+                &*snapshot,
+                &query.pub_key,
+                Height(query.since.unwrap_or(0)),
+                Duration::from_millis(timeout),
+            )
+            .map_err(|e| api::Error::BadRequest(e.to_string()))
+    }
+
+    /// Endpoint for executing a GraphQL query of the wallets, see `graphql::Query`.
+    ///
+    /// Errors of the query are reported in the `errors` field of the response.
+    pub fn graphql(
+        state: &ServiceApiState,
+        query: GraphQLRequest,
+    ) -> api::Result<serde_json::Value> {
+        let context = graphql::Context::new(state.snapshot());
+        let response = query.execute(&graphql::schema(), &context);
+        serde_json::to_value(&response).map_err(|e| api::Error::InternalError(e.into()))
+    }
+
+    /// Endpoint for getting the OpenAPI specification of the service endpoints.
+    pub fn openapi(_state: &ServiceApiState, _query: ()) -> api::Result<serde_json::Value> {
+        Ok(openapi::specification())
+    }
+
+    /// Wires the above endpoints to public scope of the given `ServiceApiBuilder`.
+    ///
+    /// The faucet and the transaction submission endpoints are rate limited by `rate_limiter`
+    /// and cache their responses by idempotency keys in `idempotency`.
+    /// The faucet, blacklist and statistics endpoints require one of `api_keys`.
+    /// The `healthz` and `readyz` probes report the health tracked by `health`.
+    /// All the endpoints follow the CORS policy of `node_config` and are traced by `tracer`.
+    pub fn wire(
+        builder: &mut ServiceApiBuilder,
+        node_config: Arc<NodeConfig>,
+        events: Arc<WalletEvents>,
+        rate_limiter: Arc<RateLimiter>,
+        api_keys: Arc<ApiKeys>,
+        idempotency: Arc<IdempotencyCache>,
+        tracer: Arc<Tracer>,
+        health: Arc<HealthMonitor>,
+    ) {
+        let feed_events = Arc::clone(&events);
+        let v1 = ApiVersion::V1;
+        let mut handlers = vec![
+            wallet_socket_endpoint(Arc::clone(&events)),
+            transaction_feed_stream_endpoint(Arc::clone(&events)),
+            authorized(
+                &api_keys,
+                submission_endpoint(
+                    "v1/faucet",
+                    Arc::clone(&rate_limiter),
+                    Arc::clone(&idempotency),
+                    Arc::clone(&tracer),
+                    |query: &FaucetQuery| Some(query.pub_key),
+                    Self::faucet,
+                ),
+            ),
+            authorized(
+                &api_keys,
+                formatted_endpoint(v1, "blacklist", Self::blacklist),
+            ),
+            authorized(&api_keys, formatted_endpoint(v1, "stats", Self::stats)),
+            submission_endpoint(
+                "v1/transactions",
+                rate_limiter,
+                idempotency,
+                Arc::clone(&tracer),
+                Self::submission_author,
+                Self::submit_transaction,
+            ),
+            health_endpoint(Arc::clone(&health)),
+            readiness_endpoint(health),
+            json_endpoint("v1/graphql", Self::graphql),
+            json_endpoint("v1/transactions/validate", Self::validate_transaction),
+            formatted_endpoint(v1, "wallets/nfts", Self::wallet_nfts),
+            formatted_endpoint(v1, "supply", Self::supply),
+            formatted_endpoint(v1, "transactions/status", Self::transaction_status),
+            formatted_endpoint(v1, "events", Self::events),
+            formatted_endpoint(v1, "openapi", Self::openapi),
+            formatted_endpoint(
+                v1,
+                "wallets/subscribe",
+                move |state: &ServiceApiState, query: WalletSubscribeQuery| {
+                    Self::subscribe_wallet(&events, state, query)
+                },
+            ),
+            formatted_endpoint(
+                v1,
+                "transactions/feed",
+                move |state: &ServiceApiState, query: TransactionFeedQuery| {
+                    Self::transaction_feed(&feed_events, state, query)
+                },
+            ),
+            formatted_endpoint(v1, "wallets/history/export", Self::export_wallet_history),
+            formatted_endpoint(v1, "wallets/watched", Self::watched_wallets),
+            formatted_endpoint(v1, "invoices", Self::invoice),
+        ];
+        for &version in &[ApiVersion::V1, ApiVersion::V2] {
+            handlers.extend(vec![
+                negotiated_endpoint(version, "wallets", Self::wallets),
+                with_wallet_etag(formatted_endpoint(
+                    version,
+                    "wallets/info",
+                    Self::wallet_info,
+                )),
+                with_wallet_etag(negotiated_endpoint(
+                    version,
+                    "wallets/history",
+                    Self::wallet_history,
+                )),
+                negotiated_endpoint(version, "wallets/top", Self::top_wallets),
+                negotiated_endpoint(version, "wallets/search", Self::search_wallets),
+            ]);
+        }
+
+        let backend = builder.public_scope().web_backend();
+        let mut paths = Vec::new();
+        for handler in handlers {
+            if !paths.contains(&handler.name) {
+                paths.push(handler.name.clone());
+            }
+            let handler = with_cors(handler, Arc::clone(&node_config));
+            backend.raw_handler(with_tracing(&tracer, handler));
+        }
+        for path in &paths {
+            let handler = preflight_endpoint(path, Arc::clone(&node_config));
+            backend.raw_handler(with_tracing(&tracer, handler));
+        }
+    }
+}
+
+/// Private service API description.
+#[derive(Debug, Clone, Copy)]
+pub struct PrivateApi;
+
+impl PrivateApi {
+    /// Endpoint for listing the registered webhooks.
+    pub fn webhooks(
+        webhooks: &Webhooks,
+        _state: &ServiceApiState,
+        _query: (),
+    ) -> api::Result<Vec<Webhook>> {
+        Ok(webhooks.list())
+    }
+
+    /// Endpoint for registering a webhook, returns the registered webhooks.
+    pub fn register_webhook(
+        webhooks: &Webhooks,
+        _state: &ServiceApiState,
+        query: Webhook,
+    ) -> api::Result<Vec<Webhook>> {
+        if !webhooks.register(query) {
+            return Err(api::Error::BadRequest(
+                "Webhook URL must be a valid http:// URL".to_owned(),
+            ));
+        }
+        Ok(webhooks.list())
+    }
+
+    /// Endpoint for unregistering a webhook, returns the registered webhooks.
+    pub fn unregister_webhook(
+        webhooks: &Webhooks,
+        _state: &ServiceApiState,
+        query: Webhook,
+    ) -> api::Result<Vec<Webhook>> {
+        if !webhooks.unregister(&query) {
+            return Err(api::Error::NotFound("Webhook not found".to_owned()));
+        }
+        Ok(webhooks.list())
+    }
+
+    /// Endpoint for getting the counters of the rate limited submissions.
+    pub fn rate_limits(
+        rate_limiter: &RateLimiter,
+        _state: &ServiceApiState,
+        _query: (),
+    ) -> api::Result<RateLimitStats> {
+        Ok(rate_limiter.stats())
+    }
+
+    /// Endpoint for exporting the state of the service to a portable snapshot.
+    pub fn snapshot(state: &ServiceApiState, _query: ()) -> api::Result<StateSnapshot> {
+        Ok(StateSnapshot::export(&*state.snapshot()))
+    }
+
+    /// Wires the above endpoints to private scope of the given `ServiceApiBuilder`.
+    pub fn wire(
+        builder: &mut ServiceApiBuilder,
+        webhooks: Arc<Webhooks>,
+        rate_limiter: Arc<RateLimiter>,
+    ) {
+        let register_webhooks = Arc::clone(&webhooks);
+        let unregister_webhooks = Arc::clone(&webhooks);
         builder
-            .public_scope()
-            .endpoint("v1/wallets/info", Self::wallet_info);
+            .private_scope()
+            .endpoint(
+                "v1/rate_limits",
+                move |state: &ServiceApiState, query: ()| {
+                    Self::rate_limits(&rate_limiter, state, query)
+                },
+            )
+            .endpoint("v1/snapshot", Self::snapshot)
+            .endpoint("v1/webhooks", move |state: &ServiceApiState, query: ()| {
+                Self::webhooks(&webhooks, state, query)
+            })
+            .endpoint_mut(
+                "v1/webhooks/register",
+                move |state: &ServiceApiState, query: Webhook| {
+                    Self::register_webhook(&register_webhooks, state, query)
+                },
+            )
+            .endpoint_mut(
+                "v1/webhooks/unregister",
+                move |state: &ServiceApiState, query: Webhook| {
+                    Self::unregister_webhook(&unregister_webhooks, state, query)
+                },
+            );
     }
 }
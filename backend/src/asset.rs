@@ -0,0 +1,70 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-defined assets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Asset information stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Asset", serde_pb_convert)]
+pub struct Asset {
+    /// Code of the asset.
+    pub code: String,
+    /// Number of decimal places used to display amounts of the asset.
+    pub decimals: u32,
+    /// `PublicKey` of the key allowed to issue the asset.
+    pub issuer: PublicKey,
+    /// Maximum amount of the asset that can be issued, `0` means no limit.
+    pub max_supply: u64,
+    /// Amount of the asset issued so far.
+    pub issued: u64,
+}
+
+impl Asset {
+    /// Create new Asset.
+    pub fn new(
+        code: &str,
+        decimals: u32,
+        &issuer: &PublicKey,
+        max_supply: u64,
+        issued: u64,
+    ) -> Self {
+        Self {
+            code: code.to_owned(),
+            decimals,
+            issuer,
+            max_supply,
+            issued,
+        }
+    }
+
+    /// Returns a copy of this asset with updated issued amount.
+    pub fn set_issued(self, issued: u64) -> Self {
+        Self::new(
+            &self.code,
+            self.decimals,
+            &self.issuer,
+            self.max_supply,
+            issued,
+        )
+    }
+
+    /// Checks whether issuing up to `issued` in total stays within `max_supply`.
+    pub fn allows_supply(&self, issued: u64) -> bool {
+        self.max_supply == 0 || issued <= self.max_supply
+    }
+}
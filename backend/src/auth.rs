@@ -0,0 +1,85 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! API key authentication of the privileged endpoints.
+
+use std::sync::Arc;
+
+use actix_web::{http::header, HttpResponse};
+use exonum::api::backends::actix::{FutureResponse, HttpRequest, RequestHandler};
+use futures::future;
+
+/// Header carrying the API key of a request.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// API keys accepted by the privileged endpoints.
+///
+/// Keys are local to the node and read from the service section of the node configuration,
+/// so they never get into the blockchain.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: Vec<String>,
+}
+
+impl ApiKeys {
+    /// Creates the set of the given keys, empty keys are ignored.
+    pub fn new(keys: Vec<String>) -> Self {
+        let keys = keys.into_iter().filter(|key| !key.is_empty()).collect();
+        ApiKeys { keys }
+    }
+
+    /// Checks whether the request carries one of the keys in the `X-API-Key` header.
+    ///
+    /// Every request is allowed if no keys are configured.
+    pub fn allows(&self, request: &HttpRequest) -> bool {
+        if self.keys.is_empty() {
+            return true;
+        }
+        let key = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        self.keys
+            .iter()
+            .any(|allowed| constant_time_eq(allowed.as_bytes(), key.as_bytes()))
+    }
+}
+
+/// Compares the byte strings in time independent of the position of the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Wraps the handler so that it answers with `401 Unauthorized` to the requests
+/// without a valid API key.
+pub fn authorized(api_keys: &Arc<ApiKeys>, handler: RequestHandler) -> RequestHandler {
+    let api_keys = Arc::clone(api_keys);
+    let inner = handler.inner;
+    let index = move |request: HttpRequest| -> FutureResponse {
+        if !api_keys.allows(&request) {
+            return Box::new(future::ok(
+                HttpResponse::Unauthorized()
+                    .header(header::WWW_AUTHENTICATE, API_KEY_HEADER)
+                    .body("Missing or invalid API key"),
+            ));
+        }
+        inner(request)
+    };
+    RequestHandler {
+        name: handler.name,
+        method: handler.method,
+        inner: Arc::new(index),
+    }
+}
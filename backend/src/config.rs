@@ -0,0 +1,259 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cryptocurrency service configuration.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Length of a sealed box in bytes in addition to its plain text.
+const SEALED_BOX_OVERHEAD: u64 = 48;
+
+/// Service configuration stored in the database at genesis.
+///
+/// Later configurations proposed through the configuration service replace it
+/// once they become actual.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Config", serde_pb_convert)]
+pub struct Config {
+    /// Keys allowed to execute administrative transactions.
+    pub admins: Vec<PublicKey>,
+    /// Keys allowed to issue new currency.
+    pub minters: Vec<PublicKey>,
+    /// Maximum amount of currency that can be issued, `0` means no limit.
+    pub max_supply: u64,
+    /// Fee charged from the sender of each `Transfer`, `0` means no fee.
+    pub transfer_fee: u64,
+    /// Wallets the collected fees are distributed to.
+    pub fee_recipients: Vec<PublicKey>,
+    /// Whether the collected fees are burned instead of being distributed.
+    pub burn_fees: bool,
+    /// Maximum amount of currency a wallet can send within `velocity_window` blocks,
+    /// `0` means no limit.
+    pub velocity_limit: u64,
+    /// Number of the latest blocks the outgoing volume is tracked for.
+    pub velocity_window: u64,
+    /// Keys allowed to set the KYC verification flag of wallets.
+    pub compliance_officers: Vec<PublicKey>,
+    /// Transfers above this amount require both parties to pass KYC, `0` means no requirement.
+    pub kyc_threshold: u64,
+    /// Number of blocks the owner can cancel an approved wallet recovery for.
+    pub recovery_challenge_period: u64,
+    /// Keys allowed to resolve disputed transfers.
+    pub arbitrators: Vec<PublicKey>,
+    /// Number of blocks after a transfer its sender can dispute it for, `0` disables disputes.
+    pub dispute_window: u64,
+    /// Amount of currency issued each block and shared among the stakes in proportion
+    /// to their amounts, `0` disables rewards.
+    pub staking_reward: u64,
+    /// Percentage of the rewards of delegated stakes paid to their validator.
+    pub delegation_commission: u64,
+    /// Interest credited to the savings per block, in basis points.
+    pub savings_interest_rate: u64,
+    /// Percentage of the borrowed amount the collateral must be worth to open a loan,
+    /// `0` disables lending.
+    pub collateral_ratio: u64,
+    /// Percentage of the borrowed amount below which the collateral worth makes the loan
+    /// liquidatable.
+    pub liquidation_ratio: u64,
+    /// Percentage of the outgoing transfer volume in the default coin credited to the sender
+    /// as loyalty points, `0` disables points.
+    pub loyalty_rate: u64,
+    /// Percentage of the total supply the votes for a parameter change must reach,
+    /// `0` disables governance.
+    pub governance_quorum: u64,
+    /// Balance of a newly created wallet.
+    pub initial_balance: u64,
+    /// Maximum length of the transfer memo in bytes.
+    pub max_memo_length: u64,
+    /// Maximum length of the wallet metadata key in bytes.
+    pub max_metadata_key_length: u64,
+    /// Maximum length of the wallet metadata value in bytes.
+    pub max_metadata_value_length: u64,
+    /// Maximum number of the wallet metadata entries.
+    pub max_metadata_entries: u64,
+    /// Maximum number of keys in the watch list.
+    pub max_watched_keys: u64,
+    /// Maximum amount a faucet request credits, `0` disables the faucet.
+    pub faucet_limit: u64,
+    /// Number of blocks a key has to wait between faucet credits.
+    pub faucet_interval: u64,
+    /// Number of the latest blocks the wallet history records are kept for, older
+    /// records are pruned leaving only the Merkle root of the full history.
+    /// `0` turns on the archive mode retaining the whole history.
+    pub history_retention: u64,
+    /// Fee charged from the author of each `ProposeParamChange`, `0` means no fee.
+    pub proposal_fee: u64,
+    /// Number of blocks a parameter change is open for voting after the proposal.
+    pub proposal_period: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            admins: Vec::new(),
+            minters: Vec::new(),
+            max_supply: 0,
+            transfer_fee: 0,
+            fee_recipients: Vec::new(),
+            burn_fees: false,
+            velocity_limit: 0,
+            velocity_window: 0,
+            compliance_officers: Vec::new(),
+            kyc_threshold: 0,
+            recovery_challenge_period: 0,
+            arbitrators: Vec::new(),
+            dispute_window: 0,
+            staking_reward: 0,
+            delegation_commission: 0,
+            savings_interest_rate: 0,
+            collateral_ratio: 0,
+            liquidation_ratio: 0,
+            loyalty_rate: 0,
+            governance_quorum: 0,
+            initial_balance: 100,
+            max_memo_length: 256,
+            max_metadata_key_length: 64,
+            max_metadata_value_length: 1024,
+            max_metadata_entries: 32,
+            max_watched_keys: 64,
+            faucet_limit: 0,
+            faucet_interval: 0,
+            history_retention: 0,
+            proposal_fee: 0,
+            proposal_period: 10_000,
+        }
+    }
+}
+
+impl Config {
+    /// Checks whether the given key is an administrator.
+    pub fn is_admin(&self, key: &PublicKey) -> bool {
+        self.admins.contains(key)
+    }
+
+    /// Checks whether the given key is allowed to issue new currency.
+    pub fn is_minter(&self, key: &PublicKey) -> bool {
+        self.minters.contains(key)
+    }
+
+    /// Checks whether the given key is a compliance officer.
+    pub fn is_compliance_officer(&self, key: &PublicKey) -> bool {
+        self.compliance_officers.contains(key)
+    }
+
+    /// Checks whether the given key is allowed to resolve disputes.
+    pub fn is_arbitrator(&self, key: &PublicKey) -> bool {
+        self.arbitrators.contains(key)
+    }
+
+    /// Checks whether a transfer of the given amount requires both parties to pass KYC.
+    pub fn requires_kyc(&self, amount: u64) -> bool {
+        self.kyc_threshold != 0 && amount > self.kyc_threshold
+    }
+
+    /// Checks whether sending `volume` within the velocity window stays within `velocity_limit`.
+    pub fn allows_velocity(&self, volume: u64) -> bool {
+        self.velocity_limit == 0 || volume <= self.velocity_limit
+    }
+
+    /// Returns a copy of this configuration with the given parameter changed,
+    /// or `None` if the parameter can't be changed by voting.
+    pub fn set_param(mut self, param: &str, value: u64) -> Option<Self> {
+        match param {
+            "max_supply" => self.max_supply = value,
+            "transfer_fee" => self.transfer_fee = value,
+            "velocity_limit" => self.velocity_limit = value,
+            "kyc_threshold" => self.kyc_threshold = value,
+            "dispute_window" => self.dispute_window = value,
+            "staking_reward" => self.staking_reward = value,
+            "savings_interest_rate" => self.savings_interest_rate = value,
+            "loyalty_rate" => self.loyalty_rate = value,
+            _ => return None,
+        }
+        Some(self)
+    }
+
+    /// Checks whether the plain and the encrypted memos fit into `max_memo_length`.
+    ///
+    /// The encrypted memo is a sealed box, so it is allowed to be longer by its overhead.
+    pub fn allows_memo(&self, memo_length: usize, encrypted_memo_length: usize) -> bool {
+        memo_length as u64 <= self.max_memo_length
+            && encrypted_memo_length as u64 <= self.max_memo_length + SEALED_BOX_OVERHEAD
+    }
+
+    /// Checks whether the metadata entry fits into the configured key and value lengths.
+    pub fn allows_metadata(&self, key_length: usize, value_length: usize) -> bool {
+        key_length as u64 <= self.max_metadata_key_length
+            && value_length as u64 <= self.max_metadata_value_length
+    }
+
+    /// Checks whether issuing up to `issued` in total stays within `max_supply`.
+    pub fn allows_supply(&self, issued: u64) -> bool {
+        self.max_supply == 0 || issued <= self.max_supply
+    }
+}
+
+/// Settings local to the node, read from the service section of the node configuration.
+///
+/// Unlike `Config`, these settings don't affect the execution of the transactions,
+/// so the nodes may set them differently.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    /// Broker the committed wallet events are published to: a `nats://` server or
+    /// an `http://` Kafka REST proxy, empty to disable publishing.
+    pub event_broker: String,
+    /// NATS subject or Kafka topic the wallet events are published to.
+    pub event_topic: String,
+    /// Maximum number of transactions a client IP address can submit within
+    /// `rate_limit_window` seconds, `0` means no limit.
+    pub ip_rate_limit: u64,
+    /// Maximum number of transactions a public key can submit within `rate_limit_window`
+    /// seconds, `0` means no limit.
+    pub key_rate_limit: u64,
+    /// Length of the rate limiting window in seconds.
+    pub rate_limit_window: u64,
+    /// Origins allowed to call the public API from browsers, `*` allows any origin.
+    pub cors_allowed_origins: Vec<String>,
+    /// Request headers allowed in the cross-origin requests.
+    pub cors_allowed_headers: Vec<String>,
+    /// Methods allowed in the cross-origin requests.
+    pub cors_allowed_methods: Vec<String>,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            event_broker: String::new(),
+            event_topic: "cryptocurrency.wallets".to_owned(),
+            ip_rate_limit: 0,
+            key_rate_limit: 0,
+            rate_limit_window: 60,
+            cors_allowed_origins: Vec::new(),
+            cors_allowed_headers: vec!["Content-Type".to_owned()],
+            cors_allowed_methods: vec!["GET".to_owned(), "POST".to_owned()],
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Checks whether the public API can be called by the pages of the given origin.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.cors_allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+}
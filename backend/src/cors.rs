@@ -0,0 +1,112 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! CORS policy of the public API, given by the node configuration.
+
+use std::sync::Arc;
+
+use actix_web::{
+    self,
+    http::{
+        header::{self, HeaderName, HeaderValue},
+        Method,
+    },
+    HttpResponse,
+};
+use exonum::api::backends::actix::{FutureResponse, HttpRequest, RequestHandler};
+use futures::{future, Future};
+
+use config::NodeConfig;
+
+/// Time the browsers may cache the preflight responses for, in seconds.
+const PREFLIGHT_MAX_AGE_SECS: u64 = 3600;
+
+/// Returns the origin of the cross-origin request if the configuration allows it.
+fn allowed_origin(request: &HttpRequest, config: &NodeConfig) -> Option<HeaderValue> {
+    let origin = request.headers().get(header::ORIGIN)?;
+    if config.allows_origin(origin.to_str().ok()?) {
+        Some(origin.clone())
+    } else {
+        None
+    }
+}
+
+/// Returns the CORS headers of the response to the request.
+fn cors_headers(request: &HttpRequest, config: &NodeConfig) -> Vec<(HeaderName, HeaderValue)> {
+    match allowed_origin(request, config) {
+        Some(origin) => vec![
+            (header::ACCESS_CONTROL_ALLOW_ORIGIN, origin),
+            (header::VARY, HeaderValue::from_static("Origin")),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Wraps the handler so that its responses, including the errors, carry the CORS headers
+/// for the origins allowed by the node configuration.
+pub fn with_cors(handler: RequestHandler, config: Arc<NodeConfig>) -> RequestHandler {
+    let inner = handler.inner;
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let headers = cors_headers(&request, &config);
+        Box::new(inner(request).then(move |result| -> actix_web::Result<HttpResponse> {
+            let mut response =
+                result.unwrap_or_else(|error| error.as_response_error().error_response());
+            for (name, value) in headers {
+                response.headers_mut().insert(name, value);
+            }
+            Ok(response)
+        }))
+    };
+    RequestHandler {
+        name: handler.name,
+        method: handler.method,
+        inner: Arc::new(index),
+    }
+}
+
+/// Creates the `OPTIONS` handler answering the CORS preflight requests to the endpoint
+/// with the methods and headers allowed by the node configuration.
+///
+/// Requests from the origins not allowed get an empty response without the CORS headers,
+/// so the browsers block the following request.
+pub fn preflight_endpoint(name: &str, config: Arc<NodeConfig>) -> RequestHandler {
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let mut response = HttpResponse::NoContent();
+        if let Some(origin) = allowed_origin(&request, &config) {
+            response
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin)
+                .header(header::VARY, "Origin")
+                .header(
+                    header::ACCESS_CONTROL_ALLOW_METHODS,
+                    config.cors_allowed_methods.join(", "),
+                )
+                .header(
+                    header::ACCESS_CONTROL_MAX_AGE,
+                    PREFLIGHT_MAX_AGE_SECS.to_string(),
+                );
+            if !config.cors_allowed_headers.is_empty() {
+                response.header(
+                    header::ACCESS_CONTROL_ALLOW_HEADERS,
+                    config.cors_allowed_headers.join(", "),
+                );
+            }
+        }
+        Box::new(future::ok(response.finish()))
+    };
+    RequestHandler {
+        name: name.to_owned(),
+        method: Method::OPTIONS,
+        inner: Arc::new(index),
+    }
+}
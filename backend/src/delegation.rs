@@ -0,0 +1,61 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Delegated spending authority.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Authority of another key to spend from the wallet up to a cumulative cap,
+/// stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Delegation", serde_pb_convert)]
+pub struct Delegation {
+    /// `PublicKey` allowed to spend from the wallet.
+    pub spender: PublicKey,
+    /// Maximum cumulative amount of currency the spender can transfer.
+    pub max_amount: u64,
+    /// Amount of currency already transferred by the spender.
+    pub spent: u64,
+    /// Height of the block starting from which the delegation expires, `0` means never.
+    pub expiry: u64,
+}
+
+impl Delegation {
+    /// Create new Delegation.
+    pub fn new(&spender: &PublicKey, max_amount: u64, spent: u64, expiry: u64) -> Self {
+        Self {
+            spender,
+            max_amount,
+            spent,
+            expiry,
+        }
+    }
+
+    /// Returns a copy of this delegation with updated spent amount.
+    pub fn set_spent(self, spent: u64) -> Self {
+        Self::new(&self.spender, self.max_amount, spent, self.expiry)
+    }
+
+    /// Returns amount of currency the spender can still transfer.
+    pub fn remaining(&self) -> u64 {
+        self.max_amount.saturating_sub(self.spent)
+    }
+
+    /// Checks whether the delegation is expired at the given height.
+    pub fn is_expired_at(&self, height: u64) -> bool {
+        self.expiry != 0 && height >= self.expiry
+    }
+}
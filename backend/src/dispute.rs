@@ -0,0 +1,59 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dispute of a transfer.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Transfer disputed by its sender, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Dispute", serde_pb_convert)]
+pub struct Dispute {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Code of the transferred asset, empty for the default coin.
+    pub asset: String,
+    /// Amount of transferred currency.
+    pub amount: u64,
+    /// Whether the dispute is resolved by the arbitrator.
+    pub resolved: bool,
+}
+
+impl Dispute {
+    /// Create new Dispute.
+    pub fn new(
+        &from: &PublicKey,
+        &to: &PublicKey,
+        asset: &str,
+        amount: u64,
+        resolved: bool,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            asset: asset.to_owned(),
+            amount,
+            resolved,
+        }
+    }
+
+    /// Returns a copy of this dispute marked as resolved.
+    pub fn resolve(self) -> Self {
+        Self::new(&self.from, &self.to, &self.asset, self.amount, true)
+    }
+}
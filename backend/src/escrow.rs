@@ -0,0 +1,45 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Escrow transfer.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Transfer locked until released or refunded by the arbitrator, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Escrow", serde_pb_convert)]
+pub struct Escrow {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// `PublicKey` of the arbitrator allowed to release or refund the funds.
+    pub arbitrator: PublicKey,
+    /// Amount of locked currency.
+    pub amount: u64,
+}
+
+impl Escrow {
+    /// Create new Escrow.
+    pub fn new(&from: &PublicKey, &to: &PublicKey, &arbitrator: &PublicKey, amount: u64) -> Self {
+        Self {
+            from,
+            to,
+            arbitrator,
+            amount,
+        }
+    }
+}
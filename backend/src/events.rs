@@ -0,0 +1,264 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Notifications about the wallet changes for the subscribed clients.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use exonum::{blockchain, crypto::PublicKey, helpers::Height, storage::Snapshot};
+use futures::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use wallet::Wallet;
+use Schema;
+
+/// Maximum number of the latest events kept for a wallet.
+const MAX_EVENTS_PER_WALLET: usize = 32;
+
+/// Maximum number of the subscribed wallets.
+const MAX_SUBSCRIPTIONS: usize = 10_000;
+
+/// Number of seconds the events of a wallet are kept for after the last request
+/// subscribing to it, unless it is streamed or waited for.
+const SUBSCRIPTION_TTL_SECS: u64 = 10 * 60;
+
+/// Change of a wallet made by a committed block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletEvent {
+    /// Height of the block that changed the wallet.
+    pub height: Height,
+    /// Wallet after the block.
+    pub wallet: Wallet,
+}
+
+/// Latest known state, events and streaming listeners of a subscribed wallet.
+#[derive(Debug)]
+struct Subscription {
+    last: Option<Wallet>,
+    events: VecDeque<WalletEvent>,
+    listeners: Vec<UnboundedSender<WalletEvent>>,
+    waiters: usize,
+    expires_at: Instant,
+}
+
+impl Subscription {
+    /// Checks whether the subscription is neither streamed nor waited for and its events
+    /// have expired.
+    fn is_expired(&self, now: Instant) -> bool {
+        self.listeners.is_empty() && self.waiters == 0 && self.expires_at <= now
+    }
+}
+
+/// Error returned when no more wallets can be subscribed to.
+#[derive(Debug, Fail)]
+#[fail(display = "Too many wallet subscriptions")]
+pub struct TooManySubscriptions;
+
+/// State of the hub shared between the committing and the waiting threads.
+#[derive(Debug, Default)]
+struct State {
+    height: Height,
+    subscriptions: HashMap<PublicKey, Subscription>,
+    block_listeners: Vec<UnboundedSender<Height>>,
+}
+
+/// Hub notifying the subscribers about the committed blocks and the wallet changes
+/// made by them.
+#[derive(Debug, Default)]
+pub struct WalletEvents {
+    state: Mutex<State>,
+    committed: Condvar,
+}
+
+impl WalletEvents {
+    /// Create new WalletEvents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the changes of the subscribed wallets made by the latest committed block
+    /// and wakes up the waiting subscribers.
+    ///
+    /// A wallet is changed if its balance, pending balance or history differ.
+    pub fn notify(&self, snapshot: &dyn Snapshot) {
+        let height = blockchain::Schema::new(snapshot).height();
+        let schema = Schema::new(snapshot);
+        let mut state = self.state.lock().unwrap();
+        state.height = height;
+        for (key, subscription) in state.subscriptions.iter_mut() {
+            let wallet = match schema.wallet(key) {
+                Some(wallet) => wallet,
+                None => continue,
+            };
+            let changed = subscription.last.as_ref().map_or(true, |last| {
+                last.balance != wallet.balance
+                    || last.pending_balance != wallet.pending_balance
+                    || last.history_hash != wallet.history_hash
+            });
+            if !changed {
+                continue;
+            }
+            let event = WalletEvent {
+                height,
+                wallet: wallet.clone(),
+            };
+            // Listeners of the closed connections are dropped.
+            subscription
+                .listeners
+                .retain(|listener| listener.unbounded_send(event.clone()).is_ok());
+            subscription.events.push_back(event);
+            if subscription.events.len() > MAX_EVENTS_PER_WALLET {
+                subscription.events.pop_front();
+            }
+            subscription.last = Some(wallet);
+        }
+        let now = Instant::now();
+        state.subscriptions.retain(|_, subscription| {
+            // Listeners of the connections closed while the wallet didn't change.
+            subscription
+                .listeners
+                .retain(|listener| !listener.is_closed());
+            !subscription.is_expired(now)
+        });
+        state
+            .block_listeners
+            .retain(|listener| listener.unbounded_send(height).is_ok());
+        self.committed.notify_all();
+    }
+
+    /// Subscribes to the changes of the wallet and waits up to `timeout` for the changes
+    /// made by the blocks above `since`.
+    ///
+    /// Returns the kept events above `since`, which are empty if the timeout expires.
+    pub fn wait(
+        &self,
+        snapshot: &dyn Snapshot,
+        key: &PublicKey,
+        since: Height,
+        timeout: Duration,
+    ) -> Result<Vec<WalletEvent>, TooManySubscriptions> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+        subscribe(&mut state, snapshot, key)?.waiters += 1;
+        loop {
+            let events = state.subscriptions[key]
+                .events
+                .iter()
+                .filter(|event| event.height > since)
+                .cloned()
+                .collect::<Vec<_>>();
+            let now = Instant::now();
+            if !events.is_empty() || now >= deadline {
+                let subscription = state.subscriptions.get_mut(key).unwrap();
+                subscription.waiters -= 1;
+                subscription.expires_at = now + ttl();
+                return Ok(events);
+            }
+            state = self
+                .committed
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Subscribes to the changes of the wallet and returns the stream of the kept events
+    /// above `since` followed by the events of the blocks committed from now on.
+    pub fn listen(
+        &self,
+        snapshot: &dyn Snapshot,
+        key: &PublicKey,
+        since: Height,
+    ) -> Result<UnboundedReceiver<WalletEvent>, TooManySubscriptions> {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut state = self.state.lock().unwrap();
+        let subscription = subscribe(&mut state, snapshot, key)?;
+        let kept = subscription
+            .events
+            .iter()
+            .filter(|event| event.height > since);
+        for event in kept {
+            let _ = sender.unbounded_send(event.clone());
+        }
+        subscription.listeners.push(sender);
+        Ok(receiver)
+    }
+
+    /// Returns the stream of the heights of the blocks committed from now on.
+    pub fn listen_blocks(&self) -> UnboundedReceiver<Height> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.state.lock().unwrap().block_listeners.push(sender);
+        receiver
+    }
+
+    /// Waits up to `timeout` for a block above the given height to be committed.
+    ///
+    /// Returns whether such a block was committed since the node start.
+    pub fn wait_for_block(&self, since: Height, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            if state.height > since || now >= deadline {
+                return state.height > since;
+            }
+            state = self
+                .committed
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+/// Returns the subscription to the wallet, starting it from the current state of the wallet
+/// if there is none, and extends the time its events are kept for.
+///
+/// Fails if `MAX_SUBSCRIPTIONS` wallets are subscribed to and none of the subscriptions
+/// has expired.
+fn subscribe<'a>(
+    state: &'a mut State,
+    snapshot: &dyn Snapshot,
+    key: &PublicKey,
+) -> Result<&'a mut Subscription, TooManySubscriptions> {
+    let now = Instant::now();
+    if !state.subscriptions.contains_key(key) && state.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+        state
+            .subscriptions
+            .retain(|_, subscription| !subscription.is_expired(now));
+        if state.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+            return Err(TooManySubscriptions);
+        }
+    }
+    let subscription = state
+        .subscriptions
+        .entry(*key)
+        .or_insert_with(|| Subscription {
+            last: Schema::new(snapshot).wallet(key),
+            events: VecDeque::new(),
+            listeners: Vec::new(),
+            waiters: 0,
+            expires_at: now,
+        });
+    subscription.expires_at = now + ttl();
+    Ok(subscription)
+}
+
+/// Returns the time the events of a subscribed wallet are kept for.
+fn ttl() -> Duration {
+    Duration::from_secs(SUBSCRIPTION_TTL_SECS)
+}
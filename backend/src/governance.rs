@@ -0,0 +1,84 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Changes of the service parameters voted by the wallet holders.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Proposed change of a service parameter, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ParamChange", serde_pb_convert)]
+pub struct ParamChange {
+    /// Name of the `Config` field to change.
+    pub param: String,
+    /// New value of the parameter.
+    pub value: u64,
+    /// Keys of the wallets voted for the change.
+    pub voters: Vec<PublicKey>,
+    /// Sum of the balances of the voters at the moment of the proposal.
+    pub weight: u64,
+    /// Sum of the balances of all the wallets at the moment of the proposal.
+    pub total_weight: u64,
+    /// Key of the wallet proposed the change.
+    pub proposer: PublicKey,
+    /// Height of the last block the change can be voted in.
+    pub expires_at: u64,
+}
+
+impl ParamChange {
+    /// Create new ParamChange.
+    pub fn new(
+        param: &str,
+        value: u64,
+        voters: Vec<PublicKey>,
+        weight: u64,
+        total_weight: u64,
+        proposer: &PublicKey,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            param: param.to_owned(),
+            value,
+            voters,
+            weight,
+            total_weight,
+            proposer: *proposer,
+            expires_at,
+        }
+    }
+
+    /// Returns a copy of this change with the vote of the given wallet added.
+    pub fn add_vote(self, voter: &PublicKey, weight: u64) -> Self {
+        let mut voters = self.voters;
+        voters.push(*voter);
+        let weight = self.weight.saturating_add(weight);
+        Self::new(
+            &self.param,
+            self.value,
+            voters,
+            weight,
+            self.total_weight,
+            &self.proposer,
+            self.expires_at,
+        )
+    }
+
+    /// Checks whether the votes reach the given percentage of the balances
+    /// at the moment of the proposal.
+    pub fn has_quorum(&self, quorum: u64) -> bool {
+        u128::from(self.weight) * 100 >= u128::from(self.total_weight) * u128::from(quorum)
+    }
+}
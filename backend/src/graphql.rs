@@ -0,0 +1,197 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GraphQL schema of the wallets, their history and pending multisign transfers.
+//!
+//! GraphQL integers are 32-bit, so amounts, balances and heights are represented
+//! as decimal strings and keys and hashes as hex strings.
+
+use std::fmt;
+
+use exonum::{crypto::PublicKey, storage::Snapshot};
+use hex;
+use juniper::{EmptyMutation, FieldResult, RootNode};
+
+use api::HistoryRecord;
+use pending_transfer::PendingTransfer;
+use wallet::Wallet;
+use Schema;
+
+/// Maximum number of history records returned by a single `history` field.
+const MAX_HISTORY_LIMIT: i32 = 1000;
+
+/// GraphQL schema of the service.
+pub type GraphQLSchema = RootNode<'static, Query, EmptyMutation<Context>>;
+
+/// Creates the GraphQL schema of the service.
+pub fn schema() -> GraphQLSchema {
+    RootNode::new(Query, EmptyMutation::new())
+}
+
+/// Context of a GraphQL query, i.e. the snapshot all its fields are read from.
+pub struct Context {
+    snapshot: Box<dyn Snapshot>,
+}
+
+impl Context {
+    /// Create new Context.
+    pub fn new(snapshot: Box<dyn Snapshot>) -> Self {
+        Self { snapshot }
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Context").finish()
+    }
+}
+
+impl juniper::Context for Context {}
+
+/// Root of the GraphQL queries.
+#[derive(Debug, Clone, Copy)]
+pub struct Query;
+
+graphql_object!(Query: Context |&self| {
+    description: "Root of the wallet queries."
+
+    field wallet(&executor, pub_key: String as "Hex-encoded public key of the wallet.")
+        -> FieldResult<Option<Wallet>> as "Wallet with the given key, if any."
+    {
+        let pub_key = hex::decode(&pub_key)
+            .ok()
+            .and_then(|bytes| PublicKey::from_slice(&bytes))
+            .ok_or("Invalid public key")?;
+        Ok(Schema::new(&executor.context().snapshot).wallet(&pub_key))
+    }
+});
+
+graphql_object!(Wallet: Context |&self| {
+    description: "Wallet of the cryptocurrency service."
+
+    field pub_key() -> String as "Public key of the wallet." {
+        hex::encode(self.pub_key.as_ref())
+    }
+
+    field name() -> &str as "Name of the wallet." {
+        &self.name
+    }
+
+    field balance() -> String as "Current balance of the wallet." {
+        self.balance.to_string()
+    }
+
+    field pending_balance() -> String as "Balance not reserved by the pending transfers." {
+        self.pending_balance.to_string()
+    }
+
+    field history_len() -> String as "Length of the transactions history." {
+        self.history_len.to_string()
+    }
+
+    field frozen() -> bool as "Whether outgoing transfers from the wallet are frozen." {
+        self.frozen
+    }
+
+    field closed() -> bool as "Whether the wallet is closed." {
+        self.closed
+    }
+
+    field kyc_verified() -> bool as "Whether the owner of the wallet has passed KYC." {
+        self.kyc_verified
+    }
+
+    field history(
+        &executor,
+        from = 0: i32 as "Index of the first returned record.",
+        limit = 100: i32 as "Maximum number of returned records, at most 1000."
+    ) -> FieldResult<Vec<HistoryRecord>> as "Decoded records of the wallet history." {
+        if from < 0 || limit < 0 {
+            return Err("History range must not be negative".into());
+        }
+        Ok(HistoryRecord::from_history(
+            &*executor.context().snapshot,
+            &self.pub_key,
+            from as u64,
+            limit.min(MAX_HISTORY_LIMIT) as u64,
+        ))
+    }
+
+    field pending_transfers(&executor) -> Vec<PendingTransfer>
+        as "Multisign transfers the wallet is waiting for."
+    {
+        let schema = Schema::new(&executor.context().snapshot);
+        schema
+            .wallet_pending_txs(&self.pub_key)
+            .iter()
+            .filter_map(|tx_hash| schema.pending_transfer(&tx_hash))
+            .collect()
+    }
+});
+
+graphql_object!(HistoryRecord: Context |&self| {
+    description: "Decoded record of the wallet history."
+
+    field height() -> String as "Height of the block with the transaction." {
+        self.height.to_string()
+    }
+
+    field tx_hash() -> String as "Hash of the transaction." {
+        hex::encode(self.tx_hash.as_ref())
+    }
+
+    field tx_type() -> &str as "Type of the transaction." {
+        &self.tx_type
+    }
+
+    field counterparty() -> Option<String> as "Other key taking part in the transaction." {
+        self.counterparty.map(|key| hex::encode(key.as_ref()))
+    }
+
+    field amount() -> Option<String> as "Amount of currency moved by the transaction." {
+        self.amount.map(|amount| amount.to_string())
+    }
+
+    field balance_after() -> Option<String> as "Balance of the wallet after the transaction." {
+        self.balance_after.map(|balance| balance.to_string())
+    }
+});
+
+graphql_object!(PendingTransfer: Context |&self| {
+    description: "Multisign transfer waiting for approvals."
+
+    field initiator() -> String as "Public key of the transfer initiator." {
+        hex::encode(self.initiator.as_ref())
+    }
+
+    field from() -> String as "Public key of the multisign sender's wallet." {
+        hex::encode(self.from.as_ref())
+    }
+
+    field to() -> String as "Public key of the receiver's wallet." {
+        hex::encode(self.to.as_ref())
+    }
+
+    field approvers() -> Vec<String> as "Approvers of the transfer." {
+        self.approvers.iter().map(|key| hex::encode(key.as_ref())).collect()
+    }
+
+    field amount() -> String as "Amount of currency to transfer." {
+        self.amount.to_string()
+    }
+
+    field quorum() -> i32 as "Number of approvals required to execute the transfer." {
+        self.quorum as i32
+    }
+});
@@ -0,0 +1,46 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hash time-locked transfer.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Transfer claimable with the preimage of its hash lock until the timeout,
+/// stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::HashLock", serde_pb_convert)]
+pub struct HashLock {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of locked currency.
+    pub amount: u64,
+    /// Height of the block starting from which the lock can be refunded.
+    pub timeout: u64,
+}
+
+impl HashLock {
+    /// Create new HashLock.
+    pub fn new(&from: &PublicKey, &to: &PublicKey, amount: u64, timeout: u64) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            timeout,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Liveness and readiness probes of the node for the orchestration systems.
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{http::Method, HttpResponse};
+use exonum::{
+    api::backends::actix::{FutureResponse, HttpRequest, RequestHandler},
+    blockchain,
+    helpers::Height,
+    storage::Snapshot,
+};
+use futures::future;
+
+use Schema;
+
+/// Time since the last commit above which the node is not ready, in seconds.
+pub const MAX_READY_LAG_SECS: u64 = 60;
+
+/// Health of the node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthReport {
+    /// Whether the service schema could be read.
+    pub schema_accessible: bool,
+    /// Height of the last committed block, if the schema could be read.
+    pub height: Option<Height>,
+    /// Time since the node committed the last block, or since it started
+    /// if it did not commit any, in seconds.
+    pub lag_secs: u64,
+    /// Whether the node is ready to serve requests.
+    pub ready: bool,
+}
+
+/// Tracks the wall-clock time of the blocks committed by the node.
+///
+/// Blocks carry no timestamps, so the time is local to the node and measured
+/// since it started.
+#[derive(Debug)]
+pub struct HealthMonitor {
+    started: Instant,
+    last_commit: Mutex<Option<Instant>>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        HealthMonitor {
+            started: Instant::now(),
+            last_commit: Mutex::new(None),
+        }
+    }
+}
+
+impl HealthMonitor {
+    /// Create new HealthMonitor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the node committed a block.
+    pub fn record_commit(&self) {
+        *self.last_commit.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns the time since the last commit, or since the node started.
+    pub fn lag(&self) -> Duration {
+        self.last_commit
+            .lock()
+            .unwrap()
+            .unwrap_or(self.started)
+            .elapsed()
+    }
+
+    /// Checks the health of the node on the given snapshot.
+    ///
+    /// The node is ready if the schema is accessible and it committed a block within
+    /// `MAX_READY_LAG_SECS` seconds.
+    pub fn report(&self, snapshot: &dyn Snapshot) -> HealthReport {
+        // Storage errors surface as panics on reading the indices.
+        let height = panic::catch_unwind(AssertUnwindSafe(|| {
+            Schema::new(snapshot).config();
+            blockchain::Schema::new(snapshot).height()
+        }))
+        .ok();
+        let lag_secs = self.lag().as_secs();
+        HealthReport {
+            schema_accessible: height.is_some(),
+            height,
+            lag_secs,
+            ready: height.is_some() && lag_secs <= MAX_READY_LAG_SECS,
+        }
+    }
+}
+
+/// Creates the `GET` handler of the probe answering with the health report,
+/// and with `503 Service Unavailable` if `healthy` does not hold for it.
+fn probe_endpoint<F>(name: &str, monitor: Arc<HealthMonitor>, healthy: F) -> RequestHandler
+where
+    F: Fn(&HealthReport) -> bool + Send + Sync + 'static,
+{
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let report = monitor.report(&*request.state().snapshot());
+        let mut response = if healthy(&report) {
+            HttpResponse::Ok()
+        } else {
+            HttpResponse::ServiceUnavailable()
+        };
+        Box::new(future::ok(response.json(report)))
+    };
+    RequestHandler {
+        name: name.to_owned(),
+        method: Method::GET,
+        inner: Arc::new(index),
+    }
+}
+
+/// Creates the liveness probe, failing if the schema is not accessible.
+pub fn health_endpoint(monitor: Arc<HealthMonitor>) -> RequestHandler {
+    probe_endpoint("healthz", monitor, |report| report.schema_accessible)
+}
+
+/// Creates the readiness probe, failing if the node is not ready to serve requests.
+pub fn readiness_endpoint(monitor: Arc<HealthMonitor>) -> RequestHandler {
+    probe_endpoint("readyz", monitor, |report| report.ready)
+}
@@ -0,0 +1,114 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Responses of the submission endpoints cached by idempotency keys.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use exonum::crypto::Hash;
+use serde_json::Value;
+
+/// Header carrying the idempotency key of a submission.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Time the responses are kept for, in seconds.
+const IDEMPOTENCY_KEY_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Maximum number of the cached responses.
+const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// Result of looking up an idempotency key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lookup {
+    /// Key was not used yet.
+    Miss,
+    /// Key was used with the same request, which got the given response.
+    Replay(Value),
+    /// Key was used with another request.
+    Conflict,
+}
+
+/// Cached response to a submission.
+#[derive(Debug, Clone)]
+struct Entry {
+    created: Instant,
+    fingerprint: Hash,
+    response: Value,
+}
+
+/// Responses of the successful submissions by their idempotency keys.
+///
+/// Retried submissions with the same key get the cached response instead of being submitted
+/// again. Responses are local to the node and kept in memory for a day.
+#[derive(Debug, Default)]
+pub struct IdempotencyCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl IdempotencyCache {
+    /// Create new IdempotencyCache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up the response to the request with the given key and fingerprint.
+    pub fn get(&self, key: &str, fingerprint: &Hash) -> Lookup {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.created.elapsed() < ttl() => {
+                if entry.fingerprint == *fingerprint {
+                    Lookup::Replay(entry.response.clone())
+                } else {
+                    Lookup::Conflict
+                }
+            }
+            _ => Lookup::Miss,
+        }
+    }
+
+    /// Caches the response to the request with the given key and fingerprint.
+    ///
+    /// Expired responses are dropped once the cache is full, and the oldest one
+    /// if none has expired.
+    pub fn insert(&self, key: String, fingerprint: Hash, response: Value) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_IDEMPOTENCY_KEYS {
+            entries.retain(|_, entry| entry.created.elapsed() < ttl());
+        }
+        if entries.len() >= MAX_IDEMPOTENCY_KEYS {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        let entry = Entry {
+            created: Instant::now(),
+            fingerprint,
+            response,
+        };
+        entries.insert(key, entry);
+    }
+}
+
+/// Returns the time the responses are kept for.
+fn ttl() -> Duration {
+    Duration::from_secs(IDEMPOTENCY_KEY_TTL_SECS)
+}
@@ -0,0 +1,53 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inheritance of inactive wallets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Beneficiary of the wallet, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Inheritance", serde_pb_convert)]
+pub struct Inheritance {
+    /// `PublicKey` of the beneficiary.
+    pub beneficiary: PublicKey,
+    /// Number of blocks without outgoing transfers after which the beneficiary can claim the
+    /// balance.
+    pub inactivity_blocks: u64,
+    /// Height of the last outgoing transfer from the wallet.
+    pub last_activity: u64,
+}
+
+impl Inheritance {
+    /// Create new Inheritance.
+    pub fn new(&beneficiary: &PublicKey, inactivity_blocks: u64, last_activity: u64) -> Self {
+        Self {
+            beneficiary,
+            inactivity_blocks,
+            last_activity,
+        }
+    }
+
+    /// Returns a copy of this inheritance with the last activity at the given height.
+    pub fn set_last_activity(self, last_activity: u64) -> Self {
+        Self::new(&self.beneficiary, self.inactivity_blocks, last_activity)
+    }
+
+    /// Checks whether the balance can be claimed at the given height.
+    pub fn is_claimable(&self, height: u64) -> bool {
+        height >= self.last_activity.saturating_add(self.inactivity_blocks)
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Invoice, i.e. payment request.
+
+use exonum::crypto::{Hash, PublicKey};
+
+use super::proto;
+
+/// Payment requested by the merchant from the payer, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Invoice", serde_pb_convert)]
+pub struct Invoice {
+    /// `PublicKey` of merchant's wallet.
+    pub merchant: PublicKey,
+    /// `PublicKey` of payer's wallet.
+    pub payer: PublicKey,
+    /// Amount of requested currency.
+    pub amount: u64,
+    /// Note describing the payment.
+    pub memo: String,
+    /// Hash of the `PayInvoice` transaction, zero hash until the invoice is paid.
+    pub payment: Hash,
+}
+
+impl Invoice {
+    /// Create new Invoice.
+    pub fn new(
+        &merchant: &PublicKey,
+        &payer: &PublicKey,
+        amount: u64,
+        memo: &str,
+        &payment: &Hash,
+    ) -> Self {
+        Self {
+            merchant,
+            payer,
+            amount,
+            memo: memo.to_owned(),
+            payment,
+        }
+    }
+
+    /// Returns a copy of this invoice paid by the given transaction.
+    pub fn set_payment(self, payment: &Hash) -> Self {
+        Self::new(
+            &self.merchant,
+            &self.payer,
+            self.amount,
+            &self.memo,
+            payment,
+        )
+    }
+
+    /// Checks whether the invoice is paid.
+    pub fn is_paid(&self) -> bool {
+        self.payment != Hash::zero()
+    }
+}
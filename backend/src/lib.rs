@@ -21,45 +21,179 @@
     bare_trait_objects
 )]
 
+extern crate actix;
+extern crate actix_web;
+extern crate base64;
+extern crate bytes;
 extern crate exonum;
 #[macro_use]
 extern crate exonum_derive;
 extern crate protobuf;
 #[macro_use]
 extern crate failure;
+extern crate futures;
+extern crate hex;
+#[macro_use]
+extern crate juniper;
+#[macro_use]
+extern crate log;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+extern crate toml;
 
 pub use schema::Schema;
 
 pub mod api;
+pub mod asset;
+pub mod auth;
+pub mod config;
+pub mod cors;
+pub mod delegation;
+pub mod dispute;
+pub mod escrow;
+pub mod events;
+pub mod governance;
+pub mod graphql;
+pub mod health;
+pub mod idempotency;
+pub mod hash_lock;
+pub mod inheritance;
+pub mod invoice;
+pub mod loan;
+pub mod logging;
+pub mod multisig_wallet;
+pub mod openapi;
+pub mod order;
+pub mod pending_transfer;
+pub mod pool;
 pub mod proto;
+pub mod pruned_history;
+pub mod publisher;
+pub mod receipt;
+pub mod rate_limit;
+pub mod recovery;
+pub mod role;
+pub mod savings;
+pub mod scheduled_transfer;
 pub mod schema;
+pub mod session_key;
+pub mod snapshot;
+pub mod spending_limit;
+pub mod sse;
+pub mod stake;
+pub mod standing_order;
+pub mod stream;
+pub mod swap;
+pub mod time_lock;
 pub mod transactions;
+pub mod vesting;
 pub mod wallet;
+pub mod watch_list;
+pub mod webhooks;
+pub mod websocket;
+pub mod whitelist;
+
+use std::{path::Path, sync::Arc};
 
 use exonum::{
     api::ServiceApiBuilder,
-    blockchain::{self, Transaction, TransactionSet},
-    crypto::Hash,
-    helpers::fabric::{self, Context},
+    blockchain::{self, ServiceContext, Transaction, TransactionSet},
+    crypto::{Hash, PublicKey},
+    helpers::fabric::{self, keys, Argument, Command, CommandExtension, CommandName, Context},
     messages::RawTransaction,
-    storage::Snapshot,
+    storage::{Fork, Snapshot},
 };
+use serde_json::Value;
 
-use transactions::WalletTransactions;
+use auth::ApiKeys;
+use config::{Config, NodeConfig};
+use events::WalletEvents;
+use health::HealthMonitor;
+use idempotency::IdempotencyCache;
+use logging::Tracer;
+use publisher::EventPublisher;
+use rate_limit::RateLimiter;
+use snapshot::StateSnapshot;
+use transactions::{HaltableTransaction, WalletTransactions};
+use webhooks::Webhooks;
 
 /// Unique service ID.
 const CRYPTOCURRENCY_SERVICE_ID: u16 = 128;
 /// Name of the service.
 const SERVICE_NAME: &str = "multisign_cryptocurrency";
-/// Initial balance of the wallet.
-const INITIAL_BALANCE: u64 = 100;
+/// Code of the default asset, i.e. the coin the service was created with.
+const DEFAULT_ASSET: &str = "";
 
 /// Exonum `Service` implementation.
 #[derive(Default, Debug)]
-pub struct Service;
+pub struct Service {
+    config: Config,
+    genesis_wallets: Vec<(PublicKey, String, u64)>,
+    snapshot: Option<StateSnapshot>,
+    node_config: Arc<NodeConfig>,
+    events: Arc<WalletEvents>,
+    webhooks: Arc<Webhooks>,
+    publisher: EventPublisher,
+    rate_limiter: Arc<RateLimiter>,
+    api_keys: Arc<ApiKeys>,
+    idempotency: Arc<IdempotencyCache>,
+    tracer: Arc<Tracer>,
+    health: Arc<HealthMonitor>,
+}
+
+impl Service {
+    /// Creates a service with the given configuration.
+    pub fn new(config: Config) -> Self {
+        Self::with_genesis_wallets(config, Vec::new())
+    }
+
+    /// Creates a service with the given configuration and the wallets funded at genesis
+    /// given by their public keys, names and balances.
+    pub fn with_genesis_wallets(
+        config: Config,
+        genesis_wallets: Vec<(PublicKey, String, u64)>,
+    ) -> Self {
+        Service {
+            config,
+            genesis_wallets,
+            snapshot: None,
+            node_config: Arc::new(NodeConfig::default()),
+            events: Arc::new(WalletEvents::new()),
+            webhooks: Arc::new(Webhooks::new()),
+            publisher: EventPublisher::new(&NodeConfig::default()),
+            rate_limiter: Arc::new(RateLimiter::new(&NodeConfig::default())),
+            api_keys: Arc::new(ApiKeys::default()),
+            idempotency: Arc::new(IdempotencyCache::new()),
+            tracer: Arc::new(Tracer::new()),
+            health: Arc::new(HealthMonitor::new()),
+        }
+    }
+
+    /// Applies the settings local to the node: event publishing, rate limits and CORS.
+    pub fn with_node_config(mut self, node_config: NodeConfig) -> Self {
+        self.publisher = EventPublisher::new(&node_config);
+        self.rate_limiter = Arc::new(RateLimiter::new(&node_config));
+        self.node_config = Arc::new(node_config);
+        self
+    }
+
+    /// Requires one of the given API keys on the privileged endpoints of the service.
+    pub fn with_api_keys(mut self, api_keys: Vec<String>) -> Self {
+        self.api_keys = Arc::new(ApiKeys::new(api_keys));
+        self
+    }
+
+    /// Restores the state of the service from the snapshot at genesis, taking the
+    /// configuration from it.
+    pub fn with_snapshot(mut self, snapshot: StateSnapshot) -> Self {
+        self.config = snapshot.config.clone();
+        self.snapshot = Some(snapshot);
+        self
+    }
+}
 
 impl blockchain::Service for Service {
     fn service_id(&self) -> u16 {
@@ -76,11 +210,142 @@ impl blockchain::Service for Service {
     }
 
     fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<dyn Transaction>, failure::Error> {
-        WalletTransactions::tx_from_raw(raw).map(Into::into)
+        // Everything but resuming the service fails while it is halted.
+        let tx = WalletTransactions::tx_from_raw(raw)?;
+        let resumes_service = match tx {
+            WalletTransactions::ResumeService(_) => true,
+            _ => false,
+        };
+        let tx = HaltableTransaction::new(tx, resumes_service);
+        Ok(Box::new(tx))
+    }
+
+    fn initialize(&self, fork: &mut Fork) -> Value {
+        if let Some(ref snapshot) = self.snapshot {
+            snapshot.restore(fork);
+        }
+        let mut schema = Schema::new(fork);
+        schema.set_config(self.config.clone());
+        for (key, name, balance) in &self.genesis_wallets {
+            schema
+                .create_genesis_wallet(key, name, *balance)
+                .expect("Total supply of the genesis wallets overflows");
+        }
+        serde_json::to_value(&self.config).unwrap()
+    }
+
+    fn before_commit(&self, fork: &mut Fork) {
+        let mut schema = Schema::new(fork);
+        schema.end_transactions();
+        schema.apply_actual_config();
+        if schema.is_halted() {
+            return;
+        }
+        let height = schema.current_height();
+        // Operations indexed by height catch up with the blocks committed while halted.
+        let due_heights = schema.due_heights(height);
+        schema.last_processed_height_entry_mut().set(height.0);
+        for &due in &due_heights {
+            schema.unlock_matured_funds(due);
+            schema.complete_recoveries(due);
+            schema.expire_session_keys(due);
+        }
+        schema.execute_standing_orders(height);
+        schema.execute_streams(height);
+        for &due in &due_heights {
+            schema.execute_scheduled_transfers(due);
+        }
+        schema.distribute_staking_rewards();
+        schema.distribute_fees();
+        schema.prune_histories(height);
+        schema.update_reference_pools();
+        for &due in &due_heights {
+            schema.expire_param_changes(due);
+        }
+    }
+
+    fn after_commit(&self, context: &ServiceContext) {
+        self.health.record_commit();
+        self.events.notify(context.snapshot());
+        self.webhooks.notify(context.snapshot());
+        self.publisher.publish(context.snapshot());
+        self.tracer.log_block(context.snapshot());
     }
 
     fn wire_api(&self, builder: &mut ServiceApiBuilder) {
-        api::PublicApi::wire(builder);
+        api::PublicApi::wire(
+            builder,
+            Arc::clone(&self.node_config),
+            Arc::clone(&self.events),
+            Arc::clone(&self.rate_limiter),
+            Arc::clone(&self.api_keys),
+            Arc::clone(&self.idempotency),
+            Arc::clone(&self.tracer),
+            Arc::clone(&self.health),
+        );
+        api::PrivateApi::wire(
+            builder,
+            Arc::clone(&self.webhooks),
+            Arc::clone(&self.rate_limiter),
+        );
+    }
+}
+
+/// Creates the service with the API keys listed in the `api_keys` array of the service
+/// section of the node configuration, restoring the state from the snapshot file
+/// given by its `snapshot` path. The other settings of the section make up
+/// the `NodeConfig` of the service.
+///
+/// Fails if the section or the snapshot can't be read.
+fn service_from_context(context: &Context) -> Result<Service, failure::Error> {
+    let service_config = context
+        .get(keys::NODE_CONFIG)
+        .ok()
+        .and_then(|node_config| node_config.services_configs.get(SERVICE_NAME).cloned());
+    let node_config = match service_config {
+        Some(ref service_config) => service_config
+            .clone()
+            .try_into::<NodeConfig>()
+            .map_err(|e| format_err!("Invalid service configuration: {}", e))?,
+        None => NodeConfig::default(),
+    };
+    let api_keys = service_config
+        .as_ref()
+        .and_then(|service_config| {
+            let keys = service_config.get("api_keys")?.as_array()?;
+            let keys = keys.iter().filter_map(toml::Value::as_str);
+            Some(keys.map(str::to_owned).collect())
+        })
+        .unwrap_or_default();
+    let service = Service::default()
+        .with_node_config(node_config)
+        .with_api_keys(api_keys);
+    let snapshot_path = service_config
+        .as_ref()
+        .and_then(|service_config| service_config.get("snapshot")?.as_str());
+    match snapshot_path {
+        Some(path) => {
+            let snapshot = StateSnapshot::read(Path::new(path))
+                .map_err(|e| format_err!("Cannot read the state snapshot {}: {}", path, e))?;
+            Ok(service.with_snapshot(snapshot))
+        }
+        None => Ok(service),
+    }
+}
+
+/// Extension of the `run` command failing with a configuration error if the service
+/// can't be created from the node configuration, so that `make_service` doesn't fail.
+#[derive(Debug)]
+struct CheckServiceConfig;
+
+impl CommandExtension for CheckServiceConfig {
+    fn args(&self) -> Vec<Argument> {
+        Vec::new()
+    }
+
+    fn execute(&self, context: Context) -> Result<Context, failure::Error> {
+        service_from_context(&context)?;
+        Ok(context)
     }
 }
 
@@ -93,7 +358,20 @@ impl fabric::ServiceFactory for ServiceFactory {
         SERVICE_NAME
     }
 
-    fn make_service(&mut self, _: &Context) -> Box<dyn blockchain::Service> {
-        Box::new(Service)
+    fn command(&mut self, command: CommandName) -> Option<Box<dyn CommandExtension>> {
+        if command == fabric::Run.name() {
+            Some(Box::new(CheckServiceConfig))
+        } else {
+            None
+        }
+    }
+
+    /// Creates the service as described by `service_from_context`.
+    ///
+    /// The configuration is checked by the `run` command beforehand.
+    fn make_service(&mut self, context: &Context) -> Box<dyn blockchain::Service> {
+        let service = service_from_context(context)
+            .expect("Service configuration is checked by the run command");
+        Box::new(service)
     }
 }
@@ -0,0 +1,51 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loan of the default coin collateralized by an asset.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Loan of the default coin against an asset, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Loan", serde_pb_convert)]
+pub struct Loan {
+    /// `PublicKey` of borrower's wallet.
+    pub borrower: PublicKey,
+    /// Code of the collateral asset.
+    pub asset: String,
+    /// Amount of the collateral asset locked by the loan.
+    pub collateral: u64,
+    /// Amount of the borrowed currency.
+    pub amount: u64,
+}
+
+impl Loan {
+    /// Create new Loan.
+    pub fn new(&borrower: &PublicKey, asset: &str, collateral: u64, amount: u64) -> Self {
+        Self {
+            borrower,
+            asset: asset.to_owned(),
+            collateral,
+            amount,
+        }
+    }
+
+    /// Checks whether the collateral worth `collateral_value` of the currency covers
+    /// the given percentage of the borrowed amount.
+    pub fn is_covered(&self, collateral_value: u128, ratio: u64) -> bool {
+        collateral_value * 100 >= u128::from(self.amount) * u128::from(ratio)
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured logs of the API requests and of the executed transactions, tied together
+//! by correlation IDs.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::{
+    self,
+    http::header::{HeaderName, HeaderValue},
+    HttpResponse,
+};
+use exonum::{
+    api::backends::actix::{FutureResponse, HttpRequest, RequestHandler},
+    blockchain::{self, TransactionErrorType, TransactionSet},
+    crypto::{self, Hash, PublicKey},
+    helpers::Height,
+    storage::Snapshot,
+};
+use futures::Future;
+use hex;
+use serde_json;
+
+use api::TransactionStatus;
+use transactions::WalletTransactions;
+use CRYPTOCURRENCY_SERVICE_ID;
+
+/// Header carrying the correlation ID of a request and of its response.
+pub const CORRELATION_ID_HEADER: &str = "x-request-id";
+
+/// Target of the log records.
+const LOG_TARGET: &str = "cryptocurrency";
+
+/// Maximum length of a correlation ID passed by a client.
+const MAX_CORRELATION_ID_LENGTH: usize = 128;
+
+/// Maximum number of the submitted transactions waiting to be logged with their correlation IDs.
+const MAX_PENDING_CORRELATIONS: usize = 10_000;
+
+/// Correlation ID of an API request, stored in the extensions of the request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationId(pub String);
+
+/// Log record of an API request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestRecord {
+    /// Correlation ID of the request.
+    pub correlation_id: String,
+    /// HTTP method of the request.
+    pub method: String,
+    /// Path of the request.
+    pub path: String,
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Time the request was handled in, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Log record of an executed service transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionRecord {
+    /// Correlation ID of the request that submitted the transaction to this node,
+    /// or the hex-encoded transaction hash if it came from elsewhere.
+    pub correlation_id: String,
+    /// Hash of the transaction.
+    pub tx_hash: Hash,
+    /// Author of the transaction.
+    pub author: PublicKey,
+    /// Name of the transaction variant.
+    pub variant: String,
+    /// Outcome of the execution.
+    pub outcome: TransactionStatus,
+}
+
+/// Structured log record, serialized as a JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogRecord {
+    /// API request was handled.
+    ApiRequest(RequestRecord),
+    /// Service transaction was executed in a committed block.
+    TransactionExecuted(TransactionRecord),
+}
+
+impl LogRecord {
+    /// Writes the record to the log as a JSON line.
+    fn log(&self) {
+        if let Ok(line) = serde_json::to_string(self) {
+            info!(target: LOG_TARGET, "{}", line);
+        }
+    }
+}
+
+/// Assigns correlation IDs to the API requests and logs the executed transactions
+/// with the IDs of the requests that submitted them.
+///
+/// The IDs of the submitted transactions are local to the node and kept in memory
+/// until the transactions are committed.
+#[derive(Debug, Default)]
+pub struct Tracer {
+    requests: AtomicUsize,
+    correlations: Mutex<HashMap<Hash, String>>,
+}
+
+impl Tracer {
+    /// Create new Tracer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a correlation ID for a request that did not pass one.
+    pub fn new_correlation_id(&self) -> String {
+        let count = self.requests.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.subsec_nanos())
+            .unwrap_or_default();
+        let seed = crypto::hash(format!("{}-{}", nanos, count).as_bytes());
+        hex::encode(&seed.as_ref()[..8])
+    }
+
+    /// Remembers the correlation ID of the request that submitted the transaction.
+    pub fn register_transaction(&self, tx_hash: Hash, correlation_id: String) {
+        let mut correlations = self.correlations.lock().unwrap();
+        if correlations.len() < MAX_PENDING_CORRELATIONS {
+            correlations.insert(tx_hash, correlation_id);
+        }
+    }
+
+    /// Returns the log records of the service transactions of the latest committed block,
+    /// forgetting their correlation IDs.
+    pub fn block_records(&self, snapshot: &dyn Snapshot) -> Vec<TransactionRecord> {
+        let general_schema = blockchain::Schema::new(snapshot);
+        let height = general_schema.height();
+        let mut correlations = self.correlations.lock().unwrap();
+
+        general_schema
+            .block_transactions(height)
+            .iter()
+            .filter_map(|tx_hash| {
+                let signed = general_schema.transactions().get(&tx_hash)?;
+                if signed.payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+                    return None;
+                }
+                let transaction = WalletTransactions::tx_from_raw(signed.payload().clone()).ok()?;
+                let result = general_schema.transaction_results().get(&tx_hash)?;
+                let correlation_id = correlations
+                    .remove(&tx_hash)
+                    .unwrap_or_else(|| hex::encode(tx_hash.as_ref()));
+                Some(TransactionRecord {
+                    correlation_id,
+                    tx_hash,
+                    author: signed.author(),
+                    variant: variant_name(&transaction),
+                    outcome: outcome(height, &result.0),
+                })
+            })
+            .collect()
+    }
+
+    /// Logs the service transactions of the latest committed block.
+    pub fn log_block(&self, snapshot: &dyn Snapshot) {
+        for record in self.block_records(snapshot) {
+            LogRecord::TransactionExecuted(record).log();
+        }
+    }
+}
+
+/// Returns the name of the transaction variant.
+fn variant_name(transaction: &WalletTransactions) -> String {
+    let debug = format!("{:?}", transaction);
+    debug.split('(').next().unwrap_or_default().to_owned()
+}
+
+/// Converts the result of the transaction execution to its status.
+fn outcome(
+    height: Height,
+    result: &Result<(), blockchain::TransactionError>,
+) -> TransactionStatus {
+    match result {
+        Ok(()) => TransactionStatus::Success { height },
+        Err(error) => {
+            let description = error.description().map(str::to_owned);
+            match error.error_type() {
+                TransactionErrorType::Code(code) => TransactionStatus::Error {
+                    height,
+                    code,
+                    description,
+                },
+                TransactionErrorType::Panic => TransactionStatus::Panic {
+                    height,
+                    description,
+                },
+            }
+        }
+    }
+}
+
+/// Returns the correlation ID passed in the request, if it is usable.
+fn passed_correlation_id(request: &HttpRequest) -> Option<String> {
+    let id = request
+        .headers()
+        .get(CORRELATION_ID_HEADER)?
+        .to_str()
+        .ok()?;
+    if id.is_empty() || id.len() > MAX_CORRELATION_ID_LENGTH {
+        return None;
+    }
+    Some(id.to_owned())
+}
+
+/// Wraps the handler so that the request gets a correlation ID, passed back in
+/// the `X-Request-Id` header of the response, and is logged once handled.
+///
+/// Clients can pass their own ID in the `X-Request-Id` header of the request.
+pub fn with_tracing(tracer: &Arc<Tracer>, handler: RequestHandler) -> RequestHandler {
+    let tracer = Arc::clone(tracer);
+    let inner = handler.inner;
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let correlation_id =
+            passed_correlation_id(&request).unwrap_or_else(|| tracer.new_correlation_id());
+        request
+            .extensions_mut()
+            .insert(CorrelationId(correlation_id.clone()));
+        let method = request.method().to_string();
+        let path = request.path().to_owned();
+        let started = Instant::now();
+
+        Box::new(inner(request).then(move |result| -> actix_web::Result<HttpResponse> {
+            let mut response =
+                result.unwrap_or_else(|error| error.as_response_error().error_response());
+            if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+                let name = HeaderName::from_static(CORRELATION_ID_HEADER);
+                response.headers_mut().insert(name, value);
+            }
+            let elapsed = started.elapsed();
+            LogRecord::ApiRequest(RequestRecord {
+                correlation_id,
+                method,
+                path,
+                status: response.status().as_u16(),
+                duration_ms: elapsed.as_secs() * 1000 + u64::from(elapsed.subsec_millis()),
+            })
+            .log();
+            Ok(response)
+        }))
+    };
+    RequestHandler {
+        name: handler.name,
+        method: handler.method,
+        inner: Arc::new(index),
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multisignature wallet.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Multisignature wallet information stored in the database.
+///
+/// Balance of the multisignature wallet is kept in the ordinary `Wallet`
+/// with the same public key.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::MultisigWallet", serde_pb_convert)]
+pub struct MultisigWallet {
+    /// `PublicKey` of the wallet.
+    pub pub_key: PublicKey,
+    /// Owners of the wallet allowed to approve transfers.
+    pub owners: Vec<PublicKey>,
+    /// Number of approvals required to execute a transfer.
+    pub quorum: u32,
+}
+
+impl MultisigWallet {
+    /// Create new MultisigWallet.
+    pub fn new(&pub_key: &PublicKey, owners: &[PublicKey], quorum: u32) -> Self {
+        Self {
+            pub_key,
+            owners: owners.to_vec(),
+            quorum,
+        }
+    }
+}
+
+/// Change of owners and quorum of the multisignature wallet waiting for approvals,
+/// stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ApproversChange", serde_pb_convert)]
+pub struct ApproversChange {
+    /// `PublicKey` of the change initiator.
+    pub initiator: PublicKey,
+    /// `PublicKey` of the multisignature wallet.
+    pub multisig_wallet: PublicKey,
+    /// New owners of the wallet.
+    pub owners: Vec<PublicKey>,
+    /// New number of approvals required to execute a transfer.
+    pub quorum: u32,
+}
+
+impl ApproversChange {
+    /// Create new ApproversChange.
+    pub fn new(
+        &initiator: &PublicKey,
+        &multisig_wallet: &PublicKey,
+        owners: &[PublicKey],
+        quorum: u32,
+    ) -> Self {
+        Self {
+            initiator,
+            multisig_wallet,
+            owners: owners.to_vec(),
+            quorum,
+        }
+    }
+}
@@ -0,0 +1,906 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenAPI specification of the service endpoints.
+//!
+//! Endpoints and types are described by the tables below. Field types are written in a short
+//! notation: `?T` is an optional field, `[T]` is an array, `{T}` is an object with string keys
+//! and `T` values; other names refer to primitive types or to the schemas of the specification.
+
+use serde_json::{Map, Value};
+
+use api::V2_ENDPOINTS;
+use auth::API_KEY_HEADER;
+use idempotency::IDEMPOTENCY_KEY_HEADER;
+use SERVICE_NAME;
+
+/// Codes, names and descriptions of the transaction errors, generated by the build script.
+const ERROR_CODES: &[(u8, &str, &str)] = include!(concat!(env!("OUT_DIR"), "/error_codes.rs"));
+
+/// Description of an endpoint.
+struct Endpoint {
+    /// HTTP method, `get` or `post`.
+    method: &'static str,
+    /// Path relative to the service API root.
+    path: &'static str,
+    /// Short summary of the endpoint.
+    summary: &'static str,
+    /// Type of the query parameters of a `get` or of the body of a `post` endpoint.
+    input: Option<&'static str>,
+    /// Type of the response.
+    output: &'static str,
+}
+
+/// Endpoints of the public API.
+const PUBLIC_ENDPOINTS: &[Endpoint] = &[
+    Endpoint {
+        method: "get",
+        path: "v1/wallets",
+        summary: "List the wallets page by page",
+        input: Some("WalletsQuery"),
+        output: "WalletsPage",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/info",
+        summary: "Get a wallet with its proofs",
+        input: Some("WalletQuery"),
+        output: "WalletInfo",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/history",
+        summary: "Get a page of the wallet history",
+        input: Some("WalletHistoryQuery"),
+        output: "WalletHistoryPage",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/history/export",
+        summary: "Export the decoded wallet history",
+        input: Some("HistoryExportQuery"),
+        output: "HistoryExport",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/top",
+        summary: "List the richest wallets",
+        input: Some("TopWalletsQuery"),
+        output: "[Wallet]",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/search",
+        summary: "Search the wallets by name prefix",
+        input: Some("WalletSearchQuery"),
+        output: "[Wallet]",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/nfts",
+        summary: "List the tokens of a wallet",
+        input: Some("WalletQuery"),
+        output: "[Hash]",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/watched",
+        summary: "Get the wallets watched by a key",
+        input: Some("WalletQuery"),
+        output: "WatchedWalletsInfo",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/subscribe",
+        summary: "Wait for the changes of a wallet",
+        input: Some("WalletSubscribeQuery"),
+        output: "[WalletEvent]",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/wallets/subscribe/ws",
+        summary: "Stream the changes of a wallet over a WebSocket connection",
+        input: Some("WalletSocketQuery"),
+        output: "WalletEvent",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/invoices",
+        summary: "Get an invoice",
+        input: Some("InvoiceQuery"),
+        output: "Invoice",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/supply",
+        summary: "Get the total supply with its proof",
+        input: None,
+        output: "SupplyInfo",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/blacklist",
+        summary: "Get the blacklist with its proof",
+        input: None,
+        output: "BlacklistInfo",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/stats",
+        summary: "Get the service statistics",
+        input: None,
+        output: "ServiceStats",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/transactions/status",
+        summary: "Get the status of a transaction",
+        input: Some("TransactionStatusQuery"),
+        output: "TransactionReceipt",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/events",
+        summary: "Query the events of the transactions by type, wallet and height",
+        input: Some("EventsQuery"),
+        output: "EventsPage",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/transactions/feed",
+        summary: "Wait for the committed service transactions",
+        input: Some("TransactionFeedQuery"),
+        output: "TransactionFeed",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/transactions/feed/stream",
+        summary: "Stream the committed service transactions as server-sent events",
+        input: Some("FeedStreamQuery"),
+        output: "TransactionFeed",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/openapi",
+        summary: "Get this specification",
+        input: None,
+        output: "Object",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/faucet",
+        summary: "Request currency from the faucet",
+        input: Some("FaucetQuery"),
+        output: "FaucetResponse",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/transactions",
+        summary: "Submit a signed transaction",
+        input: Some("SubmitTransactionQuery"),
+        output: "SubmitTransactionResponse",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/transactions/validate",
+        summary: "Validate a signed transaction without broadcasting it",
+        input: Some("ValidateTransactionQuery"),
+        output: "ValidateTransactionResponse",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/graphql",
+        summary: "Execute a GraphQL query",
+        input: Some("GraphQLRequest"),
+        output: "Object",
+    },
+    Endpoint {
+        method: "get",
+        path: "healthz",
+        summary: "Check that the node is alive",
+        input: None,
+        output: "HealthReport",
+    },
+    Endpoint {
+        method: "get",
+        path: "readyz",
+        summary: "Check that the node is ready to serve requests",
+        input: None,
+        output: "HealthReport",
+    },
+];
+
+/// Endpoints of the private API.
+const PRIVATE_ENDPOINTS: &[Endpoint] = &[
+    Endpoint {
+        method: "get",
+        path: "v1/rate_limits",
+        summary: "Get the counters of the rate limited submissions",
+        input: None,
+        output: "RateLimitStats",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/snapshot",
+        summary: "Export the state of the service to a portable snapshot",
+        input: None,
+        output: "StateSnapshot",
+    },
+    Endpoint {
+        method: "get",
+        path: "v1/webhooks",
+        summary: "List the webhooks",
+        input: None,
+        output: "[Webhook]",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/webhooks/register",
+        summary: "Register a webhook",
+        input: Some("Webhook"),
+        output: "[Webhook]",
+    },
+    Endpoint {
+        method: "post",
+        path: "v1/webhooks/unregister",
+        summary: "Unregister a webhook",
+        input: Some("Webhook"),
+        output: "[Webhook]",
+    },
+];
+
+/// Endpoints requiring an API key if the node configures any.
+const PRIVILEGED_ENDPOINTS: &[&str] = &["v1/faucet", "v1/blacklist", "v1/stats"];
+
+/// Submission endpoints, rate limited by the service configuration and accepting
+/// the `Idempotency-Key` header.
+const SUBMISSION_ENDPOINTS: &[&str] = &["v1/faucet", "v1/transactions"];
+
+/// Probes of the node health, answering with `503 Service Unavailable` when failing.
+const PROBE_ENDPOINTS: &[&str] = &["healthz", "readyz"];
+
+/// Endpoints accepting the `FormatQuery` options.
+const FORMATTED_ENDPOINTS: &[&str] = &[
+    "v1/wallets",
+    "v1/wallets/info",
+    "v1/wallets/history",
+    "v1/wallets/history/export",
+    "v1/wallets/top",
+    "v1/wallets/search",
+    "v1/wallets/watched",
+    "v1/wallets/subscribe",
+    "v1/invoices",
+    "v1/transactions/feed",
+    "v1/transactions/status",
+    "v1/events",
+];
+
+/// Object schemas given by their names and fields.
+const OBJECTS: &[(&str, &[(&str, &str)])] = &[
+    ("FormatQuery", &[("amounts_as_strings", "?bool")]),
+    ("WalletQuery", &[("pub_key", "PublicKey")]),
+    ("WalletsQuery", &[("offset", "?u64"), ("limit", "?u64")]),
+    (
+        "WalletSubscribeQuery",
+        &[
+            ("pub_key", "PublicKey"),
+            ("since", "?u64"),
+            ("timeout", "?u64"),
+        ],
+    ),
+    (
+        "WalletSocketQuery",
+        &[("pub_key", "PublicKey"), ("since", "?u64")],
+    ),
+    ("FeedStreamQuery", &[("since", "?u64")]),
+    (
+        "TransactionFeedQuery",
+        &[("since", "?u64"), ("timeout", "?u64")],
+    ),
+    (
+        "WalletSearchQuery",
+        &[("name_prefix", "String"), ("limit", "?u64")],
+    ),
+    ("TopWalletsQuery", &[("limit", "?u64")]),
+    (
+        "WalletHistoryQuery",
+        &[
+            ("pub_key", "PublicKey"),
+            ("from", "?u64"),
+            ("cursor", "?String"),
+            ("limit", "?u64"),
+            ("newest_first", "?bool"),
+            ("type", "?HistoryTxType"),
+            ("counterparty", "?PublicKey"),
+            ("from_height", "?u64"),
+            ("to_height", "?u64"),
+        ],
+    ),
+    (
+        "HistoryExportQuery",
+        &[("pub_key", "PublicKey"), ("format", "?ExportFormat")],
+    ),
+    ("InvoiceQuery", &[("invoice", "Hash")]),
+    ("TransactionStatusQuery", &[("hash", "Hash")]),
+    (
+        "EventsQuery",
+        &[
+            ("type", "?ExecutionEventType"),
+            ("wallet", "?PublicKey"),
+            ("from_height", "?u64"),
+            ("to_height", "?u64"),
+            ("from", "?u64"),
+            ("limit", "?u64"),
+        ],
+    ),
+    (
+        "SubmitTransactionQuery",
+        &[
+            ("tx_body", "String"),
+            ("encoding", "?TransactionEncoding"),
+            ("wait", "?bool"),
+            ("timeout", "?u64"),
+        ],
+    ),
+    (
+        "SubmitTransactionResponse",
+        &[("tx_hash", "Hash"), ("status", "?TransactionStatus")],
+    ),
+    (
+        "ValidateTransactionQuery",
+        &[("tx_body", "String"), ("encoding", "?TransactionEncoding")],
+    ),
+    (
+        "ValidateTransactionResponse",
+        &[("tx_hash", "Hash"), ("error", "?String")],
+    ),
+    (
+        "FaucetQuery",
+        &[("pub_key", "PublicKey"), ("amount", "u64")],
+    ),
+    ("FaucetResponse", &[("tx_hash", "Hash")]),
+    (
+        "GraphQLRequest",
+        &[
+            ("query", "String"),
+            ("operationName", "?String"),
+            ("variables", "?Object"),
+        ],
+    ),
+    (
+        "Wallet",
+        &[
+            ("pub_key", "PublicKey"),
+            ("name", "String"),
+            ("balance", "u64"),
+            ("pending_balance", "u64"),
+            ("history_len", "u64"),
+            ("history_hash", "Hash"),
+            ("assets_hash", "Hash"),
+            ("frozen", "bool"),
+            ("closed", "bool"),
+            ("kyc_verified", "bool"),
+        ],
+    ),
+    (
+        "PendingTransfer",
+        &[
+            ("initiator", "PublicKey"),
+            ("from", "PublicKey"),
+            ("to", "PublicKey"),
+            ("approvers", "[PublicKey]"),
+            ("amount", "u64"),
+            ("quorum", "u32"),
+        ],
+    ),
+    (
+        "Invoice",
+        &[
+            ("merchant", "PublicKey"),
+            ("payer", "PublicKey"),
+            ("amount", "u64"),
+            ("memo", "String"),
+            ("payment", "Hash"),
+        ],
+    ),
+    (
+        "WalletProof",
+        &[("to_table", "MapProof"), ("to_wallet", "MapProof")],
+    ),
+    (
+        "WalletHistory",
+        &[
+            ("proof", "ListProof"),
+            ("transactions", "[TransactionMessage]"),
+            ("encrypted_memos", "{[u8]}"),
+            ("refunds", "{Hash}"),
+        ],
+    ),
+    (
+        "WalletInfo",
+        &[
+            ("block_proof", "BlockProof"),
+            ("wallet_proof", "WalletProof"),
+            ("wallet_history", "?WalletHistory"),
+            ("linked_multisig_wallets", "[PublicKey]"),
+            ("pending_txs", "[Hash]"),
+            ("asset_balances", "{u64}"),
+            ("metadata", "{String}"),
+            ("loyalty_points", "u64"),
+            ("fee_payouts", "{u64}"),
+            ("staking_payouts", "{u64}"),
+        ],
+    ),
+    (
+        "WatchedWallet",
+        &[
+            ("pub_key", "PublicKey"),
+            ("wallet", "?Wallet"),
+            ("history", "[TransactionMessage]"),
+            ("pending_transfers", "[PendingTransfer]"),
+            ("asset_balances", "{u64}"),
+        ],
+    ),
+    (
+        "WatchedWalletsInfo",
+        &[("wallets", "[WatchedWallet]"), ("total_balance", "u128")],
+    ),
+    (
+        "WalletHistoryPage",
+        &[
+            ("total", "u64"),
+            ("proof", "?ListProof"),
+            ("transactions", "[TransactionMessage]"),
+            ("next_cursor", "?String"),
+        ],
+    ),
+    (
+        "HistoryRecord",
+        &[
+            ("height", "u64"),
+            ("tx_hash", "Hash"),
+            ("type", "String"),
+            ("counterparty", "?PublicKey"),
+            ("amount", "?u64"),
+            ("balance_after", "?u64"),
+        ],
+    ),
+    ("WalletsPage", &[("total", "u64"), ("wallets", "[Wallet]")]),
+    (
+        "TransactionStatus",
+        &[
+            ("type", "TransactionStatusType"),
+            ("height", "?u64"),
+            ("code", "?TransactionErrorCode"),
+            ("description", "?String"),
+        ],
+    ),
+    (
+        "TransactionReceipt",
+        &[
+            ("type", "TransactionStatusType"),
+            ("height", "?u64"),
+            ("code", "?TransactionErrorCode"),
+            ("description", "?String"),
+            ("events", "[ExecutionEvent]"),
+        ],
+    ),
+    (
+        "ExecutionEvent",
+        &[
+            ("type", "ExecutionEventType"),
+            ("wallet", "?PublicKey"),
+            ("asset", "?String"),
+            ("previous_balance", "?u64"),
+            ("balance", "?u64"),
+            ("transfer", "?Hash"),
+            ("amount", "?u64"),
+            ("approver", "?PublicKey"),
+        ],
+    ),
+    (
+        "IndexedEvent",
+        &[
+            ("position", "u64"),
+            ("height", "u64"),
+            ("tx_hash", "Hash"),
+            ("type", "ExecutionEventType"),
+            ("wallet", "?PublicKey"),
+            ("asset", "?String"),
+            ("previous_balance", "?u64"),
+            ("balance", "?u64"),
+            ("transfer", "?Hash"),
+            ("amount", "?u64"),
+            ("approver", "?PublicKey"),
+        ],
+    ),
+    (
+        "EventsPage",
+        &[
+            ("total", "u64"),
+            ("events", "[IndexedEvent]"),
+            ("next", "?u64"),
+        ],
+    ),
+    (
+        "FeedTransaction",
+        &[
+            ("tx_hash", "Hash"),
+            ("transaction", "TransactionMessage"),
+            ("status", "TransactionStatus"),
+        ],
+    ),
+    (
+        "TransactionFeed",
+        &[("height", "u64"), ("transactions", "[FeedTransaction]")],
+    ),
+    ("WalletEvent", &[("height", "u64"), ("wallet", "Wallet")]),
+    (
+        "SupplyInfo",
+        &[
+            ("block_proof", "BlockProof"),
+            ("to_table", "MapProof"),
+            ("total_supply", "u64"),
+        ],
+    ),
+    (
+        "ServiceStats",
+        &[
+            ("wallets", "u64"),
+            ("total_supply", "u64"),
+            ("transfers", "u64"),
+            ("transfer_volume", "u64"),
+            ("transactions_per_block", "f64"),
+        ],
+    ),
+    (
+        "BlacklistInfo",
+        &[
+            ("block_proof", "BlockProof"),
+            ("to_table", "MapProof"),
+            ("to_blacklist", "MapProof"),
+            ("blacklist", "[PublicKey]"),
+        ],
+    ),
+    ("Webhook", &[("url", "String"), ("wallet", "?PublicKey")]),
+    (
+        "HealthReport",
+        &[
+            ("schema_accessible", "bool"),
+            ("height", "?u64"),
+            ("lag_secs", "u64"),
+            ("ready", "bool"),
+        ],
+    ),
+    (
+        "RateLimitStats",
+        &[
+            ("accepted", "u64"),
+            ("rejected_by_ip", "u64"),
+            ("rejected_by_key", "u64"),
+        ],
+    ),
+    (
+        "Asset",
+        &[
+            ("code", "String"),
+            ("decimals", "u32"),
+            ("issuer", "PublicKey"),
+            ("max_supply", "u64"),
+            ("issued", "u64"),
+        ],
+    ),
+    (
+        "MultisigWallet",
+        &[
+            ("pub_key", "PublicKey"),
+            ("owners", "[PublicKey]"),
+            ("quorum", "u32"),
+        ],
+    ),
+    (
+        "ApproversChange",
+        &[
+            ("initiator", "PublicKey"),
+            ("multisig_wallet", "PublicKey"),
+            ("owners", "[PublicKey]"),
+            ("quorum", "u32"),
+        ],
+    ),
+    (
+        "WalletSnapshot",
+        &[
+            ("wallet", "Wallet"),
+            ("pending_txs", "[Hash]"),
+            ("asset_balances", "{u64}"),
+            ("linked_multisig_wallets", "[PublicKey]"),
+        ],
+    ),
+    (
+        "PendingTransferSnapshot",
+        &[
+            ("tx_hash", "Hash"),
+            ("transfer", "PendingTransfer"),
+            ("approvals", "[PublicKey]"),
+        ],
+    ),
+    (
+        "ApproversChangeSnapshot",
+        &[
+            ("tx_hash", "Hash"),
+            ("change", "ApproversChange"),
+            ("approvals", "[PublicKey]"),
+        ],
+    ),
+    (
+        "Counters",
+        &[
+            ("total_supply", "u64"),
+            ("issued_supply", "u64"),
+            ("wallet_count", "u64"),
+            ("transfer_count", "u64"),
+            ("transfer_volume", "u64"),
+            ("fee_pool", "u64"),
+        ],
+    ),
+    (
+        "StateSnapshot",
+        &[
+            ("version", "u32"),
+            ("height", "u64"),
+            ("config", "Object"),
+            ("counters", "Counters"),
+            ("assets", "[Asset]"),
+            ("wallets", "[WalletSnapshot]"),
+            ("wallet_names", "{PublicKey}"),
+            ("multisig_wallets", "[MultisigWallet]"),
+            ("pending_transfers", "[PendingTransferSnapshot]"),
+            ("approvers_changes", "[ApproversChangeSnapshot]"),
+        ],
+    ),
+];
+
+/// String enumeration schemas given by their names and values.
+const ENUMS: &[(&str, &[&str])] = &[
+    ("HistoryTxType", &["transfer", "issue", "multisig"]),
+    ("ExportFormat", &["json", "csv"]),
+    ("TransactionEncoding", &["hex", "base64"]),
+    (
+        "TransactionStatusType",
+        &["in_pool", "success", "error", "panic"],
+    ),
+    (
+        "ExecutionEventType",
+        &["balance_changed", "pending_transfer_created", "transfer_approved"],
+    ),
+];
+
+/// Schemas of the Exonum types given by their names and descriptions.
+const EXONUM_TYPES: &[(&str, &str)] = &[
+    ("PublicKey", "Hex-encoded Ed25519 public key."),
+    ("Hash", "Hex-encoded SHA-256 hash."),
+    (
+        "BlockProof",
+        "Exonum block with the precommits of the validators.",
+    ),
+    ("MapProof", "Exonum proof of entries of a Merkelized map."),
+    ("ListProof", "Exonum proof of a range of a Merkelized list."),
+    ("TransactionMessage", "Exonum signed transaction message."),
+];
+
+/// Returns the OpenAPI 3.0 specification of the service endpoints.
+///
+/// Private endpoints are served on the private API address of the node. Endpoints
+/// of the first version succeeded by `V2_ENDPOINTS` are marked as deprecated.
+pub fn specification() -> Value {
+    let mut paths = Map::new();
+    for (endpoints, tag) in &[(PUBLIC_ENDPOINTS, "public"), (PRIVATE_ENDPOINTS, "private")] {
+        for endpoint in endpoints.iter() {
+            let mut operation = operation(endpoint, tag);
+            let v2_path = endpoint.path.trim_start_matches("v1/");
+            if V2_ENDPOINTS.contains(&v2_path) {
+                let mut v2_operation = operation.clone();
+                v2_operation["description"] = json!(
+                    "Amounts are serialized as decimal strings unless `amounts_as_strings` \
+                     is `false`."
+                );
+                let path = format!("/api/services/{}/v2/{}", SERVICE_NAME, v2_path);
+                let item = paths.entry(path).or_insert_with(|| json!({}));
+                item[endpoint.method] = v2_operation;
+                operation["deprecated"] = json!(true);
+            }
+            let path = format!("/api/services/{}/{}", SERVICE_NAME, endpoint.path);
+            let item = paths.entry(path).or_insert_with(|| json!({}));
+            item[endpoint.method] = operation;
+        }
+    }
+
+    let mut schemas = Map::new();
+    for (name, fields) in OBJECTS {
+        schemas.insert(name.to_string(), object_schema(fields));
+    }
+    for (name, values) in ENUMS {
+        schemas.insert(
+            name.to_string(),
+            json!({ "type": "string", "enum": values }),
+        );
+    }
+    for (name, description) in EXONUM_TYPES {
+        let schema_type = match *name {
+            "PublicKey" | "Hash" => "string",
+            _ => "object",
+        };
+        schemas.insert(
+            name.to_string(),
+            json!({ "type": schema_type, "description": description }),
+        );
+    }
+    schemas.insert(
+        "HistoryExport".to_owned(),
+        json!({
+            "description": "History records, or a CSV table of them for the `csv` format.",
+            "oneOf": [type_schema("[HistoryRecord]"), type_schema("String")],
+        }),
+    );
+    schemas.insert("TransactionErrorCode".to_owned(), error_codes_schema());
+
+    json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": SERVICE_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "apiKey": { "type": "apiKey", "in": "header", "name": API_KEY_HEADER },
+            },
+        },
+    })
+}
+
+/// Describes the operation of the endpoint.
+fn operation(endpoint: &Endpoint, tag: &str) -> Value {
+    let mut operation = json!({
+        "summary": endpoint.summary,
+        "tags": [tag],
+        "responses": {
+            "200": {
+                "description": "Successful response.",
+                "content": { "application/json": { "schema": type_schema(endpoint.output) } },
+            },
+            "400": { "description": "Invalid request." },
+            "404": { "description": "Requested entity not found." },
+            "500": { "description": "Internal server error." },
+        },
+    });
+    match (endpoint.method, endpoint.input) {
+        ("get", Some(query)) => {
+            let mut parameters = query_parameters(query);
+            if FORMATTED_ENDPOINTS.contains(&endpoint.path) {
+                parameters.extend(query_parameters("FormatQuery"));
+            }
+            operation["parameters"] = parameters.into();
+        }
+        (_, Some(body)) => {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": type_schema(body) } },
+            });
+        }
+        (_, None) => {}
+    }
+    if SUBMISSION_ENDPOINTS.contains(&endpoint.path) {
+        operation["parameters"] = json!([{
+            "name": IDEMPOTENCY_KEY_HEADER,
+            "in": "header",
+            "required": false,
+            "description": "Key the retries of the submission are recognized by.",
+            "schema": { "type": "string" },
+        }]);
+        operation["responses"]["422"] =
+            json!({ "description": "Idempotency key was used with another request." });
+        operation["responses"]["429"] =
+            json!({ "description": "Rate limit exceeded, retry after `Retry-After` seconds." });
+    }
+    if PROBE_ENDPOINTS.contains(&endpoint.path) {
+        operation["responses"]["503"] = json!({
+            "description": "Node is unhealthy.",
+            "content": { "application/json": { "schema": type_schema(endpoint.output) } },
+        });
+    }
+    if PRIVILEGED_ENDPOINTS.contains(&endpoint.path) {
+        operation["security"] = json!([{ "apiKey": [] }]);
+        operation["responses"]["401"] = json!({ "description": "Missing or invalid API key." });
+    }
+    operation
+}
+
+/// Describes the fields of the object schema as query parameters.
+fn query_parameters(query: &str) -> Vec<Value> {
+    let fields = OBJECTS
+        .iter()
+        .find(|(name, _)| *name == query)
+        .map_or(&[][..], |&(_, fields)| fields);
+    fields
+        .iter()
+        .map(|(name, field_type)| {
+            json!({
+                "name": name,
+                "in": "query",
+                "required": !field_type.starts_with('?'),
+                "schema": type_schema(field_type.trim_start_matches('?')),
+            })
+        })
+        .collect()
+}
+
+/// Describes the object with the given fields.
+fn object_schema(fields: &[(&str, &str)]) -> Value {
+    let properties = fields
+        .iter()
+        .map(|(name, field_type)| {
+            let schema = type_schema(field_type.trim_start_matches('?'));
+            (name.to_string(), schema)
+        })
+        .collect::<Map<_, _>>();
+    let required = fields
+        .iter()
+        .filter(|(_, field_type)| !field_type.starts_with('?'))
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// Describes the type given in the short notation of the tables above.
+fn type_schema(field_type: &str) -> Value {
+    if field_type.starts_with('[') && field_type.ends_with(']') {
+        return json!({
+            "type": "array",
+            "items": type_schema(&field_type[1..field_type.len() - 1]),
+        });
+    }
+    if field_type.starts_with('{') && field_type.ends_with('}') {
+        return json!({
+            "type": "object",
+            "additionalProperties": type_schema(&field_type[1..field_type.len() - 1]),
+        });
+    }
+    match field_type {
+        "u8" | "u32" => json!({ "type": "integer", "format": "int64", "minimum": 0 }),
+        "u64" => json!({ "type": "integer", "format": "uint64", "minimum": 0 }),
+        "u128" => json!({ "type": "integer", "format": "uint128", "minimum": 0 }),
+        "f64" => json!({ "type": "number", "format": "double" }),
+        "bool" => json!({ "type": "boolean" }),
+        "String" => json!({ "type": "string" }),
+        "Object" => json!({ "type": "object" }),
+        name => json!({ "$ref": format!("#/components/schemas/{}", name) }),
+    }
+}
+
+/// Describes the codes of the transaction errors.
+fn error_codes_schema() -> Value {
+    let description = ERROR_CODES
+        .iter()
+        .map(|(code, name, description)| format!("* {} `{}`: {}", code, name, description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    json!({
+        "type": "integer",
+        "description": format!("Code of the transaction error:\n\n{}", description),
+        "enum": ERROR_CODES.iter().map(|error| error.0).collect::<Vec<_>>(),
+        "x-enum-varnames": ERROR_CODES.iter().map(|error| error.1).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,120 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limit order of the on-chain order book.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Order to trade the base asset for the quote one, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Order", serde_pb_convert)]
+pub struct Order {
+    /// `PublicKey` of owner's wallet.
+    pub owner: PublicKey,
+    /// Code of the traded asset, empty for the default coin.
+    pub base_asset: String,
+    /// Code of the asset the price is set in, empty for the default coin.
+    pub quote_asset: String,
+    /// Whether the owner buys the base asset or sells it.
+    pub buy: bool,
+    /// Amount of the quote asset per unit of the base asset.
+    pub price: u64,
+    /// Remaining amount of the base asset.
+    pub amount: u64,
+    /// Sequence number of the order giving time priority among orders with the same price.
+    pub seq: u64,
+}
+
+impl Order {
+    /// Create new Order.
+    pub fn new(
+        &owner: &PublicKey,
+        base_asset: &str,
+        quote_asset: &str,
+        buy: bool,
+        price: u64,
+        amount: u64,
+        seq: u64,
+    ) -> Self {
+        Self {
+            owner,
+            base_asset: base_asset.to_owned(),
+            quote_asset: quote_asset.to_owned(),
+            buy,
+            price,
+            amount,
+            seq,
+        }
+    }
+
+    /// Returns a copy of this order with updated remaining amount.
+    pub fn set_amount(self, amount: u64) -> Self {
+        Self::new(
+            &self.owner,
+            &self.base_asset,
+            &self.quote_asset,
+            self.buy,
+            self.price,
+            amount,
+            self.seq,
+        )
+    }
+
+    /// Returns name of the order book side this order belongs to.
+    pub fn book(&self) -> String {
+        Self::book_name(&self.base_asset, &self.quote_asset, self.buy)
+    }
+
+    /// Returns name of the order book side of the given market.
+    ///
+    /// Asset codes are prefixed with their lengths, so that different markets never
+    /// share a name whatever the codes contain.
+    pub fn book_name(base_asset: &str, quote_asset: &str, buy: bool) -> String {
+        let side = if buy { "buy" } else { "sell" };
+        format!(
+            "{}:{}/{}:{}/{}",
+            base_asset.len(),
+            base_asset,
+            quote_asset.len(),
+            quote_asset,
+            side
+        )
+    }
+
+    /// Returns key of the order in its order book side.
+    ///
+    /// Keys are sorted by the price priority first, i.e. the highest buy price
+    /// or the lowest sell price, and by the time priority then.
+    pub fn book_key(&self) -> Vec<u8> {
+        let priority = if self.buy {
+            u64::max_value() - self.price
+        } else {
+            self.price
+        };
+        let mut key = priority.to_be_bytes().to_vec();
+        key.extend_from_slice(&self.seq.to_be_bytes());
+        key
+    }
+
+    /// Checks whether this order can trade with the given resting one.
+    pub fn crosses(&self, resting: &Order) -> bool {
+        if self.buy {
+            self.price >= resting.price
+        } else {
+            self.price <= resting.price
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pending multisignature transfer.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Multisignature transfer waiting for approvals, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::PendingTransfer", serde_pb_convert)]
+pub struct PendingTransfer {
+    /// `PublicKey` of the transfer initiator.
+    pub initiator: PublicKey,
+    /// `PublicKey` of multisign sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Approvers of this transfer.
+    pub approvers: Vec<PublicKey>,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Number of approvals required to execute this transfer.
+    pub quorum: u32,
+}
+
+impl PendingTransfer {
+    /// Create new PendingTransfer.
+    pub fn new(
+        &initiator: &PublicKey,
+        &from: &PublicKey,
+        &to: &PublicKey,
+        approvers: &[PublicKey],
+        amount: u64,
+        quorum: u32,
+    ) -> Self {
+        Self {
+            initiator,
+            from,
+            to,
+            approvers: approvers.to_vec(),
+            amount,
+            quorum,
+        }
+    }
+}
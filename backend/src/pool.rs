@@ -0,0 +1,100 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Constant-product liquidity pool of the default coin and an asset.
+
+use super::proto;
+
+/// Reserves of the pool between the default coin and an asset, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Pool", serde_pb_convert)]
+pub struct Pool {
+    /// Code of the pooled asset.
+    pub asset: String,
+    /// Amount of the default coin in the pool.
+    pub coin_reserve: u64,
+    /// Amount of the asset in the pool.
+    pub asset_reserve: u64,
+    /// Total amount of liquidity shares issued to the providers.
+    pub total_shares: u64,
+}
+
+impl Pool {
+    /// Create new Pool.
+    pub fn new(asset: &str, coin_reserve: u64, asset_reserve: u64, total_shares: u64) -> Self {
+        Self {
+            asset: asset.to_owned(),
+            coin_reserve,
+            asset_reserve,
+            total_shares,
+        }
+    }
+
+    /// Returns shares issued for the deposit together with the amounts of the coin
+    /// and the asset actually taken from it.
+    ///
+    /// The first deposit sets the price and gets shares equal to its coin amount,
+    /// later ones are taken at the current ratio of the reserves, rounded in favour of the pool.
+    pub fn deposit(&self, coin_amount: u64, asset_amount: u64) -> (u64, u64, u64) {
+        if self.total_shares == 0 {
+            return (coin_amount, coin_amount, asset_amount);
+        }
+        let total = u128::from(self.total_shares);
+        let by_coin = u128::from(coin_amount) * total / u128::from(self.coin_reserve);
+        let by_asset = u128::from(asset_amount) * total / u128::from(self.asset_reserve);
+        let shares = by_coin.min(by_asset);
+        let coin_used = ceil_div(shares * u128::from(self.coin_reserve), total);
+        let asset_used = ceil_div(shares * u128::from(self.asset_reserve), total);
+        // All values are bounded by the deposited amounts, so they fit into `u64`.
+        (shares as u64, coin_used as u64, asset_used as u64)
+    }
+
+    /// Returns amounts of the coin and the asset released for the given shares.
+    pub fn withdrawal(&self, shares: u64) -> (u64, u64) {
+        let total = u128::from(self.total_shares);
+        let coin = u128::from(shares) * u128::from(self.coin_reserve) / total;
+        let asset = u128::from(shares) * u128::from(self.asset_reserve) / total;
+        (coin as u64, asset as u64)
+    }
+
+    /// Returns amount received for selling the given amount to the pool
+    /// keeping the product of the reserves constant.
+    ///
+    /// `buy_asset` selects the direction: pay the coin for the asset or the other way round.
+    pub fn output(&self, buy_asset: bool, amount: u64) -> u64 {
+        let (input_reserve, output_reserve) = if buy_asset {
+            (self.coin_reserve, self.asset_reserve)
+        } else {
+            (self.asset_reserve, self.coin_reserve)
+        };
+        let output = u128::from(amount) * u128::from(output_reserve)
+            / (u128::from(input_reserve) + u128::from(amount));
+        output as u64
+    }
+
+    /// Returns worth of the given amount of the asset in the default coin at the current
+    /// ratio of the reserves.
+    pub fn coin_value(&self, asset_amount: u64) -> u128 {
+        u128::from(asset_amount) * u128::from(self.coin_reserve) / u128::from(self.asset_reserve)
+    }
+
+    /// Returns a copy of this pool with updated reserves and shares.
+    pub fn set_reserves(self, coin_reserve: u64, asset_reserve: u64, total_shares: u64) -> Self {
+        Self::new(&self.asset, coin_reserve, asset_reserve, total_shares)
+    }
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> u128 {
+    (numerator + denominator - 1) / denominator
+}
@@ -17,7 +17,26 @@
 #![allow(bare_trait_objects)]
 #![allow(renamed_and_removed_lints)]
 
-pub use self::cryptocurrency::{CreateWallet, Issue, Transfer, Wallet, TransferMultisign, AcceptMultisign};
+pub use self::cryptocurrency::{
+    AcceptMultisign, AcceptSwap, AddLiquidity, AddSessionKey, Airdrop, Approve, ApproversChange,
+    Asset, BatchTransfer, BlacklistKey, Burn, CancelMultisign, CancelOrder, CancelRecovery,
+    CancelStandingOrder, CancelSwap, ChangeApprovers, ClaimInheritance, ClaimVesting,
+    ClaimWithPreimage, CloseStream, CloseWallet, CoSignedTransfer, Config, CreateEscrow,
+    CreateInvoice, CreateMultisigWallet, CreateStandingOrder, CreateVesting, CreateWallet,
+    DefineAsset, Delegate, DelegateStake, DelegatedTransfer, Delegation, DeleteMetadata,
+    DepositSavings, Dispute, DistributeDividend, Escrow, EventRecord, FaucetIssue, FreezeWallet,
+    GrantRole, Guardians, HaltService, HashLock, Inheritance, Invoice, Issue, LinkWallet, Liquidate,
+    Loan, LockWithHash, MintNft, MultisigWallet, OpenDispute, OpenLoan, OpenStream, Order,
+    ParamChange, PayInvoice, Payment, PendingTransfer, PermitTransfer, PlaceOrder, Pool,
+    ProposeParamChange, ProposeSwap, PrunedHistory, RecoverWallet, Recovery, RedeemPoints, Refund,
+    RefundEscrow, RefundHashLock, ReleaseEscrow, RemoveLiquidity, RepayLoan, ResolveDispute,
+    ResumeService, RevokeSessionKey, RotateKey, Savings, ScheduleTransfer, ScheduledTransfer,
+    SessionKey, SessionTransfer, SetBeneficiary, SetGuardians, SetKycStatus, SetMetadata,
+    SetSpendingLimit, SetWatchList, SetWhitelist, SpendingLimit, Stake, StakePosition,
+    StandingOrder, Stream, Swap, SwapViaPool, TimeLock, Transfer, TransferByName, TransferFrom,
+    TransferMultisign, TransferNft, UnblacklistKey, UnfreezeWallet, Unstake, Vesting, Vote, Wallet,
+    WalletHistoryPage, WalletList, WalletsPage, WatchList, Whitelist, WithdrawSavings,
+};
 
 include!(concat!(env!("OUT_DIR"), "/protobuf_mod.rs"));
 
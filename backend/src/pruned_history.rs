@@ -0,0 +1,45 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records of the pruned wallet histories.
+
+use exonum::crypto::Hash;
+
+use super::proto;
+
+/// Number of blocks between the prunings of the wallet histories.
+pub const HISTORY_PRUNING_INTERVAL: u64 = 100;
+
+/// Maximum number of the wallets whose histories are pruned in a single block.
+pub const HISTORY_PRUNING_BATCH: usize = 1_000;
+
+/// Part of the wallet history removed by the retention policy, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::PrunedHistory", serde_pb_convert)]
+pub struct PrunedHistory {
+    /// Number of the records pruned from the beginning of the history.
+    pub pruned_len: u64,
+    /// Merkle root of the history before the latest pruning.
+    pub history_hash: Hash,
+}
+
+impl PrunedHistory {
+    /// Create new PrunedHistory.
+    pub fn new(pruned_len: u64, &history_hash: &Hash) -> Self {
+        Self {
+            pruned_len,
+            history_hash,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Publishing of the committed wallet events to message brokers.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+use exonum::{
+    blockchain::{self, TransactionSet},
+    crypto::PublicKey,
+    storage::Snapshot,
+};
+use serde_json;
+
+use config::NodeConfig;
+use events::WalletEvent;
+use transactions::WalletTransactions;
+use webhooks::{connect, parse_url, post, retry_with_backoff};
+use {Schema, CRYPTOCURRENCY_SERVICE_ID};
+
+/// Default port of the NATS servers.
+const NATS_DEFAULT_PORT: u16 = 4222;
+
+/// Wallet events of a block queued for publishing.
+#[derive(Debug)]
+struct Publication {
+    broker: String,
+    topic: String,
+    events: Vec<WalletEvent>,
+}
+
+/// Record of the Kafka REST proxy, keyed by the wallet key to keep the events
+/// of a wallet in one partition.
+#[derive(Debug, Serialize)]
+struct KafkaRecord<'a> {
+    key: PublicKey,
+    value: &'a WalletEvent,
+}
+
+/// Request body of the Kafka REST proxy.
+#[derive(Debug, Serialize)]
+struct KafkaRecords<'a> {
+    records: Vec<KafkaRecord<'a>>,
+}
+
+/// Publisher of the committed wallet events to the broker set in the node configuration.
+///
+/// Events are published by a background thread, so an unavailable broker does not delay
+/// the commits; publishing is retried with an exponential backoff.
+#[derive(Debug, Default)]
+pub struct EventPublisher {
+    broker: String,
+    topic: String,
+    queue: Mutex<Option<mpsc::Sender<Publication>>>,
+}
+
+impl EventPublisher {
+    /// Create new EventPublisher to the broker and the topic of the node configuration.
+    pub fn new(config: &NodeConfig) -> Self {
+        EventPublisher {
+            broker: config.event_broker.clone(),
+            topic: config.event_topic.clone(),
+            queue: Mutex::new(None),
+        }
+    }
+
+    /// Queues the events of the wallets changed by the service transactions of the latest
+    /// committed block for publishing.
+    pub fn publish(&self, snapshot: &dyn Snapshot) {
+        if self.broker.is_empty() {
+            return;
+        }
+        let events = block_wallet_events(snapshot);
+        if events.is_empty() {
+            return;
+        }
+        self.enqueue(Publication {
+            broker: self.broker.clone(),
+            topic: self.topic.clone(),
+            events,
+        });
+    }
+
+    /// Passes the publication to the publishing thread, starting the thread if needed.
+    fn enqueue(&self, publication: Publication) {
+        let mut queue = self.queue.lock().unwrap();
+        let publication = match queue.as_ref().map(|sender| sender.send(publication)) {
+            Some(Ok(())) => return,
+            Some(Err(mpsc::SendError(publication))) => publication,
+            None => publication,
+        };
+        let (sender, receiver) = mpsc::channel::<Publication>();
+        thread::spawn(move || {
+            for publication in receiver {
+                let _ = retry_with_backoff(|| publish(&publication));
+            }
+        });
+        sender.send(publication).unwrap();
+        *queue = Some(sender);
+    }
+}
+
+/// Returns the events of the wallets taking part in the successful service transactions
+/// of the latest committed block.
+fn block_wallet_events(snapshot: &dyn Snapshot) -> Vec<WalletEvent> {
+    let general_schema = blockchain::Schema::new(snapshot);
+    let schema = Schema::new(snapshot);
+    let height = general_schema.height();
+    let block_transactions = general_schema.block_transactions(height);
+
+    let mut keys: Vec<PublicKey> = Vec::new();
+    for tx_hash in block_transactions.iter() {
+        let succeeded = general_schema
+            .transaction_results()
+            .get(&tx_hash)
+            .map_or(false, |result| result.0.is_ok());
+        let signed = match general_schema.transactions().get(&tx_hash) {
+            Some(signed) => signed,
+            None => continue,
+        };
+        if !succeeded || signed.payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+            continue;
+        }
+        let transaction = match WalletTransactions::tx_from_raw(signed.payload().clone()) {
+            Ok(transaction) => transaction,
+            Err(_) => continue,
+        };
+        let mut parties = transaction.parties(signed.author());
+        if let WalletTransactions::TransferByName(ref tx) = transaction {
+            parties.extend(schema.wallet_by_name(&tx.to_name));
+        }
+        for key in parties {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+
+    keys.iter()
+        .filter_map(|key| schema.wallet(key))
+        .map(|wallet| WalletEvent { height, wallet })
+        .collect()
+}
+
+/// Publishes the events to the broker chosen by the scheme of its URL.
+fn publish(publication: &Publication) -> io::Result<()> {
+    if publication.broker.starts_with("nats://") {
+        publish_nats(publication)
+    } else {
+        publish_kafka(publication)
+    }
+}
+
+/// Publishes the events to the NATS subject, one message per event.
+fn publish_nats(publication: &Publication) -> io::Result<()> {
+    let (host, port, _) = parse_url(&publication.broker, "nats://", NATS_DEFAULT_PORT)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid NATS URL"))?;
+    let mut stream = connect(&host, port)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    // The server greets the client with its `INFO` first.
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    stream.write_all(b"CONNECT {\"verbose\":false,\"pedantic\":false}\r\n")?;
+    for event in &publication.events {
+        let payload = serde_json::to_vec(event).unwrap();
+        write!(stream, "PUB {} {}\r\n", publication.topic, payload.len())?;
+        stream.write_all(&payload)?;
+        stream.write_all(b"\r\n")?;
+    }
+
+    // The server answers `PING` after processing the preceding messages.
+    stream.write_all(b"PING\r\n")?;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if line.starts_with("PONG") {
+            return Ok(());
+        }
+        if line.starts_with("-ERR") {
+            return Err(io::Error::new(io::ErrorKind::Other, line.trim().to_owned()));
+        }
+    }
+}
+
+/// Publishes the events to the Kafka topic through the REST proxy.
+fn publish_kafka(publication: &Publication) -> io::Result<()> {
+    let records = publication
+        .events
+        .iter()
+        .map(|event| KafkaRecord {
+            key: event.wallet.pub_key,
+            value: event,
+        })
+        .collect();
+    let body = serde_json::to_vec(&KafkaRecords { records }).unwrap();
+    let url = format!(
+        "{}/topics/{}",
+        publication.broker.trim_end_matches('/'),
+        publication.topic
+    );
+    post(&url, "application/vnd.kafka.json.v2+json", &body)
+}
@@ -0,0 +1,147 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rate limiting of the transaction submission endpoints.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use exonum::crypto::PublicKey;
+
+use config::NodeConfig;
+
+/// Number of tracked clients above which the clients with expired windows are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Client the submissions are counted for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Client {
+    /// IP address the request came from.
+    Ip(IpAddr),
+    /// Public key of the transaction author or of the credited wallet.
+    Key(PublicKey),
+}
+
+/// Counters of the rate limited submissions.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct RateLimitStats {
+    /// Number of the accepted submissions.
+    pub accepted: u64,
+    /// Number of the submissions rejected by the IP address limit.
+    pub rejected_by_ip: u64,
+    /// Number of the submissions rejected by the public key limit.
+    pub rejected_by_key: u64,
+}
+
+/// Number of submissions of a client within the current window.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start: Instant,
+    count: u64,
+}
+
+/// Windows of the tracked clients together with the counters.
+#[derive(Debug, Default)]
+struct State {
+    windows: HashMap<Client, Window>,
+    stats: RateLimitStats,
+}
+
+/// Limits the number of submissions per IP address and per public key within fixed windows
+/// of `NodeConfig::rate_limit_window` seconds.
+///
+/// Windows are local to the node and kept in memory, so they are reset after a restart.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    ip_limit: u64,
+    key_limit: u64,
+    window: Duration,
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+    /// Create new RateLimiter with the limits of the node configuration.
+    pub fn new(config: &NodeConfig) -> Self {
+        RateLimiter {
+            ip_limit: config.ip_rate_limit,
+            key_limit: config.key_rate_limit,
+            window: Duration::from_secs(config.rate_limit_window),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Counts a submission from the given IP address and public key against the limits.
+    ///
+    /// Returns the time until the exceeded window ends if the submission is rejected.
+    /// Rejected submissions are not counted against the windows.
+    pub fn check(&self, ip: Option<IpAddr>, key: Option<PublicKey>) -> Result<(), Duration> {
+        let window = self.window;
+        let now = Instant::now();
+        let clients = [
+            ip.map(Client::Ip).map(|client| (client, self.ip_limit)),
+            key.map(Client::Key).map(|client| (client, self.key_limit)),
+        ];
+
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        if state.windows.len() > MAX_TRACKED_CLIENTS {
+            state
+                .windows
+                .retain(|_, counted| now.duration_since(counted.start) < window);
+        }
+
+        for &(client, limit) in clients.iter().flatten() {
+            if limit == 0 {
+                continue;
+            }
+            let counted = state.windows.entry(client).or_insert(Window {
+                start: now,
+                count: 0,
+            });
+            if now.duration_since(counted.start) >= window {
+                *counted = Window {
+                    start: now,
+                    count: 0,
+                };
+            }
+            if counted.count >= limit {
+                let retry_after = window - now.duration_since(counted.start);
+                match client {
+                    Client::Ip(_) => state.stats.rejected_by_ip += 1,
+                    Client::Key(_) => state.stats.rejected_by_key += 1,
+                }
+                return Err(retry_after);
+            }
+        }
+
+        for &(client, limit) in clients.iter().flatten() {
+            if limit != 0 {
+                if let Some(counted) = state.windows.get_mut(&client) {
+                    counted.count += 1;
+                }
+            }
+        }
+        state.stats.accepted += 1;
+        Ok(())
+    }
+
+    /// Returns the counters of the rate limited submissions.
+    pub fn stats(&self) -> RateLimitStats {
+        self.state.lock().unwrap().stats
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Events emitted by the execution of the transactions.
+
+use exonum::{
+    crypto::{Hash, PublicKey},
+    helpers::Height,
+};
+
+use super::proto;
+
+/// Event emitted by the execution of a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// Balance of the wallet changed.
+    BalanceChanged {
+        /// Wallet with the changed balance.
+        wallet: PublicKey,
+        /// Code of the asset, empty for the default one.
+        asset: String,
+        /// Balance before the change.
+        previous_balance: u64,
+        /// Balance after the change.
+        balance: u64,
+    },
+    /// Multisign transfer was registered to wait for approvals.
+    PendingTransferCreated {
+        /// Hash of the pending transfer.
+        transfer: Hash,
+        /// Multisign wallet the transfer is sent from.
+        wallet: PublicKey,
+        /// Amount reserved for the transfer.
+        amount: u64,
+    },
+    /// Pending multisign transfer was approved.
+    TransferApproved {
+        /// Hash of the pending transfer.
+        transfer: Hash,
+        /// Key that approved the transfer.
+        approver: PublicKey,
+    },
+}
+
+impl ExecutionEvent {
+    /// Returns the type of the event.
+    pub fn event_type(&self) -> EventType {
+        match self {
+            ExecutionEvent::BalanceChanged { .. } => EventType::BalanceChanged,
+            ExecutionEvent::PendingTransferCreated { .. } => EventType::PendingTransferCreated,
+            ExecutionEvent::TransferApproved { .. } => EventType::TransferApproved,
+        }
+    }
+
+    /// Returns the key the event concerns: the wallet with the changed balance,
+    /// the sending multisign wallet or the approver.
+    pub fn wallet(&self) -> PublicKey {
+        match *self {
+            ExecutionEvent::BalanceChanged { wallet, .. } => wallet,
+            ExecutionEvent::PendingTransferCreated { wallet, .. } => wallet,
+            ExecutionEvent::TransferApproved { approver, .. } => approver,
+        }
+    }
+}
+
+/// Type of the execution events the event index is filtered by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// Balance of a wallet changed.
+    BalanceChanged,
+    /// Multisign transfer was registered.
+    PendingTransferCreated,
+    /// Pending multisign transfer was approved.
+    TransferApproved,
+}
+
+/// Codes of the event kinds in `EventRecord`.
+const BALANCE_CHANGED: u32 = 1;
+const PENDING_TRANSFER_CREATED: u32 = 2;
+const TRANSFER_APPROVED: u32 = 3;
+
+/// Execution event stored in the database.
+///
+/// The fields not used by the kind of the event are left empty.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::EventRecord", serde_pb_convert)]
+pub struct EventRecord {
+    /// Code of the event kind.
+    pub kind: u32,
+    /// Wallet with the changed balance, the sending multisign wallet or the approver.
+    pub key: PublicKey,
+    /// Hash of the pending transfer.
+    pub transfer: Hash,
+    /// Code of the asset of the changed balance.
+    pub asset: String,
+    /// Balance before the change.
+    pub previous_balance: u64,
+    /// Balance after the change or the reserved amount.
+    pub amount: u64,
+    /// Height of the block with the transaction.
+    pub height: u64,
+    /// Hash of the transaction that emitted the event.
+    pub tx_hash: Hash,
+}
+
+impl EventRecord {
+    /// Creates the record of the event emitted by the transaction in the block
+    /// with the given height.
+    pub fn new(event: ExecutionEvent, height: Height, tx_hash: &Hash) -> Self {
+        let empty = EventRecord {
+            kind: 0,
+            key: PublicKey::zero(),
+            transfer: Hash::zero(),
+            asset: String::new(),
+            previous_balance: 0,
+            amount: 0,
+            height: height.0,
+            tx_hash: *tx_hash,
+        };
+        match event {
+            ExecutionEvent::BalanceChanged {
+                wallet,
+                asset,
+                previous_balance,
+                balance,
+            } => EventRecord {
+                kind: BALANCE_CHANGED,
+                key: wallet,
+                asset,
+                previous_balance,
+                amount: balance,
+                ..empty
+            },
+            ExecutionEvent::PendingTransferCreated {
+                transfer,
+                wallet,
+                amount,
+            } => EventRecord {
+                kind: PENDING_TRANSFER_CREATED,
+                key: wallet,
+                transfer,
+                amount,
+                ..empty
+            },
+            ExecutionEvent::TransferApproved { transfer, approver } => EventRecord {
+                kind: TRANSFER_APPROVED,
+                key: approver,
+                transfer,
+                ..empty
+            },
+        }
+    }
+
+    /// Returns the event of the record, `None` for an unknown kind.
+    pub fn event(&self) -> Option<ExecutionEvent> {
+        let event = match self.kind {
+            BALANCE_CHANGED => ExecutionEvent::BalanceChanged {
+                wallet: self.key,
+                asset: self.asset.clone(),
+                previous_balance: self.previous_balance,
+                balance: self.amount,
+            },
+            PENDING_TRANSFER_CREATED => ExecutionEvent::PendingTransferCreated {
+                transfer: self.transfer,
+                wallet: self.key,
+                amount: self.amount,
+            },
+            TRANSFER_APPROVED => ExecutionEvent::TransferApproved {
+                transfer: self.transfer,
+                approver: self.key,
+            },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Social recovery of wallets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Guardians allowed to recover the wallet, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Guardians", serde_pb_convert)]
+pub struct Guardians {
+    /// `PublicKey`s of the guardians.
+    pub guardians: Vec<PublicKey>,
+    /// Number of guardians required to recover the wallet.
+    pub threshold: u32,
+}
+
+impl Guardians {
+    /// Create new Guardians.
+    pub fn new(guardians: &[PublicKey], threshold: u32) -> Self {
+        Self {
+            guardians: guardians.to_vec(),
+            threshold,
+        }
+    }
+
+    /// Checks whether the given key is one of the guardians.
+    pub fn is_guardian(&self, key: &PublicKey) -> bool {
+        self.guardians.contains(key)
+    }
+}
+
+/// Recovery of the wallet to a new key approved by its guardians, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Recovery", serde_pb_convert)]
+pub struct Recovery {
+    /// New `PublicKey` of the wallet.
+    pub new_key: PublicKey,
+    /// Guardians who have approved the recovery.
+    pub approvals: Vec<PublicKey>,
+    /// Height of the block the wallet is moved to the new key in, `0` until the threshold
+    /// is reached.
+    pub complete_at: u64,
+}
+
+impl Recovery {
+    /// Create new Recovery.
+    pub fn new(&new_key: &PublicKey, approvals: &[PublicKey], complete_at: u64) -> Self {
+        Self {
+            new_key,
+            approvals: approvals.to_vec(),
+            complete_at,
+        }
+    }
+
+    /// Returns a copy of this recovery approved by one more guardian.
+    pub fn approve(self, guardian: &PublicKey) -> Self {
+        let mut approvals = self.approvals;
+        approvals.push(*guardian);
+        Self::new(&self.new_key, &approvals, self.complete_at)
+    }
+
+    /// Returns a copy of this recovery scheduled for the given height.
+    pub fn schedule(self, complete_at: u64) -> Self {
+        Self::new(&self.new_key, &self.approvals, complete_at)
+    }
+
+    /// Checks whether the recovery is scheduled.
+    pub fn is_scheduled(&self) -> bool {
+        self.complete_at != 0
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Roles delegated by wallets to other keys.
+
+/// Role delegated by the wallet to another key, stored in the database as `u8`.
+///
+/// Each role includes the permissions of the lower ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Role {
+    /// Key can only view the wallet.
+    Viewer = 1,
+    /// Key can also initiate transfers from the wallet.
+    Spender = 2,
+    /// Key can also grant and revoke roles of the wallet.
+    Admin = 3,
+}
+
+impl Role {
+    /// Returns the role with the given code.
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Role::Viewer),
+            2 => Some(Role::Spender),
+            3 => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the role allows initiating transfers from the wallet.
+    pub fn can_spend(self) -> bool {
+        self >= Role::Spender
+    }
+
+    /// Checks whether the role allows granting and revoking roles of the wallet.
+    pub fn can_manage_roles(self) -> bool {
+        self >= Role::Admin
+    }
+}
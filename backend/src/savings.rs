@@ -0,0 +1,51 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interest-bearing savings sub-balance of a wallet.
+
+use super::proto;
+
+/// Savings of a wallet, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Savings", serde_pb_convert)]
+pub struct Savings {
+    /// Amount of currency in the savings including the credited interest.
+    pub principal: u64,
+    /// Height of the block the interest was last credited at.
+    pub last_accrual: u64,
+}
+
+impl Savings {
+    /// Create new Savings.
+    pub fn new(principal: u64, last_accrual: u64) -> Self {
+        Self {
+            principal,
+            last_accrual,
+        }
+    }
+
+    /// Returns simple interest accrued since the last credit until the given height
+    /// at the rate in basis points per block, or `None` if it doesn't fit into `u64`.
+    pub fn interest_at(&self, height: u64, rate: u64) -> Option<u64> {
+        let blocks = height.saturating_sub(self.last_accrual);
+        let interest = (u128::from(self.principal) * u128::from(rate))
+            .checked_mul(u128::from(blocks))?
+            / 10_000;
+        if interest > u128::from(u64::max_value()) {
+            None
+        } else {
+            Some(interest as u64)
+        }
+    }
+}
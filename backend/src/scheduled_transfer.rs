@@ -0,0 +1,45 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Transfer scheduled for a future block.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Transfer executed when the block at the given height is committed, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ScheduledTransfer", serde_pb_convert)]
+pub struct ScheduledTransfer {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Height of the block the transfer is executed in.
+    pub at_height: u64,
+}
+
+impl ScheduledTransfer {
+    /// Create new ScheduledTransfer.
+    pub fn new(&from: &PublicKey, &to: &PublicKey, amount: u64, at_height: u64) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            at_height,
+        }
+    }
+}
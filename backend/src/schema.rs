@@ -15,12 +15,48 @@
 //! Cryptocurrency database schema.
 
 use exonum::{
+    blockchain::{self, TransactionSet},
     crypto::{Hash, PublicKey},
-    storage::{Fork, ProofListIndex, ProofMapIndex, Snapshot},
+    helpers::Height,
+    storage::{
+        Entry, Fork, KeySetIndex, ListIndex, MapIndex, ProofListIndex, ProofMapIndex, Snapshot,
+    },
 };
+use serde_json;
 
+use asset::Asset;
+use config::Config;
+use delegation::Delegation;
+use dispute::Dispute;
+use escrow::Escrow;
+use governance::ParamChange;
+use hash_lock::HashLock;
+use inheritance::Inheritance;
+use invoice::Invoice;
+use loan::Loan;
+use multisig_wallet::{ApproversChange, MultisigWallet};
+use order::Order;
+use pending_transfer::PendingTransfer;
+use pool::Pool;
+use pruned_history::{PrunedHistory, HISTORY_PRUNING_BATCH, HISTORY_PRUNING_INTERVAL};
+use receipt::{EventRecord, ExecutionEvent};
+use recovery::{Guardians, Recovery};
+use role::Role;
+use savings::Savings;
+use scheduled_transfer::ScheduledTransfer;
+use session_key::SessionKey;
+use spending_limit::SpendingLimit;
+use stake::StakePosition;
+use standing_order::StandingOrder;
+use stream::Stream;
+use swap::Swap;
+use time_lock::TimeLock;
+use transactions::{Error, Transfer, WalletTransactions};
+use vesting::Vesting;
 use wallet::Wallet;
-use INITIAL_BALANCE;
+use watch_list::WatchList;
+use whitelist::Whitelist;
+use {CRYPTOCURRENCY_SERVICE_ID, DEFAULT_ASSET, SERVICE_NAME};
 
 /// Database schema for the cryptocurrency.
 #[derive(Debug)]
@@ -53,98 +89,3073 @@ where
         ProofListIndex::new_in_family("cryptocurrency.wallet_history", public_key, &self.view)
     }
 
+    /// Returns balances of the wallet with the given public key after each record
+    /// of its history.
+    pub fn wallet_history_balances(&self, public_key: &PublicKey) -> ListIndex<&T, u64> {
+        ListIndex::new_in_family(
+            "cryptocurrency.wallet_history_balances",
+            public_key,
+            &self.view,
+        )
+    }
+
+    /// Returns hashes of the pending transactions of the wallet with the given public key.
+    pub fn wallet_pending_txs(&self, public_key: &PublicKey) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.wallet_pending_txs", public_key, &self.view)
+    }
+
+    /// Returns hashes of the open orders, loans and vestings of the wallet with the given
+    /// public key.
+    pub fn wallet_positions(&self, public_key: &PublicKey) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.wallet_positions", public_key, &self.view)
+    }
+
+    /// Checks whether the wallet has funds staked, saved, provided to pools or held
+    /// by open orders, loans or vestings.
+    pub fn has_open_positions(&self, public_key: &PublicKey) -> bool {
+        self.stake(public_key).is_some()
+            || self.savings().contains(public_key)
+            || self
+                .pools()
+                .keys()
+                .any(|asset| self.pool_shares(&asset).contains(public_key))
+            || self.wallet_positions(public_key).iter().next().is_some()
+    }
+
+    /// Returns `ProofMapIndex` with the records of the pruned wallet histories.
+    pub fn pruned_histories(&self) -> ProofMapIndex<&T, PublicKey, PrunedHistory> {
+        ProofMapIndex::new("cryptocurrency.pruned_histories", &self.view)
+    }
+
+    /// Returns the record of the pruned history of the wallet, `None` if nothing
+    /// was pruned from it.
+    pub fn pruned_history(&self, pub_key: &PublicKey) -> Option<PrunedHistory> {
+        self.pruned_histories().get(pub_key)
+    }
+
+    /// Returns `Entry` with the key of the wallet the unfinished pruning of the histories
+    /// continues from.
+    pub fn history_pruning_cursor(&self) -> Entry<&T, PublicKey> {
+        Entry::new("cryptocurrency.history_pruning_cursor", &self.view)
+    }
+
     /// Returns wallet for the given public key.
     pub fn wallet(&self, pub_key: &PublicKey) -> Option<Wallet> {
         self.wallets().get(pub_key)
     }
 
-    /// Returns the state hash of cryptocurrency service.
-    pub fn state_hash(&self) -> Vec<Hash> {
-        vec![self.wallets().merkle_root()]
+    /// Returns `ProofMapIndex` with the new keys of the rotated wallets by their old keys.
+    pub fn rotated_keys(&self) -> ProofMapIndex<&T, PublicKey, PublicKey> {
+        ProofMapIndex::new("cryptocurrency.rotated_keys", &self.view)
+    }
+
+    /// Returns the current key of the wallet, following its key rotations.
+    pub fn current_key(&self, pub_key: &PublicKey) -> PublicKey {
+        let rotated_keys = self.rotated_keys();
+        let mut key = *pub_key;
+        while let Some(new_key) = rotated_keys.get(&key) {
+            key = new_key;
+        }
+        key
+    }
+
+    /// Returns wallet for the given public key, following its key rotations.
+    ///
+    /// Used for the keys stored before the wallet could be re-keyed, e.g. in escrows.
+    pub fn current_wallet(&self, pub_key: &PublicKey) -> Option<Wallet> {
+        self.wallet(&self.current_key(pub_key))
+    }
+
+    /// Returns `ProofMapIndex` with beneficiaries of the wallets.
+    pub fn inheritances(&self) -> ProofMapIndex<&T, PublicKey, Inheritance> {
+        ProofMapIndex::new("cryptocurrency.inheritances", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with session keys of the wallets.
+    pub fn session_keys(&self) -> ProofMapIndex<&T, PublicKey, SessionKey> {
+        ProofMapIndex::new("cryptocurrency.session_keys", &self.view)
+    }
+
+    /// Returns session keys expiring at the given height.
+    pub fn session_key_expirations(&self, height: u64) -> KeySetIndex<&T, PublicKey> {
+        KeySetIndex::new_in_family(
+            "cryptocurrency.session_key_expirations",
+            &height,
+            &self.view,
+        )
+    }
+
+    /// Returns `ProofMapIndex` with invoices by hashes of the `CreateInvoice` transactions.
+    pub fn invoices(&self) -> ProofMapIndex<&T, Hash, Invoice> {
+        ProofMapIndex::new("cryptocurrency.invoices", &self.view)
+    }
+
+    /// Returns invoice for the given hash of the `CreateInvoice` transaction.
+    pub fn invoice(&self, invoice_hash: &Hash) -> Option<Invoice> {
+        self.invoices().get(invoice_hash)
+    }
+
+    /// Returns `ProofMapIndex` with proposed swaps by hashes of the `ProposeSwap` transactions.
+    pub fn swaps(&self) -> ProofMapIndex<&T, Hash, Swap> {
+        ProofMapIndex::new("cryptocurrency.swaps", &self.view)
+    }
+
+    /// Returns swap for the given hash of the `ProposeSwap` transaction.
+    pub fn swap(&self, swap_hash: &Hash) -> Option<Swap> {
+        self.swaps().get(swap_hash)
+    }
+
+    /// Returns `ProofMapIndex` with resting orders by hashes of the `PlaceOrder` transactions.
+    pub fn orders(&self) -> ProofMapIndex<&T, Hash, Order> {
+        ProofMapIndex::new("cryptocurrency.orders", &self.view)
+    }
+
+    /// Returns resting order for the given hash of the `PlaceOrder` transaction.
+    pub fn order(&self, order_hash: &Hash) -> Option<Order> {
+        self.orders().get(order_hash)
+    }
+
+    /// Returns hashes of the resting orders of the given order book side sorted by their priority.
+    pub fn order_book(&self, book: &str) -> MapIndex<&T, Vec<u8>, Hash> {
+        MapIndex::new_in_family("cryptocurrency.order_book", &book.to_owned(), &self.view)
+    }
+
+    /// Returns an entry with the sequence number of the next order.
+    pub fn order_sequence_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.order_sequence", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with liquidity pools by codes of the pooled assets.
+    pub fn pools(&self) -> ProofMapIndex<&T, String, Pool> {
+        ProofMapIndex::new("cryptocurrency.pools", &self.view)
+    }
+
+    /// Returns liquidity pool of the given asset.
+    pub fn pool(&self, asset: &str) -> Option<Pool> {
+        self.pools().get(&asset.to_owned())
+    }
+
+    /// Returns `ProofMapIndex` with liquidity pools as of the end of the previous block
+    /// by codes of the pooled assets.
+    pub fn reference_pools(&self) -> ProofMapIndex<&T, String, Pool> {
+        ProofMapIndex::new("cryptocurrency.reference_pools", &self.view)
+    }
+
+    /// Returns liquidity shares of the providers of the pool of the given asset.
+    pub fn pool_shares(&self, asset: &str) -> ProofMapIndex<&T, PublicKey, u64> {
+        ProofMapIndex::new_in_family("cryptocurrency.pool_shares", &asset.to_owned(), &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with staked coins of the wallets.
+    pub fn stakes(&self) -> ProofMapIndex<&T, PublicKey, StakePosition> {
+        ProofMapIndex::new("cryptocurrency.stakes", &self.view)
+    }
+
+    /// Returns staked coins of the wallet with the given public key.
+    pub fn stake(&self, owner: &PublicKey) -> Option<StakePosition> {
+        self.stakes().get(owner)
+    }
+
+    /// Checks whether the wallet with the given public key stakes as a validator.
+    pub fn is_validator(&self, key: &PublicKey) -> bool {
+        self.stake(key)
+            .map_or(false, |stake| stake.validator == *key)
+    }
+
+    /// Returns `ProofMapIndex` with savings of the wallets.
+    pub fn savings(&self) -> ProofMapIndex<&T, PublicKey, Savings> {
+        ProofMapIndex::new("cryptocurrency.savings", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with open loans by hashes of the `OpenLoan` transactions.
+    pub fn loans(&self) -> ProofMapIndex<&T, Hash, Loan> {
+        ProofMapIndex::new("cryptocurrency.loans", &self.view)
+    }
+
+    /// Returns open loan for the given hash of the `OpenLoan` transaction.
+    pub fn loan(&self, loan_hash: &Hash) -> Option<Loan> {
+        self.loans().get(loan_hash)
+    }
+
+    /// Returns worth of the loan collateral in the default coin at the price
+    /// of the asset pool as of the end of the previous block.
+    ///
+    /// Swaps in the current block don't move this price, so it can't be manipulated
+    /// to open or liquidate a loan within one block.
+    pub fn collateral_value(&self, loan: &Loan) -> Result<u128, Error> {
+        let pool = self
+            .reference_pools()
+            .get(&loan.asset)
+            .filter(|pool| pool.asset_reserve > 0)
+            .ok_or(Error::PoolNotFound)?;
+        Ok(pool.coin_value(loan.collateral))
+    }
+
+    /// Returns `ProofMapIndex` with loyalty points of the wallets.
+    pub fn loyalty_points(&self) -> ProofMapIndex<&T, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.loyalty_points", &self.view)
+    }
+
+    /// Returns loyalty points of the wallet with the given public key.
+    pub fn loyalty_balance(&self, pub_key: &PublicKey) -> u64 {
+        self.loyalty_points().get(pub_key).unwrap_or_default()
+    }
+
+    /// Returns `ProofMapIndex` with proposed parameter changes by hashes
+    /// of the `ProposeParamChange` transactions.
+    pub fn param_changes(&self) -> ProofMapIndex<&T, Hash, ParamChange> {
+        ProofMapIndex::new("cryptocurrency.param_changes", &self.view)
+    }
+
+    /// Returns voting weights of the wallets by their keys, i.e. their balances
+    /// at the moment the given parameter change was proposed.
+    pub fn proposal_weights(&self, proposal: &Hash) -> MapIndex<&T, PublicKey, u64> {
+        MapIndex::new_in_family("cryptocurrency.proposal_weights", proposal, &self.view)
+    }
+
+    /// Returns hashes of the open parameter changes by the keys of their proposers.
+    pub fn open_proposals(&self) -> MapIndex<&T, PublicKey, Hash> {
+        MapIndex::new("cryptocurrency.open_proposals", &self.view)
+    }
+
+    /// Returns hashes of the parameter changes which can't be voted after the given height.
+    pub fn proposal_expirations(&self, height: u64) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.proposal_expirations", &height, &self.view)
+    }
+
+    /// Returns `Entry` with the flag of the read-only mode of the service.
+    pub fn halted_entry(&self) -> Entry<&T, bool> {
+        Entry::new("cryptocurrency.halted", &self.view)
+    }
+
+    /// Checks whether the service is halted by an administrator.
+    pub fn is_halted(&self) -> bool {
+        self.halted_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the height of the latest block the per-block operations
+    /// were executed for.
+    pub fn last_processed_height_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.last_processed_height", &self.view)
+    }
+
+    /// Returns the heights from the one after the latest processed block up to the given one.
+    ///
+    /// The heights skipped while the service was halted are included, so that the operations
+    /// indexed by height, like scheduled transfers, are executed once the service is resumed.
+    pub fn due_heights(&self, height: Height) -> Vec<Height> {
+        let from = self
+            .last_processed_height_entry()
+            .get()
+            .map_or(height.0, |last| last + 1);
+        (from..=height.0).map(Height).collect()
+    }
+
+    /// Returns `ProofMapIndex` with heights of the latest faucet credits by receiver keys.
+    pub fn faucet_claims(&self) -> ProofMapIndex<&T, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.faucet_claims", &self.view)
+    }
+
+    /// Checks whether the faucet can credit the given key at the current height.
+    pub fn allows_faucet_claim(&self, key: &PublicKey) -> bool {
+        match self.faucet_claims().get(key) {
+            Some(claimed_at) => {
+                let interval = self.config().faucet_interval;
+                self.current_height().0 >= claimed_at.saturating_add(interval)
+            }
+            None => true,
+        }
+    }
+
+    /// Checks whether the given key is a service key of a validator.
+    pub fn is_service_key(&self, key: &PublicKey) -> bool {
+        blockchain::Schema::new(&self.view)
+            .actual_configuration()
+            .validator_keys
+            .iter()
+            .any(|keys| keys.service_key == *key)
+    }
+
+    /// Returns `ProofMapIndex` with keys watched by their authors.
+    pub fn watch_lists(&self) -> ProofMapIndex<&T, PublicKey, WatchList> {
+        ProofMapIndex::new("cryptocurrency.watch_lists", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with guardians of the wallets.
+    pub fn guardians(&self) -> ProofMapIndex<&T, PublicKey, Guardians> {
+        ProofMapIndex::new("cryptocurrency.guardians", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with pending recoveries by keys of the recovered wallets.
+    pub fn recoveries(&self) -> ProofMapIndex<&T, PublicKey, Recovery> {
+        ProofMapIndex::new("cryptocurrency.recoveries", &self.view)
+    }
+
+    /// Returns pending recovery of the wallet with the given public key.
+    pub fn recovery(&self, wallet: &PublicKey) -> Option<Recovery> {
+        self.recoveries().get(wallet)
+    }
+
+    /// Returns keys of the wallets with the recoveries scheduled for the given height.
+    pub fn recovery_schedule(&self, height: u64) -> KeySetIndex<&T, PublicKey> {
+        KeySetIndex::new_in_family("cryptocurrency.recovery_schedule", &height, &self.view)
+    }
+
+    /// Returns balances of the wallet with the given public key in assets
+    /// other than the default one.
+    pub fn asset_balances(&self, public_key: &PublicKey) -> ProofMapIndex<&T, String, u64> {
+        ProofMapIndex::new_in_family("cryptocurrency.asset_balances", public_key, &self.view)
+    }
+
+    /// Returns balance of the wallet in the given asset available for transfers.
+    pub fn available_balance(&self, wallet: &Wallet, asset: &str) -> u64 {
+        if asset == DEFAULT_ASSET {
+            wallet.pending_balance
+        } else {
+            self.asset_balances(&wallet.pub_key)
+                .get(&asset.to_owned())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Returns `MapIndex` with public keys of the wallets by their names.
+    ///
+    /// The name belongs to the first wallet registered with it.
+    pub fn wallet_names(&self) -> MapIndex<&T, String, PublicKey> {
+        MapIndex::new("cryptocurrency.wallet_names", &self.view)
+    }
+
+    /// Returns public key of the wallet registered under the given name.
+    pub fn wallet_by_name(&self, name: &str) -> Option<PublicKey> {
+        self.wallet_names().get(&name.to_owned())
+    }
+
+    /// Returns `Entry` with the height the stored service configuration became actual from
+    /// in the configuration service.
+    pub fn config_actual_from_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.config_actual_from", &self.view)
+    }
+
+    /// Returns `Entry` with the service configuration.
+    pub fn config_entry(&self) -> Entry<&T, Config> {
+        Entry::new("cryptocurrency.config", &self.view)
+    }
+
+    /// Returns the service configuration.
+    pub fn config(&self) -> Config {
+        self.config_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the total amount of currency in circulation.
+    pub fn total_supply_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.total_supply", &self.view)
+    }
+
+    /// Returns the total amount of currency in circulation.
+    pub fn total_supply(&self) -> u64 {
+        self.total_supply_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the cumulative amount of currency issued by `Issue`.
+    pub fn issued_supply_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.issued_supply", &self.view)
+    }
+
+    /// Returns the cumulative amount of currency issued by `Issue`.
+    pub fn issued_supply(&self) -> u64 {
+        self.issued_supply_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the number of created wallets.
+    pub fn wallet_count_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.wallet_count", &self.view)
+    }
+
+    /// Returns the number of created wallets.
+    pub fn wallet_count(&self) -> u64 {
+        self.wallet_count_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the number of executed transfers.
+    pub fn transfer_count_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.transfer_count", &self.view)
+    }
+
+    /// Returns the number of executed transfers.
+    pub fn transfer_count(&self) -> u64 {
+        self.transfer_count_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with the cumulative amount of the default asset moved by transfers.
+    pub fn transfer_volume_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.transfer_volume", &self.view)
+    }
+
+    /// Returns the cumulative amount of the default asset moved by transfers.
+    pub fn transfer_volume(&self) -> u64 {
+        self.transfer_volume_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `Entry` with fees collected since the last distribution.
+    pub fn fee_pool_entry(&self) -> Entry<&T, u64> {
+        Entry::new("cryptocurrency.fee_pool", &self.view)
+    }
+
+    /// Returns amount of fees collected since the last distribution.
+    pub fn fee_pool(&self) -> u64 {
+        self.fee_pool_entry().get().unwrap_or_default()
+    }
+
+    /// Returns `ProofMapIndex` with user-defined assets.
+    pub fn assets(&self) -> ProofMapIndex<&T, String, Asset> {
+        ProofMapIndex::new("cryptocurrency.assets", &self.view)
+    }
+
+    /// Returns asset for the given code.
+    pub fn asset(&self, code: &str) -> Option<Asset> {
+        self.assets().get(&code.to_owned())
+    }
+
+    /// Returns allowances given by the wallet with the given public key to other wallets.
+    pub fn allowances(&self, owner: &PublicKey) -> ProofMapIndex<&T, PublicKey, u64> {
+        ProofMapIndex::new_in_family("cryptocurrency.allowances", owner, &self.view)
+    }
+
+    /// Returns amount of currency `spender` is allowed to transfer from the `owner` wallet.
+    pub fn allowance(&self, owner: &PublicKey, spender: &PublicKey) -> u64 {
+        self.allowances(owner).get(spender).unwrap_or_default()
+    }
+
+    /// Returns `ProofMapIndex` with heights the permits were used by `PermitTransfer` at
+    /// by hashes of the permits.
+    pub fn used_permits(&self) -> ProofMapIndex<&T, Hash, u64> {
+        ProofMapIndex::new("cryptocurrency.used_permits", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with escrows by hashes of their `CreateEscrow` transactions.
+    pub fn escrows(&self) -> ProofMapIndex<&T, Hash, Escrow> {
+        ProofMapIndex::new("cryptocurrency.escrows", &self.view)
+    }
+
+    /// Returns escrow for the given hash.
+    pub fn escrow(&self, escrow_hash: &Hash) -> Option<Escrow> {
+        self.escrows().get(escrow_hash)
+    }
+
+    /// Returns `ProofMapIndex` with hash time-locked transfers by their hash locks.
+    pub fn hash_locks(&self) -> ProofMapIndex<&T, Hash, HashLock> {
+        ProofMapIndex::new("cryptocurrency.hash_locks", &self.view)
+    }
+
+    /// Returns hash time-locked transfer for the given hash lock.
+    pub fn hash_lock(&self, hash_lock: &Hash) -> Option<HashLock> {
+        self.hash_locks().get(hash_lock)
+    }
+
+    /// Returns `ProofMapIndex` with time-locked funds by hashes of the crediting transactions.
+    pub fn time_locks(&self) -> ProofMapIndex<&T, Hash, TimeLock> {
+        ProofMapIndex::new("cryptocurrency.time_locks", &self.view)
+    }
+
+    /// Returns hashes of the time locks maturing at the given height.
+    pub fn time_lock_schedule(&self, height: u64) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.time_lock_schedule", &height, &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with vesting schedules by hashes of their creating transactions.
+    pub fn vestings(&self) -> ProofMapIndex<&T, Hash, Vesting> {
+        ProofMapIndex::new("cryptocurrency.vestings", &self.view)
+    }
+
+    /// Returns vesting schedule for the given hash.
+    pub fn vesting(&self, vesting_hash: &Hash) -> Option<Vesting> {
+        self.vestings().get(vesting_hash)
+    }
+
+    /// Returns `ProofMapIndex` with standing orders by hashes of their creating transactions.
+    pub fn standing_orders(&self) -> ProofMapIndex<&T, Hash, StandingOrder> {
+        ProofMapIndex::new("cryptocurrency.standing_orders", &self.view)
+    }
+
+    /// Returns standing order for the given hash.
+    pub fn standing_order(&self, order_hash: &Hash) -> Option<StandingOrder> {
+        self.standing_orders().get(order_hash)
+    }
+
+    /// Returns `ProofMapIndex` with payment streams by hashes of their opening transactions.
+    pub fn streams(&self) -> ProofMapIndex<&T, Hash, Stream> {
+        ProofMapIndex::new("cryptocurrency.streams", &self.view)
+    }
+
+    /// Returns payment stream for the given hash.
+    pub fn stream(&self, stream_hash: &Hash) -> Option<Stream> {
+        self.streams().get(stream_hash)
+    }
+
+    /// Returns `ProofMapIndex` with scheduled transfers by hashes of their transactions.
+    pub fn scheduled_transfers(&self) -> ProofMapIndex<&T, Hash, ScheduledTransfer> {
+        ProofMapIndex::new("cryptocurrency.scheduled_transfers", &self.view)
+    }
+
+    /// Returns hashes of the transfers scheduled for the given height.
+    pub fn transfer_schedule(&self, height: u64) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.transfer_schedule", &height, &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with spending limits of the wallets.
+    pub fn spending_limits(&self) -> ProofMapIndex<&T, PublicKey, SpendingLimit> {
+        ProofMapIndex::new("cryptocurrency.spending_limits", &self.view)
+    }
+
+    /// Returns spending limit of the wallet with the given public key.
+    pub fn spending_limit(&self, pub_key: &PublicKey) -> Option<SpendingLimit> {
+        self.spending_limits().get(pub_key)
+    }
+
+    /// Returns amounts sent from the wallet with the given public key by block heights.
+    pub fn outgoing_volumes(&self, pub_key: &PublicKey) -> MapIndex<&T, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.outgoing_volumes", pub_key, &self.view)
+    }
+
+    /// Returns `MapIndex` with encrypted memos of the transfers by transaction hashes.
+    pub fn encrypted_memos(&self) -> MapIndex<&T, Hash, Vec<u8>> {
+        MapIndex::new("cryptocurrency.encrypted_memos", &self.view)
+    }
+
+    /// Returns metadata entries of the wallet with the given public key.
+    pub fn wallet_metadata(&self, pub_key: &PublicKey) -> MapIndex<&T, String, String> {
+        MapIndex::new_in_family("cryptocurrency.wallet_metadata", pub_key, &self.view)
+    }
+
+    /// Returns amount sent from the wallet within the velocity window ending at the given height.
+    pub fn outgoing_volume(&self, pub_key: &PublicKey, height: Height) -> u64 {
+        let window_start = self.velocity_window_start(height);
+        self.outgoing_volumes(pub_key)
+            .iter_from(&window_start)
+            .fold(0, |volume, (_, amount)| volume.saturating_add(amount))
+    }
+
+    /// Returns the first height of the velocity window ending at the given height.
+    fn velocity_window_start(&self, height: Height) -> u64 {
+        let window = self.config().velocity_window;
+        (height.0 + 1).saturating_sub(window)
+    }
+
+    /// Returns fees paid to the wallet with the given public key by heights of the blocks.
+    pub fn fee_payouts(&self, pub_key: &PublicKey) -> MapIndex<&T, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.fee_payouts", pub_key, &self.view)
+    }
+
+    /// Returns staking rewards paid to the wallet with the given public key by heights
+    /// of the blocks.
+    pub fn staking_payouts(&self, pub_key: &PublicKey) -> MapIndex<&T, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.staking_payouts", pub_key, &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with blacklisted keys and heights of the blocks they were
+    /// blacklisted in.
+    pub fn blacklist(&self) -> ProofMapIndex<&T, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.blacklist", &self.view)
+    }
+
+    /// Checks whether the given key is blacklisted.
+    pub fn is_blacklisted(&self, pub_key: &PublicKey) -> bool {
+        self.blacklist().contains(pub_key)
+    }
+
+    /// Returns `ProofMapIndex` with whitelists of the senders by receivers' keys.
+    pub fn whitelists(&self) -> ProofMapIndex<&T, PublicKey, Whitelist> {
+        ProofMapIndex::new("cryptocurrency.whitelists", &self.view)
+    }
+
+    /// Checks whether the receiver accepts transfers from the sender.
+    pub fn accepts_from(&self, receiver: &PublicKey, sender: &PublicKey) -> bool {
+        self.whitelists()
+            .get(receiver)
+            .map_or(true, |whitelist| whitelist.allows(sender))
+    }
+
+    /// Returns roles delegated by the wallet with the given public key by grantees.
+    pub fn wallet_roles(&self, wallet: &PublicKey) -> ProofMapIndex<&T, PublicKey, u8> {
+        ProofMapIndex::new_in_family("cryptocurrency.wallet_roles", wallet, &self.view)
+    }
+
+    /// Returns role delegated by the wallet to the given key.
+    pub fn wallet_role(&self, wallet: &PublicKey, key: &PublicKey) -> Option<Role> {
+        self.wallet_roles(wallet)
+            .get(key)
+            .and_then(|code| Role::from_code(u32::from(code)))
+    }
+
+    /// Checks whether the key owns the wallet, i.e. is its own key or one of the owners
+    /// of the multisignature wallet.
+    pub fn is_wallet_owner(&self, wallet: &PublicKey, key: &PublicKey) -> bool {
+        wallet == key
+            || self
+                .multisig_wallet(wallet)
+                .map_or(false, |multisig| self.is_multisig_owner(&multisig, key))
+    }
+
+    /// Checks whether the key is one of the owners of the multisignature wallet,
+    /// following the key rotations of the owners.
+    pub fn is_multisig_owner(&self, multisig: &MultisigWallet, key: &PublicKey) -> bool {
+        multisig
+            .owners
+            .iter()
+            .any(|owner| self.current_key(owner) == *key)
+    }
+
+    /// Checks whether the key is allowed to approve the pending multisign transfer,
+    /// following the key rotations of the approvers.
+    pub fn is_transfer_approver(&self, transfer: &PendingTransfer, key: &PublicKey) -> bool {
+        transfer
+            .approvers
+            .iter()
+            .any(|approver| self.current_key(approver) == *key)
+    }
+
+    /// Returns spending authorities delegated by the wallet with the given public key.
+    pub fn delegations(&self, owner: &PublicKey) -> ProofMapIndex<&T, PublicKey, Delegation> {
+        ProofMapIndex::new_in_family("cryptocurrency.delegations", owner, &self.view)
+    }
+
+    /// Returns spending authority delegated by the `owner` wallet to `spender`.
+    pub fn delegation(&self, owner: &PublicKey, spender: &PublicKey) -> Option<Delegation> {
+        self.delegations(owner).get(spender)
+    }
+
+    /// Returns the committed `Transfer` transaction with the given hash together with its author.
+    pub fn committed_transfer(&self, tx_hash: &Hash) -> Option<(PublicKey, Transfer)> {
+        let signed = blockchain::Schema::new(&self.view)
+            .transactions()
+            .get(tx_hash)?;
+        if signed.payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+            return None;
+        }
+        match WalletTransactions::tx_from_raw(signed.payload().clone()) {
+            Ok(WalletTransactions::Transfer(transfer)) => Some((signed.author(), transfer)),
+            _ => None,
+        }
+    }
+
+    /// Returns height of the block the transaction with the given hash is committed in.
+    pub fn transaction_height(&self, tx_hash: &Hash) -> Option<Height> {
+        blockchain::Schema::new(&self.view)
+            .transactions_locations()
+            .get(tx_hash)
+            .map(|location| location.block_height())
+    }
+
+    /// Returns `KeySetIndex` with hashes of the successful `Transfer` transactions, which can
+    /// be refunded or disputed.
+    ///
+    /// Unlike the wallet histories, the index isn't pruned.
+    pub fn completed_transfers(&self) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new("cryptocurrency.completed_transfers", &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with disputes by hashes of the disputed transfers.
+    pub fn disputes(&self) -> ProofMapIndex<&T, Hash, Dispute> {
+        ProofMapIndex::new("cryptocurrency.disputes", &self.view)
+    }
+
+    /// Returns dispute of the transfer with the given hash.
+    pub fn dispute(&self, tx_hash: &Hash) -> Option<Dispute> {
+        self.disputes().get(tx_hash)
+    }
+
+    /// Returns `ProofMapIndex` with hashes of the `Refund` transactions by hashes
+    /// of the refunded transfers.
+    pub fn refunds(&self) -> ProofMapIndex<&T, Hash, Hash> {
+        ProofMapIndex::new("cryptocurrency.refunds", &self.view)
+    }
+
+    /// Returns height of the block being created, i.e. the one following
+    /// the latest committed block.
+    pub fn current_height(&self) -> Height {
+        blockchain::Schema::new(&self.view).height().next()
+    }
+
+    /// Returns `ProofMapIndex` with owners of the non-fungible tokens.
+    pub fn nft_owners(&self) -> ProofMapIndex<&T, Hash, PublicKey> {
+        ProofMapIndex::new("cryptocurrency.nft_owners", &self.view)
+    }
+
+    /// Returns owner of the non-fungible token with the given identifier.
+    pub fn nft_owner(&self, token: &Hash) -> Option<PublicKey> {
+        self.nft_owners().get(token)
+    }
+
+    /// Returns the index of the non-fungible tokens by the keys of their owners,
+    /// kept along with `nft_owners`.
+    pub fn owned_nfts(&self, owner: &PublicKey) -> KeySetIndex<&T, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.owned_nfts", owner, &self.view)
+    }
+
+    /// Returns identifiers of the non-fungible tokens owned by the given wallet.
+    pub fn nfts_owned_by(&self, owner: &PublicKey) -> Vec<Hash> {
+        self.owned_nfts(owner).iter().collect()
+    }
+
+    /// Returns `ProofMapIndex` with multisign wallets.
+    pub fn multisig_wallets(&self) -> ProofMapIndex<&T, PublicKey, MultisigWallet> {
+        ProofMapIndex::new("cryptocurrency.multisig_wallets", &self.view)
+    }
+
+    /// Returns multisign wallet for the given public key.
+    pub fn multisig_wallet(&self, pub_key: &PublicKey) -> Option<MultisigWallet> {
+        self.multisig_wallets().get(pub_key)
+    }
+
+    /// Returns multisign wallets linked to the wallet with the given public key.
+    pub fn linked_multisig_wallets(&self, owner: &PublicKey) -> ProofListIndex<&T, PublicKey> {
+        ProofListIndex::new_in_family("cryptocurrency.linked_multisig_wallets", owner, &self.view)
+    }
+
+    /// Returns `ProofMapIndex` with pending changes of multisign wallet owners.
+    pub fn approvers_changes(&self) -> ProofMapIndex<&T, Hash, ApproversChange> {
+        ProofMapIndex::new("cryptocurrency.approvers_changes", &self.view)
+    }
+
+    /// Returns pending change of multisign wallet owners for the given transaction hash.
+    pub fn approvers_change(&self, tx_hash: &Hash) -> Option<ApproversChange> {
+        self.approvers_changes().get(tx_hash)
+    }
+
+    /// Returns `ProofMapIndex` with pending multisign transfers.
+    pub fn pending_transfers(&self) -> ProofMapIndex<&T, Hash, PendingTransfer> {
+        ProofMapIndex::new("cryptocurrency.pending_transfers", &self.view)
+    }
+
+    /// Returns approvals of the pending multisign transfer with the given hash.
+    pub fn multisig_approvals(&self, tx_hash: &Hash) -> ProofListIndex<&T, PublicKey> {
+        ProofListIndex::new_in_family("cryptocurrency.multisig_approvals", tx_hash, &self.view)
+    }
+
+    /// Returns pending multisign transfer for the given transaction hash.
+    pub fn pending_transfer(&self, tx_hash: &Hash) -> Option<PendingTransfer> {
+        self.pending_transfers().get(tx_hash)
+    }
+
+    /// Checks whether the pending multisign transfer is approved by the given key.
+    pub fn multisig_approved_by(&self, tx_hash: &Hash, approver: &PublicKey) -> bool {
+        self.multisig_approvals(tx_hash)
+            .iter()
+            .any(|key| self.current_key(&key) == *approver)
+    }
+
+    /// Checks whether the pending multisign transfer has collected enough approvals.
+    ///
+    /// Pending transfers use the quorum fixed at initiation, while owner changes
+    /// use the current quorum of the multisign wallet.
+    pub fn multisig_quorum_reached(&self, tx_hash: &Hash) -> bool {
+        let quorum = match self.pending_transfer(tx_hash) {
+            Some(transfer) => Some(transfer.quorum),
+            None => self
+                .approvers_change(tx_hash)
+                .and_then(|change| self.multisig_wallet(&change.multisig_wallet))
+                .map(|wallet| wallet.quorum),
+        };
+        match quorum {
+            Some(quorum) => self.multisig_approvals(tx_hash).len() >= u64::from(quorum),
+            None => false,
+        }
+    }
+
+    /// Returns the events emitted by the execution of the transaction with the given hash.
+    pub fn execution_events(&self, tx_hash: &Hash) -> ListIndex<&T, EventRecord> {
+        ListIndex::new_in_family("cryptocurrency.execution_events", tx_hash, &self.view)
+    }
+
+    /// Returns the events emitted by all the transactions in the order of execution.
+    pub fn event_log(&self) -> ListIndex<&T, EventRecord> {
+        ListIndex::new("cryptocurrency.event_log", &self.view)
+    }
+
+    /// Returns the positions in the event log of the first events emitted at each height.
+    pub fn event_log_heights(&self) -> MapIndex<&T, u64, u64> {
+        MapIndex::new("cryptocurrency.event_log_heights", &self.view)
+    }
+
+    /// Returns the hash of the transaction being executed, the events are emitted for.
+    pub fn executing_transaction_entry(&self) -> Entry<&T, Hash> {
+        Entry::new("cryptocurrency.executing_transaction", &self.view)
+    }
+
+    /// Returns the state hash of cryptocurrency service.
+    pub fn state_hash(&self) -> Vec<Hash> {
+        vec![
+            self.wallets().merkle_root(),
+            self.multisig_wallets().merkle_root(),
+            self.pending_transfers().merkle_root(),
+            self.approvers_changes().merkle_root(),
+            self.total_supply_entry().hash(),
+            self.assets().merkle_root(),
+            self.nft_owners().merkle_root(),
+            self.escrows().merkle_root(),
+            self.hash_locks().merkle_root(),
+            self.time_locks().merkle_root(),
+            self.vestings().merkle_root(),
+            self.standing_orders().merkle_root(),
+            self.streams().merkle_root(),
+            self.scheduled_transfers().merkle_root(),
+            self.fee_pool_entry().hash(),
+            self.spending_limits().merkle_root(),
+            self.blacklist().merkle_root(),
+            self.whitelists().merkle_root(),
+            self.rotated_keys().merkle_root(),
+            self.guardians().merkle_root(),
+            self.recoveries().merkle_root(),
+            self.inheritances().merkle_root(),
+            self.session_keys().merkle_root(),
+            self.watch_lists().merkle_root(),
+            self.invoices().merkle_root(),
+            self.refunds().merkle_root(),
+            self.disputes().merkle_root(),
+            self.swaps().merkle_root(),
+            self.orders().merkle_root(),
+            self.pools().merkle_root(),
+            self.stakes().merkle_root(),
+            self.savings().merkle_root(),
+            self.loans().merkle_root(),
+            self.loyalty_points().merkle_root(),
+            self.param_changes().merkle_root(),
+            self.halted_entry().hash(),
+            self.faucet_claims().merkle_root(),
+            self.pruned_histories().merkle_root(),
+            self.reference_pools().merkle_root(),
+            self.config_entry().hash(),
+            self.used_permits().merkle_root(),
+        ]
+    }
+}
+
+/// Implementation of mutable methods.
+impl<'a> Schema<&'a mut Fork> {
+    /// Returns mutable `ProofMapIndex` with wallets.
+    pub fn wallets_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Wallet> {
+        ProofMapIndex::new("cryptocurrency.wallets", &mut self.view)
+    }
+
+    /// Returns history for the wallet by the given public key.
+    pub fn wallet_history_mut(
+        &mut self,
+        public_key: &PublicKey,
+    ) -> ProofListIndex<&mut Fork, Hash> {
+        ProofListIndex::new_in_family("cryptocurrency.wallet_history", public_key, &mut self.view)
+    }
+
+    /// Returns mutable balances of the wallet after each record of its history.
+    pub fn wallet_history_balances_mut(
+        &mut self,
+        public_key: &PublicKey,
+    ) -> ListIndex<&mut Fork, u64> {
+        ListIndex::new_in_family(
+            "cryptocurrency.wallet_history_balances",
+            public_key,
+            &mut self.view,
+        )
+    }
+
+    /// Returns mutable `ProofMapIndex` with the records of the pruned wallet histories.
+    pub fn pruned_histories_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, PrunedHistory> {
+        ProofMapIndex::new("cryptocurrency.pruned_histories", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the key of the wallet the pruning continues from.
+    pub fn history_pruning_cursor_mut(&mut self) -> Entry<&mut Fork, PublicKey> {
+        Entry::new("cryptocurrency.history_pruning_cursor", &mut self.view)
+    }
+
+    /// Returns mutable events emitted by the execution of the transaction with the given hash.
+    pub fn execution_events_mut(&mut self, tx_hash: &Hash) -> ListIndex<&mut Fork, EventRecord> {
+        ListIndex::new_in_family("cryptocurrency.execution_events", tx_hash, &mut self.view)
+    }
+
+    /// Returns mutable log of the events emitted by all the transactions.
+    pub fn event_log_mut(&mut self) -> ListIndex<&mut Fork, EventRecord> {
+        ListIndex::new("cryptocurrency.event_log", &mut self.view)
+    }
+
+    /// Returns mutable positions of the first events emitted at each height.
+    pub fn event_log_heights_mut(&mut self) -> MapIndex<&mut Fork, u64, u64> {
+        MapIndex::new("cryptocurrency.event_log_heights", &mut self.view)
+    }
+
+    /// Returns mutable hash of the transaction being executed.
+    pub fn executing_transaction_entry_mut(&mut self) -> Entry<&mut Fork, Hash> {
+        Entry::new("cryptocurrency.executing_transaction", &mut self.view)
+    }
+
+    /// Starts recording the events emitted by the execution of the transaction.
+    ///
+    /// Called before the execution of every transaction of the service. The recording stops
+    /// with the block, so that the balance changes of `before_commit` are not attributed
+    /// to the last transaction.
+    pub fn begin_transaction(&mut self, tx_hash: &Hash) {
+        self.executing_transaction_entry_mut().set(*tx_hash);
+    }
+
+    /// Stops recording the events.
+    pub fn end_transactions(&mut self) {
+        self.executing_transaction_entry_mut().remove();
+    }
+
+    /// Records the event emitted by the transaction being executed, if any,
+    /// both under its hash and in the event log.
+    pub fn emit_event(&mut self, event: ExecutionEvent) {
+        let tx_hash = match self.executing_transaction_entry().get() {
+            Some(tx_hash) => tx_hash,
+            None => return,
+        };
+        let height = self.current_height();
+        let record = EventRecord::new(event, height, &tx_hash);
+        self.execution_events_mut(&tx_hash).push(record.clone());
+        let position = self.event_log().len();
+        self.event_log_mut().push(record);
+        if !self.event_log_heights().contains(&height.0) {
+            self.event_log_heights_mut().put(&height.0, position);
+        }
+    }
+
+    /// Records the change of the wallet balance in the given asset.
+    fn emit_balance_change(
+        &mut self,
+        wallet: &PublicKey,
+        asset: &str,
+        previous_balance: u64,
+        balance: u64,
+    ) {
+        self.emit_event(ExecutionEvent::BalanceChanged {
+            wallet: *wallet,
+            asset: asset.to_owned(),
+            previous_balance,
+            balance,
+        });
+    }
+
+    /// Increase balance of the wallet and append new record to its history.
+    ///
+    /// Fails with `BalanceOverflow` if the new balance doesn't fit into `u64`.
+    pub fn increase_wallet_balance(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        if self.is_blacklisted(&wallet.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        let balance = wallet
+            .balance
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let pending_balance = wallet
+            .pending_balance
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let previous_balance = wallet.balance;
+        let wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+            let history_hash = history.merkle_root();
+            wallet
+                .set_balance(balance, &history_hash)
+                .set_pending_balance(pending_balance)
+        };
+        self.wallet_history_balances_mut(&wallet.pub_key)
+            .push(balance);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        self.emit_balance_change(&wallet.pub_key, DEFAULT_ASSET, previous_balance, balance);
+        Ok(wallet)
+    }
+
+    /// Increase balance of the wallet by an amount paid out while committing a block.
+    ///
+    /// Unlike `increase_wallet_balance`, nothing is appended to the wallet history, which holds
+    /// only transaction hashes; the payouts are kept in separate indexes by block heights.
+    pub fn credit_payout(&mut self, wallet: Wallet, amount: u64) -> Result<Wallet, Error> {
+        if self.is_blacklisted(&wallet.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        let balance = wallet
+            .balance
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let pending_balance = wallet
+            .pending_balance
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let previous_balance = wallet.balance;
+        let wallet = wallet.set_balances(balance, pending_balance);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        self.emit_balance_change(&wallet.pub_key, DEFAULT_ASSET, previous_balance, balance);
+        Ok(wallet)
+    }
+
+    /// Decrease balance of the wallet and append new record to its history.
+    ///
+    /// Fails with `BalanceOverflow` if the amount exceeds the balance.
+    pub fn decrease_wallet_balance(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        if self.is_blacklisted(&wallet.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        let balance = wallet
+            .balance
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let pending_balance = wallet
+            .pending_balance
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let previous_balance = wallet.balance;
+        let wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+            let history_hash = history.merkle_root();
+            wallet
+                .set_balance(balance, &history_hash)
+                .set_pending_balance(pending_balance)
+        };
+        self.wallet_history_balances_mut(&wallet.pub_key)
+            .push(balance);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        self.emit_balance_change(&wallet.pub_key, DEFAULT_ASSET, previous_balance, balance);
+        Ok(wallet)
+    }
+
+    /// Decrease pending balance of the wallet.
+    ///
+    /// Fails with `BalanceOverflow` if the amount exceeds the pending balance.
+    pub fn decrease_wallet_pending_balance(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+    ) -> Result<Wallet, Error> {
+        let balance = wallet
+            .pending_balance
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let wallet = wallet.set_pending_balance(balance);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        Ok(wallet)
+    }
+
+    /// Returns mutable balances of the wallet with the given public key in assets
+    /// other than the default one.
+    pub fn asset_balances_mut(
+        &mut self,
+        public_key: &PublicKey,
+    ) -> ProofMapIndex<&mut Fork, String, u64> {
+        ProofMapIndex::new_in_family("cryptocurrency.asset_balances", public_key, &mut self.view)
+    }
+
+    /// Increase balance of the wallet in the given asset and append new record to its history.
+    ///
+    /// Fails with `BalanceOverflow` if the new balance doesn't fit into `u64`.
+    pub fn increase_asset_balance(
+        &mut self,
+        wallet: Wallet,
+        asset: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        if asset == DEFAULT_ASSET {
+            return self.increase_wallet_balance(wallet, amount, transaction);
+        }
+        if self.is_blacklisted(&wallet.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        let balance = self
+            .available_balance(&wallet, asset)
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        Ok(self.set_asset_balance(wallet, asset, balance, transaction))
+    }
+
+    /// Decrease balance of the wallet in the given asset and append new record to its history.
+    ///
+    /// Fails with `BalanceOverflow` if the amount exceeds the balance.
+    pub fn decrease_asset_balance(
+        &mut self,
+        wallet: Wallet,
+        asset: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        if asset == DEFAULT_ASSET {
+            return self.decrease_wallet_balance(wallet, amount, transaction);
+        }
+        if self.is_blacklisted(&wallet.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        let balance = self
+            .available_balance(&wallet, asset)
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        Ok(self.set_asset_balance(wallet, asset, balance, transaction))
+    }
+
+    fn set_asset_balance(
+        &mut self,
+        wallet: Wallet,
+        asset: &str,
+        balance: u64,
+        transaction: &Hash,
+    ) -> Wallet {
+        let previous_balance = self
+            .asset_balances(&wallet.pub_key)
+            .get(&asset.to_owned())
+            .unwrap_or_default();
+        let assets_hash = {
+            let mut balances = self.asset_balances_mut(&wallet.pub_key);
+            balances.put(&asset.to_owned(), balance);
+            balances.merkle_root()
+        };
+        let wallet = self
+            .append_wallet_history(wallet, transaction)
+            .set_assets_hash(&assets_hash);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        self.emit_balance_change(&wallet.pub_key, asset, previous_balance, balance);
+        wallet
+    }
+
+    /// Increase pending balance of the wallet.
+    ///
+    /// Fails with `BalanceOverflow` if the new pending balance doesn't fit into `u64`.
+    pub fn increase_wallet_pending_balance(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+    ) -> Result<Wallet, Error> {
+        let balance = wallet
+            .pending_balance
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        let wallet = wallet.set_pending_balance(balance);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        Ok(wallet)
+    }
+
+    /// Returns mutable hashes of the pending transactions of the wallet.
+    pub fn wallet_pending_txs_mut(
+        &mut self,
+        public_key: &PublicKey,
+    ) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family(
+            "cryptocurrency.wallet_pending_txs",
+            public_key,
+            &mut self.view,
+        )
+    }
+
+    /// Add pending transfer to multisign wallet.
+    ///
+    /// The wallet record is left unchanged.
+    pub fn add_tx_to_wallet(&mut self, wallet: Wallet, tx_hash: &Hash) -> Wallet {
+        self.wallet_pending_txs_mut(&wallet.pub_key)
+            .insert(*tx_hash);
+        wallet
+    }
+
+    /// Remove pending transfer from multisign wallet.
+    ///
+    /// The wallet record is left unchanged.
+    pub fn remove_tx_from_wallet(&mut self, wallet: Wallet, tx_hash: &Hash) -> Wallet {
+        self.wallet_pending_txs_mut(&wallet.pub_key).remove(tx_hash);
+        wallet
+    }
+
+    /// Returns mutable `MapIndex` with public keys of the wallets by their names.
+    pub fn wallet_names_mut(&mut self) -> MapIndex<&mut Fork, String, PublicKey> {
+        MapIndex::new("cryptocurrency.wallet_names", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the service configuration.
+    pub fn config_entry_mut(&mut self) -> Entry<&mut Fork, Config> {
+        Entry::new("cryptocurrency.config", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the height the stored service configuration became actual from.
+    pub fn config_actual_from_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.config_actual_from", &mut self.view)
+    }
+
+    /// Store the service section of the actual blockchain configuration if it became actual
+    /// after the stored one.
+    ///
+    /// Parameters changed by voting are kept until the next configuration becomes actual.
+    pub fn apply_actual_config(&mut self) {
+        let actual = blockchain::Schema::new(&self.view).actual_configuration();
+        let actual_from = actual.actual_from.0;
+        if actual_from <= self.config_actual_from_entry().get().unwrap_or_default() {
+            return;
+        }
+        let config = actual
+            .services
+            .get(SERVICE_NAME)
+            .and_then(|value| serde_json::from_value::<Config>(value.clone()).ok());
+        if let Some(config) = config {
+            self.set_config(config);
+        }
+        self.config_actual_from_entry_mut().set(actual_from);
+    }
+
+    /// Store the service configuration.
+    pub fn set_config(&mut self, config: Config) {
+        self.config_entry_mut().set(config);
+    }
+
+    /// Returns mutable `Entry` with the total amount of currency in circulation.
+    pub fn total_supply_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.total_supply", &mut self.view)
+    }
+
+    /// Increase the total amount of currency in circulation.
+    pub fn increase_total_supply(&mut self, amount: u64) -> Result<(), Error> {
+        let total_supply = self
+            .total_supply()
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        self.total_supply_entry_mut().set(total_supply);
+        Ok(())
+    }
+
+    /// Decrease the total amount of currency in circulation.
+    pub fn decrease_total_supply(&mut self, amount: u64) -> Result<(), Error> {
+        let total_supply = self
+            .total_supply()
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        self.total_supply_entry_mut().set(total_supply);
+        Ok(())
+    }
+
+    /// Returns mutable `Entry` with the number of created wallets.
+    pub fn wallet_count_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.wallet_count", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the number of executed transfers.
+    pub fn transfer_count_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.transfer_count", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the cumulative amount of the default asset moved by transfers.
+    pub fn transfer_volume_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.transfer_volume", &mut self.view)
+    }
+
+    /// Counts a transfer of the given amount of the asset in the statistics.
+    ///
+    /// The volume saturates instead of failing the transfer.
+    pub fn record_transfer(&mut self, asset: &str, amount: u64) {
+        let count = self.transfer_count();
+        self.transfer_count_entry_mut().set(count + 1);
+        if asset == DEFAULT_ASSET {
+            let volume = self.transfer_volume().saturating_add(amount);
+            self.transfer_volume_entry_mut().set(volume);
+        }
+    }
+
+    /// Returns mutable `Entry` with the cumulative amount of currency issued by `Issue`.
+    pub fn issued_supply_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.issued_supply", &mut self.view)
+    }
+
+    /// Increase the cumulative issued amount, respecting the configured maximum supply.
+    pub fn increase_issued_supply(&mut self, amount: u64) -> Result<(), Error> {
+        let issued_supply = self
+            .issued_supply()
+            .checked_add(amount)
+            .ok_or(Error::SupplyCapExceeded)?;
+        if !self.config().allows_supply(issued_supply) {
+            Err(Error::SupplyCapExceeded)?
+        }
+        self.issued_supply_entry_mut().set(issued_supply);
+        Ok(())
+    }
+
+    /// Decrease the cumulative amount of issued currency, e.g. when a loan is repaid,
+    /// so that the repaid amount can be issued again.
+    pub fn decrease_issued_supply(&mut self, amount: u64) -> Result<(), Error> {
+        let issued_supply = self
+            .issued_supply()
+            .checked_sub(amount)
+            .ok_or(Error::BalanceOverflow)?;
+        self.issued_supply_entry_mut().set(issued_supply);
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with spending limits of the wallets.
+    pub fn spending_limits_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, SpendingLimit> {
+        ProofMapIndex::new("cryptocurrency.spending_limits", &mut self.view)
+    }
+
+    /// Returns mutable amounts sent from the wallet by block heights.
+    pub fn outgoing_volumes_mut(&mut self, pub_key: &PublicKey) -> MapIndex<&mut Fork, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.outgoing_volumes", pub_key, &mut self.view)
+    }
+
+    /// Returns mutable `KeySetIndex` with hashes of the successful `Transfer` transactions.
+    pub fn completed_transfers_mut(&mut self) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new("cryptocurrency.completed_transfers", &mut self.view)
+    }
+
+    /// Returns mutable `MapIndex` with encrypted memos of the transfers.
+    pub fn encrypted_memos_mut(&mut self) -> MapIndex<&mut Fork, Hash, Vec<u8>> {
+        MapIndex::new("cryptocurrency.encrypted_memos", &mut self.view)
+    }
+
+    /// Returns mutable metadata entries of the wallet.
+    pub fn wallet_metadata_mut(
+        &mut self,
+        pub_key: &PublicKey,
+    ) -> MapIndex<&mut Fork, String, String> {
+        MapIndex::new_in_family("cryptocurrency.wallet_metadata", pub_key, &mut self.view)
+    }
+
+    /// Add the amount to the volume sent from the wallet in the current block.
+    ///
+    /// Fails if the volume within the velocity window exceeds `Config::velocity_limit`.
+    /// Volumes sent before the window are removed.
+    pub fn record_outgoing_volume(
+        &mut self,
+        pub_key: &PublicKey,
+        amount: u64,
+    ) -> Result<(), Error> {
+        if self.config().velocity_limit == 0 {
+            return Ok(());
+        }
+        let height = self.current_height();
+        let volume = self
+            .outgoing_volume(pub_key, height)
+            .checked_add(amount)
+            .ok_or(Error::VelocityLimitExceeded)?;
+        if !self.config().allows_velocity(volume) {
+            Err(Error::VelocityLimitExceeded)?
+        }
+
+        let window_start = self.velocity_window_start(height);
+        let expired = self
+            .outgoing_volumes(pub_key)
+            .keys()
+            .take_while(|&block| block < window_start)
+            .collect::<Vec<_>>();
+        let mut volumes = self.outgoing_volumes_mut(pub_key);
+        for block in expired {
+            volumes.remove(&block);
+        }
+        let sent = volumes.get(&height.0).unwrap_or_default();
+        volumes.put(&height.0, sent + amount);
+        Ok(())
+    }
+
+    /// Check the KYC status and the limits of the wallet on an outgoing payment of the given asset
+    /// and record it.
+    ///
+    /// Every payment from a wallet passes this check once, either when it is charged with
+    /// `debit_payment` or when its amount is reserved with `reserve_payment`.
+    /// The spending limit doesn't apply to the payments co-signed by the wallet's co-signer.
+    fn check_outgoing_payment(
+        &mut self,
+        wallet: &Wallet,
+        asset: &str,
+        amount: u64,
+        co_signed: bool,
+    ) -> Result<(), Error> {
+        if self.config().requires_kyc(amount) && !wallet.kyc_verified {
+            Err(Error::KycRequired)?
+        }
+        if asset == DEFAULT_ASSET {
+            match self.spending_limit(&wallet.pub_key) {
+                Some(ref limit) if !co_signed && limit.is_exceeded_by(amount) => {
+                    Err(Error::SpendingLimitExceeded)?
+                }
+                _ => {}
+            }
+            self.record_outgoing_volume(&wallet.pub_key, amount)?;
+        }
+        Ok(())
+    }
+
+    /// Check whether the wallet may receive a payment of the given amount.
+    fn check_incoming_payment(&self, wallet: &Wallet, amount: u64) -> Result<(), Error> {
+        if self.config().requires_kyc(amount) && !wallet.kyc_verified {
+            Err(Error::KycRequired)?
+        }
+        Ok(())
+    }
+
+    /// Charge an outgoing payment of the given asset from the wallet within its limits
+    /// and append new record to its history.
+    pub fn debit_payment(
+        &mut self,
+        wallet: Wallet,
+        asset: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        self.check_outgoing_payment(&wallet, asset, amount, false)?;
+        self.decrease_asset_balance(wallet, asset, amount, transaction)
+    }
+
+    /// Charge an outgoing payment of the default coin co-signed by the co-signer of the wallet,
+    /// so that its spending limit doesn't apply.
+    pub fn debit_co_signed_payment(
+        &mut self,
+        wallet: Wallet,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        self.check_outgoing_payment(&wallet, DEFAULT_ASSET, amount, true)?;
+        self.decrease_wallet_balance(wallet, amount, transaction)
+    }
+
+    /// Reserve an outgoing payment of the default coin on the wallet within its limits
+    /// and add the given hash to its pending txs.
+    ///
+    /// The payment is charged from the reserve later without checking the limits again.
+    pub fn reserve_payment(
+        &mut self,
+        wallet: Wallet,
+        tx_hash: &Hash,
+        amount: u64,
+    ) -> Result<Wallet, Error> {
+        self.check_outgoing_payment(&wallet, DEFAULT_ASSET, amount, false)?;
+        self.reserve_funds(wallet, tx_hash, amount)
+    }
+
+    /// Credit an incoming payment of the given asset to the wallet and append new record
+    /// to its history.
+    ///
+    /// Fails with `KycRequired` if the payment exceeds `Config::kyc_threshold`
+    /// and the wallet isn't verified.
+    pub fn credit_payment(
+        &mut self,
+        wallet: Wallet,
+        asset: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        self.check_incoming_payment(&wallet, amount)?;
+        self.increase_asset_balance(wallet, asset, amount, transaction)
+    }
+
+    /// Returns mutable `ProofMapIndex` with whitelists of the senders.
+    pub fn whitelists_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Whitelist> {
+        ProofMapIndex::new("cryptocurrency.whitelists", &mut self.view)
+    }
+
+    /// Returns mutable spending authorities delegated by the wallet.
+    pub fn delegations_mut(
+        &mut self,
+        owner: &PublicKey,
+    ) -> ProofMapIndex<&mut Fork, PublicKey, Delegation> {
+        ProofMapIndex::new_in_family("cryptocurrency.delegations", owner, &mut self.view)
+    }
+
+    /// Returns mutable roles delegated by the wallet.
+    pub fn wallet_roles_mut(
+        &mut self,
+        wallet: &PublicKey,
+    ) -> ProofMapIndex<&mut Fork, PublicKey, u8> {
+        ProofMapIndex::new_in_family("cryptocurrency.wallet_roles", wallet, &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with the new keys of the rotated wallets.
+    pub fn rotated_keys_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, PublicKey> {
+        ProofMapIndex::new("cryptocurrency.rotated_keys", &mut self.view)
+    }
+
+    /// Move the wallet to the new key and tombstone its old key.
+    ///
+    /// Every index keyed by the wallet key, from history and balances to allowances,
+    /// velocity volumes and owned tokens, is moved along with the wallet. Multisign
+    /// wallets and pending transfers listing the old key as an owner follow the rotation
+    /// through `current_key`. Allowances and roles granted *to* the old key stay with it
+    /// and should be re-granted by their owners.
+    pub fn rotate_wallet_key(&mut self, wallet: Wallet, new_key: &PublicKey) -> Wallet {
+        let old_key = wallet.pub_key;
+
+        let history = self.wallet_history(&old_key).iter().collect::<Vec<_>>();
+        self.wallet_history_mut(new_key).extend(history);
+        self.wallet_history_mut(&old_key).clear();
+        let balances = self
+            .wallet_history_balances(&old_key)
+            .iter()
+            .collect::<Vec<_>>();
+        self.wallet_history_balances_mut(new_key).extend(balances);
+        self.wallet_history_balances_mut(&old_key).clear();
+        let pending_txs = self.wallet_pending_txs(&old_key).iter().collect::<Vec<_>>();
+        for tx_hash in pending_txs {
+            self.wallet_pending_txs_mut(new_key).insert(tx_hash);
+        }
+        self.wallet_pending_txs_mut(&old_key).clear();
+        let positions = self.wallet_positions(&old_key).iter().collect::<Vec<_>>();
+        for position in positions {
+            self.wallet_positions_mut(new_key).insert(position);
+        }
+        self.wallet_positions_mut(&old_key).clear();
+        if let Some(pruned) = self.pruned_history(&old_key) {
+            self.pruned_histories_mut().remove(&old_key);
+            self.pruned_histories_mut().put(new_key, pruned);
+        }
+
+        let balances = self.asset_balances(&old_key).iter().collect::<Vec<_>>();
+        for (asset, balance) in balances {
+            self.asset_balances_mut(new_key).put(&asset, balance);
+        }
+        self.asset_balances_mut(&old_key).clear();
+
+        let metadata = self.wallet_metadata(&old_key).iter().collect::<Vec<_>>();
+        for (key, value) in metadata {
+            self.wallet_metadata_mut(new_key).put(&key, value);
+        }
+        self.wallet_metadata_mut(&old_key).clear();
+
+        let pools = self.pools().keys().collect::<Vec<_>>();
+        for asset in pools {
+            if let Some(shares) = self.pool_shares(&asset).get(&old_key) {
+                self.pool_shares_mut(&asset).remove(&old_key);
+                self.pool_shares_mut(&asset).put(new_key, shares);
+            }
+        }
+
+        if let Some(limit) = self.spending_limit(&old_key) {
+            self.spending_limits_mut().remove(&old_key);
+            self.spending_limits_mut().put(new_key, limit);
+        }
+        if let Some(whitelist) = self.whitelists().get(&old_key) {
+            self.whitelists_mut().remove(&old_key);
+            self.whitelists_mut().put(new_key, whitelist);
+        }
+        if let Some(guardians) = self.guardians().get(&old_key) {
+            self.guardians_mut().remove(&old_key);
+            self.guardians_mut().put(new_key, guardians);
+        }
+        self.recoveries_mut().remove(&old_key);
+        let points = self.loyalty_balance(&old_key);
+        if points > 0 {
+            self.loyalty_points_mut().remove(&old_key);
+            self.loyalty_points_mut().put(new_key, points);
+        }
+        if let Some(savings) = self.savings().get(&old_key) {
+            self.savings_mut().remove(&old_key);
+            self.savings_mut().put(new_key, savings);
+        }
+        if let Some(stake) = self.stake(&old_key) {
+            self.stakes_mut().remove(&old_key);
+            self.stakes_mut().put(new_key, stake);
+        }
+        if let Some(inheritance) = self.inheritances().get(&old_key) {
+            self.inheritances_mut().remove(&old_key);
+            self.inheritances_mut().put(new_key, inheritance);
+        }
+        if let Some(height) = self.blacklist().get(&old_key) {
+            self.blacklist_mut().remove(&old_key);
+            self.blacklist_mut().put(new_key, height);
+        }
+        if let Some(height) = self.faucet_claims().get(&old_key) {
+            self.faucet_claims_mut().remove(&old_key);
+            self.faucet_claims_mut().put(new_key, height);
+        }
+        if let Some(watch_list) = self.watch_lists().get(&old_key) {
+            self.watch_lists_mut().remove(&old_key);
+            self.watch_lists_mut().put(new_key, watch_list);
+        }
+        if let Some(proposal) = self.open_proposals().get(&old_key) {
+            self.open_proposals_mut().remove(&old_key);
+            self.open_proposals_mut().put(new_key, proposal);
+        }
+
+        let volumes = self.outgoing_volumes(&old_key).iter().collect::<Vec<_>>();
+        for (height, volume) in volumes {
+            self.outgoing_volumes_mut(new_key).put(&height, volume);
+        }
+        self.outgoing_volumes_mut(&old_key).clear();
+        let payouts = self.fee_payouts(&old_key).iter().collect::<Vec<_>>();
+        for (height, amount) in payouts {
+            self.fee_payouts_mut(new_key).put(&height, amount);
+        }
+        self.fee_payouts_mut(&old_key).clear();
+        let payouts = self.staking_payouts(&old_key).iter().collect::<Vec<_>>();
+        for (height, amount) in payouts {
+            self.staking_payouts_mut(new_key).put(&height, amount);
+        }
+        self.staking_payouts_mut(&old_key).clear();
+
+        let allowances = self.allowances(&old_key).iter().collect::<Vec<_>>();
+        for (spender, amount) in allowances {
+            self.allowances_mut(new_key).put(&spender, amount);
+        }
+        self.allowances_mut(&old_key).clear();
+        let delegations = self.delegations(&old_key).iter().collect::<Vec<_>>();
+        for (spender, delegation) in delegations {
+            self.delegations_mut(new_key).put(&spender, delegation);
+        }
+        self.delegations_mut(&old_key).clear();
+        let roles = self.wallet_roles(&old_key).iter().collect::<Vec<_>>();
+        for (grantee, role) in roles {
+            self.wallet_roles_mut(new_key).put(&grantee, role);
+        }
+        self.wallet_roles_mut(&old_key).clear();
+
+        let linked = self
+            .linked_multisig_wallets(&old_key)
+            .iter()
+            .collect::<Vec<_>>();
+        self.linked_multisig_wallets_mut(new_key).extend(linked);
+        self.linked_multisig_wallets_mut(&old_key).clear();
+        for token in self.nfts_owned_by(&old_key) {
+            self.set_nft_owner(&token, new_key);
+        }
+        if self.wallet_by_name(&wallet.name) == Some(old_key) {
+            self.wallet_names_mut().put(&wallet.name, *new_key);
+        }
+
+        let wallet = wallet.set_pub_key(new_key);
+        self.wallets_mut().remove(&old_key);
+        self.wallets_mut().put(new_key, wallet.clone());
+        self.rotated_keys_mut().put(&old_key, *new_key);
+        wallet
+    }
+
+    /// Returns mutable `ProofMapIndex` with beneficiaries of the wallets.
+    pub fn inheritances_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Inheritance> {
+        ProofMapIndex::new("cryptocurrency.inheritances", &mut self.view)
+    }
+
+    /// Postpone the inheritance of the wallet with the given public key after a transaction
+    /// signed by its owner.
+    pub fn record_activity(&mut self, pub_key: &PublicKey) {
+        if let Some(inheritance) = self.inheritances().get(pub_key) {
+            let height = self.current_height().0;
+            self.inheritances_mut()
+                .put(pub_key, inheritance.set_last_activity(height));
+        }
+    }
+
+    /// Returns mutable `ProofMapIndex` with session keys of the wallets.
+    pub fn session_keys_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, SessionKey> {
+        ProofMapIndex::new("cryptocurrency.session_keys", &mut self.view)
+    }
+
+    /// Returns mutable session keys expiring at the given height.
+    pub fn session_key_expirations_mut(
+        &mut self,
+        height: u64,
+    ) -> KeySetIndex<&mut Fork, PublicKey> {
+        KeySetIndex::new_in_family(
+            "cryptocurrency.session_key_expirations",
+            &height,
+            &mut self.view,
+        )
+    }
+
+    /// Store the session key to be removed at its expiration height.
+    pub fn add_session_key(&mut self, key: &PublicKey, session: SessionKey) {
+        self.session_key_expirations_mut(session.expires_at)
+            .insert(*key);
+        self.session_keys_mut().put(key, session);
+    }
+
+    /// Remove the session keys expiring at the given height.
+    ///
+    /// Keys revoked or re-added with another expiration height in the meantime are skipped.
+    pub fn expire_session_keys(&mut self, height: Height) {
+        let expiring = self
+            .session_key_expirations(height.0)
+            .iter()
+            .collect::<Vec<_>>();
+        for key in expiring {
+            let expired = self
+                .session_keys()
+                .get(&key)
+                .map_or(false, |session| session.expires_at == height.0);
+            if expired {
+                self.session_keys_mut().remove(&key);
+            }
+        }
+        self.session_key_expirations_mut(height.0).clear();
+    }
+
+    /// Returns mutable `ProofMapIndex` with disputes.
+    pub fn disputes_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Dispute> {
+        ProofMapIndex::new("cryptocurrency.disputes", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with hashes of the `Refund` transactions.
+    pub fn refunds_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Hash> {
+        ProofMapIndex::new("cryptocurrency.refunds", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with invoices.
+    pub fn invoices_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Invoice> {
+        ProofMapIndex::new("cryptocurrency.invoices", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with proposed swaps.
+    pub fn swaps_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Swap> {
+        ProofMapIndex::new("cryptocurrency.swaps", &mut self.view)
+    }
+
+    /// Returns mutable hashes of the open orders, loans and vestings of the wallet.
+    pub fn wallet_positions_mut(&mut self, public_key: &PublicKey) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family(
+            "cryptocurrency.wallet_positions",
+            public_key,
+            &mut self.view,
+        )
+    }
+
+    /// Record the open order, loan or vesting of the wallet with the given key.
+    pub fn open_position(&mut self, owner: &PublicKey, position: &Hash) {
+        let owner = self.current_key(owner);
+        self.wallet_positions_mut(&owner).insert(*position);
+    }
+
+    /// Remove the closed order, loan or vesting of the wallet with the given key.
+    pub fn close_position(&mut self, owner: &PublicKey, position: &Hash) {
+        let owner = self.current_key(owner);
+        self.wallet_positions_mut(&owner).remove(position);
+    }
+
+    /// Returns mutable `ProofMapIndex` with resting orders.
+    pub fn orders_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Order> {
+        ProofMapIndex::new("cryptocurrency.orders", &mut self.view)
+    }
+
+    /// Returns mutable hashes of the resting orders of the given order book side.
+    pub fn order_book_mut(&mut self, book: &str) -> MapIndex<&mut Fork, Vec<u8>, Hash> {
+        MapIndex::new_in_family(
+            "cryptocurrency.order_book",
+            &book.to_owned(),
+            &mut self.view,
+        )
+    }
+
+    /// Returns a mutable entry with the sequence number of the next order.
+    pub fn order_sequence_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.order_sequence", &mut self.view)
+    }
+
+    /// Returns the sequence number for a new order and advances the sequence.
+    pub fn next_order_seq(&mut self) -> u64 {
+        let seq = self.order_sequence_entry().get().unwrap_or_default();
+        self.order_sequence_entry_mut().set(seq + 1);
+        seq
+    }
+
+    /// Returns the amount of the quote asset reserved for the given amount of the order.
+    ///
+    /// Buy orders reserve the quote asset at their price, sell orders reserve the base asset.
+    pub fn order_reserve(order: &Order, amount: u64) -> Result<(String, u64), Error> {
+        if order.buy {
+            let reserve = amount
+                .checked_mul(order.price)
+                .ok_or(Error::BalanceOverflow)?;
+            Ok((order.quote_asset.clone(), reserve))
+        } else {
+            Ok((order.base_asset.clone(), amount))
+        }
+    }
+
+    /// Add the amount of the asset to the current wallet of the given key.
+    fn credit_order_owner(
+        &mut self,
+        owner: &PublicKey,
+        asset: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let wallet = self.current_wallet(owner).ok_or(Error::ReceiverNotFound)?;
+        self.increase_asset_balance(wallet, asset, amount, transaction)?;
+        Ok(())
+    }
+
+    /// Trade the incoming order with the resting orders of the opposite side at their prices,
+    /// best price and then earliest first, and put its remainder to the order book.
+    ///
+    /// The funds of the incoming order must already be reserved. Trades are recorded
+    /// in the wallet histories under the hash of the incoming order.
+    pub fn match_order(&mut self, order: Order, order_hash: &Hash) -> Result<(), Error> {
+        let mut order = order;
+        let opposite = Order::book_name(&order.base_asset, &order.quote_asset, !order.buy);
+        while order.amount > 0 {
+            let best = self.order_book(&opposite).iter().next();
+            let (resting_key, resting_hash) = match best {
+                Some(best) => best,
+                None => break,
+            };
+            let resting = self.order(&resting_hash).ok_or(Error::OrderNotFound)?;
+            if !order.crosses(&resting) {
+                break;
+            }
+
+            let fill = order.amount.min(resting.amount);
+            let quote_amount = fill
+                .checked_mul(resting.price)
+                .ok_or(Error::BalanceOverflow)?;
+            let (buyer, seller) = if order.buy {
+                (order.owner, resting.owner)
+            } else {
+                (resting.owner, order.owner)
+            };
+            self.credit_order_owner(&buyer, &order.base_asset, fill, order_hash)?;
+            self.credit_order_owner(&seller, &order.quote_asset, quote_amount, order_hash)?;
+            if order.buy {
+                // The buyer reserved the quote asset at its own price, which may be higher.
+                let (_, reserved) = Self::order_reserve(&order, fill)?;
+                let change = reserved - quote_amount;
+                self.credit_order_owner(&buyer, &order.quote_asset, change, order_hash)?;
+            }
+
+            let remaining = resting.amount - fill;
+            if remaining == 0 {
+                self.order_book_mut(&opposite).remove(&resting_key);
+                self.orders_mut().remove(&resting_hash);
+                self.close_position(&resting.owner, &resting_hash);
+            } else {
+                self.orders_mut()
+                    .put(&resting_hash, resting.set_amount(remaining));
+            }
+            let remaining = order.amount - fill;
+            order = order.set_amount(remaining);
+        }
+
+        if order.amount > 0 {
+            self.order_book_mut(&order.book())
+                .put(&order.book_key(), *order_hash);
+            self.open_position(&order.owner, order_hash);
+            self.orders_mut().put(order_hash, order);
+        }
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with liquidity pools.
+    pub fn pools_mut(&mut self) -> ProofMapIndex<&mut Fork, String, Pool> {
+        ProofMapIndex::new("cryptocurrency.pools", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with liquidity pools as of the end of the previous block.
+    pub fn reference_pools_mut(&mut self) -> ProofMapIndex<&mut Fork, String, Pool> {
+        ProofMapIndex::new("cryptocurrency.reference_pools", &mut self.view)
     }
-}
 
-/// Implementation of mutable methods.
-impl<'a> Schema<&'a mut Fork> {
-    /// Returns mutable `ProofMapIndex` with wallets.
-    pub fn wallets_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Wallet> {
-        ProofMapIndex::new("cryptocurrency.wallets", &mut self.view)
+    /// Copy the liquidity pools to the reference pools used to price loan collateral
+    /// in the next block.
+    pub fn update_reference_pools(&mut self) {
+        let pools = self.pools().values().collect::<Vec<_>>();
+        let mut reference_pools = self.reference_pools_mut();
+        reference_pools.clear();
+        for pool in pools {
+            reference_pools.put(&pool.asset.clone(), pool);
+        }
     }
 
-    /// Returns history for the wallet by the given public key.
-    pub fn wallet_history_mut(
+    /// Returns mutable liquidity shares of the providers of the pool of the given asset.
+    pub fn pool_shares_mut(&mut self, asset: &str) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new_in_family(
+            "cryptocurrency.pool_shares",
+            &asset.to_owned(),
+            &mut self.view,
+        )
+    }
+
+    /// Returns mutable `ProofMapIndex` with staked coins of the wallets.
+    pub fn stakes_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, StakePosition> {
+        ProofMapIndex::new("cryptocurrency.stakes", &mut self.view)
+    }
+
+    /// Lock the amount of the wallet balance in its stake with the given validator.
+    pub fn stake_coins(
         &mut self,
-        public_key: &PublicKey,
-    ) -> ProofListIndex<&mut Fork, Hash> {
-        ProofListIndex::new_in_family("cryptocurrency.wallet_history", public_key, &mut self.view)
+        wallet: Wallet,
+        validator: &PublicKey,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let owner = wallet.pub_key;
+        let stake = match self.stake(&owner) {
+            Some(stake) => {
+                if stake.validator != *validator {
+                    Err(Error::StakeValidatorMismatch)?
+                }
+                stake
+            }
+            None => StakePosition::new(validator, 0),
+        };
+        let staked = stake
+            .amount
+            .checked_add(amount)
+            .ok_or(Error::BalanceOverflow)?;
+
+        if wallet.pending_balance < amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        self.debit_payment(wallet, DEFAULT_ASSET, amount, transaction)?;
+        self.stakes_mut().put(&owner, stake.set_amount(staked));
+        Ok(())
     }
 
-    /// Increase balance of the wallet and append new record to its history.
+    /// Pay the reward to the open wallet with the given public key, returns the paid amount.
     ///
-    /// Panics if there is no wallet with given public key.
-    pub fn increase_wallet_balance(&mut self, wallet: Wallet, amount: u64, transaction: &Hash) {
-        let wallet = {
-            let mut history = self.wallet_history_mut(&wallet.pub_key);
-            history.push(*transaction);
-            let history_hash = history.merkle_root();
-            let balance = wallet.balance;
-            wallet.set_balance(balance + amount, &history_hash)
+    /// The reward is recorded in `staking_payouts` of the wallet by the block height.
+    fn pay_reward(&mut self, key: &PublicKey, amount: u64, height: Height) -> u64 {
+        if amount == 0 {
+            return 0;
+        }
+        match self.wallet(key) {
+            Some(ref wallet) if !wallet.closed => {
+                if self.credit_payout(wallet.clone(), amount).is_err() {
+                    return 0;
+                }
+                let paid = self.staking_payouts(key).get(&height.0).unwrap_or_default();
+                self.staking_payouts_mut(key)
+                    .put(&height.0, paid.saturating_add(amount));
+                amount
+            }
+            _ => 0,
+        }
+    }
+
+    /// Issue `Config::staking_reward` and share it among the stakes in proportion to their
+    /// amounts.
+    ///
+    /// Validators get `Config::delegation_commission` percent of the rewards of the stakes
+    /// delegated to them. Nothing is issued if the reward would exceed the maximum supply.
+    pub fn distribute_staking_rewards(&mut self) {
+        let config = self.config();
+        let stakes = self.stakes().iter().collect::<Vec<_>>();
+        let total_staked = stakes
+            .iter()
+            .map(|(_, stake)| u128::from(stake.amount))
+            .sum::<u128>();
+        if config.staking_reward == 0 || total_staked == 0 {
+            return;
+        }
+        match self.issued_supply().checked_add(config.staking_reward) {
+            Some(issued) if config.allows_supply(issued) => {}
+            _ => return,
+        }
+
+        let height = self.current_height();
+        let commission_rate = u128::from(config.delegation_commission.min(100));
+        let mut paid = 0;
+        for (owner, stake) in stakes {
+            // Shares never exceed the reward, so they fit into `u64`.
+            let share = u128::from(config.staking_reward) * u128::from(stake.amount) / total_staked;
+            let commission = if stake.validator != owner && self.is_validator(&stake.validator) {
+                share * commission_rate / 100
+            } else {
+                0
+            };
+            paid += self.pay_reward(&owner, (share - commission) as u64, height);
+            paid += self.pay_reward(&stake.validator, commission as u64, height);
+        }
+
+        if paid > 0 && self.increase_issued_supply(paid).is_ok() {
+            let _ = self.increase_total_supply(paid);
+        }
+    }
+
+    /// Returns mutable `ProofMapIndex` with savings of the wallets.
+    pub fn savings_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Savings> {
+        ProofMapIndex::new("cryptocurrency.savings", &mut self.view)
+    }
+
+    /// Returns savings of the wallet with the given public key with the interest accrued
+    /// until the current height credited to them.
+    ///
+    /// The interest is issued as new currency and counts towards `Config::max_supply`.
+    pub fn accrue_savings(&mut self, owner: &PublicKey) -> Result<Savings, Error> {
+        let height = self.current_height().0;
+        let savings = match self.savings().get(owner) {
+            Some(savings) => savings,
+            None => return Ok(Savings::new(0, height)),
         };
-        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        let rate = self.config().savings_interest_rate;
+        let interest = savings
+            .interest_at(height, rate)
+            .ok_or(Error::BalanceOverflow)?;
+        let principal = savings
+            .principal
+            .checked_add(interest)
+            .ok_or(Error::BalanceOverflow)?;
+        if interest > 0 {
+            self.increase_issued_supply(interest)?;
+            self.increase_total_supply(interest)?;
+        }
+        Ok(Savings::new(principal, height))
     }
 
-    /// Decrease balance of the wallet and append new record to its history.
+    /// Returns mutable `ProofMapIndex` with open loans.
+    pub fn loans_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Loan> {
+        ProofMapIndex::new("cryptocurrency.loans", &mut self.view)
+    }
+
+    /// Burn the borrowed amount from the wallet, give it the collateral and close the loan.
+    pub fn close_loan(
+        &mut self,
+        wallet: Wallet,
+        loan_hash: &Hash,
+        loan: &Loan,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        if wallet.pending_balance < loan.amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        let wallet = self.decrease_wallet_balance(wallet, loan.amount, transaction)?;
+        self.decrease_total_supply(loan.amount)?;
+        self.decrease_issued_supply(loan.amount)?;
+        self.increase_asset_balance(wallet, &loan.asset, loan.collateral, transaction)?;
+        self.loans_mut().remove(loan_hash);
+        self.close_position(&loan.borrower, loan_hash);
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with loyalty points of the wallets.
+    pub fn loyalty_points_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.loyalty_points", &mut self.view)
+    }
+
+    /// Credit `Config::loyalty_rate` percent of the transferred amount to the sender
+    /// as loyalty points.
+    pub fn earn_loyalty_points(&mut self, pub_key: &PublicKey, amount: u64) -> Result<(), Error> {
+        let rate = self.config().loyalty_rate;
+        let earned = u128::from(amount) * u128::from(rate) / 100;
+        if earned == 0 {
+            return Ok(());
+        }
+        let points = u128::from(self.loyalty_balance(pub_key)) + earned;
+        if points > u128::from(u64::max_value()) {
+            Err(Error::BalanceOverflow)?
+        }
+        self.loyalty_points_mut().put(pub_key, points as u64);
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with proposed parameter changes.
+    pub fn param_changes_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, ParamChange> {
+        ProofMapIndex::new("cryptocurrency.param_changes", &mut self.view)
+    }
+
+    /// Returns mutable voting weights of the wallets for the given parameter change.
+    pub fn proposal_weights_mut(&mut self, proposal: &Hash) -> MapIndex<&mut Fork, PublicKey, u64> {
+        MapIndex::new_in_family("cryptocurrency.proposal_weights", proposal, &mut self.view)
+    }
+
+    /// Returns mutable hashes of the open parameter changes by the keys of their proposers.
+    pub fn open_proposals_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, Hash> {
+        MapIndex::new("cryptocurrency.open_proposals", &mut self.view)
+    }
+
+    /// Returns mutable hashes of the parameter changes expiring at the given height.
+    pub fn proposal_expirations_mut(&mut self, height: u64) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family(
+            "cryptocurrency.proposal_expirations",
+            &height,
+            &mut self.view,
+        )
+    }
+
+    /// Store the proposed parameter change along with the voting weights of the wallets.
+    pub fn open_param_change(
+        &mut self,
+        proposal: &Hash,
+        change: ParamChange,
+        weights: Vec<(PublicKey, u64)>,
+    ) {
+        let mut proposal_weights = self.proposal_weights_mut(proposal);
+        for (pub_key, weight) in weights {
+            proposal_weights.put(&pub_key, weight);
+        }
+        self.open_proposals_mut().put(&change.proposer, *proposal);
+        self.proposal_expirations_mut(change.expires_at)
+            .insert(*proposal);
+        self.param_changes_mut().put(proposal, change);
+    }
+
+    /// Remove the parameter change along with its voting weights, allowing its proposer
+    /// to propose another change.
+    pub fn close_param_change(&mut self, proposal: &Hash) {
+        let change = match self.param_changes().get(proposal) {
+            Some(change) => change,
+            None => return,
+        };
+        let proposer = self.current_key(&change.proposer);
+        if self.open_proposals().get(&proposer) == Some(*proposal) {
+            self.open_proposals_mut().remove(&proposer);
+        }
+        self.proposal_expirations_mut(change.expires_at)
+            .remove(proposal);
+        self.proposal_weights_mut(proposal).clear();
+        self.param_changes_mut().remove(proposal);
+    }
+
+    /// Remove the parameter changes which haven't reached the quorum by the given height.
+    pub fn expire_param_changes(&mut self, height: Height) {
+        let expired = self
+            .proposal_expirations(height.0)
+            .iter()
+            .collect::<Vec<_>>();
+        for proposal in expired {
+            self.close_param_change(&proposal);
+        }
+        self.proposal_expirations_mut(height.0).clear();
+    }
+
+    /// Returns mutable `Entry` with the flag of the read-only mode of the service.
+    pub fn halted_entry_mut(&mut self) -> Entry<&mut Fork, bool> {
+        Entry::new("cryptocurrency.halted", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with the height of the latest processed block.
+    pub fn last_processed_height_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.last_processed_height", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with heights of the latest faucet credits.
+    pub fn faucet_claims_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.faucet_claims", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with keys watched by their authors.
+    pub fn watch_lists_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, WatchList> {
+        ProofMapIndex::new("cryptocurrency.watch_lists", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with guardians of the wallets.
+    pub fn guardians_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Guardians> {
+        ProofMapIndex::new("cryptocurrency.guardians", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with pending recoveries.
+    pub fn recoveries_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Recovery> {
+        ProofMapIndex::new("cryptocurrency.recoveries", &mut self.view)
+    }
+
+    /// Returns mutable keys of the wallets with the recoveries scheduled for the given height.
+    pub fn recovery_schedule_mut(&mut self, height: u64) -> KeySetIndex<&mut Fork, PublicKey> {
+        KeySetIndex::new_in_family("cryptocurrency.recovery_schedule", &height, &mut self.view)
+    }
+
+    /// Store the recovery of the wallet, scheduling its completion if it is approved.
+    pub fn put_recovery(&mut self, wallet: &PublicKey, recovery: Recovery) {
+        if recovery.is_scheduled() {
+            self.recovery_schedule_mut(recovery.complete_at)
+                .insert(*wallet);
+        }
+        self.recoveries_mut().put(wallet, recovery);
+    }
+
+    /// Move the wallets with the recoveries scheduled for the given height to their new keys.
+    ///
+    /// Recoveries cancelled in the meantime are skipped, and the ones to the keys which
+    /// became used in the meantime are dropped.
+    pub fn complete_recoveries(&mut self, height: Height) {
+        let scheduled = self.recovery_schedule(height.0).iter().collect::<Vec<_>>();
+        self.recovery_schedule_mut(height.0).clear();
+        for key in scheduled {
+            let recovery = match self.recovery(&key) {
+                Some(recovery) => recovery,
+                None => continue,
+            };
+            if recovery.complete_at != height.0 {
+                continue;
+            }
+            self.recoveries_mut().remove(&key);
+            let new_key = recovery.new_key;
+            if self.wallet(&new_key).is_some() || self.rotated_keys().contains(&new_key) {
+                continue;
+            }
+            if self.multisig_wallet(&key).is_some() {
+                continue;
+            }
+            if let Some(wallet) = self.wallet(&key) {
+                self.rotate_wallet_key(wallet, &new_key);
+            }
+        }
+    }
+
+    /// Returns mutable `ProofMapIndex` with blacklisted keys.
+    pub fn blacklist_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new("cryptocurrency.blacklist", &mut self.view)
+    }
+
+    /// Returns mutable `Entry` with collected fees.
+    pub fn fee_pool_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("cryptocurrency.fee_pool", &mut self.view)
+    }
+
+    /// Charge the fee from the wallet and add it to the fee pool.
+    pub fn charge_fee(
+        &mut self,
+        wallet: Wallet,
+        fee: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        if wallet.pending_balance < fee {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        let fee_pool = self
+            .fee_pool()
+            .checked_add(fee)
+            .ok_or(Error::BalanceOverflow)?;
+        self.fee_pool_entry_mut().set(fee_pool);
+        self.decrease_wallet_balance(wallet, fee, transaction)
+    }
+
+    /// Split the fee pool equally between the open wallets listed in `Config::fee_recipients`,
+    /// or burn it if `Config::burn_fees` is set.
+    ///
+    /// The payouts are recorded in `fee_payouts` of the recipients by block heights rather than
+    /// in their histories. The remainder of the division stays in the pool until the next distribution.
+    pub fn distribute_fees(&mut self) {
+        let fee_pool = self.fee_pool();
+        if self.config().burn_fees {
+            if self.decrease_total_supply(fee_pool).is_ok() {
+                self.fee_pool_entry_mut().set(0);
+            }
+            return;
+        }
+
+        let recipients = self
+            .config()
+            .fee_recipients
+            .into_iter()
+            .filter(|key| self.wallet(key).map_or(false, |wallet| !wallet.closed))
+            .collect::<Vec<_>>();
+        if fee_pool == 0 || recipients.is_empty() {
+            return;
+        }
+
+        let share = fee_pool / recipients.len() as u64;
+        if share == 0 {
+            return;
+        }
+        let height = self.current_height().0;
+        let mut distributed = 0;
+        for key in &recipients {
+            if let Some(wallet) = self.wallet(key) {
+                if self.credit_payout(wallet, share).is_ok() {
+                    let paid = self.fee_payouts(key).get(&height).unwrap_or_default();
+                    self.fee_payouts_mut(key).put(&height, paid + share);
+                    distributed += share;
+                }
+            }
+        }
+        self.fee_pool_entry_mut().set(fee_pool - distributed);
+    }
+
+    /// Returns mutable fees paid to the wallet by heights of the blocks.
+    pub fn fee_payouts_mut(&mut self, pub_key: &PublicKey) -> MapIndex<&mut Fork, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.fee_payouts", pub_key, &mut self.view)
+    }
+
+    /// Returns mutable staking rewards paid to the wallet by heights of the blocks.
+    pub fn staking_payouts_mut(&mut self, pub_key: &PublicKey) -> MapIndex<&mut Fork, u64, u64> {
+        MapIndex::new_in_family("cryptocurrency.staking_payouts", pub_key, &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with user-defined assets.
+    pub fn assets_mut(&mut self) -> ProofMapIndex<&mut Fork, String, Asset> {
+        ProofMapIndex::new("cryptocurrency.assets", &mut self.view)
+    }
+
+    /// Register new asset issued by the given key.
+    pub fn define_asset(&mut self, code: &str, decimals: u32, issuer: &PublicKey, max_supply: u64) {
+        let asset = Asset::new(code, decimals, issuer, max_supply, 0);
+        self.assets_mut().put(&code.to_owned(), asset);
+    }
+
+    /// Increase the issued amount of the asset, respecting its maximum supply.
+    ///
+    /// Fails with `AssetNotFound` if there is no asset with the given code.
+    pub fn increase_asset_issued(&mut self, code: &str, amount: u64) -> Result<(), Error> {
+        let asset = self.asset(code).ok_or(Error::AssetNotFound)?;
+        let issued = asset
+            .issued
+            .checked_add(amount)
+            .ok_or(Error::SupplyCapExceeded)?;
+        if !asset.allows_supply(issued) {
+            Err(Error::SupplyCapExceeded)?
+        }
+        let asset = asset.set_issued(issued);
+        self.assets_mut().put(&code.to_owned(), asset);
+        Ok(())
+    }
+
+    /// Returns mutable allowances given by the wallet with the given public key to other wallets.
+    pub fn allowances_mut(
+        &mut self,
+        owner: &PublicKey,
+    ) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new_in_family("cryptocurrency.allowances", owner, &mut self.view)
+    }
+
+    /// Set amount of currency `spender` is allowed to transfer from the `owner` wallet.
+    pub fn set_allowance(&mut self, owner: &PublicKey, spender: &PublicKey, amount: u64) {
+        let mut allowances = self.allowances_mut(owner);
+        if amount == 0 {
+            allowances.remove(spender);
+        } else {
+            allowances.put(spender, amount);
+        }
+    }
+
+    /// Returns mutable `ProofMapIndex` with heights the used permits were used at.
+    pub fn used_permits_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, u64> {
+        ProofMapIndex::new("cryptocurrency.used_permits", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with owners of the non-fungible tokens.
+    pub fn nft_owners_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, PublicKey> {
+        ProofMapIndex::new("cryptocurrency.nft_owners", &mut self.view)
+    }
+
+    /// Returns mutable identifiers of the non-fungible tokens owned by the given wallet.
+    pub fn owned_nfts_mut(&mut self, owner: &PublicKey) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.owned_nfts", owner, &mut self.view)
+    }
+
+    /// Record the new owner of the non-fungible token, moving it from the previous one.
+    pub fn set_nft_owner(&mut self, token: &Hash, owner: &PublicKey) {
+        if let Some(previous) = self.nft_owner(token) {
+            self.owned_nfts_mut(&previous).remove(token);
+        }
+        self.owned_nfts_mut(owner).insert(*token);
+        self.nft_owners_mut().put(token, *owner);
+    }
+
+    /// Returns mutable `ProofMapIndex` with multisign wallets.
+    pub fn multisig_wallets_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, MultisigWallet> {
+        ProofMapIndex::new("cryptocurrency.multisig_wallets", &mut self.view)
+    }
+
+    /// Returns mutable multisign wallets linked to the wallet with the given public key.
+    pub fn linked_multisig_wallets_mut(
+        &mut self,
+        owner: &PublicKey,
+    ) -> ProofListIndex<&mut Fork, PublicKey> {
+        ProofListIndex::new_in_family(
+            "cryptocurrency.linked_multisig_wallets",
+            owner,
+            &mut self.view,
+        )
+    }
+
+    /// Link the owner's wallet to the multisign wallet.
+    pub fn link_wallet(&mut self, owner: &PublicKey, multisig_wallet: &PublicKey) {
+        self.linked_multisig_wallets_mut(owner).push(*multisig_wallet);
+    }
+
+    /// Returns mutable `ProofMapIndex` with pending changes of multisign wallet owners.
+    pub fn approvers_changes_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, ApproversChange> {
+        ProofMapIndex::new("cryptocurrency.approvers_changes", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with pending multisign transfers.
+    pub fn pending_transfers_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, PendingTransfer> {
+        ProofMapIndex::new("cryptocurrency.pending_transfers", &mut self.view)
+    }
+
+    /// Returns mutable approvals of the pending multisign transfer with the given hash.
+    pub fn multisig_approvals_mut(
+        &mut self,
+        tx_hash: &Hash,
+    ) -> ProofListIndex<&mut Fork, PublicKey> {
+        ProofListIndex::new_in_family("cryptocurrency.multisig_approvals", tx_hash, &mut self.view)
+    }
+
+    /// Register pending multisign transfer and reserve its amount on the sender's wallet.
+    pub fn create_pending_transfer(
+        &mut self,
+        sender: Wallet,
+        transfer: PendingTransfer,
+        tx_hash: &Hash,
+    ) -> Result<Wallet, Error> {
+        let sender = self.reserve_payment(sender, tx_hash, transfer.amount)?;
+        self.emit_event(ExecutionEvent::PendingTransferCreated {
+            transfer: *tx_hash,
+            wallet: transfer.from,
+            amount: transfer.amount,
+        });
+        self.pending_transfers_mut().put(tx_hash, transfer);
+        Ok(sender)
+    }
+
+    /// Record approval of the pending multisign transfer.
+    pub fn add_multisig_approval(&mut self, tx_hash: &Hash, approver: &PublicKey) {
+        self.multisig_approvals_mut(tx_hash).push(*approver);
+        self.emit_event(ExecutionEvent::TransferApproved {
+            transfer: *tx_hash,
+            approver: *approver,
+        });
+    }
+
+    /// Execute pending multisign transfer and drop its approvals.
+    ///
+    /// The reserved amount is returned to the pending balance of the sender
+    /// and then charged together with the balance; it was already counted towards
+    /// the sender's limits when reserved.
+    ///
+    /// Panics if there is no pending transfer with given hash.
+    pub fn release_multisig_transfer(
+        &mut self,
+        sender: Wallet,
+        receiver: Wallet,
+        tx_hash: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .pending_transfer(tx_hash)
+            .expect("Pending transfer doesn't exist")
+            .amount;
+        let sender = self.remove_tx_from_wallet(sender, tx_hash);
+        let sender = self.increase_wallet_pending_balance(sender, amount)?;
+        self.decrease_wallet_balance(sender, amount, tx_hash)?;
+        self.credit_payment(receiver, DEFAULT_ASSET, amount, tx_hash)?;
+        self.pending_transfers_mut().remove(tx_hash);
+        self.multisig_approvals_mut(tx_hash).clear();
+        Ok(())
+    }
+
+    /// Cancel pending multisign transfer and restore the reserved funds.
+    ///
+    /// Panics if there is no pending transfer with given hash.
+    pub fn cancel_multisig_transfer(
+        &mut self,
+        sender: Wallet,
+        tx_hash: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .pending_transfer(tx_hash)
+            .expect("Pending transfer doesn't exist")
+            .amount;
+        let sender = self.remove_tx_from_wallet(sender, tx_hash);
+        self.increase_wallet_pending_balance(sender, amount)?;
+        self.pending_transfers_mut().remove(tx_hash);
+        self.multisig_approvals_mut(tx_hash).clear();
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with escrows.
+    pub fn escrows_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Escrow> {
+        ProofMapIndex::new("cryptocurrency.escrows", &mut self.view)
+    }
+
+    /// Reserve the amount on the wallet and add the given hash to its pending txs.
+    pub fn reserve_funds(
+        &mut self,
+        wallet: Wallet,
+        tx_hash: &Hash,
+        amount: u64,
+    ) -> Result<Wallet, Error> {
+        let wallet = self.add_tx_to_wallet(wallet, tx_hash);
+        self.decrease_wallet_pending_balance(wallet, amount)
+    }
+
+    /// Return the reserved amount to the pending balance of the wallet and remove
+    /// the given hash from its pending txs.
+    pub fn unreserve_funds(
+        &mut self,
+        wallet: Wallet,
+        tx_hash: &Hash,
+        amount: u64,
+    ) -> Result<Wallet, Error> {
+        let wallet = self.remove_tx_from_wallet(wallet, tx_hash);
+        self.increase_wallet_pending_balance(wallet, amount)
+    }
+
+    /// Register escrow and reserve its amount on the sender's wallet.
+    pub fn create_escrow(
+        &mut self,
+        sender: Wallet,
+        escrow: Escrow,
+        escrow_hash: &Hash,
+    ) -> Result<Wallet, Error> {
+        let sender = self.reserve_payment(sender, escrow_hash, escrow.amount)?;
+        let sender = self.append_wallet_history(sender, escrow_hash);
+        self.escrows_mut().put(escrow_hash, escrow);
+        Ok(sender)
+    }
+
+    /// Transfer the funds locked in escrow to the receiver.
+    ///
+    /// The amount was counted towards the sender's limits when the escrow was created.
+    ///
+    /// Panics if there is no escrow with given hash.
+    pub fn release_escrow(
+        &mut self,
+        sender: Wallet,
+        receiver: Wallet,
+        escrow_hash: &Hash,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .escrow(escrow_hash)
+            .expect("Escrow doesn't exist")
+            .amount;
+        let sender = self.unreserve_funds(sender, escrow_hash, amount)?;
+        self.decrease_wallet_balance(sender, amount, transaction)?;
+        self.credit_payment(receiver, DEFAULT_ASSET, amount, transaction)?;
+        self.escrows_mut().remove(escrow_hash);
+        Ok(())
+    }
+
+    /// Return the funds locked in escrow to the sender.
+    ///
+    /// Panics if there is no escrow with given hash.
+    pub fn refund_escrow(
+        &mut self,
+        sender: Wallet,
+        escrow_hash: &Hash,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .escrow(escrow_hash)
+            .expect("Escrow doesn't exist")
+            .amount;
+        let sender = self.unreserve_funds(sender, escrow_hash, amount)?;
+        self.append_wallet_history(sender, transaction);
+        self.escrows_mut().remove(escrow_hash);
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with hash time-locked transfers.
+    pub fn hash_locks_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, HashLock> {
+        ProofMapIndex::new("cryptocurrency.hash_locks", &mut self.view)
+    }
+
+    /// Register hash time-locked transfer and reserve its amount on the sender's wallet.
+    pub fn create_hash_lock(
+        &mut self,
+        sender: Wallet,
+        lock: HashLock,
+        hash_lock: &Hash,
+        transaction: &Hash,
+    ) -> Result<Wallet, Error> {
+        let sender = self.reserve_payment(sender, hash_lock, lock.amount)?;
+        let sender = self.append_wallet_history(sender, transaction);
+        self.hash_locks_mut().put(hash_lock, lock);
+        Ok(sender)
+    }
+
+    /// Transfer the funds locked with the hash to the receiver.
+    ///
+    /// The amount was counted towards the sender's limits when the funds were locked.
+    ///
+    /// Panics if there is no hash lock with given hash.
+    pub fn claim_hash_lock(
+        &mut self,
+        sender: Wallet,
+        receiver: Wallet,
+        hash_lock: &Hash,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .hash_lock(hash_lock)
+            .expect("Hash lock doesn't exist")
+            .amount;
+        let sender = self.unreserve_funds(sender, hash_lock, amount)?;
+        self.decrease_wallet_balance(sender, amount, transaction)?;
+        self.credit_payment(receiver, DEFAULT_ASSET, amount, transaction)?;
+        self.hash_locks_mut().remove(hash_lock);
+        Ok(())
+    }
+
+    /// Return the funds locked with the hash to the sender.
+    ///
+    /// Panics if there is no hash lock with given hash.
+    pub fn refund_hash_lock(
+        &mut self,
+        sender: Wallet,
+        hash_lock: &Hash,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let amount = self
+            .hash_lock(hash_lock)
+            .expect("Hash lock doesn't exist")
+            .amount;
+        let sender = self.unreserve_funds(sender, hash_lock, amount)?;
+        self.append_wallet_history(sender, transaction);
+        self.hash_locks_mut().remove(hash_lock);
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with time-locked funds.
+    pub fn time_locks_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, TimeLock> {
+        ProofMapIndex::new("cryptocurrency.time_locks", &mut self.view)
+    }
+
+    /// Lock the funds credited to the wallet by the given transaction until the unlock height.
+    pub fn lock_funds(
+        &mut self,
+        wallet: Wallet,
+        tx_hash: &Hash,
+        amount: u64,
+        unlock_at: u64,
+    ) -> Result<Wallet, Error> {
+        let lock = TimeLock::new(&wallet.pub_key, amount, unlock_at);
+        let wallet = self.reserve_funds(wallet, tx_hash, amount)?;
+        self.time_lock_schedule_mut(unlock_at).insert(*tx_hash);
+        self.time_locks_mut().put(tx_hash, lock);
+        Ok(wallet)
+    }
+
+    /// Returns mutable hashes of the time locks maturing at the given height.
+    pub fn time_lock_schedule_mut(&mut self, height: u64) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.time_lock_schedule", &height, &mut self.view)
+    }
+
+    /// Unlock the time-locked funds maturing at the given height.
+    ///
+    /// Locks which can't be released, e.g. of a removed wallet, are dropped.
+    pub fn unlock_matured_funds(&mut self, height: Height) {
+        let matured = self.time_lock_schedule(height.0).iter().collect::<Vec<_>>();
+        for tx_hash in matured {
+            let lock = match self.time_locks().get(&tx_hash) {
+                Some(lock) => lock,
+                None => continue,
+            };
+            if let Some(wallet) = self.current_wallet(&lock.owner) {
+                self.unreserve_funds(wallet, &tx_hash, lock.amount).ok();
+            }
+            self.time_locks_mut().remove(&tx_hash);
+        }
+        self.time_lock_schedule_mut(height.0).clear();
+    }
+
+    /// Returns mutable `ProofMapIndex` with standing orders.
+    pub fn standing_orders_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, StandingOrder> {
+        ProofMapIndex::new("cryptocurrency.standing_orders", &mut self.view)
+    }
+
+    /// Make payments of the standing orders due at the given height.
+    ///
+    /// Each payment is recorded in the wallet histories under the hash of the order.
+    /// Orders which can't be paid or scheduled any further are removed.
+    pub fn execute_standing_orders(&mut self, height: Height) {
+        let due = self
+            .standing_orders()
+            .iter()
+            .filter(|(_, order)| order.next_at <= height.0)
+            .collect::<Vec<_>>();
+        for (order_hash, order) in due {
+            let paid = self
+                .transfer_scheduled(&order.from, &order.to, order.amount, &order_hash)
+                .is_ok();
+            match order.reschedule() {
+                Some(order) if paid => self.standing_orders_mut().put(&order_hash, order),
+                _ => self.standing_orders_mut().remove(&order_hash),
+            }
+        }
+    }
+
+    /// Transfer funds on behalf of the sender outside of a transaction, e.g. while
+    /// committing a block.
+    ///
+    /// The transfer is recorded in the wallet histories under the given hash.
+    /// Nothing is changed if the transfer fails.
+    fn transfer_scheduled(
+        &mut self,
+        from: &PublicKey,
+        to: &PublicKey,
+        amount: u64,
+        record: &Hash,
+    ) -> Result<(), Error> {
+        let sender = self.current_wallet(from).ok_or(Error::SenderNotFound)?;
+        let receiver = self.current_wallet(to).ok_or(Error::ReceiverNotFound)?;
+        if self.is_blacklisted(&sender.pub_key) || self.is_blacklisted(&receiver.pub_key) {
+            Err(Error::Blacklisted)?
+        }
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+        if !self.accepts_from(&receiver.pub_key, &sender.pub_key) {
+            Err(Error::NotWhitelisted)?
+        }
+        if sender.pending_balance < amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        if receiver.balance.checked_add(amount).is_none()
+            || receiver.pending_balance.checked_add(amount).is_none()
+        {
+            Err(Error::BalanceOverflow)?
+        }
+        self.check_incoming_payment(&receiver, amount)?;
+        // All the checks are done above, since there is no rollback outside of a transaction;
+        // a payment over the sender's limits fails before changing anything.
+        self.debit_payment(sender, DEFAULT_ASSET, amount, record)?;
+        let receiver = self.current_wallet(to).ok_or(Error::ReceiverNotFound)?;
+        self.credit_payment(receiver, DEFAULT_ASSET, amount, record)?;
+        Ok(())
+    }
+
+    /// Returns mutable `ProofMapIndex` with payment streams.
+    pub fn streams_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Stream> {
+        ProofMapIndex::new("cryptocurrency.streams", &mut self.view)
+    }
+
+    /// Returns mutable `ProofMapIndex` with scheduled transfers.
+    pub fn scheduled_transfers_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, ScheduledTransfer> {
+        ProofMapIndex::new("cryptocurrency.scheduled_transfers", &mut self.view)
+    }
+
+    /// Returns mutable hashes of the transfers scheduled for the given height.
+    pub fn transfer_schedule_mut(&mut self, height: u64) -> KeySetIndex<&mut Fork, Hash> {
+        KeySetIndex::new_in_family("cryptocurrency.transfer_schedule", &height, &mut self.view)
+    }
+
+    /// Store the transfer to be executed at its height.
+    pub fn schedule_transfer(&mut self, transfer: ScheduledTransfer, tx_hash: &Hash) {
+        let at_height = transfer.at_height;
+        self.transfer_schedule_mut(at_height).insert(*tx_hash);
+        self.scheduled_transfers_mut().put(tx_hash, transfer);
+    }
+
+    /// Execute the transfers scheduled for the given height.
+    ///
+    /// Each transfer is recorded in the wallet histories under the hash of its transaction.
+    /// Transfers which can't be made are dropped.
+    pub fn execute_scheduled_transfers(&mut self, height: Height) {
+        let due = self.transfer_schedule(height.0).iter().collect::<Vec<_>>();
+        for tx_hash in due {
+            if let Some(transfer) = self.scheduled_transfers().get(&tx_hash) {
+                // Failed transfers are dropped along with the successful ones.
+                self.transfer_scheduled(&transfer.from, &transfer.to, transfer.amount, &tx_hash)
+                    .ok();
+            }
+            self.scheduled_transfers_mut().remove(&tx_hash);
+        }
+        self.transfer_schedule_mut(height.0).clear();
+    }
+
+    /// Make payments of the streams opened before the given height.
+    ///
+    /// Each payment is recorded in the wallet histories under the hash of the stream.
+    /// Streams which can't be paid are closed.
+    pub fn execute_streams(&mut self, height: Height) {
+        let active = self
+            .streams()
+            .iter()
+            .filter(|(_, stream)| stream.opened_at < height.0)
+            .collect::<Vec<_>>();
+        for (stream_hash, stream) in active {
+            let paid = self.transfer_scheduled(
+                &stream.from,
+                &stream.to,
+                stream.rate_per_block,
+                &stream_hash,
+            );
+            if paid.is_err() {
+                self.streams_mut().remove(&stream_hash);
+            }
+        }
+    }
+
+    /// Returns mutable `ProofMapIndex` with vesting schedules.
+    pub fn vestings_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Vesting> {
+        ProofMapIndex::new("cryptocurrency.vestings", &mut self.view)
+    }
+
+    /// Release the vested but not yet claimed funds to the beneficiary.
+    ///
+    /// Returns the released amount.
+    pub fn claim_vesting(
+        &mut self,
+        beneficiary: Wallet,
+        vesting_hash: &Hash,
+        transaction: &Hash,
+    ) -> Result<u64, Error> {
+        let vesting = self.vesting(vesting_hash).ok_or(Error::VestingNotFound)?;
+        let amount = vesting.claimable_at(self.current_height().0);
+        if amount == 0 {
+            Err(Error::NothingToClaim)?
+        }
+        let claimed = vesting.claimed + amount;
+        self.increase_wallet_balance(beneficiary, amount, transaction)?;
+        self.increase_total_supply(amount)?;
+        if claimed == vesting.amount {
+            self.close_position(&vesting.beneficiary, vesting_hash);
+        }
+        self.vestings_mut()
+            .put(vesting_hash, vesting.set_claimed(claimed));
+        Ok(amount)
+    }
+
+    /// Append new record to the wallet history without changing its balance.
     ///
     /// Panics if there is no wallet with given public key.
-    pub fn decrease_wallet_balance(&mut self, wallet: Wallet, amount: u64, transaction: &Hash) {
+    pub fn append_wallet_history(&mut self, wallet: Wallet, transaction: &Hash) -> Wallet {
         let wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
             let history_hash = history.merkle_root();
             let balance = wallet.balance;
-            wallet.set_balance(balance - amount, &history_hash)
+            wallet.set_balance(balance, &history_hash)
         };
+        self.wallet_history_balances_mut(&wallet.pub_key)
+            .push(wallet.balance);
         self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        wallet
     }
 
-    /// Decrease pending balance of the wallet.
+    /// Prune the wallet history records older than `history_retention` blocks.
     ///
-    /// Panics if there is no wallet with given public key.
-    pub fn decrease_wallet_pending_balance(&mut self, wallet: Wallet, amount: u64) {
-        let wallet = {
-            let balance = wallet.balance;
-            wallet.set_pending_balance(balance - amount)
+    /// A pass over the wallets starts every `HISTORY_PRUNING_INTERVAL` blocks and prunes
+    /// up to `HISTORY_PRUNING_BATCH` wallets per block, continuing in the next blocks
+    /// until all of them are pruned. Does nothing in the archive mode.
+    pub fn prune_histories(&mut self, height: Height) {
+        let retention = self.config().history_retention;
+        let cursor = self.history_pruning_cursor().get();
+        if retention == 0 || height.0 <= retention {
+            return;
+        }
+        if cursor.is_none() && height.0 % HISTORY_PRUNING_INTERVAL != 0 {
+            return;
+        }
+        let horizon = Height(height.0 - retention);
+        // One more key is read to find where the next block continues from.
+        let mut keys = match cursor {
+            Some(cursor) => self
+                .wallets()
+                .keys_from(&cursor)
+                .take(HISTORY_PRUNING_BATCH + 1)
+                .collect::<Vec<_>>(),
+            None => self
+                .wallets()
+                .keys()
+                .take(HISTORY_PRUNING_BATCH + 1)
+                .collect::<Vec<_>>(),
         };
-        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        let next = keys.get(HISTORY_PRUNING_BATCH).cloned();
+        keys.truncate(HISTORY_PRUNING_BATCH);
+        for key in keys {
+            self.prune_wallet_history(&key, horizon);
+            self.prune_payouts(&key, horizon);
+        }
+        match next {
+            Some(next) => self.history_pruning_cursor_mut().set(next),
+            None => self.history_pruning_cursor_mut().remove(),
+        }
     }
 
-    /// Add pending transfer to multisign wallet.
+    /// Remove the payouts to the wallet made below the `horizon`.
+    pub fn prune_payouts(&mut self, key: &PublicKey, horizon: Height) {
+        let pruned_fees = self
+            .fee_payouts(key)
+            .keys()
+            .take_while(|&height| height < horizon.0)
+            .collect::<Vec<_>>();
+        let mut fee_payouts = self.fee_payouts_mut(key);
+        for height in pruned_fees {
+            fee_payouts.remove(&height);
+        }
+
+        let pruned_rewards = self
+            .staking_payouts(key)
+            .keys()
+            .take_while(|&height| height < horizon.0)
+            .collect::<Vec<_>>();
+        let mut staking_payouts = self.staking_payouts_mut(key);
+        for height in pruned_rewards {
+            staking_payouts.remove(&height);
+        }
+    }
+
+    /// Remove the records of the transactions committed below the `horizon` from
+    /// the beginning of the wallet history.
+    ///
+    /// The Merkle root of the history before the pruning is kept in `pruned_histories`
+    /// and the wallet gets the length and root of the retained records.
+    pub fn prune_wallet_history(&mut self, key: &PublicKey, horizon: Height) {
+        let (pruned, retained, balances, history_hash) = {
+            let history = self.wallet_history(key);
+            let pruned = history
+                .iter()
+                .take_while(|record| {
+                    self.transaction_height(record)
+                        .map_or(false, |height| height < horizon)
+                })
+                .count() as u64;
+            if pruned == 0 {
+                return;
+            }
+            let history_balances = self.wallet_history_balances(key);
+            // Records appended before the balances were tracked have no balance.
+            let untracked = history.len().saturating_sub(history_balances.len());
+            (
+                pruned,
+                history.iter_from(pruned).collect::<Vec<_>>(),
+                history_balances
+                    .iter_from(pruned.saturating_sub(untracked))
+                    .collect::<Vec<_>>(),
+                history.merkle_root(),
+            )
+        };
+
+        let (retained_len, retained_hash) = {
+            let mut history = self.wallet_history_mut(key);
+            history.clear();
+            history.extend(retained);
+            (history.len(), history.merkle_root())
+        };
+        {
+            let mut history_balances = self.wallet_history_balances_mut(key);
+            history_balances.clear();
+            history_balances.extend(balances);
+        }
+
+        let pruned_len = self
+            .pruned_history(key)
+            .map_or(0, |record| record.pruned_len);
+        self.pruned_histories_mut()
+            .put(key, PrunedHistory::new(pruned_len + pruned, &history_hash));
+        if let Some(wallet) = self.wallet(key) {
+            self.wallets_mut()
+                .put(key, wallet.set_history(retained_len, &retained_hash));
+        }
+    }
+
+    /// Freeze or unfreeze outgoing transfers from the wallet.
     ///
     /// Panics if there is no wallet with given public key.
-    pub fn add_tx_to_wallet(&mut self, wallet: Wallet, tx_hash: &Hash) -> Wallet {
-        let wallet = wallet.add_pending_tx(tx_hash);
+    pub fn set_wallet_frozen(&mut self, wallet: Wallet, frozen: bool) -> Wallet {
+        let wallet = wallet.set_frozen(frozen);
         self.wallets_mut().put(&wallet.pub_key, wallet.clone());
         wallet
     }
 
-    /// Remove pending transfer from multisign wallet.
+    /// Set the KYC verification flag of the wallet.
+    pub fn set_wallet_kyc_verified(&mut self, wallet: Wallet, kyc_verified: bool) -> Wallet {
+        let wallet = wallet.set_kyc_verified(kyc_verified);
+        self.wallets_mut().put(&wallet.pub_key, wallet.clone());
+        wallet
+    }
+
+    /// Mark the wallet as closed.
     ///
     /// Panics if there is no wallet with given public key.
-    pub fn remove_tx_from_wallet(&mut self, wallet: Wallet, tx_hash: &Hash) -> Wallet {
-        let wallet = wallet.delete_pending_tx(tx_hash);
+    pub fn close_wallet(&mut self, wallet: Wallet) -> Wallet {
+        let wallet = wallet.set_closed();
         self.wallets_mut().put(&wallet.pub_key, wallet.clone());
         wallet
     }
 
+    /// Apply pending change of multisign wallet owners and record it
+    /// in the wallet history.
+    ///
+    /// Panics if there is no pending change with given hash.
+    pub fn apply_approvers_change(&mut self, tx_hash: &Hash) {
+        let change = self
+            .approvers_change(tx_hash)
+            .expect("Approvers change doesn't exist");
+        let key = change.multisig_wallet;
+        let wallet = self.wallet(&key).expect("Wallet doesn't exist");
+        self.append_wallet_history(wallet, tx_hash);
+        let multisig = MultisigWallet::new(&key, &change.owners, change.quorum);
+        self.multisig_wallets_mut().put(&key, multisig);
+        self.approvers_changes_mut().remove(tx_hash);
+        self.multisig_approvals_mut(tx_hash).clear();
+    }
+
     /// Create new wallet and append first record to its history.
-    pub fn create_wallet(&mut self, key: &PublicKey, name: &str, transaction: &Hash) {
+    pub fn create_wallet(
+        &mut self,
+        key: &PublicKey,
+        name: &str,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        let initial_balance = self.config().initial_balance;
         let wallet = {
             let mut history = self.wallet_history_mut(key);
             history.push(*transaction);
             let history_hash = history.merkle_root();
-            let pending_txs = Vec::new();
-            Wallet::new(key, name, INITIAL_BALANCE, INITIAL_BALANCE, &pending_txs, history.len(), &history_hash)
+            Wallet::new(
+                key,
+                name,
+                initial_balance,
+                initial_balance,
+                history.len(),
+                &history_hash,
+                &Hash::zero(),
+                false,
+                false,
+                false,
+            )
         };
         self.wallets_mut().put(key, wallet);
+        self.wallet_history_balances_mut(key).push(initial_balance);
+        if initial_balance > 0 {
+            self.emit_balance_change(key, DEFAULT_ASSET, 0, initial_balance);
+        }
+        if self.wallet_by_name(name).is_none() {
+            self.wallet_names_mut().put(&name.to_owned(), *key);
+        }
+        let wallet_count = self.wallet_count();
+        self.wallet_count_entry_mut().set(wallet_count + 1);
+        self.increase_total_supply(initial_balance)
+    }
+
+    /// Create new wallet with the given balance and an empty history at genesis.
+    ///
+    /// Keys already having a wallet are skipped.
+    pub fn create_genesis_wallet(
+        &mut self,
+        key: &PublicKey,
+        name: &str,
+        balance: u64,
+    ) -> Result<(), Error> {
+        if self.wallet(key).is_some() {
+            return Ok(());
+        }
+        let history_hash = self.wallet_history(key).merkle_root();
+        let wallet = Wallet::new(
+            key,
+            name,
+            balance,
+            balance,
+            0,
+            &history_hash,
+            &Hash::zero(),
+            false,
+            false,
+            false,
+        );
+        self.wallets_mut().put(key, wallet);
+        if self.wallet_by_name(name).is_none() {
+            self.wallet_names_mut().put(&name.to_owned(), *key);
+        }
+        let wallet_count = self.wallet_count();
+        self.wallet_count_entry_mut().set(wallet_count + 1);
+        self.increase_total_supply(balance)
+    }
+
+    /// Create new multisign wallet together with the wallet holding its balance.
+    pub fn create_multisig_wallet(
+        &mut self,
+        key: &PublicKey,
+        name: &str,
+        owners: &[PublicKey],
+        quorum: u32,
+        transaction: &Hash,
+    ) -> Result<(), Error> {
+        self.create_wallet(key, name, transaction)?;
+        let wallet = MultisigWallet::new(key, owners, quorum);
+        self.multisig_wallets_mut().put(key, wallet);
+        Ok(())
     }
 }
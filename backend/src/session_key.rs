@@ -0,0 +1,48 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Short-lived session keys of the wallets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Secondary key allowed to transfer small amounts from the wallet until it expires,
+/// stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SessionKey", serde_pb_convert)]
+pub struct SessionKey {
+    /// `PublicKey` of the wallet.
+    pub wallet: PublicKey,
+    /// Maximum amount of currency the key can transfer at once.
+    pub max_amount: u64,
+    /// Height of the block starting from which the key expires.
+    pub expires_at: u64,
+}
+
+impl SessionKey {
+    /// Create new SessionKey.
+    pub fn new(&wallet: &PublicKey, max_amount: u64, expires_at: u64) -> Self {
+        Self {
+            wallet,
+            max_amount,
+            expires_at,
+        }
+    }
+
+    /// Checks whether the key is expired at the given height.
+    pub fn is_expired_at(&self, height: u64) -> bool {
+        height >= self.expires_at
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Portable snapshots of the service state used for migrations and backups.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use exonum::{
+    blockchain,
+    crypto::{Hash, PublicKey},
+    helpers::Height,
+    storage::{Fork, Snapshot},
+};
+use serde_json;
+
+use asset::Asset;
+use config::Config;
+use multisig_wallet::{ApproversChange, MultisigWallet};
+use pending_transfer::PendingTransfer;
+use wallet::Wallet;
+use Schema;
+
+/// Version of the snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Wallet with its pending transactions, balances in the assets and linked
+/// multisign wallets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletSnapshot {
+    /// Wallet record.
+    pub wallet: Wallet,
+    /// Hashes of the pending transactions of the wallet.
+    pub pending_txs: Vec<Hash>,
+    /// Balances in the assets other than the default one by their codes.
+    pub asset_balances: BTreeMap<String, u64>,
+    /// Multisign wallets the wallet owner is linked to.
+    pub linked_multisig_wallets: Vec<PublicKey>,
+}
+
+/// Pending multisign transfer with its approvals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransferSnapshot {
+    /// Hash of the transaction that created the transfer.
+    pub tx_hash: Hash,
+    /// Pending transfer.
+    pub transfer: PendingTransfer,
+    /// Keys that approved the transfer.
+    pub approvals: Vec<PublicKey>,
+}
+
+/// Pending change of multisign wallet owners with its approvals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApproversChangeSnapshot {
+    /// Hash of the transaction that proposed the change.
+    pub tx_hash: Hash,
+    /// Pending change.
+    pub change: ApproversChange,
+    /// Keys that approved the change.
+    pub approvals: Vec<PublicKey>,
+}
+
+/// Counters of the service.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Counters {
+    /// Total amount of currency in circulation.
+    pub total_supply: u64,
+    /// Cumulative amount of currency issued by `Issue`.
+    pub issued_supply: u64,
+    /// Number of created wallets.
+    pub wallet_count: u64,
+    /// Number of executed transfers.
+    pub transfer_count: u64,
+    /// Cumulative amount of the default asset moved by transfers.
+    pub transfer_volume: u64,
+    /// Fees collected since the last distribution.
+    pub fee_pool: u64,
+}
+
+/// Snapshot of the service state.
+///
+/// Wallet histories are not included, since the transactions they refer to are
+/// not known to a fresh blockchain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// Version of the snapshot format.
+    pub version: u32,
+    /// Height of the last block committed before the export.
+    pub height: Height,
+    /// Service configuration.
+    pub config: Config,
+    /// Counters of the service.
+    pub counters: Counters,
+    /// User-defined assets.
+    pub assets: Vec<Asset>,
+    /// Wallets.
+    pub wallets: Vec<WalletSnapshot>,
+    /// Keys of the wallets by their names.
+    pub wallet_names: BTreeMap<String, PublicKey>,
+    /// Multisign wallets.
+    pub multisig_wallets: Vec<MultisigWallet>,
+    /// Pending multisign transfers.
+    pub pending_transfers: Vec<PendingTransferSnapshot>,
+    /// Pending changes of multisign wallet owners.
+    pub approvers_changes: Vec<ApproversChangeSnapshot>,
+}
+
+impl StateSnapshot {
+    /// Exports the state of the service from the given snapshot.
+    pub fn export(snapshot: &dyn Snapshot) -> Self {
+        let schema = Schema::new(snapshot);
+        let counters = Counters {
+            total_supply: schema.total_supply(),
+            issued_supply: schema.issued_supply(),
+            wallet_count: schema.wallet_count(),
+            transfer_count: schema.transfer_count(),
+            transfer_volume: schema.transfer_volume(),
+            fee_pool: schema.fee_pool(),
+        };
+        let wallets = schema
+            .wallets()
+            .values()
+            .map(|wallet| WalletSnapshot {
+                pending_txs: schema.wallet_pending_txs(&wallet.pub_key).iter().collect(),
+                asset_balances: schema.asset_balances(&wallet.pub_key).iter().collect(),
+                linked_multisig_wallets: schema
+                    .linked_multisig_wallets(&wallet.pub_key)
+                    .iter()
+                    .collect(),
+                wallet,
+            })
+            .collect();
+        let pending_transfers = schema
+            .pending_transfers()
+            .iter()
+            .map(|(tx_hash, transfer)| PendingTransferSnapshot {
+                approvals: schema.multisig_approvals(&tx_hash).iter().collect(),
+                tx_hash,
+                transfer,
+            })
+            .collect();
+        let approvers_changes = schema
+            .approvers_changes()
+            .iter()
+            .map(|(tx_hash, change)| ApproversChangeSnapshot {
+                approvals: schema.multisig_approvals(&tx_hash).iter().collect(),
+                tx_hash,
+                change,
+            })
+            .collect();
+
+        StateSnapshot {
+            version: SNAPSHOT_VERSION,
+            height: blockchain::Schema::new(snapshot).height(),
+            config: schema.config(),
+            counters,
+            assets: schema.assets().values().collect(),
+            wallets,
+            wallet_names: schema.wallet_names().iter().collect(),
+            multisig_wallets: schema.multisig_wallets().values().collect(),
+            pending_transfers,
+            approvers_changes,
+        }
+    }
+
+    /// Reads the snapshot from the JSON file.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        let snapshot: Self = serde_json::from_reader(io::BufReader::new(file))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported snapshot version {}", snapshot.version),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Restores the state of the service from the snapshot.
+    ///
+    /// Wallets get an empty history, like the genesis wallets.
+    pub fn restore(&self, fork: &mut Fork) {
+        let mut schema = Schema::new(fork);
+        schema.set_config(self.config.clone());
+        for asset in &self.assets {
+            schema.assets_mut().put(&asset.code, asset.clone());
+        }
+        for snapshot in &self.wallets {
+            let key = snapshot.wallet.pub_key;
+            let history_hash = schema.wallet_history(&key).merkle_root();
+            let wallet = snapshot.wallet.clone().set_history(0, &history_hash);
+            for tx_hash in &snapshot.pending_txs {
+                schema.wallet_pending_txs_mut(&key).insert(*tx_hash);
+            }
+            for (asset, balance) in &snapshot.asset_balances {
+                schema.asset_balances_mut(&key).put(asset, *balance);
+            }
+            schema
+                .linked_multisig_wallets_mut(&key)
+                .extend(snapshot.linked_multisig_wallets.iter().cloned());
+            schema.wallets_mut().put(&key, wallet);
+        }
+        for (name, key) in &self.wallet_names {
+            schema.wallet_names_mut().put(name, *key);
+        }
+        for wallet in &self.multisig_wallets {
+            schema
+                .multisig_wallets_mut()
+                .put(&wallet.pub_key, wallet.clone());
+        }
+        for pending in &self.pending_transfers {
+            schema
+                .pending_transfers_mut()
+                .put(&pending.tx_hash, pending.transfer.clone());
+            schema
+                .multisig_approvals_mut(&pending.tx_hash)
+                .extend(pending.approvals.iter().cloned());
+        }
+        for pending in &self.approvers_changes {
+            schema
+                .approvers_changes_mut()
+                .put(&pending.tx_hash, pending.change.clone());
+            schema
+                .multisig_approvals_mut(&pending.tx_hash)
+                .extend(pending.approvals.iter().cloned());
+        }
+
+        let counters = &self.counters;
+        schema.total_supply_entry_mut().set(counters.total_supply);
+        schema.issued_supply_entry_mut().set(counters.issued_supply);
+        schema.wallet_count_entry_mut().set(counters.wallet_count);
+        schema
+            .transfer_count_entry_mut()
+            .set(counters.transfer_count);
+        schema
+            .transfer_volume_entry_mut()
+            .set(counters.transfer_volume);
+        schema.fee_pool_entry_mut().set(counters.fee_pool);
+    }
+}
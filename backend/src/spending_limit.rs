@@ -0,0 +1,41 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spending limit of the wallet.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Maximum amount of a single transfer from the wallet, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SpendingLimit", serde_pb_convert)]
+pub struct SpendingLimit {
+    /// Maximum amount of currency transferred without co-signing.
+    pub limit: u64,
+    /// `PublicKey` allowed to co-sign transfers above the limit.
+    pub co_signer: PublicKey,
+}
+
+impl SpendingLimit {
+    /// Create new SpendingLimit.
+    pub fn new(limit: u64, &co_signer: &PublicKey) -> Self {
+        Self { limit, co_signer }
+    }
+
+    /// Checks whether the transfer of the given amount needs to be co-signed.
+    pub fn is_exceeded_by(&self, amount: u64) -> bool {
+        amount > self.limit
+    }
+}
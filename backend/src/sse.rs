@@ -0,0 +1,92 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server-sent events endpoint streaming the committed service transactions.
+
+use std::sync::Arc;
+
+use actix_web::{error, http::Method, FromRequest, HttpResponse, Query};
+use bytes::Bytes;
+use exonum::{
+    api::backends::actix::{FutureResponse, HttpRequest, RequestHandler},
+    helpers::Height,
+};
+use futures::{future, stream, Stream};
+use serde_json;
+
+use api::{FeedStreamQuery, PublicApi, TransactionFeed};
+use events::WalletEvents;
+
+/// Formats the transactions of the feed as a `transactions` event identified by the height
+/// of the last covered block, so that reconnecting clients resume from it.
+fn format_event(feed: &TransactionFeed) -> Result<Bytes, error::Error> {
+    let data = serde_json::to_string(feed)?;
+    Ok(Bytes::from(format!(
+        "id: {}\nevent: transactions\ndata: {}\n\n",
+        feed.height, data
+    )))
+}
+
+/// Returns the height given by the `Last-Event-ID` header sent by reconnecting clients.
+fn last_event_id(request: &HttpRequest) -> Option<u64> {
+    request
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Creates the handler of the `v1/transactions/feed/stream` endpoint streaming
+/// a `text/event-stream` body with the service transactions of the blocks above `since`,
+/// starting with the already committed ones.
+pub fn transaction_feed_stream_endpoint(events: Arc<WalletEvents>) -> RequestHandler {
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let response =
+            Query::<FeedStreamQuery>::from_request(&request, &Default::default()).map(|query| {
+                let state = request.state().clone();
+                let mut since =
+                    Height(query.since.or_else(|| last_event_id(&request)).unwrap_or(0));
+                // The blocks are listened to before the committed ones are sent,
+                // so that none of them is missed.
+                let blocks = events.listen_blocks().map(|_| ());
+                let body = stream::once(Ok(()))
+                    .chain(blocks)
+                    .map_err(|_| error::ErrorInternalServerError("Node is shutting down"))
+                    .map(move |_| {
+                        let mut feeds = Vec::new();
+                        loop {
+                            let feed = PublicApi::collect_feed(&state, since);
+                            if feed.height <= since {
+                                break;
+                            }
+                            since = feed.height;
+                            feeds.push(feed);
+                        }
+                        stream::iter_ok(feeds)
+                    })
+                    .flatten()
+                    .and_then(|feed| format_event(&feed));
+                HttpResponse::Ok()
+                    .content_type("text/event-stream")
+                    .header("Cache-Control", "no-cache")
+                    .streaming(body)
+            });
+        Box::new(future::result(response))
+    };
+    RequestHandler {
+        name: "v1/transactions/feed/stream".to_owned(),
+        method: Method::GET,
+        inner: Arc::new(index),
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coins locked by a wallet to earn block rewards.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Coins staked by a wallet with a validator, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::StakePosition", serde_pb_convert)]
+pub struct StakePosition {
+    /// `PublicKey` of the validator the stake is delegated to, the owner's own key
+    /// if it stakes as a validator itself.
+    pub validator: PublicKey,
+    /// Amount of the staked currency.
+    pub amount: u64,
+}
+
+impl StakePosition {
+    /// Create new StakePosition.
+    pub fn new(&validator: &PublicKey, amount: u64) -> Self {
+        Self { validator, amount }
+    }
+
+    /// Returns a copy of this stake with updated amount.
+    pub fn set_amount(self, amount: u64) -> Self {
+        Self::new(&self.validator, amount)
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Standing payment order.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Payment repeated every `interval` blocks, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::StandingOrder", serde_pb_convert)]
+pub struct StandingOrder {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency transferred with each payment.
+    pub amount: u64,
+    /// Number of blocks between payments.
+    pub interval: u64,
+    /// Height of the block the next payment is made in.
+    pub next_at: u64,
+}
+
+impl StandingOrder {
+    /// Create new StandingOrder.
+    pub fn new(
+        &from: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        interval: u64,
+        next_at: u64,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            interval,
+            next_at,
+        }
+    }
+
+    /// Returns a copy of this order scheduled for the next interval, `None` if
+    /// its height overflows.
+    pub fn reschedule(self) -> Option<Self> {
+        let next_at = self.next_at.checked_add(self.interval)?;
+        Some(Self::new(
+            &self.from,
+            &self.to,
+            self.amount,
+            self.interval,
+            next_at,
+        ))
+    }
+}
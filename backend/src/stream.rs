@@ -0,0 +1,50 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Payment stream.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Payment made in every block after the stream is opened, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Stream", serde_pb_convert)]
+pub struct Stream {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency transferred in each block.
+    pub rate_per_block: u64,
+    /// Height of the block the stream was opened in.
+    pub opened_at: u64,
+}
+
+impl Stream {
+    /// Create new Stream.
+    pub fn new(&from: &PublicKey, &to: &PublicKey, rate_per_block: u64, opened_at: u64) -> Self {
+        Self {
+            from,
+            to,
+            rate_per_block,
+            opened_at,
+        }
+    }
+
+    /// Checks whether the given key is the sender or the receiver of the stream.
+    pub fn is_party(&self, key: &PublicKey) -> bool {
+        self.from == *key || self.to == *key
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomic swap of two assets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Exchange of two assets proposed to the counterparty, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Swap", serde_pb_convert)]
+pub struct Swap {
+    /// `PublicKey` of proposer's wallet.
+    pub proposer: PublicKey,
+    /// `PublicKey` of counterparty's wallet.
+    pub counterparty: PublicKey,
+    /// Code of the asset the proposer gives, empty for the default coin.
+    pub give_asset: String,
+    /// Amount of the asset the proposer gives.
+    pub give_amount: u64,
+    /// Code of the asset the proposer takes, empty for the default coin.
+    pub take_asset: String,
+    /// Amount of the asset the proposer takes.
+    pub take_amount: u64,
+    /// Height of the block starting from which the proposal expires.
+    pub expires_at: u64,
+}
+
+impl Swap {
+    /// Create new Swap.
+    pub fn new(
+        &proposer: &PublicKey,
+        &counterparty: &PublicKey,
+        give_asset: &str,
+        give_amount: u64,
+        take_asset: &str,
+        take_amount: u64,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            proposer,
+            counterparty,
+            give_asset: give_asset.to_owned(),
+            give_amount,
+            take_asset: take_asset.to_owned(),
+            take_amount,
+            expires_at,
+        }
+    }
+
+    /// Checks whether the proposal is expired at the given height.
+    pub fn is_expired_at(&self, height: u64) -> bool {
+        height >= self.expires_at
+    }
+}
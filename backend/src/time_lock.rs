@@ -0,0 +1,43 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Time-locked funds.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Funds credited to the wallet which can't be spent before the unlock height,
+/// stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::TimeLock", serde_pb_convert)]
+pub struct TimeLock {
+    /// `PublicKey` of the wallet owning the funds.
+    pub owner: PublicKey,
+    /// Amount of locked currency.
+    pub amount: u64,
+    /// Height of the block starting from which the funds can be spent.
+    pub unlock_at: u64,
+}
+
+impl TimeLock {
+    /// Create new TimeLock.
+    pub fn new(&owner: &PublicKey, amount: u64, unlock_at: u64) -> Self {
+        Self {
+            owner,
+            amount,
+            unlock_at,
+        }
+    }
+}
@@ -18,45 +18,736 @@
 // ECR-1771 for the details.
 #![allow(bare_trait_objects)]
 
+use std::{collections::BTreeSet, fmt};
+
 use exonum::{
     blockchain::{ExecutionError, ExecutionResult, Transaction, TransactionContext},
-    crypto::{Hash, PublicKey, SecretKey},
+    crypto::{self, Hash, PublicKey, SecretKey, Signature},
     messages::{Message, RawTransaction, Signed},
+    storage::Fork,
 };
 
 use super::proto;
+use delegation::Delegation;
+use dispute::Dispute;
+use escrow::Escrow;
+use governance::ParamChange;
+use hash_lock::HashLock;
+use inheritance::Inheritance;
+use invoice::Invoice;
+use loan::Loan;
+use multisig_wallet::ApproversChange;
+use order::Order;
+use pending_transfer::PendingTransfer;
+use pool::Pool;
+use recovery::{Guardians, Recovery};
+use role::Role;
+use savings::Savings;
+use scheduled_transfer::ScheduledTransfer;
 use schema::Schema;
-use CRYPTOCURRENCY_SERVICE_ID;
-
-const ERROR_SENDER_SAME_AS_RECEIVER: u8 = 0;
+use session_key::SessionKey;
+use spending_limit::SpendingLimit;
+use standing_order::StandingOrder;
+use stream::Stream;
+use swap::Swap;
+use vesting::Vesting;
+use watch_list::WatchList;
+use whitelist::Whitelist;
+use {CRYPTOCURRENCY_SERVICE_ID, DEFAULT_ASSET};
 
 /// Error codes emitted by wallet transactions during execution.
 #[derive(Debug, Fail)]
 #[repr(u8)]
 pub enum Error {
-    /// Wallet already exists.
+    /// Wallet already exists or its key was rotated.
     ///
-    /// Can be emitted by `CreateWallet`.
+    /// Can be emitted by `CreateWallet` or `CreateMultisigWallet`.
     #[fail(display = "Wallet already exists")]
     WalletAlreadyExists = 0,
 
     /// Sender doesn't exist.
     ///
-    /// Can be emitted by `Transfer`.
+    /// Can be emitted by any transaction transferring funds or `Burn`.
     #[fail(display = "Sender doesn't exist")]
     SenderNotFound = 1,
 
     /// Receiver doesn't exist.
     ///
-    /// Can be emitted by `Transfer` or `Issue`.
+    /// Can be emitted by any transaction transferring funds, `Issue`, `Airdrop` or
+    /// `SetBeneficiary`.
     #[fail(display = "Receiver doesn't exist")]
     ReceiverNotFound = 2,
 
     /// Insufficient currency amount.
     ///
-    /// Can be emitted by `Transfer`.
+    /// Can be emitted by any transaction transferring, exchanging, staking or lending funds,
+    /// `Burn` or `Vote`.
     #[fail(display = "Insufficient currency amount")]
     InsufficientCurrencyAmount = 3,
+
+    /// Quorum is zero or exceeds the number of owners or guardians.
+    ///
+    /// Can be emitted by `CreateMultisigWallet`, `ChangeApprovers` or `SetGuardians`.
+    #[fail(display = "Invalid quorum")]
+    InvalidQuorum = 4,
+
+    /// Approver has already approved the transfer.
+    ///
+    /// Can be emitted by `AcceptMultisign` or `RecoverWallet`.
+    #[fail(display = "Transfer is already approved by this approver")]
+    AlreadyApproved = 5,
+
+    /// Pending multisign transfer doesn't exist.
+    ///
+    /// Can be emitted by `AcceptMultisign` or `CancelMultisign`.
+    #[fail(display = "Pending transfer doesn't exist")]
+    PendingTransferNotFound = 6,
+
+    /// Author is neither the initiator of the transfer nor the wallet owner.
+    ///
+    /// Can be emitted by `CancelMultisign`, `CancelStandingOrder`, `CloseStream`, `CancelSwap` or
+    /// `CancelOrder`.
+    #[fail(display = "Not allowed to cancel the transfer")]
+    CancelNotAllowed = 7,
+
+    /// Multisign wallet doesn't exist.
+    ///
+    /// Can be emitted by `TransferMultisign`, `LinkWallet` or `ChangeApprovers`.
+    #[fail(display = "Multisign wallet doesn't exist")]
+    MultisigWalletNotFound = 8,
+
+    /// Wallet is already linked to the multisign wallet.
+    ///
+    /// Can be emitted by `LinkWallet`.
+    #[fail(display = "Wallet is already linked")]
+    WalletAlreadyLinked = 9,
+
+    /// Author is not an owner of the multisign wallet.
+    ///
+    /// Can be emitted by `LinkWallet`, `ChangeApprovers` or `AcceptMultisign`.
+    #[fail(display = "Not an owner of the multisign wallet")]
+    NotAnOwner = 10,
+
+    /// Wallet is frozen.
+    ///
+    /// Can be emitted by any transaction transferring funds from the wallet.
+    #[fail(display = "Wallet is frozen")]
+    WalletFrozen = 11,
+
+    /// Author is not an administrator of the service.
+    ///
+    /// Can be emitted by `FreezeWallet`, `UnfreezeWallet`, `BlacklistKey`, `UnblacklistKey`,
+    /// `HaltService` or `ResumeService`.
+    #[fail(display = "Not an administrator")]
+    NotAnAdmin = 12,
+
+    /// Wallet doesn't exist.
+    ///
+    /// Can be emitted by `FreezeWallet`, `UnfreezeWallet`, `MintNft`, `Approve`, `SetWhitelist`,
+    /// `SetKycStatus`, `GrantRole`, `Delegate`, `RotateKey`, `SetGuardians`, `RecoverWallet`,
+    /// `SetBeneficiary`, `AddSessionKey`, `SetMetadata` or `CreateInvoice`.
+    #[fail(display = "Wallet doesn't exist")]
+    WalletNotFound = 13,
+
+    /// Wallet has pending multisign transfers or escrows.
+    ///
+    /// Can be emitted by `CloseWallet`.
+    #[fail(display = "Wallet has pending transfers")]
+    PendingTransfersExist = 14,
+
+    /// Wallet is closed.
+    ///
+    /// Can be emitted by any transaction transferring funds, `Issue`, `Airdrop`, `CloseWallet`
+    /// or the transactions withdrawing funds from stakes, savings, pools, orders and loans.
+    #[fail(display = "Wallet is closed")]
+    WalletClosed = 15,
+
+    /// Batch transfer has no payments.
+    ///
+    /// Can be emitted by `BatchTransfer`.
+    #[fail(display = "Batch transfer is empty")]
+    EmptyBatch = 16,
+
+    /// Plain or encrypted memo exceeds the maximum length.
+    ///
+    /// Can be emitted by `Transfer` or `CreateInvoice`.
+    #[fail(display = "Memo is too long")]
+    MemoTooLong = 17,
+
+    /// Balance or total supply doesn't fit into `u64`.
+    ///
+    /// Can be emitted by any transaction changing balances.
+    #[fail(display = "Balance overflow")]
+    BalanceOverflow = 18,
+
+    /// Amount is zero.
+    ///
+    /// Can be emitted by any transaction transferring, exchanging or staking funds, `Issue`,
+    /// `Burn`, `AddSessionKey` or `CreateInvoice`.
+    #[fail(display = "Amount must be positive")]
+    NonPositiveAmount = 19,
+
+    /// Sender is the same as receiver.
+    ///
+    /// Can be emitted by any transaction transferring funds, `CloseWallet`, `SetBeneficiary` or
+    /// `CreateInvoice`.
+    #[fail(display = "Sender is the same as receiver")]
+    SenderSameAsReceiver = 20,
+
+    /// Author is not allowed to issue currency or the asset.
+    ///
+    /// Can be emitted by `Issue`, `CreateVesting` or `Airdrop`.
+    #[fail(display = "Not authorized to issue currency")]
+    UnauthorizedIssuer = 21,
+
+    /// Issue would exceed the configured maximum supply.
+    ///
+    /// Can be emitted by `Issue`, `CreateVesting`, `Airdrop`, `DepositSavings`,
+    /// `WithdrawSavings`, `RedeemPoints`, `FaucetIssue` or `OpenLoan`.
+    #[fail(display = "Supply cap exceeded")]
+    SupplyCapExceeded = 22,
+
+    /// Asset with the same code already exists.
+    ///
+    /// Can be emitted by `DefineAsset`.
+    #[fail(display = "Asset already exists")]
+    AssetAlreadyExists = 23,
+
+    /// Asset doesn't exist.
+    ///
+    /// Can be emitted by `Issue`, `ProposeSwap`, `PlaceOrder`, `AddLiquidity` or `OpenLoan`.
+    #[fail(display = "Asset not found")]
+    AssetNotFound = 24,
+
+    /// Non-fungible token doesn't exist.
+    ///
+    /// Can be emitted by `TransferNft`.
+    #[fail(display = "Token not found")]
+    NftNotFound = 25,
+
+    /// Author doesn't own the non-fungible token.
+    ///
+    /// Can be emitted by `TransferNft`.
+    #[fail(display = "Not a token owner")]
+    NotNftOwner = 26,
+
+    /// Transferred amount exceeds the allowance given by the owner.
+    ///
+    /// Can be emitted by `TransferFrom`.
+    #[fail(display = "Insufficient allowance")]
+    InsufficientAllowance = 27,
+
+    /// Permit signature doesn't match the owner's key.
+    ///
+    /// Can be emitted by `PermitTransfer`.
+    #[fail(display = "Invalid permit signature")]
+    InvalidPermitSignature = 28,
+
+    /// Permit has already been used.
+    ///
+    /// Can be emitted by `PermitTransfer`.
+    #[fail(display = "Permit already used")]
+    PermitAlreadyUsed = 29,
+
+    /// Escrow doesn't exist.
+    ///
+    /// Can be emitted by `ReleaseEscrow` or `RefundEscrow`.
+    #[fail(display = "Escrow not found")]
+    EscrowNotFound = 30,
+
+    /// Author is not the arbitrator of the escrow or a configured arbitrator.
+    ///
+    /// Can be emitted by `ReleaseEscrow`, `RefundEscrow` or `ResolveDispute`.
+    #[fail(display = "Not an arbitrator")]
+    NotAnArbitrator = 31,
+
+    /// Transfer with the same hash lock already exists.
+    ///
+    /// Can be emitted by `LockWithHash`.
+    #[fail(display = "Hash lock already exists")]
+    HashLockAlreadyExists = 32,
+
+    /// Transfer with the given hash lock doesn't exist.
+    ///
+    /// Can be emitted by `ClaimWithPreimage` or `RefundHashLock`.
+    #[fail(display = "Hash lock not found")]
+    HashLockNotFound = 33,
+
+    /// Timeout of the hash lock has been reached.
+    ///
+    /// Can be emitted by `LockWithHash` or `ClaimWithPreimage`.
+    #[fail(display = "Hash lock expired")]
+    HashLockExpired = 34,
+
+    /// Timeout of the hash lock has not been reached yet.
+    ///
+    /// Can be emitted by `RefundHashLock`.
+    #[fail(display = "Hash lock not expired")]
+    HashLockNotExpired = 35,
+
+    /// Funds of assets other than the default one can't be time-locked.
+    ///
+    /// Can be emitted by `Transfer`.
+    #[fail(display = "Time lock is supported only for the default asset")]
+    AssetTimeLockUnsupported = 36,
+
+    /// Vesting schedule doesn't exist.
+    ///
+    /// Can be emitted by `ClaimVesting`.
+    #[fail(display = "Vesting not found")]
+    VestingNotFound = 37,
+
+    /// No vested funds are available to claim.
+    ///
+    /// Can be emitted by `ClaimVesting`.
+    #[fail(display = "Nothing to claim")]
+    NothingToClaim = 38,
+
+    /// Vesting duration is zero or shorter than the cliff.
+    ///
+    /// Can be emitted by `CreateVesting`.
+    #[fail(display = "Invalid vesting schedule")]
+    InvalidVestingSchedule = 39,
+
+    /// Interval of the standing order is zero or too large to schedule its payments.
+    ///
+    /// Can be emitted by `CreateStandingOrder`.
+    #[fail(display = "Invalid interval")]
+    InvalidInterval = 40,
+
+    /// Standing order doesn't exist.
+    ///
+    /// Can be emitted by `CancelStandingOrder`.
+    #[fail(display = "Standing order not found")]
+    StandingOrderNotFound = 41,
+
+    /// Payment stream doesn't exist.
+    ///
+    /// Can be emitted by `CloseStream`.
+    #[fail(display = "Stream not found")]
+    StreamNotFound = 42,
+
+    /// Transfer is scheduled for a block which is already committed or being created.
+    ///
+    /// Can be emitted by `ScheduleTransfer`.
+    #[fail(display = "Scheduled height has already passed")]
+    ScheduledHeightPassed = 43,
+
+    /// Airdrop has no recipients.
+    ///
+    /// Can be emitted by `Airdrop`.
+    #[fail(display = "Airdrop has no recipients")]
+    NoAirdropRecipients = 44,
+
+    /// None of the other wallets has a positive balance to receive the dividend.
+    ///
+    /// Can be emitted by `DistributeDividend`.
+    #[fail(display = "No wallets to receive the dividend")]
+    NoDividendRecipients = 45,
+
+    /// Transfer exceeds the spending limit of the sender's wallet and isn't co-signed.
+    ///
+    /// Can be emitted by any transaction paying the default coin from the wallet, e.g. `Transfer`,
+    /// `BatchTransfer`, `CreateEscrow`, `LockWithHash`, `PlaceOrder` or `Stake`.
+    #[fail(display = "Spending limit exceeded")]
+    SpendingLimitExceeded = 46,
+
+    /// Co-signature doesn't match the co-signer of the spending limit.
+    ///
+    /// Can be emitted by `CoSignedTransfer`.
+    #[fail(display = "Invalid co-signature")]
+    InvalidCoSignature = 47,
+
+    /// Amount sent from the wallet within the velocity window exceeds the configured limit.
+    ///
+    /// Can be emitted by any transaction paying from the wallet, e.g. `Transfer`,
+    /// `BatchTransfer`, `DistributeDividend`, `CreateEscrow`, `LockWithHash`,
+    /// `TransferMultisign`, `CloseWallet`, `PlaceOrder`, `SwapViaPool` or `Stake`.
+    #[fail(display = "Velocity limit exceeded")]
+    VelocityLimitExceeded = 48,
+
+    /// Sender or receiver of the funds is blacklisted.
+    ///
+    /// Can be emitted by any transaction changing the balance of a blacklisted wallet.
+    #[fail(display = "Key is blacklisted")]
+    Blacklisted = 49,
+
+    /// Key to remove from the blacklist isn't blacklisted.
+    ///
+    /// Can be emitted by `UnblacklistKey`.
+    #[fail(display = "Key is not blacklisted")]
+    NotBlacklisted = 50,
+
+    /// Receiver accepts transfers only from whitelisted senders.
+    ///
+    /// Can be emitted by `Transfer`, `TransferByName`, `TransferFrom`, `PermitTransfer`,
+    /// `CoSignedTransfer`, `BatchTransfer` or `ClaimInheritance`.
+    #[fail(display = "Sender is not whitelisted by the receiver")]
+    NotWhitelisted = 51,
+
+    /// Author is not a compliance officer of the service.
+    ///
+    /// Can be emitted by `SetKycStatus`.
+    #[fail(display = "Not a compliance officer")]
+    NotAComplianceOfficer = 52,
+
+    /// Transfer above the KYC threshold between wallets which haven't both passed KYC.
+    ///
+    /// Can be emitted by any transaction paying from or to the wallet, e.g. `Transfer`,
+    /// `BatchTransfer`, `DistributeDividend`, `CreateEscrow`, `ReleaseEscrow`, `LockWithHash`,
+    /// `ClaimWithPreimage`, `TransferMultisign`, `CloseWallet`, `PlaceOrder`, `SwapViaPool`,
+    /// `RemoveLiquidity`, `ClaimInheritance` or `Stake`.
+    #[fail(display = "Both parties must pass KYC for the transfer")]
+    KycRequired = 53,
+
+    /// Role code is unknown.
+    ///
+    /// Can be emitted by `GrantRole`.
+    #[fail(display = "Unknown role")]
+    UnknownRole = 54,
+
+    /// Author neither owns the wallet nor has the admin role of it.
+    ///
+    /// Can be emitted by `GrantRole`.
+    #[fail(display = "Not allowed to manage roles of the wallet")]
+    RoleManagementNotAllowed = 55,
+
+    /// Author has neither the spender role of the wallet nor a delegation from it.
+    ///
+    /// Can be emitted by `DelegatedTransfer`.
+    #[fail(display = "Not allowed to spend from the wallet")]
+    NotASpender = 56,
+
+    /// Transfer exceeds the amount remaining under the delegation.
+    ///
+    /// Can be emitted by `DelegatedTransfer`.
+    #[fail(display = "Delegation cap exceeded")]
+    DelegationCapExceeded = 57,
+
+    /// Delegation has expired.
+    ///
+    /// Can be emitted by `DelegatedTransfer`.
+    #[fail(display = "Delegation expired")]
+    DelegationExpired = 58,
+
+    /// New key already belongs to a wallet or was used by a rotated one, or the session key
+    /// belongs to another wallet.
+    ///
+    /// Can be emitted by `RotateKey`, `RecoverWallet` or `AddSessionKey`.
+    #[fail(display = "Key is already used")]
+    KeyAlreadyUsed = 59,
+
+    /// Keys of multisign wallets can't be rotated.
+    ///
+    /// Can be emitted by `RotateKey`.
+    #[fail(display = "Multisign wallet key can't be rotated")]
+    MultisigKeyRotation = 60,
+
+    /// Author is not a guardian of the wallet.
+    ///
+    /// Can be emitted by `RecoverWallet`.
+    #[fail(display = "Not a guardian of the wallet")]
+    NotAGuardian = 61,
+
+    /// Wallet has no pending recovery.
+    ///
+    /// Can be emitted by `CancelRecovery`.
+    #[fail(display = "Recovery not found")]
+    RecoveryNotFound = 62,
+
+    /// Pending recovery of the wallet is to another key.
+    ///
+    /// Can be emitted by `RecoverWallet`.
+    #[fail(display = "Recovery to another key is in progress")]
+    RecoveryKeyMismatch = 63,
+
+    /// Author is not the beneficiary of the wallet.
+    ///
+    /// Can be emitted by `ClaimInheritance`.
+    #[fail(display = "Not a beneficiary of the wallet")]
+    NotABeneficiary = 64,
+
+    /// Wallet had outgoing transfers within its inactivity period.
+    ///
+    /// Can be emitted by `ClaimInheritance`.
+    #[fail(display = "Wallet is still active")]
+    WalletStillActive = 65,
+
+    /// Author is not a session key of any wallet.
+    ///
+    /// Can be emitted by `RevokeSessionKey` or `SessionTransfer`.
+    #[fail(display = "Session key not found")]
+    SessionKeyNotFound = 66,
+
+    /// Session key is expired.
+    ///
+    /// Can be emitted by `AddSessionKey` or `SessionTransfer`.
+    #[fail(display = "Session key expired")]
+    SessionKeyExpired = 67,
+
+    /// Amount exceeds the maximum the session key can transfer at once.
+    ///
+    /// Can be emitted by `SessionTransfer`.
+    #[fail(display = "Amount exceeds the session key limit")]
+    SessionAmountExceeded = 68,
+
+    /// Watch list exceeds the maximum number of keys.
+    ///
+    /// Can be emitted by `SetWatchList`.
+    #[fail(display = "Watch list is too long")]
+    WatchListTooLong = 69,
+
+    /// Metadata key or value exceeds the maximum length, or the wallet has too many entries.
+    ///
+    /// Can be emitted by `SetMetadata`.
+    #[fail(display = "Metadata is too large")]
+    MetadataTooLarge = 70,
+
+    /// Wallet has no metadata entry with the given key.
+    ///
+    /// Can be emitted by `DeleteMetadata`.
+    #[fail(display = "Metadata entry not found")]
+    MetadataNotFound = 71,
+
+    /// Invoice doesn't exist.
+    ///
+    /// Can be emitted by `PayInvoice`.
+    #[fail(display = "Invoice not found")]
+    InvoiceNotFound = 72,
+
+    /// Author is not the payer of the invoice.
+    ///
+    /// Can be emitted by `PayInvoice`.
+    #[fail(display = "Not a payer of the invoice")]
+    NotAPayer = 73,
+
+    /// Invoice is already paid.
+    ///
+    /// Can be emitted by `PayInvoice`.
+    #[fail(display = "Invoice is already paid")]
+    InvoiceAlreadyPaid = 74,
+
+    /// Author hasn't received a transfer with the given hash.
+    ///
+    /// Can be emitted by `Refund` or `OpenDispute`.
+    #[fail(display = "Transfer not found")]
+    TransferNotFound = 75,
+
+    /// Transfer is already refunded.
+    ///
+    /// Can be emitted by `Refund`, `OpenDispute` or `ResolveDispute`.
+    #[fail(display = "Transfer is already refunded")]
+    AlreadyRefunded = 76,
+
+    /// Dispute window of the transfer has passed.
+    ///
+    /// Can be emitted by `OpenDispute`.
+    #[fail(display = "Dispute window has passed")]
+    DisputeWindowPassed = 77,
+
+    /// Transfer is already disputed.
+    ///
+    /// Can be emitted by `OpenDispute`.
+    #[fail(display = "Transfer is already disputed")]
+    AlreadyDisputed = 78,
+
+    /// Transfer has no open dispute.
+    ///
+    /// Can be emitted by `ResolveDispute`.
+    #[fail(display = "Dispute not found")]
+    DisputeNotFound = 79,
+
+    /// Swap proposal doesn't exist.
+    ///
+    /// Can be emitted by `AcceptSwap` or `CancelSwap`.
+    #[fail(display = "Swap not found")]
+    SwapNotFound = 80,
+
+    /// Author is not the counterparty of the swap.
+    ///
+    /// Can be emitted by `AcceptSwap`.
+    #[fail(display = "Not a counterparty of the swap")]
+    NotACounterparty = 81,
+
+    /// Swap proposal is expired.
+    ///
+    /// Can be emitted by `ProposeSwap` or `AcceptSwap`.
+    #[fail(display = "Swap expired")]
+    SwapExpired = 82,
+
+    /// Both sides of the swap are in the same asset.
+    ///
+    /// Can be emitted by `ProposeSwap`.
+    #[fail(display = "Swapped assets must differ")]
+    SameSwapAssets = 83,
+
+    /// Market doesn't trade two different assets.
+    ///
+    /// Can be emitted by `PlaceOrder`.
+    #[fail(display = "Market must trade two different assets")]
+    InvalidMarket = 84,
+
+    /// Resting order doesn't exist.
+    ///
+    /// Can be emitted by `CancelOrder`.
+    #[fail(display = "Order not found")]
+    OrderNotFound = 85,
+
+    /// Liquidity pool of the asset doesn't exist or is empty.
+    ///
+    /// Can be emitted by `RemoveLiquidity`, `SwapViaPool`, `OpenLoan` or `Liquidate`.
+    #[fail(display = "Pool not found")]
+    PoolNotFound = 86,
+
+    /// Amount is too small to get any shares or output from the pool.
+    ///
+    /// Can be emitted by `AddLiquidity` or `SwapViaPool`.
+    #[fail(display = "Amount is too small for the pool liquidity")]
+    InsufficientLiquidity = 87,
+
+    /// Provider doesn't own enough liquidity shares.
+    ///
+    /// Can be emitted by `RemoveLiquidity`.
+    #[fail(display = "Insufficient pool shares")]
+    InsufficientShares = 88,
+
+    /// Output of the swap is below the minimum set by the author.
+    ///
+    /// Can be emitted by `SwapViaPool`.
+    #[fail(display = "Output is below the minimum")]
+    SlippageExceeded = 89,
+
+    /// Author already staked with another validator.
+    ///
+    /// Can be emitted by `Stake` or `DelegateStake`.
+    #[fail(display = "Stake is delegated to another validator")]
+    StakeValidatorMismatch = 90,
+
+    /// Stake is delegated to a wallet that doesn't stake as a validator.
+    ///
+    /// Can be emitted by `DelegateStake`.
+    #[fail(display = "Not a validator")]
+    NotAValidator = 91,
+
+    /// Author staked less than the amount to unstake.
+    ///
+    /// Can be emitted by `Unstake`.
+    #[fail(display = "Insufficient staked amount")]
+    InsufficientStake = 92,
+
+    /// Savings are less than the amount to withdraw.
+    ///
+    /// Can be emitted by `WithdrawSavings`.
+    #[fail(display = "Insufficient savings")]
+    InsufficientSavings = 93,
+
+    /// Lending is disabled by the configuration.
+    ///
+    /// Can be emitted by `OpenLoan`.
+    #[fail(display = "Lending is disabled")]
+    LendingDisabled = 94,
+
+    /// Collateral isn't worth enough to open the loan.
+    ///
+    /// Can be emitted by `OpenLoan`.
+    #[fail(display = "Collateral is insufficient for the loan")]
+    InsufficientCollateral = 95,
+
+    /// Open loan doesn't exist.
+    ///
+    /// Can be emitted by `RepayLoan` or `Liquidate`.
+    #[fail(display = "Loan not found")]
+    LoanNotFound = 96,
+
+    /// Author isn't the borrower of the loan.
+    ///
+    /// Can be emitted by `RepayLoan`.
+    #[fail(display = "Not a borrower of the loan")]
+    NotABorrower = 97,
+
+    /// Loan collateral is still worth enough to prevent the liquidation.
+    ///
+    /// Can be emitted by `Liquidate`.
+    #[fail(display = "Loan is sufficiently collateralized")]
+    LoanHealthy = 98,
+
+    /// Author has less loyalty points than it redeems.
+    ///
+    /// Can be emitted by `RedeemPoints`.
+    #[fail(display = "Insufficient loyalty points")]
+    InsufficientPoints = 99,
+
+    /// Governance is disabled by the configuration.
+    ///
+    /// Can be emitted by `ProposeParamChange` or `Vote`.
+    #[fail(display = "Governance is disabled")]
+    GovernanceDisabled = 100,
+
+    /// Parameter can't be changed by voting.
+    ///
+    /// Can be emitted by `ProposeParamChange`.
+    #[fail(display = "Unknown parameter")]
+    UnknownParam = 101,
+
+    /// Parameter change isn't proposed, is already applied or has expired.
+    ///
+    /// Can be emitted by `Vote`.
+    #[fail(display = "Proposal not found")]
+    ProposalNotFound = 102,
+
+    /// Author already voted for the parameter change.
+    ///
+    /// Can be emitted by `Vote`.
+    #[fail(display = "Already voted for the proposal")]
+    AlreadyVoted = 103,
+
+    /// Service is switched into the read-only mode.
+    ///
+    /// Can be emitted by any transaction but `ResumeService`.
+    #[fail(display = "Service is halted")]
+    ServiceHalted = 104,
+
+    /// Author isn't a service key of a validator.
+    ///
+    /// Can be emitted by `FaucetIssue`.
+    #[fail(display = "Not a faucet key")]
+    NotAFaucet = 105,
+
+    /// Amount exceeds the faucet limit or the faucet is disabled.
+    ///
+    /// Can be emitted by `FaucetIssue`.
+    #[fail(display = "Amount exceeds the faucet limit")]
+    FaucetLimitExceeded = 106,
+
+    /// Receiver got currency from the faucet less than `Config::faucet_interval` blocks ago.
+    ///
+    /// Can be emitted by `FaucetIssue`.
+    #[fail(display = "Faucet was used by the key recently")]
+    FaucetRateLimited = 107,
+
+    /// Asset code is empty or contains `/`.
+    ///
+    /// Can be emitted by `DefineAsset`.
+    #[fail(display = "Invalid asset code")]
+    InvalidAssetCode = 108,
+
+    /// Wallet has time-locked funds.
+    ///
+    /// Can be emitted by `CloseWallet`.
+    #[fail(display = "Wallet has time-locked funds")]
+    FundsTimeLocked = 109,
+
+    /// Wallet has staked or saved funds, pool shares, open orders, loans or vestings.
+    ///
+    /// Can be emitted by `CloseWallet`.
+    #[fail(display = "Wallet has open positions")]
+    OpenPositionsExist = 110,
+
+    /// Author of the proposal has another parameter change open for voting.
+    ///
+    /// Can be emitted by `ProposeParamChange`.
+    #[fail(display = "Another proposal of the wallet is open")]
+    ProposalAlreadyOpen = 111,
 }
 
 impl From<Error> for ExecutionError {
@@ -67,6 +758,8 @@ impl From<Error> for ExecutionError {
 }
 
 /// Transfer `amount` of the currency from one wallet to another.
+///
+/// The sender is also charged `Config::transfer_fee` in the default coin.
 #[derive(Clone, Debug, ProtobufConvert)]
 #[exonum(pb = "proto::Transfer", serde_pb_convert)]
 pub struct Transfer {
@@ -78,6 +771,63 @@ pub struct Transfer {
     ///
     /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
     pub seed: u64,
+    /// Optional note attached to the transfer.
+    ///
+    /// The memo is stored with the transaction, so it is returned together
+    /// with the wallet history.
+    pub memo: String,
+    /// Code of the transferred asset, empty for the default coin.
+    pub asset: String,
+    /// Height of the block starting from which the receiver can spend the funds,
+    /// `0` if the funds are not locked.
+    ///
+    /// Until then the amount is credited to the balance of the receiver,
+    /// but not to its pending balance.
+    pub unlock_at: u64,
+    /// Optional note sealed to the receiver's public key, e.g. with `crypto_box_seal`.
+    ///
+    /// The service can't read the memo; it stores the ciphertext under the transaction hash,
+    /// so it is returned together with the wallet history.
+    pub encrypted_memo: Vec<u8>,
+}
+
+/// Transfer `amount` of the currency to the wallet registered under the given name.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::TransferByName", serde_pb_convert)]
+pub struct TransferByName {
+    /// Name of receiver's wallet.
+    pub to_name: String,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Single payment of the batch transfer.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Payment", serde_pb_convert)]
+pub struct Payment {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+}
+
+/// Transfer currency from one wallet to several others atomically.
+///
+/// Either all the receivers are credited or the whole transaction fails.
+/// The sender is charged `Config::transfer_fee` once for the whole batch.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::BatchTransfer", serde_pb_convert)]
+pub struct BatchTransfer {
+    /// Payments of this transfer.
+    pub payments: Vec<Payment>,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
 }
 
 /// Multisignature transfer `amount` of the currency from one multisig wallet to another.
@@ -88,8 +838,6 @@ pub struct TransferMultisign {
     pub from: PublicKey,
     /// `PublicKey` of receiver's wallet.
     pub to: PublicKey,
-    /// Approvers of this transfer.
-    pub approvers: Vec<PublicKey>,
     /// Amount of currency to transfer.
     pub amount: u64,
     /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
@@ -104,255 +852,5863 @@ pub struct TransferMultisign {
 pub struct AcceptMultisign {
     /// Hash of the accepted transfer.
     pub tx_hash: Hash,
-    /// `PublicKey` of multisign sender's wallet.
-    pub from: PublicKey,
-    /// `PublicKey` of receiver's wallet.
-    pub to: PublicKey,
-    /// Approvers of this transfer
-    pub approvers: Vec<PublicKey>,
     /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
     ///
     /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
     pub seed: u64,
 }
 
-/// Issue `amount` of the currency to the `wallet`.
-#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
-#[exonum(pb = "proto::Issue")]
-pub struct Issue {
-    /// Issued amount of currency.
-    pub amount: u64,
+/// Cancel pending multisignature transfer.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CancelMultisign", serde_pb_convert)]
+pub struct CancelMultisign {
+    /// Hash of the cancelled transfer.
+    pub tx_hash: Hash,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Issue `amount` of the currency or another asset to the `wallet`.
+///
+/// Only keys listed in `Config::minters` are allowed to issue the default coin, and the
+/// cumulative issued amount can't exceed `Config::max_supply`. Other assets are issued
+/// by the issuer registered with `DefineAsset`, within the asset's maximum supply.
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Issue")]
+pub struct Issue {
+    /// Issued amount of currency.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+    /// Code of the issued asset, empty for the default coin.
+    pub asset: String,
+}
+
+/// Burn `amount` of the currency from the author's wallet.
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Burn")]
+pub struct Burn {
+    /// Burned amount of currency.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Issue `amount` of the currency to each of the `recipients`, or to every open wallet
+/// if the list is empty.
+///
+/// Only keys listed in `Config::minters` are allowed to make airdrops, and the whole
+/// issued amount counts towards `Config::max_supply`. Each recipient is credited once,
+/// even if listed several times; blacklisted wallets are skipped.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Airdrop", serde_pb_convert)]
+pub struct Airdrop {
+    /// Wallets to credit, empty for all open wallets.
+    pub recipients: Vec<PublicKey>,
+    /// Amount of currency issued to each recipient.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Distribute `total_amount` of the currency from the author's wallet across all other open
+/// wallets proportionally to their balances.
+///
+/// Each share is `total_amount * balance / sum_of_balances` rounded down, so the shares
+/// don't depend on the order of wallets. The undistributed remainder stays in the author's
+/// wallet, and wallets whose share is zero are not credited.
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DistributeDividend")]
+pub struct DistributeDividend {
+    /// Amount of currency to distribute.
+    pub total_amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Create wallet with the given `name`.
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateWallet")]
+pub struct CreateWallet {
+    /// Name of the new wallet.
+    pub name: String,
+}
+
+/// Create multisignature wallet with the given `name`, `owners` and `quorum`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateMultisigWallet", serde_pb_convert)]
+pub struct CreateMultisigWallet {
+    /// Name of the new wallet.
+    pub name: String,
+    /// Owners of the wallet allowed to approve transfers.
+    pub owners: Vec<PublicKey>,
+    /// Number of approvals required to execute a transfer.
+    pub quorum: u32,
+}
+
+/// Change owners and quorum of the multisignature wallet.
+///
+/// The change is applied after it is approved by the quorum of the current owners.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ChangeApprovers", serde_pb_convert)]
+pub struct ChangeApprovers {
+    /// `PublicKey` of the multisignature wallet.
+    pub multisig_wallet: PublicKey,
+    /// New owners of the wallet.
+    pub owners: Vec<PublicKey>,
+    /// New number of approvals required to execute a transfer.
+    pub quorum: u32,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Freeze outgoing transfers from the wallet.
+///
+/// Can be executed only by the service administrators.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::FreezeWallet", serde_pb_convert)]
+pub struct FreezeWallet {
+    /// `PublicKey` of the frozen wallet.
+    pub pub_key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Unfreeze outgoing transfers from the wallet.
+///
+/// Can be executed only by the service administrators.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::UnfreezeWallet", serde_pb_convert)]
+pub struct UnfreezeWallet {
+    /// `PublicKey` of the unfrozen wallet.
+    pub pub_key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Blacklist the key, so that all transfers to or from it fail.
+///
+/// Can be executed only by the service administrators.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::BlacklistKey", serde_pb_convert)]
+pub struct BlacklistKey {
+    /// Blacklisted `PublicKey`.
+    pub pub_key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Remove the key from the blacklist.
+///
+/// Can be executed only by the service administrators.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::UnblacklistKey", serde_pb_convert)]
+pub struct UnblacklistKey {
+    /// `PublicKey` removed from the blacklist.
+    pub pub_key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Set the KYC verification flag of the wallet.
+///
+/// Can be executed only by the compliance officers of the service.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetKycStatus", serde_pb_convert)]
+pub struct SetKycStatus {
+    /// `PublicKey` of the wallet.
+    pub pub_key: PublicKey,
+    /// Whether the owner of the wallet has passed KYC verification.
+    pub verified: bool,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Register guardians allowed to recover the author's wallet to a new key.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetGuardians", serde_pb_convert)]
+pub struct SetGuardians {
+    /// `PublicKey`s of the guardians, empty to disable recovery.
+    pub guardians: Vec<PublicKey>,
+    /// Number of guardians required to recover the wallet.
+    pub threshold: u32,
+}
+
+/// Approve recovery of the wallet to a new key as its guardian, e.g. if the owner lost the key.
+///
+/// Once `threshold` guardians approve the same key, the wallet is moved to it as with
+/// `RotateKey` after `Config::recovery_challenge_period` blocks, unless the owner cancels
+/// the recovery with `CancelRecovery`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RecoverWallet", serde_pb_convert)]
+pub struct RecoverWallet {
+    /// `PublicKey` of the recovered wallet.
+    pub wallet: PublicKey,
+    /// New `PublicKey` of the wallet.
+    pub new_key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Cancel the recovery of the author's wallet during the challenge period.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CancelRecovery", serde_pb_convert)]
+pub struct CancelRecovery {
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Set the beneficiary of the author's wallet.
+///
+/// Transactions with zero `inactivity_blocks` remove the beneficiary.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetBeneficiary", serde_pb_convert)]
+pub struct SetBeneficiary {
+    /// `PublicKey` of the beneficiary.
+    pub beneficiary: PublicKey,
+    /// Number of blocks without outgoing transfers after which the beneficiary can claim
+    /// the balance.
+    pub inactivity_blocks: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Claim the available balance of the inactive wallet as its beneficiary.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ClaimInheritance", serde_pb_convert)]
+pub struct ClaimInheritance {
+    /// `PublicKey` of the inactive wallet.
+    pub wallet: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Authorize a short-lived session key to transfer small amounts from the author's wallet,
+/// e.g. on a kiosk or a mobile device.
+///
+/// Transactions for a key the wallet already has replace its limit and expiry.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::AddSessionKey", serde_pb_convert)]
+pub struct AddSessionKey {
+    /// `PublicKey` of the session key.
+    pub key: PublicKey,
+    /// Maximum amount of currency the key can transfer at once.
+    pub max_amount: u64,
+    /// Height of the block starting from which the key expires.
+    pub expires_at: u64,
+}
+
+/// Revoke the session key of the author's wallet before it expires.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RevokeSessionKey", serde_pb_convert)]
+pub struct RevokeSessionKey {
+    /// `PublicKey` of the session key.
+    pub key: PublicKey,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Transfer `amount` of the currency from the wallet the author is a session key of.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SessionTransfer", serde_pb_convert)]
+pub struct SessionTransfer {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Replace the list of keys watched by the author.
+///
+/// Watched keys need no secret key or even a wallet; the API aggregates their balances,
+/// histories and pending transfers in one call.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetWatchList", serde_pb_convert)]
+pub struct SetWatchList {
+    /// Watched `PublicKey`s, empty to clear the list.
+    pub keys: Vec<PublicKey>,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Set the metadata entry of the author's wallet, e.g. an avatar hash or contact info.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetMetadata", serde_pb_convert)]
+pub struct SetMetadata {
+    /// Key of the entry.
+    pub key: String,
+    /// Value of the entry.
+    pub value: String,
+}
+
+/// Delete the metadata entry of the author's wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DeleteMetadata", serde_pb_convert)]
+pub struct DeleteMetadata {
+    /// Key of the entry.
+    pub key: String,
+}
+
+/// Request a payment of `amount` of the currency from the payer to the author's wallet.
+///
+/// The invoice is identified by the hash of this transaction, so the merchant can reconcile
+/// the payment by it instead of matching raw transfers.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateInvoice", serde_pb_convert)]
+pub struct CreateInvoice {
+    /// Amount of requested currency.
+    pub amount: u64,
+    /// `PublicKey` of payer's wallet.
+    pub payer: PublicKey,
+    /// Note describing the payment.
+    pub memo: String,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Pay the invoice issued to the author.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::PayInvoice", serde_pb_convert)]
+pub struct PayInvoice {
+    /// Hash of the `CreateInvoice` transaction.
+    pub invoice: Hash,
+}
+
+/// Send the exact amount of the transfer received by the author back to its sender.
+///
+/// The hash of this transaction is stored under the hash of the refunded transfer,
+/// linking both history entries.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Refund", serde_pb_convert)]
+pub struct Refund {
+    /// Hash of the refunded `Transfer` transaction.
+    pub transfer: Hash,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Dispute the transfer sent by the author within `Config::dispute_window` blocks after it.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::OpenDispute", serde_pb_convert)]
+pub struct OpenDispute {
+    /// Hash of the disputed `Transfer` transaction.
+    pub transfer: Hash,
+}
+
+/// Resolve the disputed transfer as one of `Config::arbitrators`, optionally clawing back
+/// its amount from the receiver.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ResolveDispute", serde_pb_convert)]
+pub struct ResolveDispute {
+    /// Hash of the disputed `Transfer` transaction.
+    pub transfer: Hash,
+    /// Whether the amount is clawed back from the receiver.
+    pub claw_back: bool,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Propose the counterparty to exchange `give_amount` of one asset of the author
+/// for `take_amount` of another one.
+///
+/// Nothing is reserved until the counterparty accepts the proposal with `AcceptSwap`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ProposeSwap", serde_pb_convert)]
+pub struct ProposeSwap {
+    /// `PublicKey` of counterparty's wallet.
+    pub counterparty: PublicKey,
+    /// Code of the asset the author gives, empty for the default coin.
+    pub give_asset: String,
+    /// Amount of the asset the author gives.
+    pub give_amount: u64,
+    /// Code of the asset the author takes, empty for the default coin.
+    pub take_asset: String,
+    /// Amount of the asset the author takes.
+    pub take_amount: u64,
+    /// Height of the block starting from which the proposal expires.
+    pub expires_at: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Accept the swap proposed to the author, exchanging both assets in one atomic execution.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::AcceptSwap", serde_pb_convert)]
+pub struct AcceptSwap {
+    /// Hash of the `ProposeSwap` transaction.
+    pub swap: Hash,
+}
+
+/// Cancel the swap proposed by the author.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CancelSwap", serde_pb_convert)]
+pub struct CancelSwap {
+    /// Hash of the `ProposeSwap` transaction.
+    pub swap: Hash,
+}
+
+/// Place a limit order to buy or sell `amount` of the base asset at `price` units
+/// of the quote asset each.
+///
+/// The order reserves the funds it trades, is matched with the resting orders
+/// of the opposite side right away, and its remainder rests in the order book.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::PlaceOrder", serde_pb_convert)]
+pub struct PlaceOrder {
+    /// Code of the traded asset, empty for the default coin.
+    pub base_asset: String,
+    /// Code of the asset the price is set in, empty for the default coin.
+    pub quote_asset: String,
+    /// Whether the author buys the base asset or sells it.
+    pub buy: bool,
+    /// Amount of the quote asset per unit of the base asset.
+    pub price: u64,
+    /// Amount of the base asset.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Cancel the resting order placed by the author and release its reserved funds.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CancelOrder", serde_pb_convert)]
+pub struct CancelOrder {
+    /// Hash of the `PlaceOrder` transaction.
+    pub order: Hash,
+}
+
+/// Deposit the default coin and an asset to their constant-product pool in exchange
+/// for liquidity shares.
+///
+/// The first deposit creates the pool and sets its price. Later deposits are taken
+/// at the current ratio of the reserves, up to the given amounts.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::AddLiquidity", serde_pb_convert)]
+pub struct AddLiquidity {
+    /// Code of the pooled asset.
+    pub asset: String,
+    /// Maximal amount of the default coin to deposit.
+    pub coin_amount: u64,
+    /// Maximal amount of the asset to deposit.
+    pub asset_amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Redeem liquidity shares for the proportional part of both pool reserves.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RemoveLiquidity", serde_pb_convert)]
+pub struct RemoveLiquidity {
+    /// Code of the pooled asset.
+    pub asset: String,
+    /// Amount of the liquidity shares to redeem.
+    pub shares: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Exchange the default coin and an asset via their pool keeping the product
+/// of its reserves constant.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SwapViaPool", serde_pb_convert)]
+pub struct SwapViaPool {
+    /// Code of the pooled asset.
+    pub asset: String,
+    /// Whether the author pays the coin for the asset or the other way round.
+    pub buy_asset: bool,
+    /// Amount paid to the pool.
+    pub amount: u64,
+    /// Minimal amount the author agrees to receive.
+    pub min_output: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Stake `amount` of the author's coins as a validator to earn block rewards.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Stake", serde_pb_convert)]
+pub struct Stake {
+    /// Amount of currency to stake.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Stake `amount` of the author's coins with a validator, which gets
+/// `Config::delegation_commission` percent of their rewards.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DelegateStake", serde_pb_convert)]
+pub struct DelegateStake {
+    /// `PublicKey` of the validator.
+    pub validator: PublicKey,
+    /// Amount of currency to stake.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Return `amount` of the staked coins to the author's wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Unstake", serde_pb_convert)]
+pub struct Unstake {
+    /// Amount of currency to unstake.
+    pub amount: u64,
     /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
     ///
     /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
     pub seed: u64,
 }
 
-/// Create wallet with the given `name`.
-#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
-#[exonum(pb = "proto::CreateWallet")]
-pub struct CreateWallet {
-    /// Name of the new wallet.
-    pub name: String,
+/// Move `amount` of currency from the author's wallet to its savings, which earn
+/// `Config::savings_interest_rate` per block.
+///
+/// The interest accrued so far is credited to the savings first.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DepositSavings", serde_pb_convert)]
+pub struct DepositSavings {
+    /// Amount of currency to deposit.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Move `amount` of currency from the author's savings, including the accrued interest,
+/// to its wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::WithdrawSavings", serde_pb_convert)]
+pub struct WithdrawSavings {
+    /// Amount of currency to withdraw.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Borrow `amount` of the currency against `collateral` of the asset.
+///
+/// The collateral is valued at the spot price of the asset pool and must be worth
+/// `Config::collateral_ratio` percent of the amount. The borrowed currency is issued
+/// on opening and burned on repayment.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::OpenLoan", serde_pb_convert)]
+pub struct OpenLoan {
+    /// Code of the collateral asset.
+    pub asset: String,
+    /// Amount of the collateral asset.
+    pub collateral: u64,
+    /// Amount of currency to borrow.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Repay the borrowed amount of the author's loan and release its collateral.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RepayLoan", serde_pb_convert)]
+pub struct RepayLoan {
+    /// Hash of the `OpenLoan` transaction.
+    pub loan: Hash,
+}
+
+/// Repay the borrowed amount of a loan whose collateral is worth less than
+/// `Config::liquidation_ratio` percent of it, and take the collateral.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Liquidate", serde_pb_convert)]
+pub struct Liquidate {
+    /// Hash of the `OpenLoan` transaction.
+    pub loan: Hash,
+}
+
+/// Exchange `points` of the author's loyalty points for the same amount of the currency.
+///
+/// Points are earned with outgoing transfers at `Config::loyalty_rate`; the redeemed
+/// currency is issued and counts towards `Config::max_supply`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RedeemPoints", serde_pb_convert)]
+pub struct RedeemPoints {
+    /// Amount of points to redeem.
+    pub points: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Propose to change a parameter of the service configuration.
+///
+/// Only `max_supply`, `transfer_fee`, `velocity_limit`, `kyc_threshold`, `dispute_window`,
+/// `staking_reward`, `savings_interest_rate` and `loyalty_rate` can be changed by voting.
+///
+/// The author pays `Config::proposal_fee` and can't propose another change while this one
+/// is open for voting, i.e. for `Config::proposal_period` blocks or until it is applied.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ProposeParamChange", serde_pb_convert)]
+pub struct ProposeParamChange {
+    /// Name of the `Config` field to change.
+    pub param: String,
+    /// New value of the parameter.
+    pub value: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Vote for the proposed parameter change with the author's balance.
+///
+/// The change is applied once the votes reach `Config::governance_quorum` percent
+/// of the balances at the moment of the proposal.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Vote", serde_pb_convert)]
+pub struct Vote {
+    /// Hash of the `ProposeParamChange` transaction.
+    pub proposal: Hash,
+}
+
+/// Switch the service into the read-only mode: all other transactions fail with
+/// `ServiceHalted` and per-block operations are suspended until `ResumeService`.
+///
+/// Only administrators can halt the service.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::HaltService", serde_pb_convert)]
+pub struct HaltService {
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Switch the service back from the read-only mode.
+///
+/// Only administrators can resume the service.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ResumeService", serde_pb_convert)]
+pub struct ResumeService {
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Issue `amount` of the currency to a wallet on request to the faucet endpoint.
+///
+/// Signed with the service key of the validator node serving the request. The amount
+/// is capped by `Config::faucet_limit` and a key can be credited once in
+/// `Config::faucet_interval` blocks.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::FaucetIssue", serde_pb_convert)]
+pub struct FaucetIssue {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to issue.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Move the author's wallet to a new key, e.g. if the old one is compromised.
+///
+/// The old key is tombstoned: it can't be used for a new wallet, and funds reserved
+/// under it by pending transfers, escrows or locks are settled with the moved wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RotateKey", serde_pb_convert)]
+pub struct RotateKey {
+    /// New `PublicKey` of the wallet.
+    pub new_pub_key: PublicKey,
+}
+
+/// Close the author's wallet and transfer its remaining balance to another wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CloseWallet", serde_pb_convert)]
+pub struct CloseWallet {
+    /// `PublicKey` of the wallet receiving the remaining balance.
+    pub to: PublicKey,
+}
+
+/// Allow `spender` to transfer up to `amount` of the currency from the author's wallet.
+///
+/// The new allowance replaces the previous one.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Approve", serde_pb_convert)]
+pub struct Approve {
+    /// `PublicKey` of the wallet allowed to spend the funds.
+    pub spender: PublicKey,
+    /// Allowed amount of currency.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Transfer `amount` of the currency from `owner` to `to` using the allowance
+/// given to the author.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::TransferFrom", serde_pb_convert)]
+pub struct TransferFrom {
+    /// `PublicKey` of the wallet the funds are taken from.
+    pub owner: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Transfer authorized off-chain by the owner of the funds and submitted by any relayer.
+///
+/// The owner signs the data returned by `PermitTransfer::permit_data`, and the service
+/// verifies this signature instead of the transaction author. Each permit can be used once.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::PermitTransfer", serde_pb_convert)]
+pub struct PermitTransfer {
+    /// `PublicKey` of the wallet the funds are taken from.
+    pub owner: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number making each permit unique.
+    pub seed: u64,
+    /// Signature of the permit data made with the owner's key.
+    pub signature: Signature,
+}
+
+/// Lock `amount` of the currency from the author's wallet until the arbitrator
+/// releases it to `to` or refunds it.
+///
+/// The escrow is identified by the hash of this transaction.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateEscrow", serde_pb_convert)]
+pub struct CreateEscrow {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// `PublicKey` of the arbitrator.
+    pub arbitrator: PublicKey,
+    /// Amount of currency to lock.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Release the locked funds to the receiver.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ReleaseEscrow", serde_pb_convert)]
+pub struct ReleaseEscrow {
+    /// Hash of the `CreateEscrow` transaction.
+    pub escrow: Hash,
+}
+
+/// Refund the locked funds to the sender.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RefundEscrow", serde_pb_convert)]
+pub struct RefundEscrow {
+    /// Hash of the `CreateEscrow` transaction.
+    pub escrow: Hash,
+}
+
+/// Lock `amount` of the currency from the author's wallet until `to` reveals
+/// the preimage of `hash_lock` or the `timeout` height is reached.
+///
+/// Together with a similar lock on another chain it allows atomic cross-chain swaps.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::LockWithHash", serde_pb_convert)]
+pub struct LockWithHash {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to lock.
+    pub amount: u64,
+    /// Hash of the secret preimage.
+    pub hash_lock: Hash,
+    /// Height of the block starting from which the lock can be refunded.
+    pub timeout: u64,
+}
+
+/// Claim the locked funds for the receiver by revealing the preimage.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ClaimWithPreimage", serde_pb_convert)]
+pub struct ClaimWithPreimage {
+    /// Secret preimage of the hash lock.
+    pub preimage: Vec<u8>,
+}
+
+/// Refund the expired locked funds to the sender.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RefundHashLock", serde_pb_convert)]
+pub struct RefundHashLock {
+    /// Hash lock of the transfer.
+    pub hash_lock: Hash,
+}
+
+/// Issue `amount` of the currency vested to the beneficiary over time.
+///
+/// Only keys listed in `Config::minters` are allowed to create vesting schedules.
+/// The schedule is identified by the hash of this transaction.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateVesting", serde_pb_convert)]
+pub struct CreateVesting {
+    /// `PublicKey` of the beneficiary's wallet.
+    pub beneficiary: PublicKey,
+    /// Total amount of currency in the schedule.
+    pub amount: u64,
+    /// Height of the block the vesting starts at.
+    pub start: u64,
+    /// Number of blocks since the start before which nothing is vested.
+    pub cliff: u64,
+    /// Number of blocks since the start after which everything is vested.
+    pub duration: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Release the vested but not yet claimed funds to the beneficiary.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ClaimVesting", serde_pb_convert)]
+pub struct ClaimVesting {
+    /// Hash of the `CreateVesting` transaction.
+    pub vesting: Hash,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Transfer `amount` of the currency from the author's wallet to `to` every `interval` blocks.
+///
+/// The first payment is made `interval` blocks after this transaction. Payments are made
+/// while committing blocks until the order is cancelled or the sender can't pay it.
+/// The order is identified by the hash of this transaction.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CreateStandingOrder", serde_pb_convert)]
+pub struct CreateStandingOrder {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency transferred with each payment.
+    pub amount: u64,
+    /// Number of blocks between payments.
+    pub interval: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Cancel the standing order created by the author.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CancelStandingOrder", serde_pb_convert)]
+pub struct CancelStandingOrder {
+    /// Hash of the `CreateStandingOrder` transaction.
+    pub order: Hash,
+}
+
+/// Transfer `rate_per_block` of the currency from the author's wallet to `to` in every block,
+/// e.g. for salaries or subscriptions.
+///
+/// Payments start with the block following this transaction and are made while committing
+/// blocks until the stream is closed or the sender can't pay it.
+/// The stream is identified by the hash of this transaction.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::OpenStream", serde_pb_convert)]
+pub struct OpenStream {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency transferred in each block.
+    pub rate_per_block: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Close the payment stream, allowed for its sender or receiver.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CloseStream", serde_pb_convert)]
+pub struct CloseStream {
+    /// Hash of the `OpenStream` transaction.
+    pub stream: Hash,
+}
+
+/// Transfer `amount` of the currency from the author's wallet to `to` when the block
+/// at `at_height` is committed.
+///
+/// The funds aren't reserved: the transfer is dropped if it can't be made at that height.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ScheduleTransfer", serde_pb_convert)]
+pub struct ScheduleTransfer {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Height of the block the transfer is executed in.
+    pub at_height: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Limit the amount of a single transfer of the default coin from the author's wallet.
+///
+/// Transfers above the limit are rejected unless made with `CoSignedTransfer`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetSpendingLimit", serde_pb_convert)]
+pub struct SetSpendingLimit {
+    /// Maximum amount of currency transferred without co-signing, `0` removes the limit.
+    pub limit: u64,
+    /// `PublicKey` allowed to co-sign transfers above the limit.
+    pub co_signer: PublicKey,
+}
+
+/// Transfer `amount` of the currency above the author's spending limit.
+///
+/// The co-signer of the limit signs the data returned by `CoSignedTransfer::co_sign_data`.
+/// If the author has no spending limit, the co-signature isn't checked.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::CoSignedTransfer", serde_pb_convert)]
+pub struct CoSignedTransfer {
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+    /// Signature of the co-signer over the transfer data.
+    pub co_signature: Signature,
+}
+
+/// Accept transfers to the author's wallet only from the given senders.
+///
+/// Transactions with an empty list make the wallet accept transfers from anyone again.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetWhitelist", serde_pb_convert)]
+pub struct SetWhitelist {
+    /// `PublicKey`s of the approved senders, empty to accept transfers from anyone.
+    pub senders: Vec<PublicKey>,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Grant the role of the wallet to another key, e.g. to let employees spend from
+/// a company wallet without being its owners.
+///
+/// Allowed for the owners of the wallet and the keys with the admin role of it.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::GrantRole", serde_pb_convert)]
+pub struct GrantRole {
+    /// `PublicKey` of the wallet.
+    pub wallet: PublicKey,
+    /// `PublicKey` the role is granted to.
+    pub grantee: PublicKey,
+    /// Code of the `Role`, `0` revokes the role.
+    pub role: u32,
+}
+
+/// Allow another key to spend from the author's wallet up to a cumulative cap
+/// with `DelegatedTransfer`.
+///
+/// Replaces the previous delegation to the same key, resetting the spent amount.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Delegate", serde_pb_convert)]
+pub struct Delegate {
+    /// `PublicKey` allowed to spend from the wallet.
+    pub spender: PublicKey,
+    /// Maximum cumulative amount of currency the spender can transfer, `0` revokes
+    /// the delegation.
+    pub max_amount: u64,
+    /// Height of the block starting from which the delegation expires, `0` means never.
+    pub expiry: u64,
+}
+
+/// Transfer `amount` of the currency from the wallet the author has the spender role of,
+/// or within the cap delegated to the author with `Delegate`.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DelegatedTransfer", serde_pb_convert)]
+pub struct DelegatedTransfer {
+    /// `PublicKey` of sender's wallet.
+    pub from: PublicKey,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+    /// Amount of currency to transfer.
+    pub amount: u64,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Mint a new non-fungible token owned by the author.
+///
+/// The token is identified by the hash of this transaction.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::MintNft", serde_pb_convert)]
+pub struct MintNft {
+    /// Arbitrary data describing the token.
+    pub metadata: String,
+    /// Auxiliary number to guarantee [non-idempotence][idempotence] of transactions.
+    ///
+    /// [idempotence]: https://en.wikipedia.org/wiki/Idempotence
+    pub seed: u64,
+}
+
+/// Transfer the non-fungible token to another wallet.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::TransferNft", serde_pb_convert)]
+pub struct TransferNft {
+    /// Identifier of the token.
+    pub token: Hash,
+    /// `PublicKey` of receiver's wallet.
+    pub to: PublicKey,
+}
+
+/// Register a new asset issued by the author.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::DefineAsset", serde_pb_convert)]
+pub struct DefineAsset {
+    /// Code of the asset.
+    pub code: String,
+    /// Number of decimal places used to display amounts of the asset.
+    pub decimals: u32,
+    /// Maximum amount of the asset that can be issued, `0` means no limit.
+    pub max_supply: u64,
+}
+
+/// Link the author's wallet to the multisignature wallet it co-owns.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::LinkWallet", serde_pb_convert)]
+pub struct LinkWallet {
+    /// `PublicKey` of the multisignature wallet.
+    pub multisig_wallet: PublicKey,
+}
+
+/// Transaction group.
+#[derive(Serialize, Deserialize, Clone, Debug, TransactionSet)]
+pub enum WalletTransactions {
+    /// Transfer tx.
+    Transfer(Transfer),
+    /// Transfer by name tx.
+    TransferByName(TransferByName),
+    /// Batch transfer tx.
+    BatchTransfer(BatchTransfer),
+    /// Multisign transfer tx.
+    TransferMultisign(TransferMultisign),
+    /// Accept multisign transfer
+    AcceptMultisign(AcceptMultisign),
+    /// Cancel multisign transfer.
+    CancelMultisign(CancelMultisign),
+    /// Issue tx.
+    Issue(Issue),
+    /// Burn tx.
+    Burn(Burn),
+    /// CreateWallet tx.
+    CreateWallet(CreateWallet),
+    /// CreateMultisigWallet tx.
+    CreateMultisigWallet(CreateMultisigWallet),
+    /// LinkWallet tx.
+    LinkWallet(LinkWallet),
+    /// ChangeApprovers tx.
+    ChangeApprovers(ChangeApprovers),
+    /// FreezeWallet tx.
+    FreezeWallet(FreezeWallet),
+    /// UnfreezeWallet tx.
+    UnfreezeWallet(UnfreezeWallet),
+    /// CloseWallet tx.
+    CloseWallet(CloseWallet),
+    /// DefineAsset tx.
+    DefineAsset(DefineAsset),
+    /// MintNft tx.
+    MintNft(MintNft),
+    /// TransferNft tx.
+    TransferNft(TransferNft),
+    /// Approve tx.
+    Approve(Approve),
+    /// TransferFrom tx.
+    TransferFrom(TransferFrom),
+    /// PermitTransfer tx.
+    PermitTransfer(PermitTransfer),
+    /// CreateEscrow tx.
+    CreateEscrow(CreateEscrow),
+    /// ReleaseEscrow tx.
+    ReleaseEscrow(ReleaseEscrow),
+    /// RefundEscrow tx.
+    RefundEscrow(RefundEscrow),
+    /// LockWithHash tx.
+    LockWithHash(LockWithHash),
+    /// ClaimWithPreimage tx.
+    ClaimWithPreimage(ClaimWithPreimage),
+    /// RefundHashLock tx.
+    RefundHashLock(RefundHashLock),
+    /// CreateVesting tx.
+    CreateVesting(CreateVesting),
+    /// ClaimVesting tx.
+    ClaimVesting(ClaimVesting),
+    /// CreateStandingOrder tx.
+    CreateStandingOrder(CreateStandingOrder),
+    /// CancelStandingOrder tx.
+    CancelStandingOrder(CancelStandingOrder),
+    /// OpenStream tx.
+    OpenStream(OpenStream),
+    /// CloseStream tx.
+    CloseStream(CloseStream),
+    /// ScheduleTransfer tx.
+    ScheduleTransfer(ScheduleTransfer),
+    /// Airdrop tx.
+    Airdrop(Airdrop),
+    /// DistributeDividend tx.
+    DistributeDividend(DistributeDividend),
+    /// SetSpendingLimit tx.
+    SetSpendingLimit(SetSpendingLimit),
+    /// CoSignedTransfer tx.
+    CoSignedTransfer(CoSignedTransfer),
+    /// BlacklistKey tx.
+    BlacklistKey(BlacklistKey),
+    /// UnblacklistKey tx.
+    UnblacklistKey(UnblacklistKey),
+    /// SetWhitelist tx.
+    SetWhitelist(SetWhitelist),
+    /// SetKycStatus tx.
+    SetKycStatus(SetKycStatus),
+    /// GrantRole tx.
+    GrantRole(GrantRole),
+    /// DelegatedTransfer tx.
+    DelegatedTransfer(DelegatedTransfer),
+    /// Delegate tx.
+    Delegate(Delegate),
+    /// RotateKey tx.
+    RotateKey(RotateKey),
+    /// SetGuardians tx.
+    SetGuardians(SetGuardians),
+    /// RecoverWallet tx.
+    RecoverWallet(RecoverWallet),
+    /// CancelRecovery tx.
+    CancelRecovery(CancelRecovery),
+    /// SetBeneficiary tx.
+    SetBeneficiary(SetBeneficiary),
+    /// ClaimInheritance tx.
+    ClaimInheritance(ClaimInheritance),
+    /// AddSessionKey tx.
+    AddSessionKey(AddSessionKey),
+    /// RevokeSessionKey tx.
+    RevokeSessionKey(RevokeSessionKey),
+    /// SessionTransfer tx.
+    SessionTransfer(SessionTransfer),
+    /// SetWatchList tx.
+    SetWatchList(SetWatchList),
+    /// SetMetadata tx.
+    SetMetadata(SetMetadata),
+    /// DeleteMetadata tx.
+    DeleteMetadata(DeleteMetadata),
+    /// CreateInvoice tx.
+    CreateInvoice(CreateInvoice),
+    /// PayInvoice tx.
+    PayInvoice(PayInvoice),
+    /// Refund tx.
+    Refund(Refund),
+    /// OpenDispute tx.
+    OpenDispute(OpenDispute),
+    /// ResolveDispute tx.
+    ResolveDispute(ResolveDispute),
+    /// ProposeSwap tx.
+    ProposeSwap(ProposeSwap),
+    /// AcceptSwap tx.
+    AcceptSwap(AcceptSwap),
+    /// CancelSwap tx.
+    CancelSwap(CancelSwap),
+    /// PlaceOrder tx.
+    PlaceOrder(PlaceOrder),
+    /// CancelOrder tx.
+    CancelOrder(CancelOrder),
+    /// AddLiquidity tx.
+    AddLiquidity(AddLiquidity),
+    /// RemoveLiquidity tx.
+    RemoveLiquidity(RemoveLiquidity),
+    /// SwapViaPool tx.
+    SwapViaPool(SwapViaPool),
+    /// Stake tx.
+    Stake(Stake),
+    /// DelegateStake tx.
+    DelegateStake(DelegateStake),
+    /// Unstake tx.
+    Unstake(Unstake),
+    /// DepositSavings tx.
+    DepositSavings(DepositSavings),
+    /// WithdrawSavings tx.
+    WithdrawSavings(WithdrawSavings),
+    /// OpenLoan tx.
+    OpenLoan(OpenLoan),
+    /// RepayLoan tx.
+    RepayLoan(RepayLoan),
+    /// Liquidate tx.
+    Liquidate(Liquidate),
+    /// RedeemPoints tx.
+    RedeemPoints(RedeemPoints),
+    /// ProposeParamChange tx.
+    ProposeParamChange(ProposeParamChange),
+    /// Vote tx.
+    Vote(Vote),
+    /// HaltService tx.
+    HaltService(HaltService),
+    /// ResumeService tx.
+    ResumeService(ResumeService),
+    /// FaucetIssue tx.
+    FaucetIssue(FaucetIssue),
+}
+
+impl WalletTransactions {
+    /// Returns the keys taking part in the transaction: its author and the wallets
+    /// the transaction moves currency between.
+    pub fn parties(&self, author: PublicKey) -> Vec<PublicKey> {
+        let mut parties = vec![author];
+        match *self {
+            WalletTransactions::Transfer(ref tx) => parties.push(tx.to),
+            WalletTransactions::TransferFrom(ref tx) => parties.extend(&[tx.owner, tx.to]),
+            WalletTransactions::PermitTransfer(ref tx) => parties.extend(&[tx.owner, tx.to]),
+            WalletTransactions::CoSignedTransfer(ref tx) => parties.push(tx.to),
+            WalletTransactions::DelegatedTransfer(ref tx) => parties.extend(&[tx.from, tx.to]),
+            WalletTransactions::SessionTransfer(ref tx) => parties.push(tx.to),
+            WalletTransactions::TransferMultisign(ref tx) => parties.extend(&[tx.from, tx.to]),
+            WalletTransactions::FaucetIssue(ref tx) => parties.push(tx.to),
+            _ => {}
+        }
+        parties
+    }
+
+    /// Returns the amount of currency the transaction moves, issues or burns.
+    pub fn amount(&self) -> Option<u64> {
+        match *self {
+            WalletTransactions::Transfer(ref tx) => Some(tx.amount),
+            WalletTransactions::TransferByName(ref tx) => Some(tx.amount),
+            WalletTransactions::TransferFrom(ref tx) => Some(tx.amount),
+            WalletTransactions::PermitTransfer(ref tx) => Some(tx.amount),
+            WalletTransactions::CoSignedTransfer(ref tx) => Some(tx.amount),
+            WalletTransactions::DelegatedTransfer(ref tx) => Some(tx.amount),
+            WalletTransactions::SessionTransfer(ref tx) => Some(tx.amount),
+            WalletTransactions::TransferMultisign(ref tx) => Some(tx.amount),
+            WalletTransactions::Issue(ref tx) => Some(tx.amount),
+            WalletTransactions::FaucetIssue(ref tx) => Some(tx.amount),
+            WalletTransactions::Burn(ref tx) => Some(tx.amount),
+            _ => None,
+        }
+    }
+}
+
+/// Environment the service transactions are executed in.
+///
+/// Besides the `TransactionContext` of the blockchain, it is implemented by `DryRunContext`,
+/// with which the API executes transactions against a throwaway fork.
+pub trait ExecutionContext {
+    /// Returns the public key of the transaction author.
+    fn author(&self) -> PublicKey;
+    /// Returns the hash of the transaction.
+    fn tx_hash(&self) -> Hash;
+    /// Returns the fork the transaction changes.
+    fn fork(&mut self) -> &mut Fork;
+}
+
+impl<'a> ExecutionContext for TransactionContext<'a> {
+    fn author(&self) -> PublicKey {
+        TransactionContext::author(self)
+    }
+
+    fn tx_hash(&self) -> Hash {
+        TransactionContext::tx_hash(self)
+    }
+
+    fn fork(&mut self) -> &mut Fork {
+        TransactionContext::fork(self)
+    }
+}
+
+/// Context of a transaction executed outside of a block, e.g. to validate it.
+pub struct DryRunContext<'a> {
+    fork: &'a mut Fork,
+    author: PublicKey,
+    tx_hash: Hash,
+}
+
+impl<'a> DryRunContext<'a> {
+    /// Creates the context of the given signed transaction.
+    pub fn new(fork: &'a mut Fork, tx: &Signed<RawTransaction>) -> Self {
+        DryRunContext {
+            fork,
+            author: tx.author(),
+            tx_hash: tx.hash(),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for DryRunContext<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DryRunContext")
+            .field("author", &self.author)
+            .field("tx_hash", &self.tx_hash)
+            .finish()
+    }
+}
+
+impl<'a> ExecutionContext for DryRunContext<'a> {
+    fn author(&self) -> PublicKey {
+        self.author
+    }
+
+    fn tx_hash(&self) -> Hash {
+        self.tx_hash
+    }
+
+    fn fork(&mut self) -> &mut Fork {
+        self.fork
+    }
+}
+
+/// Service transaction executable in any `ExecutionContext`.
+///
+/// `Transaction` is implemented for every transaction of the service by forwarding
+/// to this trait.
+pub trait ExecuteTransaction {
+    /// Executes the transaction, see `Transaction::execute`.
+    fn execute<C: ExecutionContext>(&self, context: C) -> ExecutionResult;
+}
+
+macro_rules! impl_transactions {
+    ($($name:ident),* $(,)*) => {
+        $(
+            impl Transaction for $name {
+                fn execute(&self, context: TransactionContext) -> ExecutionResult {
+                    ExecuteTransaction::execute(self, context)
+                }
+            }
+        )*
+
+        impl ExecuteTransaction for WalletTransactions {
+            fn execute<C: ExecutionContext>(&self, context: C) -> ExecutionResult {
+                match *self {
+                    $(WalletTransactions::$name(ref tx) => ExecuteTransaction::execute(tx, context),)*
+                }
+            }
+        }
+    };
+}
+
+impl_transactions!(
+    Transfer, TransferByName, BatchTransfer, TransferMultisign, AcceptMultisign, CancelMultisign,
+    Issue, Burn, CreateWallet, CreateMultisigWallet, LinkWallet, ChangeApprovers, FreezeWallet,
+    UnfreezeWallet, CloseWallet, DefineAsset, MintNft, TransferNft, Approve, TransferFrom,
+    PermitTransfer, CreateEscrow, ReleaseEscrow, RefundEscrow, LockWithHash, ClaimWithPreimage,
+    RefundHashLock, CreateVesting, ClaimVesting, CreateStandingOrder, CancelStandingOrder,
+    OpenStream, CloseStream, ScheduleTransfer, Airdrop, DistributeDividend, SetSpendingLimit,
+    CoSignedTransfer, BlacklistKey, UnblacklistKey, SetWhitelist, SetKycStatus, GrantRole,
+    DelegatedTransfer, Delegate, RotateKey, SetGuardians, RecoverWallet, CancelRecovery,
+    SetBeneficiary, ClaimInheritance, AddSessionKey, RevokeSessionKey, SessionTransfer,
+    SetWatchList, SetMetadata, DeleteMetadata, CreateInvoice, PayInvoice, Refund, OpenDispute,
+    ResolveDispute, ProposeSwap, AcceptSwap, CancelSwap, PlaceOrder, CancelOrder, AddLiquidity,
+    RemoveLiquidity, SwapViaPool, Stake, DelegateStake, Unstake, DepositSavings, WithdrawSavings,
+    OpenLoan, RepayLoan, Liquidate, RedeemPoints, ProposeParamChange, Vote, HaltService,
+    ResumeService, FaucetIssue,
+);
+
+/// Transaction failing with `ServiceHalted` while the service is halted, unless it
+/// resumes the service.
+///
+/// Every transaction of the service is wrapped, so that the events emitted by it are
+/// recorded under its own hash rather than the hash of the previous transaction in the block.
+/// The transaction also counts as an activity of its author postponing the inheritance
+/// of the author's wallet.
+#[derive(Debug, Serialize)]
+pub struct HaltableTransaction {
+    tx: WalletTransactions,
+    resumes_service: bool,
+}
+
+impl HaltableTransaction {
+    /// Wraps the given transaction, `resumes_service` lets it be executed while
+    /// the service is halted.
+    pub fn new(tx: WalletTransactions, resumes_service: bool) -> Self {
+        HaltableTransaction {
+            tx,
+            resumes_service,
+        }
+    }
+}
+
+impl ExecuteTransaction for HaltableTransaction {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = context.author();
+        let hash = context.tx_hash();
+        {
+            let mut schema = Schema::new(context.fork());
+            if schema.is_halted() && !self.resumes_service {
+                Err(Error::ServiceHalted)?
+            }
+            schema.begin_transaction(&hash);
+            schema.record_activity(&author);
+        }
+        ExecuteTransaction::execute(&self.tx, context)
+    }
+}
+
+impl Transaction for HaltableTransaction {
+    fn execute(&self, context: TransactionContext) -> ExecutionResult {
+        ExecuteTransaction::execute(self, context)
+    }
+}
+
+impl CreateWallet {
+    #[doc(hidden)]
+    pub fn sign(name: &str, pk: &PublicKey, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                name: name.to_owned(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Issue {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Self::sign_with_asset(pk, DEFAULT_ASSET, amount, seed, sk)
+    }
+
+    #[doc(hidden)]
+    pub fn sign_with_asset(
+        pk: &PublicKey,
+        asset: &str,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                amount,
+                seed,
+                asset: asset.to_owned(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Burn {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { amount, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl CreateMultisigWallet {
+    #[doc(hidden)]
+    pub fn sign(
+        name: &str,
+        owners: &[PublicKey],
+        quorum: u32,
+        pk: &PublicKey,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                name: name.to_owned(),
+                owners: owners.to_vec(),
+                quorum,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ChangeApprovers {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &multisig_wallet: &PublicKey,
+        owners: &[PublicKey],
+        quorum: u32,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                multisig_wallet,
+                owners: owners.to_vec(),
+                quorum,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl FreezeWallet {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &pub_key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { pub_key, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl UnfreezeWallet {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &pub_key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { pub_key, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CloseWallet {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &to: &PublicKey, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { to }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl DefineAsset {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        code: &str,
+        decimals: u32,
+        max_supply: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                code: code.to_owned(),
+                decimals,
+                max_supply,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Approve {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &spender: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                spender,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl TransferFrom {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &owner: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                owner,
+                to,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl PermitTransfer {
+    /// Returns the data the owner signs to authorize the transfer.
+    pub fn permit_data(owner: &PublicKey, to: &PublicKey, amount: u64, seed: u64) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&CRYPTOCURRENCY_SERVICE_ID.to_le_bytes());
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(to.as_ref());
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&seed.to_le_bytes());
+        data
+    }
+
+    /// Signs the permit with the owner's secret key.
+    pub fn sign_permit(
+        owner: &PublicKey,
+        to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        owner_sk: &SecretKey,
+    ) -> Signature {
+        crypto::sign(&Self::permit_data(owner, to, amount, seed), owner_sk)
+    }
+
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &owner: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        &signature: &Signature,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                owner,
+                to,
+                amount,
+                seed,
+                signature,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CreateEscrow {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        &arbitrator: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                arbitrator,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ReleaseEscrow {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &escrow: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { escrow }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl RefundEscrow {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &escrow: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { escrow }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl LockWithHash {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        &hash_lock: &Hash,
+        timeout: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                hash_lock,
+                timeout,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ClaimWithPreimage {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, preimage: &[u8], sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                preimage: preimage.to_vec(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RefundHashLock {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &hash_lock: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { hash_lock }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl CreateVesting {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &beneficiary: &PublicKey,
+        amount: u64,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                beneficiary,
+                amount,
+                start,
+                cliff,
+                duration,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ClaimVesting {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &vesting: &Hash,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { vesting, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl CreateStandingOrder {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        interval: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                interval,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CancelStandingOrder {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &order: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { order }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl OpenStream {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        rate_per_block: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                rate_per_block,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CloseStream {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &stream: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { stream }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl ScheduleTransfer {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        at_height: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                at_height,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Airdrop {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        recipients: &[PublicKey],
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                recipients: recipients.to_vec(),
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl DistributeDividend {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        total_amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { total_amount, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SetSpendingLimit {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        limit: u64,
+        &co_signer: &PublicKey,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { limit, co_signer },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CoSignedTransfer {
+    /// Returns the data the co-signer signs to authorize the transfer.
+    pub fn co_sign_data(from: &PublicKey, to: &PublicKey, amount: u64, seed: u64) -> Vec<u8> {
+        let mut data = b"co-sign".to_vec();
+        data.extend_from_slice(&PermitTransfer::permit_data(from, to, amount, seed));
+        data
+    }
+
+    /// Signs the transfer with the co-signer's secret key.
+    pub fn co_sign(
+        from: &PublicKey,
+        to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        co_signer_sk: &SecretKey,
+    ) -> Signature {
+        crypto::sign(&Self::co_sign_data(from, to, amount, seed), co_signer_sk)
+    }
+
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        &co_signature: &Signature,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                seed,
+                co_signature,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl BlacklistKey {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &pub_key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { pub_key, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl UnblacklistKey {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &pub_key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { pub_key, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SetWhitelist {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        senders: &[PublicKey],
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                senders: senders.to_vec(),
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SetKycStatus {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &pub_key: &PublicKey,
+        verified: bool,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                pub_key,
+                verified,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl GrantRole {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &wallet: &PublicKey,
+        &grantee: &PublicKey,
+        role: u32,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                wallet,
+                grantee,
+                role,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Delegate {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &spender: &PublicKey,
+        max_amount: u64,
+        expiry: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                spender,
+                max_amount,
+                expiry,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl DelegatedTransfer {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &from: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                from,
+                to,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RotateKey {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &new_pub_key: &PublicKey,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { new_pub_key }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl SetGuardians {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        guardians: &[PublicKey],
+        threshold: u32,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                guardians: guardians.to_vec(),
+                threshold,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RecoverWallet {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &wallet: &PublicKey,
+        &new_key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                wallet,
+                new_key,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CancelRecovery {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl SetBeneficiary {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &beneficiary: &PublicKey,
+        inactivity_blocks: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                beneficiary,
+                inactivity_blocks,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ClaimInheritance {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &wallet: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { wallet, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl AddSessionKey {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &key: &PublicKey,
+        max_amount: u64,
+        expires_at: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                key,
+                max_amount,
+                expires_at,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RevokeSessionKey {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &key: &PublicKey,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { key, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl SessionTransfer {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { to, amount, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SetWatchList {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        keys: &[PublicKey],
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                keys: keys.to_vec(),
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SetMetadata {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, key: &str, value: &str, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl DeleteMetadata {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, key: &str, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                key: key.to_owned(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CreateInvoice {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        amount: u64,
+        &payer: &PublicKey,
+        memo: &str,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                amount,
+                payer,
+                memo: memo.to_owned(),
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl PayInvoice {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &invoice: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { invoice }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl Refund {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &transfer: &Hash,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { transfer, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl OpenDispute {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &transfer: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { transfer }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl ResolveDispute {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &transfer: &Hash,
+        claw_back: bool,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                transfer,
+                claw_back,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl ProposeSwap {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &counterparty: &PublicKey,
+        give_asset: &str,
+        give_amount: u64,
+        take_asset: &str,
+        take_amount: u64,
+        expires_at: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                counterparty,
+                give_asset: give_asset.to_owned(),
+                give_amount,
+                take_asset: take_asset.to_owned(),
+                take_amount,
+                expires_at,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl AcceptSwap {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &swap: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { swap }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl CancelSwap {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &swap: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { swap }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl PlaceOrder {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        base_asset: &str,
+        quote_asset: &str,
+        buy: bool,
+        price: u64,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                base_asset: base_asset.to_owned(),
+                quote_asset: quote_asset.to_owned(),
+                buy,
+                price,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CancelOrder {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &order: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { order }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl AddLiquidity {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        asset: &str,
+        coin_amount: u64,
+        asset_amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                asset: asset.to_owned(),
+                coin_amount,
+                asset_amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RemoveLiquidity {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        asset: &str,
+        shares: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                asset: asset.to_owned(),
+                shares,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl SwapViaPool {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        asset: &str,
+        buy_asset: bool,
+        amount: u64,
+        min_output: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                asset: asset.to_owned(),
+                buy_asset,
+                amount,
+                min_output,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Stake {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { amount, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl DelegateStake {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &validator: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                validator,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Unstake {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { amount, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl DepositSavings {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { amount, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl WithdrawSavings {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, amount: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { amount, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl OpenLoan {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        asset: &str,
+        collateral: u64,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                asset: asset.to_owned(),
+                collateral,
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl RepayLoan {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &loan: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { loan }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl Liquidate {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &loan: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { loan }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl RedeemPoints {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, points: u64, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { points, seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl ProposeParamChange {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        param: &str,
+        value: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                param: param.to_owned(),
+                value,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Vote {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, &proposal: &Hash, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { proposal }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl HaltService {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl ResumeService {
+    #[doc(hidden)]
+    pub fn sign(pk: &PublicKey, seed: u64, sk: &SecretKey) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { seed }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl FaucetIssue {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { to, amount, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl MintNft {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        metadata: &str,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                metadata: metadata.to_owned(),
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl TransferNft {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &token: &Hash,
+        &to: &PublicKey,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(Self { token, to }, CRYPTOCURRENCY_SERVICE_ID, *pk, sk)
+    }
+}
+
+impl LinkWallet {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &multisig_wallet: &PublicKey,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { multisig_wallet },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl Transfer {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Self::sign_with_memo(pk, to, amount, seed, "", sk)
+    }
+
+    #[doc(hidden)]
+    pub fn sign_with_memo(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        memo: &str,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                seed,
+                memo: memo.to_owned(),
+                asset: DEFAULT_ASSET.to_owned(),
+                unlock_at: 0,
+                encrypted_memo: Vec::new(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn sign_with_encrypted_memo(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        encrypted_memo: &[u8],
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                seed,
+                memo: String::new(),
+                asset: DEFAULT_ASSET.to_owned(),
+                unlock_at: 0,
+                encrypted_memo: encrypted_memo.to_vec(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn sign_with_asset(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        asset: &str,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                seed,
+                memo: String::new(),
+                asset: asset.to_owned(),
+                unlock_at: 0,
+                encrypted_memo: Vec::new(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn sign_with_unlock(
+        pk: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        unlock_at: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to,
+                amount,
+                seed,
+                memo: String::new(),
+                asset: DEFAULT_ASSET.to_owned(),
+                unlock_at,
+                encrypted_memo: Vec::new(),
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl TransferByName {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        to_name: &str,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                to_name: to_name.to_owned(),
+                amount,
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl BatchTransfer {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        payments: &[Payment],
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self {
+                payments: payments.to_vec(),
+                seed,
+            },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl TransferMultisign {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &from: &PublicKey,
+        &to: &PublicKey,
+        amount: u64,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { from, to, amount, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl AcceptMultisign {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &tx_hash: &Hash,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { tx_hash, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+impl CancelMultisign {
+    #[doc(hidden)]
+    pub fn sign(
+        pk: &PublicKey,
+        &tx_hash: &Hash,
+        seed: u64,
+        sk: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction(
+            Self { tx_hash, seed },
+            CRYPTOCURRENCY_SERVICE_ID,
+            *pk,
+            sk,
+        )
+    }
+}
+
+/// Moves `amount` of the currency between two ordinary wallets within the sender's
+/// spending limit.
+///
+/// Shared by `Transfer`, `TransferByName`, `TransferFrom` and `PermitTransfer`.
+fn execute_transfer(
+    schema: &mut Schema<&mut Fork>,
+    from: &PublicKey,
+    to: &PublicKey,
+    asset: &str,
+    amount: u64,
+    hash: &Hash,
+) -> ExecutionResult {
+    execute_payment(schema, from, to, asset, amount, hash, false)
+}
+
+/// Moves `amount` of the currency between two ordinary wallets and charges the transfer fee
+/// from the sender; the sender's spending limit doesn't apply if the payment is co-signed.
+fn execute_payment(
+    schema: &mut Schema<&mut Fork>,
+    from: &PublicKey,
+    to: &PublicKey,
+    asset: &str,
+    amount: u64,
+    hash: &Hash,
+    co_signed: bool,
+) -> ExecutionResult {
+    if from == to {
+        Err(Error::SenderSameAsReceiver)?
+    }
+
+    if amount == 0 {
+        Err(Error::NonPositiveAmount)?
+    }
+
+    let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+    let receiver = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+
+    if sender.frozen {
+        Err(Error::WalletFrozen)?
+    }
+
+    if receiver.closed {
+        Err(Error::WalletClosed)?
+    }
+
+    if !schema.accepts_from(to, from) {
+        Err(Error::NotWhitelisted)?
+    }
+
+    if schema.available_balance(&sender, asset) < amount {
+        Err(Error::InsufficientCurrencyAmount)?
+    }
+
+    if asset == DEFAULT_ASSET {
+        schema.earn_loyalty_points(from, amount)?;
+    }
+    schema.record_transfer(asset, amount);
+
+    if co_signed {
+        schema.debit_co_signed_payment(sender, amount, hash)?;
+    } else {
+        schema.debit_payment(sender, asset, amount, hash)?;
+    }
+    schema.credit_payment(receiver, asset, amount, hash)?;
+
+    charge_transfer_fee(schema, from, hash)
+}
+
+/// Charges `Config::transfer_fee` in the default coin from the sender of a transfer.
+///
+/// Charged once per transaction, so a batch of payments pays a single fee.
+fn charge_transfer_fee(
+    schema: &mut Schema<&mut Fork>,
+    from: &PublicKey,
+    hash: &Hash,
+) -> ExecutionResult {
+    let fee = schema.config().transfer_fee;
+    if fee > 0 {
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+        schema.charge_fee(sender, fee, hash)?;
+    }
+    Ok(())
+}
+
+impl ExecuteTransaction for Transfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema
+            .config()
+            .allows_memo(self.memo.len(), self.encrypted_memo.len())
+        {
+            Err(Error::MemoTooLong)?
+        }
+
+        if self.unlock_at != 0 && self.asset != DEFAULT_ASSET {
+            Err(Error::AssetTimeLockUnsupported)?
+        }
+
+        execute_transfer(&mut schema, from, &self.to, &self.asset, self.amount, &hash)?;
+        schema.completed_transfers_mut().insert(hash);
+
+        if self.unlock_at > schema.current_height().0 {
+            let receiver = schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+            schema.lock_funds(receiver, &hash, self.amount, self.unlock_at)?;
+        }
+
+        if !self.encrypted_memo.is_empty() {
+            schema
+                .encrypted_memos_mut()
+                .put(&hash, self.encrypted_memo.clone());
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for TransferByName {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let to = schema
+            .wallet_by_name(&self.to_name)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        execute_transfer(&mut schema, from, &to, DEFAULT_ASSET, self.amount, &hash)
+    }
+}
+
+impl ExecuteTransaction for BatchTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.payments.is_empty() {
+            Err(Error::EmptyBatch)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        let mut total: u64 = 0;
+        for payment in &self.payments {
+            if payment.to == *from {
+                Err(Error::SenderSameAsReceiver)?
+            }
+
+            if payment.amount == 0 {
+                Err(Error::NonPositiveAmount)?
+            }
+
+            let receiver = schema.wallet(&payment.to).ok_or(Error::ReceiverNotFound)?;
+
+            if receiver.closed {
+                Err(Error::WalletClosed)?
+            }
+
+            if !schema.accepts_from(&payment.to, from) {
+                Err(Error::NotWhitelisted)?
+            }
+
+            total = total
+                .checked_add(payment.amount)
+                .ok_or(Error::BalanceOverflow)?;
+        }
+
+        if sender.pending_balance < total {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        schema.debit_payment(sender, DEFAULT_ASSET, total, &hash)?;
+        for payment in &self.payments {
+            // Receiver is re-read because it may occur in the batch several times.
+            let receiver = schema.wallet(&payment.to).ok_or(Error::ReceiverNotFound)?;
+            schema.credit_payment(receiver, DEFAULT_ASSET, payment.amount, &hash)?;
+        }
+
+        charge_transfer_fee(&mut schema, from, &hash)
+    }
+}
+
+impl ExecuteTransaction for TransferMultisign {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let significant = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let from = &self.from;
+        let to = &self.to;
+        let amount = self.amount;
+
+        if from == to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let multisig = schema
+            .multisig_wallet(from)
+            .ok_or(Error::MultisigWalletNotFound)?;
+
+        let is_owner = schema.is_multisig_owner(&multisig, significant);
+        let is_spender = schema
+            .wallet_role(from, significant)
+            .map_or(false, Role::can_spend);
+
+        if !is_owner && !is_spender {
+            Err(Error::SenderNotFound)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if sender.pending_balance < amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        let transfer = PendingTransfer::new(
+            significant,
+            from,
+            to,
+            &multisig.owners,
+            amount,
+            multisig.quorum,
+        );
+        let sender = schema.create_pending_transfer(sender, transfer, &hash)?;
+
+        // Transfers initiated by spenders who aren't owners wait for the owners' approvals.
+        if is_owner {
+            schema.add_multisig_approval(&hash, significant);
+
+            if schema.multisig_quorum_reached(&hash) {
+                schema.release_multisig_transfer(sender, receiver, &hash)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for AcceptMultisign {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let significant = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let hash = &self.tx_hash;
+
+        if let Some(change) = schema.approvers_change(hash) {
+            let multisig = schema
+                .multisig_wallet(&change.multisig_wallet)
+                .ok_or(Error::MultisigWalletNotFound)?;
+
+            if !schema.is_multisig_owner(&multisig, significant) {
+                Err(Error::NotAnOwner)?
+            }
+
+            if schema.multisig_approved_by(hash, significant) {
+                Err(Error::AlreadyApproved)?
+            }
+
+            schema.add_multisig_approval(hash, significant);
+
+            if schema.multisig_quorum_reached(hash) {
+                schema.apply_approvers_change(hash);
+            }
+
+            return Ok(());
+        }
+
+        let transfer = schema
+            .pending_transfer(hash)
+            .ok_or(Error::PendingTransferNotFound)?;
+
+        if !schema.is_transfer_approver(&transfer, significant) {
+            Err(Error::SenderNotFound)?
+        }
+
+        if schema.multisig_approved_by(hash, significant) {
+            Err(Error::AlreadyApproved)?
+        }
+
+        let sender = schema
+            .current_wallet(&transfer.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema
+            .current_wallet(&transfer.to)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.add_multisig_approval(hash, significant);
+
+        if schema.multisig_quorum_reached(hash) {
+            schema.release_multisig_transfer(sender, receiver, hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CancelMultisign {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let hash = &self.tx_hash;
+
+        let transfer = schema
+            .pending_transfer(hash)
+            .ok_or(Error::PendingTransferNotFound)?;
+
+        let sender = schema
+            .current_wallet(&transfer.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        if *author != transfer.initiator && *author != sender.pub_key {
+            Err(Error::CancelNotAllowed)?
+        }
+
+        schema.cancel_multisig_transfer(sender, hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Issue {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.asset == DEFAULT_ASSET {
+            if !schema.config().is_minter(pub_key) {
+                Err(Error::UnauthorizedIssuer)?
+            }
+        } else {
+            let asset = schema.asset(&self.asset).ok_or(Error::AssetNotFound)?;
+            if asset.issuer != *pub_key {
+                Err(Error::UnauthorizedIssuer)?
+            }
+        }
+
+        if let Some(wallet) = schema.wallet(pub_key) {
+            if wallet.closed {
+                Err(Error::WalletClosed)?
+            }
+            let amount = self.amount;
+            if self.asset == DEFAULT_ASSET {
+                schema.increase_issued_supply(amount)?;
+                schema.increase_total_supply(amount)?;
+            } else {
+                schema.increase_asset_issued(&self.asset, amount)?;
+            }
+            schema.increase_asset_balance(wallet, &self.asset, amount, &hash)?;
+            Ok(())
+        } else {
+            Err(Error::ReceiverNotFound)?
+        }
+    }
+}
+
+impl ExecuteTransaction for Burn {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let amount = self.amount;
+
+        if amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let wallet = schema.wallet(pub_key).ok_or(Error::SenderNotFound)?;
+
+        if wallet.pending_balance < amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        schema.decrease_wallet_balance(wallet, amount, &hash)?;
+        schema.decrease_total_supply(amount)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CreateWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if schema.wallet(pub_key).is_none() && !schema.rotated_keys().contains(pub_key) {
+            let name = &self.name;
+            schema.create_wallet(pub_key, name, &hash)?;
+            Ok(())
+        } else {
+            Err(Error::WalletAlreadyExists)?
+        }
+    }
+}
+
+impl ExecuteTransaction for CreateMultisigWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.quorum == 0 || self.quorum as usize > self.owners.len() {
+            Err(Error::InvalidQuorum)?
+        }
+
+        if schema.wallet(pub_key).is_none() && !schema.rotated_keys().contains(pub_key) {
+            let name = &self.name;
+            schema.create_multisig_wallet(pub_key, name, &self.owners, self.quorum, &hash)?;
+            Ok(())
+        } else {
+            Err(Error::WalletAlreadyExists)?
+        }
+    }
+}
+
+impl ExecuteTransaction for LinkWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let multisig_key = &self.multisig_wallet;
+
+        schema.wallet(pub_key).ok_or(Error::SenderNotFound)?;
+
+        let multisig = schema
+            .multisig_wallet(multisig_key)
+            .ok_or(Error::MultisigWalletNotFound)?;
+
+        if !schema.is_multisig_owner(&multisig, pub_key) {
+            Err(Error::NotAnOwner)?
+        }
+
+        if schema
+            .linked_multisig_wallets(pub_key)
+            .iter()
+            .any(|key| key == *multisig_key)
+        {
+            Err(Error::WalletAlreadyLinked)?
+        }
+
+        schema.link_wallet(pub_key, multisig_key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ChangeApprovers {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let significant = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let multisig_key = &self.multisig_wallet;
+
+        let multisig = schema
+            .multisig_wallet(multisig_key)
+            .ok_or(Error::MultisigWalletNotFound)?;
+
+        if !schema.is_multisig_owner(&multisig, significant) {
+            Err(Error::NotAnOwner)?
+        }
+
+        if self.quorum == 0 || self.quorum as usize > self.owners.len() {
+            Err(Error::InvalidQuorum)?
+        }
+
+        let change = ApproversChange::new(significant, multisig_key, &self.owners, self.quorum);
+        schema.approvers_changes_mut().put(&hash, change);
+        schema.add_multisig_approval(&hash, significant);
+
+        if schema.multisig_quorum_reached(&hash) {
+            schema.apply_approvers_change(&hash);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for FreezeWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
+        }
+
+        let wallet = schema.wallet(&self.pub_key).ok_or(Error::WalletNotFound)?;
+        schema.set_wallet_frozen(wallet, true);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for UnfreezeWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
+        }
+
+        let wallet = schema.wallet(&self.pub_key).ok_or(Error::WalletNotFound)?;
+        schema.set_wallet_frozen(wallet, false);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CloseWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let to = &self.to;
+
+        if from == to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.closed || receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if schema
+            .wallet_pending_txs(from)
+            .iter()
+            .any(|tx_hash| schema.time_locks().contains(&tx_hash))
+        {
+            Err(Error::FundsTimeLocked)?
+        }
+
+        if schema.wallet_pending_txs(from).iter().next().is_some() {
+            Err(Error::PendingTransfersExist)?
+        }
+
+        if schema.has_open_positions(from) {
+            Err(Error::OpenPositionsExist)?
+        }
+
+        let amount = sender.balance;
+        let sender = schema.close_wallet(sender);
+        let mut sender = schema.debit_payment(sender, DEFAULT_ASSET, amount, &hash)?;
+        let mut receiver = schema.credit_payment(receiver, DEFAULT_ASSET, amount, &hash)?;
+
+        let assets = schema
+            .asset_balances(from)
+            .iter()
+            .filter(|&(_, balance)| balance > 0)
+            .collect::<Vec<_>>();
+        for (asset, balance) in assets {
+            sender = schema.debit_payment(sender, &asset, balance, &hash)?;
+            receiver = schema.credit_payment(receiver, &asset, balance, &hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for DefineAsset {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let issuer = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.code.is_empty() || self.code.contains('/') {
+            Err(Error::InvalidAssetCode)?
+        }
+
+        if schema.asset(&self.code).is_some() {
+            Err(Error::AssetAlreadyExists)?
+        }
+
+        schema.define_asset(&self.code, self.decimals, issuer, self.max_supply);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for MintNft {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::WalletNotFound)?;
+
+        schema.set_nft_owner(&hash, owner);
+        schema.append_wallet_history(wallet, &hash);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for TransferNft {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let owner = schema.nft_owner(&self.token).ok_or(Error::NftNotFound)?;
+
+        if owner != *from {
+            Err(Error::NotNftOwner)?
+        }
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.set_nft_owner(&self.token, &self.to);
+        schema.append_wallet_history(sender, &hash);
+        schema.append_wallet_history(receiver, &hash);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Approve {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(owner).ok_or(Error::WalletNotFound)?;
+
+        schema.set_allowance(owner, &self.spender, self.amount);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for TransferFrom {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let spender = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let allowance = schema.allowance(&self.owner, spender);
+
+        if allowance < self.amount {
+            Err(Error::InsufficientAllowance)?
+        }
+
+        execute_transfer(
+            &mut schema,
+            &self.owner,
+            &self.to,
+            DEFAULT_ASSET,
+            self.amount,
+            &hash,
+        )?;
+
+        schema.set_allowance(&self.owner, spender, allowance - self.amount);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for PermitTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let data = Self::permit_data(&self.owner, &self.to, self.amount, self.seed);
+
+        if !crypto::verify(&self.signature, &data, &self.owner) {
+            Err(Error::InvalidPermitSignature)?
+        }
+
+        let permit = crypto::hash(&data);
+
+        if schema.used_permits().contains(&permit) {
+            Err(Error::PermitAlreadyUsed)?
+        }
+
+        execute_transfer(
+            &mut schema,
+            &self.owner,
+            &self.to,
+            DEFAULT_ASSET,
+            self.amount,
+            &hash,
+        )?;
+
+        let height = schema.current_height().0;
+        schema.used_permits_mut().put(&permit, height);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CreateEscrow {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if sender.pending_balance < self.amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        let escrow = Escrow::new(from, &self.to, &self.arbitrator, self.amount);
+        schema.create_escrow(sender, escrow, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ReleaseEscrow {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let escrow = schema.escrow(&self.escrow).ok_or(Error::EscrowNotFound)?;
+
+        if escrow.arbitrator != *author {
+            Err(Error::NotAnArbitrator)?
+        }
+
+        let sender = schema
+            .current_wallet(&escrow.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema
+            .current_wallet(&escrow.to)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.release_escrow(sender, receiver, &self.escrow, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RefundEscrow {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let escrow = schema.escrow(&self.escrow).ok_or(Error::EscrowNotFound)?;
+
+        if escrow.arbitrator != *author {
+            Err(Error::NotAnArbitrator)?
+        }
+
+        let sender = schema
+            .current_wallet(&escrow.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        schema.refund_escrow(sender, &self.escrow, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for LockWithHash {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.timeout <= schema.current_height().0 {
+            Err(Error::HashLockExpired)?
+        }
+
+        if schema.hash_lock(&self.hash_lock).is_some() {
+            Err(Error::HashLockAlreadyExists)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if sender.pending_balance < self.amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        let lock = HashLock::new(from, &self.to, self.amount, self.timeout);
+        schema.create_hash_lock(sender, lock, &self.hash_lock, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ClaimWithPreimage {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let hash_lock = crypto::hash(&self.preimage);
+
+        let lock = schema
+            .hash_lock(&hash_lock)
+            .ok_or(Error::HashLockNotFound)?;
+
+        if lock.timeout <= schema.current_height().0 {
+            Err(Error::HashLockExpired)?
+        }
+
+        let sender = schema
+            .current_wallet(&lock.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        let receiver = schema
+            .current_wallet(&lock.to)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.claim_hash_lock(sender, receiver, &hash_lock, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RefundHashLock {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let lock = schema
+            .hash_lock(&self.hash_lock)
+            .ok_or(Error::HashLockNotFound)?;
+
+        if lock.timeout > schema.current_height().0 {
+            Err(Error::HashLockNotExpired)?
+        }
+
+        let sender = schema
+            .current_wallet(&lock.from)
+            .ok_or(Error::SenderNotFound)?;
+
+        schema.refund_hash_lock(sender, &self.hash_lock, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CreateVesting {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let issuer = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.duration == 0 || self.cliff > self.duration {
+            Err(Error::InvalidVestingSchedule)?
+        }
+
+        if !schema.config().is_minter(issuer) {
+            Err(Error::UnauthorizedIssuer)?
+        }
+
+        schema
+            .wallet(&self.beneficiary)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        schema.increase_issued_supply(self.amount)?;
+
+        let vesting = Vesting::new(
+            &self.beneficiary,
+            self.amount,
+            0,
+            self.start,
+            self.cliff,
+            self.duration,
+        );
+        schema.open_position(&self.beneficiary, &hash);
+        schema.vestings_mut().put(&hash, vesting);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ClaimVesting {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let vesting = schema
+            .vesting(&self.vesting)
+            .ok_or(Error::VestingNotFound)?;
+
+        let beneficiary = schema
+            .wallet(&vesting.beneficiary)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        if beneficiary.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.claim_vesting(beneficiary, &self.vesting, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CreateStandingOrder {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.interval == 0 {
+            Err(Error::InvalidInterval)?
+        }
+
+        schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        let next_at = schema
+            .current_height()
+            .0
+            .checked_add(self.interval)
+            .ok_or(Error::InvalidInterval)?;
+        let order = StandingOrder::new(from, &self.to, self.amount, self.interval, next_at);
+        schema.standing_orders_mut().put(&hash, order);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CancelStandingOrder {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let order = schema
+            .standing_order(&self.order)
+            .ok_or(Error::StandingOrderNotFound)?;
+
+        if order.from != *author {
+            Err(Error::CancelNotAllowed)?
+        }
+
+        schema.standing_orders_mut().remove(&self.order);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for OpenStream {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.rate_per_block == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        let opened_at = schema.current_height().0;
+        let stream = Stream::new(from, &self.to, self.rate_per_block, opened_at);
+        schema.streams_mut().put(&hash, stream);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CloseStream {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let stream = schema.stream(&self.stream).ok_or(Error::StreamNotFound)?;
+
+        if !stream.is_party(author) {
+            Err(Error::CancelNotAllowed)?
+        }
+
+        schema.streams_mut().remove(&self.stream);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ScheduleTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if *from == self.to {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.at_height <= schema.current_height().0 {
+            Err(Error::ScheduledHeightPassed)?
+        }
+
+        schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        let transfer = ScheduledTransfer::new(from, &self.to, self.amount, self.at_height);
+        schema.schedule_transfer(transfer, &hash);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Airdrop {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if !schema.config().is_minter(pub_key) {
+            Err(Error::UnauthorizedIssuer)?
+        }
+
+        let recipients = if self.recipients.is_empty() {
+            schema
+                .wallets()
+                .values()
+                .filter(|wallet| !wallet.closed && !schema.is_blacklisted(&wallet.pub_key))
+                .map(|wallet| wallet.pub_key)
+                .collect::<BTreeSet<_>>()
+        } else {
+            let mut recipients = BTreeSet::new();
+            for key in &self.recipients {
+                let receiver = schema.wallet(key).ok_or(Error::ReceiverNotFound)?;
+                if receiver.closed {
+                    Err(Error::WalletClosed)?
+                }
+                // Blacklisted wallets are skipped like in the airdrop to every wallet.
+                if !schema.is_blacklisted(key) {
+                    recipients.insert(*key);
+                }
+            }
+            recipients
+        };
+
+        if recipients.is_empty() {
+            Err(Error::NoAirdropRecipients)?
+        }
+
+        let total = self
+            .amount
+            .checked_mul(recipients.len() as u64)
+            .ok_or(Error::BalanceOverflow)?;
+        schema.increase_issued_supply(total)?;
+        schema.increase_total_supply(total)?;
+        for key in &recipients {
+            let receiver = schema.wallet(key).ok_or(Error::ReceiverNotFound)?;
+            schema.increase_wallet_balance(receiver, self.amount, &hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for DistributeDividend {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.total_amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if sender.pending_balance < self.total_amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        let holders = schema
+            .wallets()
+            .values()
+            .filter(|wallet| {
+                wallet.pub_key != *from
+                    && !wallet.closed
+                    && wallet.balance > 0
+                    && !schema.is_blacklisted(&wallet.pub_key)
+            })
+            .map(|wallet| (wallet.pub_key, wallet.balance))
+            .collect::<Vec<_>>();
+        let total_balance = holders
+            .iter()
+            .map(|&(_, balance)| u128::from(balance))
+            .sum::<u128>();
+
+        let shares = holders
+            .into_iter()
+            .map(|(key, balance)| {
+                let share = u128::from(self.total_amount) * u128::from(balance) / total_balance;
+                (key, share as u64)
+            })
+            .filter(|&(_, share)| share > 0)
+            .collect::<Vec<_>>();
+
+        if shares.is_empty() {
+            Err(Error::NoDividendRecipients)?
+        }
+
+        let distributed = shares.iter().map(|&(_, share)| share).sum();
+        schema.debit_payment(sender, DEFAULT_ASSET, distributed, &hash)?;
+        for (key, share) in shares {
+            let receiver = schema.wallet(&key).ok_or(Error::ReceiverNotFound)?;
+            schema.credit_payment(receiver, DEFAULT_ASSET, share, &hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetSpendingLimit {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::SenderNotFound)?;
+
+        if self.limit == 0 {
+            schema.spending_limits_mut().remove(pub_key);
+        } else {
+            let limit = SpendingLimit::new(self.limit, &self.co_signer);
+            schema.spending_limits_mut().put(pub_key, limit);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CoSignedTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let from = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if let Some(limit) = schema.spending_limit(from) {
+            let data = Self::co_sign_data(from, &self.to, self.amount, self.seed);
+            if !crypto::verify(&self.co_signature, &data, &limit.co_signer) {
+                Err(Error::InvalidCoSignature)?
+            }
+        }
+
+        execute_payment(
+            &mut schema,
+            from,
+            &self.to,
+            DEFAULT_ASSET,
+            self.amount,
+            &hash,
+            true,
+        )
+    }
+}
+
+impl ExecuteTransaction for BlacklistKey {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
+        }
+
+        if !schema.is_blacklisted(&self.pub_key) {
+            let height = schema.current_height().0;
+            schema.blacklist_mut().put(&self.pub_key, height);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for UnblacklistKey {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
+        }
+
+        if !schema.is_blacklisted(&self.pub_key) {
+            Err(Error::NotBlacklisted)?
+        }
+
+        schema.blacklist_mut().remove(&self.pub_key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetWhitelist {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        if self.senders.is_empty() {
+            schema.whitelists_mut().remove(pub_key);
+        } else {
+            schema
+                .whitelists_mut()
+                .put(pub_key, Whitelist::new(&self.senders));
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetKycStatus {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_compliance_officer(author) {
+            Err(Error::NotAComplianceOfficer)?
+        }
+
+        let wallet = schema.wallet(&self.pub_key).ok_or(Error::WalletNotFound)?;
+        schema.set_wallet_kyc_verified(wallet, self.verified);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for GrantRole {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(&self.wallet).ok_or(Error::WalletNotFound)?;
+
+        let is_admin = schema
+            .wallet_role(&self.wallet, author)
+            .map_or(false, Role::can_manage_roles);
+
+        if !schema.is_wallet_owner(&self.wallet, author) && !is_admin {
+            Err(Error::RoleManagementNotAllowed)?
+        }
+
+        if self.role == 0 {
+            schema.wallet_roles_mut(&self.wallet).remove(&self.grantee);
+        } else {
+            let role = Role::from_code(self.role).ok_or(Error::UnknownRole)?;
+            schema
+                .wallet_roles_mut(&self.wallet)
+                .put(&self.grantee, role as u8);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for DelegatedTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let spender = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let is_spender = schema
+            .wallet_role(&self.from, spender)
+            .map_or(false, Role::can_spend);
+
+        if is_spender {
+            return execute_transfer(
+                &mut schema,
+                &self.from,
+                &self.to,
+                DEFAULT_ASSET,
+                self.amount,
+                &hash,
+            );
+        }
+
+        let delegation = schema
+            .delegation(&self.from, spender)
+            .ok_or(Error::NotASpender)?;
+
+        if delegation.is_expired_at(schema.current_height().0) {
+            Err(Error::DelegationExpired)?
+        }
+
+        if delegation.remaining() < self.amount {
+            Err(Error::DelegationCapExceeded)?
+        }
+
+        execute_transfer(
+            &mut schema,
+            &self.from,
+            &self.to,
+            DEFAULT_ASSET,
+            self.amount,
+            &hash,
+        )?;
+
+        let spent = delegation.spent + self.amount;
+        schema
+            .delegations_mut(&self.from)
+            .put(spender, delegation.set_spent(spent));
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Delegate {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(owner).ok_or(Error::WalletNotFound)?;
+
+        if self.max_amount == 0 {
+            schema.delegations_mut(owner).remove(&self.spender);
+        } else {
+            let delegation = Delegation::new(&self.spender, self.max_amount, 0, self.expiry);
+            schema.delegations_mut(owner).put(&self.spender, delegation);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RotateKey {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        if schema.multisig_wallet(pub_key).is_some() {
+            Err(Error::MultisigKeyRotation)?
+        }
+
+        if schema.is_blacklisted(pub_key) {
+            Err(Error::Blacklisted)?
+        }
+
+        let new_key = &self.new_pub_key;
+        if schema.wallet(new_key).is_some() || schema.rotated_keys().contains(new_key) {
+            Err(Error::KeyAlreadyUsed)?
+        }
+
+        schema.rotate_wallet_key(wallet, new_key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetGuardians {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        if self.guardians.is_empty() {
+            schema.guardians_mut().remove(pub_key);
+        } else {
+            if self.threshold == 0 || self.threshold as usize > self.guardians.len() {
+                Err(Error::InvalidQuorum)?
+            }
+            let guardians = Guardians::new(&self.guardians, self.threshold);
+            schema.guardians_mut().put(pub_key, guardians);
+        }
+        schema.recoveries_mut().remove(pub_key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RecoverWallet {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let guardian = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(&self.wallet).ok_or(Error::WalletNotFound)?;
+
+        let guardians = schema
+            .guardians()
+            .get(&self.wallet)
+            .ok_or(Error::NotAGuardian)?;
+
+        if !guardians.is_guardian(guardian) {
+            Err(Error::NotAGuardian)?
+        }
+
+        let new_key = &self.new_key;
+        if schema.wallet(new_key).is_some() || schema.rotated_keys().contains(new_key) {
+            Err(Error::KeyAlreadyUsed)?
+        }
+
+        let recovery = schema
+            .recovery(&self.wallet)
+            .unwrap_or_else(|| Recovery::new(new_key, &[], 0));
+
+        if recovery.new_key != *new_key {
+            Err(Error::RecoveryKeyMismatch)?
+        }
+
+        if recovery.approvals.contains(guardian) {
+            Err(Error::AlreadyApproved)?
+        }
+
+        let mut recovery = recovery.approve(guardian);
+        if !recovery.is_scheduled() && recovery.approvals.len() >= guardians.threshold as usize {
+            let challenge_period = schema.config().recovery_challenge_period;
+            let complete_at = schema.current_height().0 + challenge_period.max(1);
+            recovery = recovery.schedule(complete_at);
+        }
+        schema.put_recovery(&self.wallet, recovery);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CancelRecovery {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.recovery(pub_key).ok_or(Error::RecoveryNotFound)?;
+        schema.recoveries_mut().remove(pub_key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetBeneficiary {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        if self.inactivity_blocks == 0 {
+            schema.inheritances_mut().remove(pub_key);
+            return Ok(());
+        }
+
+        if self.beneficiary == *pub_key {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        schema
+            .wallet(&self.beneficiary)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        let height = schema.current_height().0;
+        let inheritance = Inheritance::new(&self.beneficiary, self.inactivity_blocks, height);
+        schema.inheritances_mut().put(pub_key, inheritance);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ClaimInheritance {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let beneficiary = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.current_key(&self.wallet);
+
+        let inheritance = schema
+            .inheritances()
+            .get(&wallet)
+            .ok_or(Error::NotABeneficiary)?;
+
+        if schema.current_key(&inheritance.beneficiary) != *beneficiary {
+            Err(Error::NotABeneficiary)?
+        }
+
+        if !inheritance.is_claimable(schema.current_height().0) {
+            Err(Error::WalletStillActive)?
+        }
+
+        let sender = schema.wallet(&wallet).ok_or(Error::SenderNotFound)?;
+        let receiver = schema.wallet(beneficiary).ok_or(Error::ReceiverNotFound)?;
+
+        if sender.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if receiver.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if !schema.accepts_from(beneficiary, &wallet) {
+            Err(Error::NotWhitelisted)?
+        }
+
+        let amount = sender.pending_balance;
+        if amount == 0 {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        schema.inheritances_mut().remove(&wallet);
+        schema.debit_payment(sender, DEFAULT_ASSET, amount, &hash)?;
+        schema.credit_payment(receiver, DEFAULT_ASSET, amount, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for AddSessionKey {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        if self.max_amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let session = SessionKey::new(pub_key, self.max_amount, self.expires_at);
+        if session.is_expired_at(schema.current_height().0) {
+            Err(Error::SessionKeyExpired)?
+        }
+
+        let key = &self.key;
+        if let Some(other) = schema.session_keys().get(key) {
+            if schema.current_key(&other.wallet) != *pub_key {
+                Err(Error::KeyAlreadyUsed)?
+            }
+        }
+
+        schema.add_session_key(key, session);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RevokeSessionKey {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let session = schema
+            .session_keys()
+            .get(&self.key)
+            .ok_or(Error::SessionKeyNotFound)?;
+
+        if schema.current_key(&session.wallet) != *pub_key {
+            Err(Error::SessionKeyNotFound)?
+        }
+
+        schema.session_keys_mut().remove(&self.key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SessionTransfer {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let key = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let session = schema
+            .session_keys()
+            .get(key)
+            .ok_or(Error::SessionKeyNotFound)?;
+
+        if session.is_expired_at(schema.current_height().0) {
+            Err(Error::SessionKeyExpired)?
+        }
+
+        if self.amount > session.max_amount {
+            Err(Error::SessionAmountExceeded)?
+        }
+
+        let from = schema.current_key(&session.wallet);
+        execute_transfer(
+            &mut schema,
+            &from,
+            &self.to,
+            DEFAULT_ASSET,
+            self.amount,
+            &hash,
+        )
+    }
+}
+
+impl ExecuteTransaction for SetWatchList {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if self.keys.len() as u64 > schema.config().max_watched_keys {
+            Err(Error::WatchListTooLong)?
+        }
+
+        if self.keys.is_empty() {
+            schema.watch_lists_mut().remove(pub_key);
+        } else {
+            schema
+                .watch_lists_mut()
+                .put(pub_key, WatchList::new(&self.keys));
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SetMetadata {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(pub_key).ok_or(Error::WalletNotFound)?;
+
+        let config = schema.config();
+        if !config.allows_metadata(self.key.len(), self.value.len()) {
+            Err(Error::MetadataTooLarge)?
+        }
+
+        let metadata = schema.wallet_metadata(pub_key);
+        if !metadata.contains(&self.key)
+            && metadata.keys().count() as u64 >= config.max_metadata_entries
+        {
+            Err(Error::MetadataTooLarge)?
+        }
+
+        schema
+            .wallet_metadata_mut(pub_key)
+            .put(&self.key, self.value.clone());
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for DeleteMetadata {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let pub_key = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.wallet_metadata(pub_key).contains(&self.key) {
+            Err(Error::MetadataNotFound)?
+        }
+
+        schema.wallet_metadata_mut(pub_key).remove(&self.key);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CreateInvoice {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let merchant = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(merchant).ok_or(Error::WalletNotFound)?;
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if merchant == &self.payer {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if !schema.config().allows_memo(self.memo.len(), 0) {
+            Err(Error::MemoTooLong)?
+        }
+
+        let invoice = Invoice::new(
+            merchant,
+            &self.payer,
+            self.amount,
+            &self.memo,
+            &Hash::zero(),
+        );
+        schema.invoices_mut().put(&hash, invoice);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for PayInvoice {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let payer = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let invoice = schema
+            .invoice(&self.invoice)
+            .ok_or(Error::InvoiceNotFound)?;
+
+        if schema.current_key(&invoice.payer) != *payer {
+            Err(Error::NotAPayer)?
+        }
+
+        if invoice.is_paid() {
+            Err(Error::InvoiceAlreadyPaid)?
+        }
+
+        let merchant = schema.current_key(&invoice.merchant);
+        execute_transfer(
+            &mut schema,
+            payer,
+            &merchant,
+            DEFAULT_ASSET,
+            invoice.amount,
+            &hash,
+        )?;
+
+        schema
+            .invoices_mut()
+            .put(&self.invoice, invoice.set_payment(&hash));
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Refund {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let receiver = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let (sender, transfer) = schema
+            .committed_transfer(&self.transfer)
+            .ok_or(Error::TransferNotFound)?;
+
+        let received = schema.current_key(&transfer.to) == *receiver
+            && schema.completed_transfers().contains(&self.transfer);
+        if !received {
+            Err(Error::TransferNotFound)?
+        }
+
+        if schema.refunds().contains(&self.transfer) {
+            Err(Error::AlreadyRefunded)?
+        }
+
+        let sender = schema.current_key(&sender);
+        execute_transfer(
+            &mut schema,
+            receiver,
+            &sender,
+            &transfer.asset,
+            transfer.amount,
+            &hash,
+        )?;
+
+        schema.refunds_mut().put(&self.transfer, hash);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for OpenDispute {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let sender = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let (author, transfer) = schema
+            .committed_transfer(&self.transfer)
+            .ok_or(Error::TransferNotFound)?;
+
+        let sent = schema.current_key(&author) == *sender
+            && schema.completed_transfers().contains(&self.transfer);
+        if !sent {
+            Err(Error::TransferNotFound)?
+        }
+
+        let window = schema.config().dispute_window;
+        let sent_at = schema
+            .transaction_height(&self.transfer)
+            .ok_or(Error::TransferNotFound)?;
+        if window == 0 || schema.current_height().0 > sent_at.0 + window {
+            Err(Error::DisputeWindowPassed)?
+        }
+
+        if schema.dispute(&self.transfer).is_some() {
+            Err(Error::AlreadyDisputed)?
+        }
+
+        if schema.refunds().contains(&self.transfer) {
+            Err(Error::AlreadyRefunded)?
+        }
+
+        let dispute = Dispute::new(
+            &author,
+            &transfer.to,
+            &transfer.asset,
+            transfer.amount,
+            false,
+        );
+        schema.disputes_mut().put(&self.transfer, dispute);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ResolveDispute {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_arbitrator(author) {
+            Err(Error::NotAnArbitrator)?
+        }
+
+        let dispute = schema
+            .dispute(&self.transfer)
+            .filter(|dispute| !dispute.resolved)
+            .ok_or(Error::DisputeNotFound)?;
+
+        if self.claw_back {
+            if schema.refunds().contains(&self.transfer) {
+                Err(Error::AlreadyRefunded)?
+            }
+
+            let receiver = schema
+                .current_wallet(&dispute.to)
+                .ok_or(Error::ReceiverNotFound)?;
+
+            let sender = schema
+                .current_wallet(&dispute.from)
+                .ok_or(Error::SenderNotFound)?;
+
+            if schema.available_balance(&receiver, &dispute.asset) < dispute.amount {
+                Err(Error::InsufficientCurrencyAmount)?
+            }
+
+            // The claw-back is ordered by an arbitrator and returns a payment which passed
+            // the limits when it was made, so neither leg is checked against them again.
+            schema.decrease_asset_balance(receiver, &dispute.asset, dispute.amount, &hash)?;
+            schema.increase_asset_balance(sender, &dispute.asset, dispute.amount, &hash)?;
+            schema.refunds_mut().put(&self.transfer, hash);
+        }
+
+        schema.disputes_mut().put(&self.transfer, dispute.resolve());
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for ProposeSwap {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let proposer = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(proposer).ok_or(Error::SenderNotFound)?;
+        schema
+            .wallet(&self.counterparty)
+            .ok_or(Error::ReceiverNotFound)?;
+
+        if proposer == &self.counterparty {
+            Err(Error::SenderSameAsReceiver)?
+        }
+
+        if self.give_amount == 0 || self.take_amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.give_asset == self.take_asset {
+            Err(Error::SameSwapAssets)?
+        }
+
+        for asset in &[&self.give_asset, &self.take_asset] {
+            if asset.as_str() != DEFAULT_ASSET && schema.asset(asset).is_none() {
+                Err(Error::AssetNotFound)?
+            }
+        }
+
+        let swap = Swap::new(
+            proposer,
+            &self.counterparty,
+            &self.give_asset,
+            self.give_amount,
+            &self.take_asset,
+            self.take_amount,
+            self.expires_at,
+        );
+        if swap.is_expired_at(schema.current_height().0) {
+            Err(Error::SwapExpired)?
+        }
+        schema.swaps_mut().put(&hash, swap);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for AcceptSwap {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let counterparty = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let swap = schema.swap(&self.swap).ok_or(Error::SwapNotFound)?;
+
+        if schema.current_key(&swap.counterparty) != *counterparty {
+            Err(Error::NotACounterparty)?
+        }
+
+        if swap.is_expired_at(schema.current_height().0) {
+            Err(Error::SwapExpired)?
+        }
+
+        // A failed leg rolls back the whole transaction, so either both legs happen or none.
+        let proposer = schema.current_key(&swap.proposer);
+        execute_transfer(
+            &mut schema,
+            &proposer,
+            counterparty,
+            &swap.give_asset,
+            swap.give_amount,
+            &hash,
+        )?;
+        execute_transfer(
+            &mut schema,
+            counterparty,
+            &proposer,
+            &swap.take_asset,
+            swap.take_amount,
+            &hash,
+        )?;
+
+        schema.swaps_mut().remove(&self.swap);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CancelSwap {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let proposer = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        let swap = schema.swap(&self.swap).ok_or(Error::SwapNotFound)?;
+
+        if schema.current_key(&swap.proposer) != *proposer {
+            Err(Error::CancelNotAllowed)?
+        }
+
+        schema.swaps_mut().remove(&self.swap);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for PlaceOrder {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.price == 0 || self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.base_asset == self.quote_asset {
+            Err(Error::InvalidMarket)?
+        }
+
+        for asset in &[&self.base_asset, &self.quote_asset] {
+            if asset.as_str() != DEFAULT_ASSET && schema.asset(asset).is_none() {
+                Err(Error::AssetNotFound)?
+            }
+        }
+
+        let seq = schema.next_order_seq();
+        let order = Order::new(
+            owner,
+            &self.base_asset,
+            &self.quote_asset,
+            self.buy,
+            self.price,
+            self.amount,
+            seq,
+        );
+
+        let (asset, reserve) = Schema::order_reserve(&order, self.amount)?;
+        if schema.available_balance(&wallet, &asset) < reserve {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        schema.debit_payment(wallet, &asset, reserve, &hash)?;
+
+        schema.match_order(order, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for CancelOrder {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let order = schema.order(&self.order).ok_or(Error::OrderNotFound)?;
+
+        if schema.current_key(&order.owner) != *owner {
+            Err(Error::CancelNotAllowed)?
+        }
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        // The reserve was charged from this wallet as a payment when the order was placed,
+        // so returning it is not a new incoming payment.
+        let (asset, reserve) = Schema::order_reserve(&order, order.amount)?;
+        schema.increase_asset_balance(wallet, &asset, reserve, &hash)?;
+
+        schema
+            .order_book_mut(&order.book())
+            .remove(&order.book_key());
+        schema.orders_mut().remove(&self.order);
+        schema.close_position(owner, &self.order);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for AddLiquidity {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let provider = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(provider).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.coin_amount == 0 || self.asset_amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.asset == DEFAULT_ASSET || schema.asset(&self.asset).is_none() {
+            Err(Error::AssetNotFound)?
+        }
+
+        let pool = schema
+            .pool(&self.asset)
+            .unwrap_or_else(|| Pool::new(&self.asset, 0, 0, 0));
+        let (shares, coin_used, asset_used) = pool.deposit(self.coin_amount, self.asset_amount);
+        if shares == 0 {
+            Err(Error::InsufficientLiquidity)?
+        }
+
+        if wallet.pending_balance < coin_used
+            || schema.available_balance(&wallet, &self.asset) < asset_used
+        {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        let wallet = schema.decrease_asset_balance(wallet, DEFAULT_ASSET, coin_used, &hash)?;
+        schema.decrease_asset_balance(wallet, &self.asset, asset_used, &hash)?;
+
+        let coin_reserve = pool
+            .coin_reserve
+            .checked_add(coin_used)
+            .ok_or(Error::BalanceOverflow)?;
+        let asset_reserve = pool
+            .asset_reserve
+            .checked_add(asset_used)
+            .ok_or(Error::BalanceOverflow)?;
+        let total_shares = pool
+            .total_shares
+            .checked_add(shares)
+            .ok_or(Error::BalanceOverflow)?;
+        let owned = schema
+            .pool_shares(&self.asset)
+            .get(provider)
+            .unwrap_or_default();
+
+        schema.pools_mut().put(
+            &self.asset,
+            pool.set_reserves(coin_reserve, asset_reserve, total_shares),
+        );
+        schema
+            .pool_shares_mut(&self.asset)
+            .put(provider, owned + shares);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for RemoveLiquidity {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let provider = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(provider).ok_or(Error::SenderNotFound)?;
+
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if self.shares == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let pool = schema
+            .pool(&self.asset)
+            .filter(|pool| pool.total_shares > 0)
+            .ok_or(Error::PoolNotFound)?;
+
+        let owned = schema
+            .pool_shares(&self.asset)
+            .get(provider)
+            .unwrap_or_default();
+        if owned < self.shares {
+            Err(Error::InsufficientShares)?
+        }
+
+        let (coin, asset) = pool.withdrawal(self.shares);
+        let wallet = schema.credit_payment(wallet, DEFAULT_ASSET, coin, &hash)?;
+        schema.credit_payment(wallet, &self.asset, asset, &hash)?;
+
+        let coin_reserve = pool.coin_reserve - coin;
+        let asset_reserve = pool.asset_reserve - asset;
+        let total_shares = pool.total_shares - self.shares;
+        schema.pools_mut().put(
+            &self.asset,
+            pool.set_reserves(coin_reserve, asset_reserve, total_shares),
+        );
+        if owned == self.shares {
+            schema.pool_shares_mut(&self.asset).remove(provider);
+        } else {
+            schema
+                .pool_shares_mut(&self.asset)
+                .put(provider, owned - self.shares);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for SwapViaPool {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(author).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let pool = schema
+            .pool(&self.asset)
+            .filter(|pool| pool.total_shares > 0)
+            .ok_or(Error::PoolNotFound)?;
+
+        let output = pool.output(self.buy_asset, self.amount);
+        if output == 0 {
+            Err(Error::InsufficientLiquidity)?
+        }
+        if output < self.min_output {
+            Err(Error::SlippageExceeded)?
+        }
+
+        let (input_asset, output_asset) = if self.buy_asset {
+            (DEFAULT_ASSET, self.asset.as_str())
+        } else {
+            (self.asset.as_str(), DEFAULT_ASSET)
+        };
+        if schema.available_balance(&wallet, input_asset) < self.amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        let wallet = schema.debit_payment(wallet, input_asset, self.amount, &hash)?;
+        schema.credit_payment(wallet, output_asset, output, &hash)?;
+
+        let (coin_reserve, asset_reserve) = if self.buy_asset {
+            let coin_reserve = pool
+                .coin_reserve
+                .checked_add(self.amount)
+                .ok_or(Error::BalanceOverflow)?;
+            (coin_reserve, pool.asset_reserve - output)
+        } else {
+            let asset_reserve = pool
+                .asset_reserve
+                .checked_add(self.amount)
+                .ok_or(Error::BalanceOverflow)?;
+            (pool.coin_reserve - output, asset_reserve)
+        };
+        let total_shares = pool.total_shares;
+        schema.pools_mut().put(
+            &self.asset,
+            pool.set_reserves(coin_reserve, asset_reserve, total_shares),
+        );
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for Stake {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        schema.stake_coins(wallet, owner, self.amount, &hash)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for DelegateStake {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if !schema.is_validator(&self.validator) {
+            Err(Error::NotAValidator)?
+        }
+
+        schema.stake_coins(wallet, &self.validator, self.amount, &hash)?;
+
+        Ok(())
+    }
 }
 
-/// Transaction group.
-#[derive(Serialize, Deserialize, Clone, Debug, TransactionSet)]
-pub enum WalletTransactions {
-    /// Transfer tx.
-    Transfer(Transfer),
-    /// Multisign transfer tx.
-    TransferMultisign(TransferMultisign),
-    /// Accept multisign transfer
-    AcceptMultisign(AcceptMultisign),
-    /// Issue tx.
-    Issue(Issue),
-    /// CreateWallet tx.
-    CreateWallet(CreateWallet),
+impl ExecuteTransaction for Unstake {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let stake = schema.stake(owner).ok_or(Error::InsufficientStake)?;
+        if stake.amount < self.amount {
+            Err(Error::InsufficientStake)?
+        }
+
+        schema.increase_wallet_balance(wallet, self.amount, &hash)?;
+        if stake.amount == self.amount {
+            schema.stakes_mut().remove(owner);
+        } else {
+            let remaining = stake.amount - self.amount;
+            schema.stakes_mut().put(owner, stake.set_amount(remaining));
+        }
+
+        Ok(())
+    }
 }
 
-impl CreateWallet {
-    #[doc(hidden)]
-    pub fn sign(name: &str, pk: &PublicKey, sk: &SecretKey) -> Signed<RawTransaction> {
-        Message::sign_transaction(
-            Self {
-                name: name.to_owned(),
-            },
-            CRYPTOCURRENCY_SERVICE_ID,
-            *pk,
-            sk,
-        )
+impl ExecuteTransaction for DepositSavings {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if wallet.pending_balance < self.amount {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+
+        let savings = schema.accrue_savings(owner)?;
+        let principal = savings
+            .principal
+            .checked_add(self.amount)
+            .ok_or(Error::BalanceOverflow)?;
+
+        schema.decrease_wallet_balance(wallet, self.amount, &hash)?;
+        schema
+            .savings_mut()
+            .put(owner, Savings::new(principal, savings.last_accrual));
+
+        Ok(())
     }
 }
 
-impl Transfer {
-    #[doc(hidden)]
-    pub fn sign(
-        pk: &PublicKey,
-        &to: &PublicKey,
-        amount: u64,
-        seed: u64,
-        sk: &SecretKey,
-    ) -> Signed<RawTransaction> {
-        Message::sign_transaction(
-            Self { to, amount, seed },
-            CRYPTOCURRENCY_SERVICE_ID,
-            *pk,
-            sk,
-        )
+impl ExecuteTransaction for WithdrawSavings {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
+
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        let savings = schema.accrue_savings(owner)?;
+        if savings.principal < self.amount {
+            Err(Error::InsufficientSavings)?
+        }
+
+        schema.increase_wallet_balance(wallet, self.amount, &hash)?;
+        let principal = savings.principal - self.amount;
+        if principal == 0 {
+            schema.savings_mut().remove(owner);
+        } else {
+            schema
+                .savings_mut()
+                .put(owner, Savings::new(principal, savings.last_accrual));
+        }
+
+        Ok(())
     }
 }
 
-impl TransferMultisign {
-    #[doc(hidden)]
-    pub fn sign(
-        pk: &PublicKey,
-        &from: &PublicKey,
-        &to: &PublicKey,
-        ref users: &Vec<PublicKey>,
-        amount: u64,
-        seed: u64,
-        sk: &SecretKey,
-    ) -> Signed<RawTransaction> {
-        let approvers = users.to_vec();
-        Message::sign_transaction(
-            Self { from, to, approvers, amount, seed },
-            CRYPTOCURRENCY_SERVICE_ID,
-            *pk,
-            sk,
-        )
+impl ExecuteTransaction for OpenLoan {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let borrower = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let wallet = schema.wallet(borrower).ok_or(Error::SenderNotFound)?;
+
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
+        }
+
+        let ratio = schema.config().collateral_ratio;
+        if ratio == 0 {
+            Err(Error::LendingDisabled)?
+        }
+
+        if self.collateral == 0 || self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
+        }
+
+        if self.asset == DEFAULT_ASSET || schema.asset(&self.asset).is_none() {
+            Err(Error::AssetNotFound)?
+        }
+
+        let loan = Loan::new(borrower, &self.asset, self.collateral, self.amount);
+        if !loan.is_covered(schema.collateral_value(&loan)?, ratio) {
+            Err(Error::InsufficientCollateral)?
+        }
+
+        if schema.available_balance(&wallet, &self.asset) < self.collateral {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
+        let wallet = schema.decrease_asset_balance(wallet, &self.asset, self.collateral, &hash)?;
+        schema.increase_issued_supply(self.amount)?;
+        schema.increase_total_supply(self.amount)?;
+        schema.increase_wallet_balance(wallet, self.amount, &hash)?;
+        schema.open_position(borrower, &hash);
+        schema.loans_mut().put(&hash, loan);
+
+        Ok(())
     }
 }
 
-impl AcceptMultisign {
-    #[doc(hidden)]
-    pub fn sign(
-        pk: &PublicKey,
-        &tx_hash: &Hash,
-        &from: &PublicKey,
-        &to: &PublicKey,
-        ref users: &Vec<PublicKey>,
-        seed: u64,
-        sk: &SecretKey,
-    ) -> Signed<RawTransaction> {
-        let approvers = users.to_vec();
-        Message::sign_transaction(
-            Self { tx_hash, from, to, approvers, seed },
-            CRYPTOCURRENCY_SERVICE_ID,
-            *pk,
-            sk,
-        )
+impl ExecuteTransaction for RepayLoan {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        let loan = schema.loan(&self.loan).ok_or(Error::LoanNotFound)?;
+
+        if schema.current_key(&loan.borrower) != *author {
+            Err(Error::NotABorrower)?
+        }
+
+        let wallet = schema.wallet(author).ok_or(Error::SenderNotFound)?;
+
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
+
+        schema.close_loan(wallet, &self.loan, &loan, &hash)?;
+
+        Ok(())
     }
 }
 
-impl Transaction for Transfer {
-    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
-        let from = &context.author();
+impl ExecuteTransaction for Liquidate {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let liquidator = &context.author();
         let hash = context.tx_hash();
 
         let mut schema = Schema::new(context.fork());
 
-        let to = &self.to;
-        let amount = self.amount;
+        let wallet = schema.wallet(liquidator).ok_or(Error::SenderNotFound)?;
 
-        if from == to {
-            return Err(ExecutionError::new(ERROR_SENDER_SAME_AS_RECEIVER));
+        if wallet.frozen {
+            Err(Error::WalletFrozen)?
         }
 
-        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+        if wallet.closed {
+            Err(Error::WalletClosed)?
+        }
 
-        let receiver = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+        let loan = schema.loan(&self.loan).ok_or(Error::LoanNotFound)?;
 
-        if sender.balance < amount {
-            Err(Error::InsufficientCurrencyAmount)?
+        let ratio = schema.config().liquidation_ratio;
+        if loan.is_covered(schema.collateral_value(&loan)?, ratio) {
+            Err(Error::LoanHealthy)?
         }
 
-        schema.decrease_wallet_balance(sender, amount, &hash);
-        schema.increase_wallet_balance(receiver, amount, &hash);
+        schema.close_loan(wallet, &self.loan, &loan, &hash)?;
 
         Ok(())
     }
 }
 
-impl Transaction for TransferMultisign {
-    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
-        let significant = &context.author();
+impl ExecuteTransaction for RedeemPoints {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let owner = &context.author();
         let hash = context.tx_hash();
 
         let mut schema = Schema::new(context.fork());
 
-        let from = &self.from;
-        let to = &self.to;
-        let amount = self.amount;
+        let wallet = schema.wallet(owner).ok_or(Error::SenderNotFound)?;
 
-        if from == to {
-            return Err(ExecutionError::new(ERROR_SENDER_SAME_AS_RECEIVER));
+        if self.points == 0 {
+            Err(Error::NonPositiveAmount)?
         }
 
-        self.approvers.iter().find(|&&x| x == *significant).ok_or(Error::SenderNotFound)?;
+        let points = schema.loyalty_balance(owner);
+        if points < self.points {
+            Err(Error::InsufficientPoints)?
+        }
 
-        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+        schema.increase_issued_supply(self.points)?;
+        schema.increase_total_supply(self.points)?;
+        schema.increase_wallet_balance(wallet, self.points, &hash)?;
+        if points == self.points {
+            schema.loyalty_points_mut().remove(owner);
+        } else {
+            schema.loyalty_points_mut().put(owner, points - self.points);
+        }
 
-        schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+        Ok(())
+    }
+}
 
-        if sender.balance < amount {
-            Err(Error::InsufficientCurrencyAmount)?
+impl ExecuteTransaction for ProposeParamChange {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+        let hash = context.tx_hash();
+
+        let mut schema = Schema::new(context.fork());
+
+        schema.wallet(author).ok_or(Error::SenderNotFound)?;
+
+        let config = schema.config();
+        if config.governance_quorum == 0 {
+            Err(Error::GovernanceDisabled)?
+        }
+
+        if config.set_param(&self.param, self.value).is_none() {
+            Err(Error::UnknownParam)?
+        }
+
+        if schema.open_proposals().contains(author) {
+            Err(Error::ProposalAlreadyOpen)?
+        }
+
+        if config.proposal_fee > 0 {
+            let wallet = schema.wallet(author).ok_or(Error::SenderNotFound)?;
+            schema.charge_fee(wallet, config.proposal_fee, &hash)?;
         }
 
-        let sender = schema.add_tx_to_wallet(sender, &hash);
-        schema.decrease_wallet_pending_balance(sender, amount);
+        // Votes are weighted by the balances at the moment of the proposal, so that
+        // the same coins can't be voted again after moving them to another wallet.
+        let weights = schema
+            .wallets()
+            .values()
+            .filter(|wallet| !wallet.closed && wallet.balance > 0)
+            .map(|wallet| (wallet.pub_key, wallet.balance))
+            .collect::<Vec<_>>();
+        let total_weight = weights
+            .iter()
+            .fold(0_u64, |total, &(_, balance)| total.saturating_add(balance));
+
+        let expires_at = schema.current_height().0 + config.proposal_period;
+        let change = ParamChange::new(
+            &self.param,
+            self.value,
+            Vec::new(),
+            0,
+            total_weight,
+            author,
+            expires_at,
+        );
+        schema.open_param_change(&hash, change, weights);
 
         Ok(())
     }
 }
 
-impl Transaction for AcceptMultisign {
-    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
-        let significant = &context.author();
+impl ExecuteTransaction for Vote {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let voter = &context.author();
 
         let mut schema = Schema::new(context.fork());
 
-        let hash = &self.tx_hash;
-        let from = &self.from;
-        let to = &self.to;
+        schema.wallet(voter).ok_or(Error::SenderNotFound)?;
 
-        if from == to {
-            return Err(ExecutionError::new(ERROR_SENDER_SAME_AS_RECEIVER));
+        let config = schema.config();
+        if config.governance_quorum == 0 {
+            Err(Error::GovernanceDisabled)?
         }
 
-        let sender = schema.wallet(from).ok_or(Error::SenderNotFound)?;
+        let change = schema
+            .param_changes()
+            .get(&self.proposal)
+            .ok_or(Error::ProposalNotFound)?;
 
-        let receiver = schema.wallet(to).ok_or(Error::ReceiverNotFound)?;
+        if change.voters.contains(voter) {
+            Err(Error::AlreadyVoted)?
+        }
 
-        let pending_txs = sender.pending_txs.clone();
+        let weight = schema
+            .proposal_weights(&self.proposal)
+            .get(voter)
+            .unwrap_or_default();
+        if weight == 0 {
+            Err(Error::InsufficientCurrencyAmount)?
+        }
 
-        if let Some(tx_hash) = pending_txs.iter().find(|&&x| x == *hash) {
-            if let Some(_pub_key) = self.approvers.iter().find(|&&x| x == *significant) {
-                let sender = schema.remove_tx_from_wallet(sender, &tx_hash);
-                let new_amount = sender.balance - sender.pending_balance;
-                schema.decrease_wallet_balance(sender, new_amount, &tx_hash);
-                schema.increase_wallet_balance(receiver, new_amount, &tx_hash);
-                return Ok(());
-            }
-            else {
-                Err(Error::SenderNotFound)?
-            }
+        let change = change.add_vote(voter, weight);
+        if change.has_quorum(config.governance_quorum) {
+            let config = config
+                .set_param(&change.param, change.value)
+                .ok_or(Error::UnknownParam)?;
+            schema.set_config(config);
+            schema.close_param_change(&self.proposal);
+        } else {
+            schema.param_changes_mut().put(&self.proposal, change);
         }
 
         Ok(())
     }
 }
 
-impl Transaction for Issue {
-    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
-        let pub_key = &context.author();
-        let hash = context.tx_hash();
+impl ExecuteTransaction for HaltService {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
 
         let mut schema = Schema::new(context.fork());
 
-        if let Some(wallet) = schema.wallet(pub_key) {
-            let amount = self.amount;
-            schema.increase_wallet_balance(wallet, amount, &hash);
-            Ok(())
-        } else {
-            Err(Error::ReceiverNotFound)?
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
         }
+
+        schema.halted_entry_mut().set(true);
+
+        Ok(())
     }
 }
 
-impl Transaction for CreateWallet {
-    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
-        let pub_key = &context.author();
+impl ExecuteTransaction for ResumeService {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
+
+        let mut schema = Schema::new(context.fork());
+
+        if !schema.config().is_admin(author) {
+            Err(Error::NotAnAdmin)?
+        }
+
+        schema.halted_entry_mut().set(false);
+
+        Ok(())
+    }
+}
+
+impl ExecuteTransaction for FaucetIssue {
+    fn execute<C: ExecutionContext>(&self, mut context: C) -> ExecutionResult {
+        let author = &context.author();
         let hash = context.tx_hash();
 
         let mut schema = Schema::new(context.fork());
 
-        if schema.wallet(pub_key).is_none() {
-            let name = &self.name;
-            schema.create_wallet(pub_key, name, &hash);
-            Ok(())
-        } else {
-            Err(Error::WalletAlreadyExists)?
+        if !schema.is_service_key(author) {
+            Err(Error::NotAFaucet)?
+        }
+
+        if self.amount == 0 {
+            Err(Error::NonPositiveAmount)?
         }
+
+        if self.amount > schema.config().faucet_limit {
+            Err(Error::FaucetLimitExceeded)?
+        }
+
+        if !schema.allows_faucet_claim(&self.to) {
+            Err(Error::FaucetRateLimited)?
+        }
+
+        let receiver = schema.wallet(&self.to).ok_or(Error::ReceiverNotFound)?;
+
+        schema.increase_issued_supply(self.amount)?;
+        schema.increase_total_supply(self.amount)?;
+        schema.increase_wallet_balance(receiver, self.amount, &hash)?;
+        let height = schema.current_height().0;
+        schema.faucet_claims_mut().put(&self.to, height);
+
+        Ok(())
     }
 }
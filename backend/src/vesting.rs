@@ -0,0 +1,91 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Vesting schedule.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Issued funds released to the beneficiary over time, stored in the database.
+///
+/// Nothing is vested before `start + cliff`, then the funds vest linearly
+/// until `start + duration`. A schedule with `cliff == duration` releases
+/// all funds at once.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Vesting", serde_pb_convert)]
+pub struct Vesting {
+    /// `PublicKey` of the beneficiary's wallet.
+    pub beneficiary: PublicKey,
+    /// Total amount of currency in the schedule.
+    pub amount: u64,
+    /// Amount already claimed by the beneficiary.
+    pub claimed: u64,
+    /// Height of the block the vesting starts at.
+    pub start: u64,
+    /// Number of blocks since the start before which nothing is vested.
+    pub cliff: u64,
+    /// Number of blocks since the start after which everything is vested.
+    pub duration: u64,
+}
+
+impl Vesting {
+    /// Create new Vesting.
+    pub fn new(
+        &beneficiary: &PublicKey,
+        amount: u64,
+        claimed: u64,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+    ) -> Self {
+        Self {
+            beneficiary,
+            amount,
+            claimed,
+            start,
+            cliff,
+            duration,
+        }
+    }
+
+    /// Returns a copy of this vesting with updated claimed amount.
+    pub fn set_claimed(self, claimed: u64) -> Self {
+        Self::new(
+            &self.beneficiary,
+            self.amount,
+            claimed,
+            self.start,
+            self.cliff,
+            self.duration,
+        )
+    }
+
+    /// Returns amount vested by the given height.
+    pub fn vested_at(&self, height: u64) -> u64 {
+        let elapsed = height.saturating_sub(self.start);
+        if elapsed < self.cliff {
+            0
+        } else if elapsed >= self.duration {
+            self.amount
+        } else {
+            (u128::from(self.amount) * u128::from(elapsed) / u128::from(self.duration)) as u64
+        }
+    }
+
+    /// Returns amount vested by the given height, but not claimed yet.
+    pub fn claimable_at(&self, height: u64) -> u64 {
+        self.vested_at(height) - self.claimed
+    }
+}
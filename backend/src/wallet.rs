@@ -24,21 +24,24 @@ use super::proto;
 pub struct Wallet {
     /// `PublicKey` of the wallet.
     pub pub_key: PublicKey,
-    /// Linked multisig wallet.
-    /// TODO. Some decision to link single wallet and multisign wallet
-    //pub multisig_wallet: PublicKey,
     /// Name of the wallet.
     pub name: String,
     /// Current balance of the wallet.
     pub balance: u64,
     /// Current pending balance
     pub pending_balance: u64,
-    /// Pending txs
-    pub pending_txs: Vec<Hash>,
     /// Length of the transactions history.
     pub history_len: u64,
     /// `Hash` of the transactions history.
     pub history_hash: Hash,
+    /// `Hash` of the balances in assets other than the default one.
+    pub assets_hash: Hash,
+    /// Whether outgoing transfers from the wallet are frozen.
+    pub frozen: bool,
+    /// Whether the wallet is closed.
+    pub closed: bool,
+    /// Whether the owner of the wallet has passed KYC verification.
+    pub kyc_verified: bool,
 }
 
 impl Wallet {
@@ -48,19 +51,24 @@ impl Wallet {
         name: &str,
         balance: u64,
         pending_balance: u64,
-        pending_txs_list: &[Hash],
         history_len: u64,
         &history_hash: &Hash,
+        &assets_hash: &Hash,
+        frozen: bool,
+        closed: bool,
+        kyc_verified: bool,
     ) -> Self {
-        let pending_txs = pending_txs_list.to_vec();
         Self {
             pub_key,
             name: name.to_owned(),
             balance,
             pending_balance,
-            pending_txs,
             history_len,
             history_hash,
+            assets_hash,
+            frozen,
+            closed,
+            kyc_verified,
         }
     }
     /// Returns a copy of this wallet with updated balance.
@@ -70,9 +78,28 @@ impl Wallet {
             &self.name,
             balance,
             self.pending_balance,
-            &self.pending_txs,
             self.history_len + 1,
             history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
+        )
+    }
+    /// Returns a copy of this wallet with updated balance and pending balance
+    /// without a new record in its history.
+    pub fn set_balances(self, balance: u64, pending_balance: u64) -> Self {
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            balance,
+            pending_balance,
+            self.history_len,
+            &self.history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
         )
     }
     /// Returns a copy of this wallet with updated pending balance.
@@ -82,39 +109,102 @@ impl Wallet {
             &self.name,
             self.balance,
             balance,
-            &self.pending_txs,
             self.history_len,
             &self.history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
         )
     }
-    /// Returns a copy of this wallet with updated pending_txs.
-    pub fn add_pending_tx(self, tx_hash: &Hash) -> Self {
-        let mut pending_txs = self.pending_txs;
-        pending_txs.push(*tx_hash);
+    /// Returns a copy of this wallet with updated frozen flag.
+    pub fn set_frozen(self, frozen: bool) -> Self {
         Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
             self.pending_balance,
-            &pending_txs,
             self.history_len,
             &self.history_hash,
+            &self.assets_hash,
+            frozen,
+            self.closed,
+            self.kyc_verified,
         )
     }
-    /// Returns a copy of this wallet with updated pending_txs.
-    pub fn delete_pending_tx(self, tx_hash: &Hash) -> Self {
-        let mut pending_txs = self.pending_txs;
-        if let Some(index) = pending_txs.iter().position(|x| *x == *tx_hash) {
-            pending_txs.remove(index);
-        }
+    /// Returns a copy of this wallet with updated KYC verification flag.
+    pub fn set_kyc_verified(self, kyc_verified: bool) -> Self {
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.pending_balance,
+            self.history_len,
+            &self.history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            kyc_verified,
+        )
+    }
+    /// Returns a copy of this wallet owned by another key.
+    pub fn set_pub_key(self, pub_key: &PublicKey) -> Self {
+        Self::new(
+            pub_key,
+            &self.name,
+            self.balance,
+            self.pending_balance,
+            self.history_len,
+            &self.history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
+        )
+    }
+    /// Returns a copy of this wallet with updated hash of the asset balances.
+    pub fn set_assets_hash(self, assets_hash: &Hash) -> Self {
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.pending_balance,
+            self.history_len,
+            &self.history_hash,
+            assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
+        )
+    }
+    /// Returns a copy of this wallet with the length and hash of the pruned history.
+    pub fn set_history(self, history_len: u64, history_hash: &Hash) -> Self {
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.pending_balance,
+            history_len,
+            history_hash,
+            &self.assets_hash,
+            self.frozen,
+            self.closed,
+            self.kyc_verified,
+        )
+    }
+    /// Returns a copy of this wallet marked as closed.
+    pub fn set_closed(self) -> Self {
         Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
             self.pending_balance,
-            &pending_txs,
             self.history_len,
             &self.history_hash,
+            &self.assets_hash,
+            self.frozen,
+            true,
+            self.kyc_verified,
         )
     }
 }
@@ -0,0 +1,36 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watch-only lists of the wallets.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Keys the author watches without owning them, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::WatchList", serde_pb_convert)]
+pub struct WatchList {
+    /// Watched `PublicKey`s.
+    pub keys: Vec<PublicKey>,
+}
+
+impl WatchList {
+    /// Create new WatchList.
+    pub fn new(keys: &[PublicKey]) -> Self {
+        Self {
+            keys: keys.to_vec(),
+        }
+    }
+}
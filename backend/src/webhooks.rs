@@ -0,0 +1,333 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Webhook notifications about the committed service transactions.
+
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{mpsc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use exonum::{
+    blockchain::{self, TransactionErrorType, TransactionSet},
+    crypto::{Hash, PublicKey},
+    helpers::Height,
+    storage::Snapshot,
+};
+use serde_json;
+
+use transactions::WalletTransactions;
+use {Schema, CRYPTOCURRENCY_SERVICE_ID};
+
+/// Maximum number of attempts to deliver a notification.
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry of a failed delivery, doubled by every next retry.
+const INITIAL_RETRY_DELAY_MS: u64 = 500;
+
+/// Timeout of connecting to a webhook and of exchanging data with it.
+const DELIVERY_TIMEOUT_MS: u64 = 5_000;
+
+/// Registered callback URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    /// `http://` URL the notifications are posted to.
+    pub url: String,
+    /// Wallet the notifications are limited to, or `None` for all notifications.
+    pub wallet: Option<PublicKey>,
+}
+
+/// Kind of a webhook notification.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Currency was transferred between wallets.
+    Transfer,
+    /// Multisign transfer awaits approvals of the wallet owners.
+    ApprovalRequired,
+    /// Transaction failed.
+    FailedTransaction,
+}
+
+/// Notification posted to the webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Notification {
+    /// Kind of the notification.
+    pub kind: NotificationKind,
+    /// Height of the block with the transaction.
+    pub height: Height,
+    /// Hash of the transaction.
+    pub tx_hash: Hash,
+    /// Wallets concerned by the notification.
+    pub wallets: Vec<PublicKey>,
+    /// Amount of currency moved by the transaction, if any.
+    pub amount: Option<u64>,
+    /// Code of the error of a failed transaction.
+    pub error_code: Option<u8>,
+    /// Description of the error of a failed transaction.
+    pub error: Option<String>,
+}
+
+/// Notification queued for delivery to a webhook.
+#[derive(Debug)]
+struct Delivery {
+    url: String,
+    body: Vec<u8>,
+}
+
+/// Registry of the webhooks notified about the committed service transactions.
+///
+/// Webhooks are local to the node and kept in memory, so they have to be registered again
+/// after a restart. Notifications are delivered one by one by a background thread.
+#[derive(Debug, Default)]
+pub struct Webhooks {
+    hooks: Mutex<Vec<Webhook>>,
+    queue: Mutex<Option<mpsc::Sender<Delivery>>>,
+}
+
+impl Webhooks {
+    /// Create new Webhooks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the registered webhooks.
+    pub fn list(&self) -> Vec<Webhook> {
+        self.hooks.lock().unwrap().clone()
+    }
+
+    /// Registers the webhook unless it is already registered.
+    ///
+    /// Returns `false` if the URL is not a valid `http://` URL.
+    pub fn register(&self, webhook: Webhook) -> bool {
+        if parse_url(&webhook.url, "http://", 80).is_none() {
+            return false;
+        }
+        let mut hooks = self.hooks.lock().unwrap();
+        if !hooks.contains(&webhook) {
+            hooks.push(webhook);
+        }
+        true
+    }
+
+    /// Unregisters the webhook, returns `false` if it was not registered.
+    pub fn unregister(&self, webhook: &Webhook) -> bool {
+        let mut hooks = self.hooks.lock().unwrap();
+        let len = hooks.len();
+        hooks.retain(|hook| hook != webhook);
+        hooks.len() != len
+    }
+
+    /// Queues the notifications about the service transactions of the latest committed block
+    /// for delivery to the webhooks they concern.
+    pub fn notify(&self, snapshot: &dyn Snapshot) {
+        let hooks = self.list();
+        if hooks.is_empty() {
+            return;
+        }
+        for notification in block_notifications(snapshot) {
+            let body = serde_json::to_vec(&notification).unwrap();
+            let concerned = hooks.iter().filter(|hook| {
+                hook.wallet
+                    .map_or(true, |wallet| notification.wallets.contains(&wallet))
+            });
+            for hook in concerned {
+                self.enqueue(Delivery {
+                    url: hook.url.clone(),
+                    body: body.clone(),
+                });
+            }
+        }
+    }
+
+    /// Passes the delivery to the delivery thread, starting the thread if needed.
+    fn enqueue(&self, delivery: Delivery) {
+        let mut queue = self.queue.lock().unwrap();
+        let delivery = match queue.as_ref().map(|sender| sender.send(delivery)) {
+            Some(Ok(())) => return,
+            Some(Err(mpsc::SendError(delivery))) => delivery,
+            None => delivery,
+        };
+        let (sender, receiver) = mpsc::channel::<Delivery>();
+        thread::spawn(move || {
+            for delivery in receiver {
+                deliver(&delivery);
+            }
+        });
+        sender.send(delivery).unwrap();
+        *queue = Some(sender);
+    }
+}
+
+/// Returns the notifications about the service transactions of the latest committed block.
+fn block_notifications(snapshot: &dyn Snapshot) -> Vec<Notification> {
+    let general_schema = blockchain::Schema::new(snapshot);
+    let schema = Schema::new(snapshot);
+    let height = general_schema.height();
+
+    general_schema
+        .block_transactions(height)
+        .iter()
+        .filter_map(|tx_hash| {
+            let signed = general_schema.transactions().get(&tx_hash)?;
+            if signed.payload().service_id() != CRYPTOCURRENCY_SERVICE_ID {
+                return None;
+            }
+            let transaction = WalletTransactions::tx_from_raw(signed.payload().clone()).ok()?;
+            let author = signed.author();
+            let result = general_schema.transaction_results().get(&tx_hash)?;
+
+            let mut notification = Notification {
+                kind: NotificationKind::Transfer,
+                height,
+                tx_hash,
+                wallets: vec![author],
+                amount: transaction.amount(),
+                error_code: None,
+                error: None,
+            };
+            if let Err(ref error) = result.0 {
+                notification.kind = NotificationKind::FailedTransaction;
+                notification.error = error.description().map(str::to_owned);
+                if let TransactionErrorType::Code(code) = error.error_type() {
+                    notification.error_code = Some(code);
+                }
+                return Some(notification);
+            }
+
+            match transaction {
+                WalletTransactions::TransferMultisign(ref tx) => {
+                    let wallet = schema.multisig_wallet(&tx.from)?;
+                    notification.kind = NotificationKind::ApprovalRequired;
+                    notification.wallets = wallet
+                        .owners
+                        .iter()
+                        .map(|owner| schema.current_key(owner))
+                        .collect();
+                }
+                WalletTransactions::TransferByName(ref tx) => {
+                    notification
+                        .wallets
+                        .extend(schema.wallet_by_name(&tx.to_name));
+                }
+                WalletTransactions::Transfer(_)
+                | WalletTransactions::TransferFrom(_)
+                | WalletTransactions::PermitTransfer(_)
+                | WalletTransactions::CoSignedTransfer(_)
+                | WalletTransactions::DelegatedTransfer(_)
+                | WalletTransactions::SessionTransfer(_) => {
+                    notification.wallets = transaction.parties(author);
+                }
+                _ => return None,
+            }
+            Some(notification)
+        })
+        .collect()
+}
+
+/// Posts the notification to the webhook, retrying with an exponential backoff.
+fn deliver(delivery: &Delivery) {
+    let _ = retry_with_backoff(|| post(&delivery.url, "application/json", &delivery.body));
+}
+
+/// Calls `attempt` until it succeeds, at most `MAX_DELIVERY_ATTEMPTS` times, sleeping
+/// between the attempts for a delay doubled after each of them.
+///
+/// Errors of the `InvalidInput` kind, such as invalid URLs, are not retried.
+pub fn retry_with_backoff<F>(mut attempt: F) -> io::Result<()>
+where
+    F: FnMut() -> io::Result<()>,
+{
+    let mut delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS);
+    let mut attempts = 1;
+    loop {
+        match attempt() {
+            Err(ref e)
+                if e.kind() != io::ErrorKind::InvalidInput && attempts < MAX_DELIVERY_ATTEMPTS =>
+            {
+                thread::sleep(delay);
+                delay *= 2;
+                attempts += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Posts the body to the `http://` URL, succeeding if the response status is `2xx`.
+pub fn post(url: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    let (host, port, path) = parse_url(url, "http://", 80).ok_or_else(invalid_url)?;
+    let mut stream = connect(&host, port)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: {}\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        path,
+        host,
+        port,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+
+    // The status line starts with `HTTP/1.1 2xx` for the successful responses.
+    let mut status_line = [0; 12];
+    stream.read_exact(&mut status_line)?;
+    if status_line[9] == b'2' {
+        Ok(())
+    } else {
+        let message = "Server rejected the request";
+        Err(io::Error::new(io::ErrorKind::Other, message))
+    }
+}
+
+/// Connects to the host with the delivery timeouts.
+pub fn connect(host: &str, port: u16) -> io::Result<TcpStream> {
+    let address = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(invalid_url)?;
+    let timeout = Duration::from_millis(DELIVERY_TIMEOUT_MS);
+    let stream = TcpStream::connect_timeout(&address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    Ok(stream)
+}
+
+/// Splits the URL with the given scheme into its host, port and path.
+pub fn parse_url(url: &str, scheme: &str, default_port: u16) -> Option<(String, u16, String)> {
+    if !url.starts_with(scheme) {
+        return None;
+    }
+    let rest = &url[scheme.len()..];
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rfind(':') {
+        Some(index) => (&authority[..index], authority[index + 1..].parse().ok()?),
+        None => (authority, default_port),
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_owned(), port, path.to_owned()))
+}
+
+fn invalid_url() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "Invalid URL")
+}
@@ -0,0 +1,94 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! WebSocket endpoint streaming the changes of a wallet.
+
+use std::sync::Arc;
+
+use actix::{Actor, ActorContext, AsyncContext, StreamHandler};
+use actix_web::{error, http::Method, ws, FromRequest, Query};
+use exonum::{
+    api::{
+        backends::actix::{FutureResponse, HttpRequest, RequestHandler},
+        ServiceApiState,
+    },
+    helpers::Height,
+};
+use futures::{future, sync::mpsc::UnboundedReceiver};
+use serde_json;
+
+use api::WalletSocketQuery;
+use events::{WalletEvent, WalletEvents};
+
+/// Connection sending the changes of the subscribed wallet to the client as JSON text messages.
+struct WalletSocket {
+    events: Option<UnboundedReceiver<WalletEvent>>,
+}
+
+impl Actor for WalletSocket {
+    type Context = ws::WebsocketContext<Self, ServiceApiState>;
+
+    fn started(&mut self, context: &mut Self::Context) {
+        if let Some(events) = self.events.take() {
+            context.add_stream(events);
+        }
+    }
+}
+
+impl StreamHandler<WalletEvent, ()> for WalletSocket {
+    fn handle(&mut self, event: WalletEvent, context: &mut Self::Context) {
+        match serde_json::to_string(&event) {
+            Ok(text) => context.text(text),
+            Err(_) => context.stop(),
+        }
+    }
+}
+
+impl StreamHandler<ws::Message, ws::ProtocolError> for WalletSocket {
+    fn handle(&mut self, message: ws::Message, context: &mut Self::Context) {
+        match message {
+            ws::Message::Ping(payload) => context.pong(&payload),
+            ws::Message::Close(_) => context.stop(),
+            _ => {}
+        }
+    }
+}
+
+/// Creates the handler of the `v1/wallets/subscribe/ws` endpoint upgrading the request
+/// to a WebSocket connection that streams the changes of the wallet given by `pub_key`
+/// made by the blocks above `since`.
+pub fn wallet_socket_endpoint(events: Arc<WalletEvents>) -> RequestHandler {
+    let index = move |request: HttpRequest| -> FutureResponse {
+        let response = Query::<WalletSocketQuery>::from_request(&request, &Default::default())
+            .and_then(|query| {
+                let snapshot = request.state().snapshot();
+                let since = Height(query.since.unwrap_or(0));
+                let events = events
+                    .listen(&*snapshot, &query.pub_key, since)
+                    .map_err(error::ErrorServiceUnavailable)?;
+                ws::start(
+                    &request,
+                    WalletSocket {
+                        events: Some(events),
+                    },
+                )
+            });
+        Box::new(future::result(response))
+    };
+    RequestHandler {
+        name: "v1/wallets/subscribe/ws".to_owned(),
+        method: Method::GET,
+        inner: Arc::new(index),
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Whitelist of the senders.
+
+use exonum::crypto::PublicKey;
+
+use super::proto;
+
+/// Senders the wallet accepts transfers from, stored in the database.
+#[derive(Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::Whitelist", serde_pb_convert)]
+pub struct Whitelist {
+    /// `PublicKey`s of the approved senders.
+    pub senders: Vec<PublicKey>,
+}
+
+impl Whitelist {
+    /// Create new Whitelist.
+    pub fn new(senders: &[PublicKey]) -> Self {
+        Self {
+            senders: senders.to_vec(),
+        }
+    }
+
+    /// Checks whether the given key is an approved sender.
+    pub fn allows(&self, sender: &PublicKey) -> bool {
+        self.senders.contains(sender)
+    }
+}
@@ -18,29 +18,78 @@
 //! Note how API tests predominantly use `TestKitApi` to send transactions and make assertions
 //! about the storage state.
 
+extern crate base64;
 extern crate exonum;
-extern crate exonum_cryptocurrency_advanced as cryptocurrency;
+extern crate exonum_test_task as cryptocurrency;
 extern crate exonum_testkit;
+extern crate hex;
+extern crate protobuf;
+extern crate reqwest;
 #[macro_use]
 extern crate serde_json;
 
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
 use exonum::{
     api::node::public::explorer::{TransactionQuery, TransactionResponse},
-    crypto::{self, Hash, PublicKey, SecretKey},
+    blockchain::Blockchain,
+    crypto::{self, CryptoHash, Hash, PublicKey, SecretKey},
+    helpers::Height,
     messages::{self, RawTransaction, Signed},
+    proto::ProtobufConvert,
 };
 use exonum_testkit::{ApiKind, TestKit, TestKitApi, TestKitBuilder};
 
 // Import data types used in tests from the crate where the service is defined.
 use cryptocurrency::{
-    api::{WalletInfo, WalletQuery},
-    transactions::{CreateWallet, Transfer},
+    api::{
+        BlacklistInfo, EventsPage, EventsQuery, ExportFormat, FaucetQuery, FaucetResponse,
+        HistoryExport, HistoryExportQuery, HistoryTxType, InvoiceQuery, ServiceStats,
+        SubmitTransactionQuery, SubmitTransactionResponse, SupplyInfo, TopWalletsQuery,
+        TransactionEncoding, TransactionFeed, TransactionFeedQuery, TransactionReceipt,
+        TransactionStatus, TransactionStatusQuery, ValidateTransactionQuery,
+        ValidateTransactionResponse, WalletHistoryPage, WalletHistoryQuery, WalletInfo, WalletQuery,
+        WalletSearchQuery, WalletSubscribeQuery, WalletsPage, WalletsQuery, WatchedWalletsInfo,
+    },
+    config::{Config, NodeConfig},
+    events::WalletEvent,
+    health::HealthReport,
+    invoice::Invoice,
+    logging::Tracer,
+    proto,
+    rate_limit::RateLimitStats,
+    receipt::{EventType, ExecutionEvent},
+    snapshot::StateSnapshot,
+    transactions::{
+        AcceptMultisign, AcceptSwap, AddLiquidity, AddSessionKey, Airdrop, Approve, BatchTransfer,
+        BlacklistKey, Burn, CancelMultisign, CancelOrder, CancelRecovery, CancelStandingOrder,
+        CancelSwap, ChangeApprovers, ClaimInheritance, ClaimVesting, ClaimWithPreimage,
+        CloseStream, CloseWallet, CoSignedTransfer, CreateEscrow, CreateInvoice,
+        CreateMultisigWallet, CreateStandingOrder, CreateVesting, CreateWallet, DefineAsset,
+        Delegate, DelegateStake, DelegatedTransfer, DeleteMetadata, DepositSavings,
+        DistributeDividend, FaucetIssue, FreezeWallet, GrantRole, HaltService, Issue, LinkWallet,
+        Liquidate, LockWithHash, MintNft, OpenDispute, OpenLoan, OpenStream, PayInvoice, Payment,
+        PermitTransfer, PlaceOrder, ProposeParamChange, ProposeSwap, RecoverWallet, RedeemPoints,
+        Refund, RefundEscrow, RefundHashLock, ReleaseEscrow, RemoveLiquidity, RepayLoan,
+        ResolveDispute, ResumeService, RevokeSessionKey, RotateKey, ScheduleTransfer,
+        SessionTransfer, SetBeneficiary, SetGuardians, SetKycStatus, SetMetadata, SetSpendingLimit,
+        SetWatchList, SetWhitelist, Stake, SwapViaPool, Transfer, TransferByName, TransferFrom,
+        TransferMultisign, TransferNft, UnblacklistKey, UnfreezeWallet, Unstake, Vote,
+        WithdrawSavings,
+    },
     wallet::Wallet,
-    Service,
+    webhooks::{Notification, NotificationKind, Webhook},
+    Service, CRYPTOCURRENCY_SERVICE_ID,
 };
 
 // Imports shared test constants.
-use constants::{ALICE_NAME, BOB_NAME};
+use constants::{ALICE_NAME, BOB_NAME, CAROL_NAME, SHARED_NAME};
 
 mod constants;
 
@@ -60,139 +109,4729 @@ fn test_create_wallet() {
     assert_eq!(wallet.balance, 100);
 }
 
-/// Check that the transfer transaction works as intended.
+/// Check that the transfer transaction works as intended.
+#[test]
+fn test_transfer() {
+    // Create 2 wallets.
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+    api.assert_tx_status(tx_alice.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_bob.hash(), &json!({ "type": "success" }));
+
+    // Check that the initial Alice's and Bob's balances persisted by the service.
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+
+    // Transfer funds by invoking the corresponding API method.
+    let tx = Transfer::sign(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transferred amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // After the transfer transaction is included into a block, we may check new wallet
+    // balances.
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 90);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 110);
+}
+
+/// Check that the transfer memo is returned together with the wallet history.
+#[test]
+fn test_transfer_with_memo() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign_with_memo(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transferred amount
+        0,  // seed
+        "Lunch",
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet_info = api.get_wallet_info(tx_bob.author());
+    let history = wallet_info.wallet_history.unwrap();
+    let last = serde_json::to_value(history.transactions.last().unwrap()).unwrap();
+    assert_eq!(last["debug"]["memo"], json!("Lunch"));
+}
+
+/// Check that a transfer with too long memo fails as expected.
+#[test]
+fn test_transfer_with_too_long_memo() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let memo = "x".repeat(257);
+    let tx = Transfer::sign_with_memo(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transferred amount
+        0,  // seed
+        &memo,
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 17, "description": "Memo is too long" }),
+    );
+}
+
+/// Check that the service applies its parameters from the configuration proposed
+/// through the configuration mechanism once it becomes actual.
+#[test]
+fn test_runtime_config_change() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(3));
+    proposal.set_service_config(
+        "multisign_cryptocurrency",
+        Config {
+            initial_balance: 50,
+            max_memo_length: 4,
+            ..Config::default()
+        },
+    );
+    testkit.commit_configuration_change(proposal);
+    testkit.create_blocks_until(Height(5));
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    let tx = Transfer::sign_with_memo(&alice, &bob, 10, 0, "rent", &key_alice);
+    let tx_long = Transfer::sign_with_memo(&alice, &bob, 10, 1, "lunch", &key_alice);
+    api.transfer(&tx);
+    api.transfer(&tx_long);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_long.hash(),
+        &json!({ "type": "error", "code": 17, "description": "Memo is too long" }),
+    );
+    assert_eq!(api.get_wallet(tx_carol.author()).unwrap().balance, 50);
+}
+
+/// Check that the wallets passed to the service constructor are funded at genesis.
+#[test]
+fn test_genesis_wallets() {
+    let (genesis_pk, genesis_sk) = crypto::gen_keypair();
+    let service = Service::with_genesis_wallets(
+        Config::default(),
+        vec![(genesis_pk, "Treasury".to_owned(), 500)],
+    );
+    let mut testkit = TestKitBuilder::validator().with_service(service).create();
+    let api = CryptocurrencyApi {
+        inner: testkit.api(),
+    };
+    assert_eq!(api.get_wallet(genesis_pk).unwrap().balance, 500);
+
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&genesis_pk, &tx_alice.author(), 200, 0, &genesis_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(genesis_pk).unwrap().balance, 300);
+    assert_eq!(api.get_wallet(tx_alice.author()).unwrap().balance, 300);
+}
+
+/// Check that the state exported to a snapshot is restored by a fresh service.
+#[test]
+fn test_state_snapshot() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let snapshot = api.get_snapshot();
+    assert_eq!(snapshot.wallets.len(), 2);
+    assert_eq!(snapshot.counters.total_supply, 200);
+    assert_eq!(snapshot.wallet_names[ALICE_NAME], alice);
+
+    let service = Service::default().with_snapshot(snapshot.clone());
+    let mut testkit = TestKitBuilder::validator().with_service(service).create();
+    let api = CryptocurrencyApi {
+        inner: testkit.api(),
+    };
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.balance, 90);
+    assert_eq!(wallet.history_len, 0);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+    assert_eq!(api.get_snapshot().counters, snapshot.counters);
+
+    let tx = Transfer::sign(&alice, &bob, 20, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+}
+
+/// Check that the wallet info proofs can be verified up to the block state hash.
+#[test]
+fn test_wallet_info_proofs() {
+    let (genesis_pk, _) = crypto::gen_keypair();
+    let service = Service::with_genesis_wallets(
+        Config::default(),
+        vec![(genesis_pk, "Treasury".to_owned(), 500)],
+    );
+    let mut testkit = TestKitBuilder::validator().with_service(service).create();
+    let api = CryptocurrencyApi {
+        inner: testkit.api(),
+    };
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    // Genesis wallets have no history to prove.
+    assert!(api.get_wallet_info(genesis_pk).wallet_history.is_none());
+
+    let wallet_info = api.get_wallet_info(tx_alice.author());
+    let state_hash = *wallet_info.block_proof.block.state_hash();
+
+    let to_table = wallet_info.wallet_proof.to_table.check().unwrap();
+    assert_eq!(to_table.merkle_root(), state_hash);
+    let table_key = Blockchain::service_table_unique_key(CRYPTOCURRENCY_SERVICE_ID, 0);
+    let wallets_hash = to_table
+        .all_entries()
+        .find(|(ref k, _)| **k == table_key)
+        .and_then(|tuple| tuple.1)
+        .cloned()
+        .unwrap();
+
+    let to_wallet = wallet_info.wallet_proof.to_wallet.check().unwrap();
+    assert_eq!(to_wallet.merkle_root(), wallets_hash);
+    let wallet = to_wallet
+        .all_entries()
+        .find(|(ref k, _)| **k == tx_alice.author())
+        .and_then(|tuple| tuple.1)
+        .cloned()
+        .unwrap();
+
+    let history = wallet_info.wallet_history.unwrap();
+    let records = history
+        .proof
+        .validate(wallet.history_hash, wallet.history_len)
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(*records[0].1, tx_alice.hash());
+}
+
+/// Check that the total supply can be verified up to the precommits of the block.
+#[test]
+fn test_total_supply_proof() {
+    let (mut testkit, api) = create_testkit();
+    api.create_wallet(ALICE_NAME);
+    api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let supply_info = api.get_supply_info();
+    assert_eq!(supply_info.total_supply, 200);
+
+    let block = &supply_info.block_proof.block;
+    let block_hash = block.hash();
+    assert!(!supply_info.block_proof.precommits.is_empty());
+    for precommit in &supply_info.block_proof.precommits {
+        assert_eq!(*precommit.payload().block_hash(), block_hash);
+    }
+
+    let to_table = supply_info.to_table.check().unwrap();
+    assert_eq!(to_table.merkle_root(), *block.state_hash());
+    let table_key = Blockchain::service_table_unique_key(CRYPTOCURRENCY_SERVICE_ID, 4);
+    let supply_hash = to_table
+        .all_entries()
+        .find(|(ref k, _)| **k == table_key)
+        .and_then(|tuple| tuple.1)
+        .cloned()
+        .unwrap();
+    assert_eq!(supply_hash, supply_info.total_supply.hash());
+}
+
+/// Check that the transaction status goes from the pool to the execution result.
+#[test]
+fn test_transaction_status() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.poll_events();
+    assert_eq!(
+        api.get_transaction_status(tx_alice.hash()),
+        TransactionStatus::InPool
+    );
+
+    testkit.create_block();
+    assert_eq!(
+        api.get_transaction_status(tx_alice.hash()),
+        TransactionStatus::Success { height: Height(1) }
+    );
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 110, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    assert_eq!(
+        api.get_transaction_status(tx.hash()),
+        TransactionStatus::Error {
+            height: Height(2),
+            code: 3,
+            description: Some("Insufficient currency amount".to_owned()),
+        }
+    );
+}
+
+/// Check that transactions signed offline are accepted in both encodings.
+#[test]
+fn test_submit_transaction() {
+    let (mut testkit, api) = create_testkit();
+    let (pk_alice, key_alice) = crypto::gen_keypair();
+    let (pk_bob, key_bob) = crypto::gen_keypair();
+
+    let tx_alice = CreateWallet::sign(ALICE_NAME, &pk_alice, &key_alice);
+    let tx_body = base64::encode(&hex::decode(messages::to_hex_string(&tx_alice)).unwrap());
+    let response = api
+        .submit_transaction(tx_body, TransactionEncoding::Base64)
+        .unwrap();
+    assert_eq!(response.tx_hash, tx_alice.hash());
+
+    let tx_bob = CreateWallet::sign(BOB_NAME, &pk_bob, &key_bob);
+    let tx_body = messages::to_hex_string(&tx_bob);
+    let response = api
+        .submit_transaction(tx_body, TransactionEncoding::Hex)
+        .unwrap();
+    assert_eq!(response.tx_hash, tx_bob.hash());
+
+    testkit.create_block();
+    api.assert_tx_status(tx_alice.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_bob.hash(), &json!({ "type": "success" }));
+
+    // Malformed transactions are rejected before reaching the pool.
+    let response = api.submit_transaction("deadbeef".to_owned(), TransactionEncoding::Hex);
+    assert!(response.is_none());
+}
+
+/// Check that waiting for a transaction returns its execution result once it is committed.
+#[test]
+fn test_submit_transaction_and_wait() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    // Committed transactions are reported without waiting.
+    let response = api.submit_transaction_and_wait(&tx_alice, 1_000);
+    assert_eq!(response.tx_hash, tx_alice.hash());
+    assert_eq!(
+        response.status,
+        Some(TransactionStatus::Success { height: Height(1) })
+    );
+
+    // Nothing commits blocks in the testkit while the request waits, so it times out.
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    let response = api.submit_transaction_and_wait(&tx, 200);
+    assert_eq!(response.tx_hash, tx.hash());
+    match response.status {
+        None | Some(TransactionStatus::InPool) => {}
+        status => panic!("Unexpected transaction status {:?}", status),
+    }
+
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+}
+
+/// Check that validating a transaction neither broadcasts it nor accepts known transactions.
+#[test]
+fn test_validate_transaction() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    let response = api.validate_transaction(&tx);
+    assert_eq!(response.tx_hash, tx.hash());
+    assert_eq!(response.error, None);
+
+    // The validated transaction is not broadcast.
+    testkit.create_block();
+    assert_eq!(api.get_wallet(tx_alice.author()).unwrap().balance, 100);
+
+    // The transaction is executed, so an overdrawn transfer fails.
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 110, 1, &key_alice);
+    let response = api.validate_transaction(&tx);
+    assert_eq!(
+        response.error,
+        Some("Insufficient currency amount".to_owned())
+    );
+
+    let response = api.validate_transaction(&tx_alice);
+    assert_eq!(
+        response.error,
+        Some("Transaction is already known to the node".to_owned())
+    );
+}
+
+/// Check that the feed returns the committed service transactions with their statuses.
+#[test]
+fn test_transaction_feed() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 110, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let feed = api.get_transaction_feed(0, 100);
+    assert_eq!(feed.height, Height(2));
+    assert_eq!(feed.transactions.len(), 3);
+    assert_eq!(feed.transactions[2].tx_hash, tx.hash());
+    assert_eq!(
+        feed.transactions[2].status,
+        TransactionStatus::Error {
+            height: Height(2),
+            code: 3,
+            description: Some("Insufficient currency amount".to_owned()),
+        }
+    );
+
+    // Nothing is committed while the request waits.
+    let feed = api.get_transaction_feed(2, 100);
+    assert_eq!(feed.height, Height(2));
+    assert!(feed.transactions.is_empty());
+}
+
+/// Check that the webhooks registered for a wallet are notified about its transfers.
+#[test]
+fn test_webhooks() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let webhook = Webhook {
+        url: format!("http://{}/hook", listener.local_addr().unwrap()),
+        wallet: Some(tx_bob.author()),
+    };
+    assert_eq!(api.register_webhook(&webhook), vec![webhook.clone()]);
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let notification: Notification = serde_json::from_slice(&receive_webhook(&listener)).unwrap();
+    assert_eq!(notification.kind, NotificationKind::Transfer);
+    assert_eq!(notification.height, Height(2));
+    assert_eq!(notification.tx_hash, tx.hash());
+    assert_eq!(
+        notification.wallets,
+        vec![tx_alice.author(), tx_bob.author()]
+    );
+    assert_eq!(notification.amount, Some(10));
+
+    assert!(api.unregister_webhook(&webhook).is_empty());
+}
+
+/// Check that the wallet events of the committed blocks are published to the NATS subject
+/// set in the node configuration.
+#[test]
+fn test_nats_event_publishing() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let (mut testkit, api) = create_testkit_with_node_config(NodeConfig {
+        event_broker: format!("nats://{}", listener.local_addr().unwrap()),
+        event_topic: "wallets".to_owned(),
+        ..NodeConfig::default()
+    });
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let messages = receive_nats_messages(&listener);
+    assert_eq!(messages.len(), 2);
+    for (subject, _) in &messages {
+        assert_eq!(subject, "wallets");
+    }
+    let mut keys: Vec<_> = messages
+        .iter()
+        .map(|(_, payload)| {
+            let event: WalletEvent = serde_json::from_slice(payload).unwrap();
+            assert_eq!(event.height, Height(1));
+            assert_eq!(event.wallet.balance, 100);
+            event.wallet.pub_key
+        })
+        .collect();
+    let mut expected = vec![tx_alice.author(), tx_bob.author()];
+    keys.sort();
+    expected.sort();
+    assert_eq!(keys, expected);
+}
+
+/// Check that a GraphQL query fetches the selected fields of a wallet and its history.
+#[test]
+fn test_graphql_wallet_query() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let query = format!(
+        "{{ wallet(pubKey: \"{}\") {{ name balance history(from: 1) {{ txType amount \
+         balanceAfter }} pendingTransfers {{ amount }} }} }}",
+        hex::encode(alice.as_ref())
+    );
+    let response = api.graphql(&query);
+    assert_eq!(
+        response,
+        json!({
+            "data": {
+                "wallet": {
+                    "name": ALICE_NAME,
+                    "balance": "90",
+                    "history": [
+                        { "txType": "Transfer", "amount": "10", "balanceAfter": "90" }
+                    ],
+                    "pendingTransfers": []
+                }
+            }
+        })
+    );
+
+    let response = api.graphql("{ wallet(pubKey: \"00\") { name } }");
+    assert!(response["errors"].is_array());
+}
+
+/// Check that the OpenAPI specification describes the endpoints, resolves all its schema
+/// references and lists the transaction error codes.
+#[test]
+fn test_openapi_specification() {
+    let (_testkit, api) = create_testkit();
+    let spec = api.get_openapi();
+    assert_eq!(spec["openapi"], "3.0.0");
+
+    let paths = &spec["paths"];
+    let wallets = &paths["/api/services/multisign_cryptocurrency/v1/wallets/info"]["get"];
+    assert_eq!(wallets["parameters"][0]["name"], "pub_key");
+    assert_eq!(
+        wallets["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/WalletInfo"
+    );
+    let register = &paths["/api/services/multisign_cryptocurrency/v1/webhooks/register"]["post"];
+    assert_eq!(register["tags"][0], "private");
+
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    let mut refs = Vec::new();
+    collect_refs(&spec, &mut refs);
+    for reference in refs {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        assert!(
+            schemas.contains_key(name),
+            "Unresolved reference {}",
+            reference
+        );
+    }
+
+    let error_codes = &schemas["TransactionErrorCode"];
+    assert_eq!(error_codes["enum"][3], 3);
+    assert_eq!(
+        error_codes["x-enum-varnames"][3],
+        "InsufficientCurrencyAmount"
+    );
+    assert!(error_codes["x-enum-varnames"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("FaucetRateLimited")));
+}
+
+/// Collects the schema references of the JSON value.
+fn collect_refs(value: &serde_json::Value, refs: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    serde_json::Value::String(reference) if key == "$ref" => {
+                        refs.push(reference.clone())
+                    }
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_refs(value, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check that the transaction feed stream sends the committed blocks as server-sent events.
+#[test]
+fn test_transaction_feed_stream() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let response = api.get_response("v1/transactions/feed/stream", &[]);
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    let mut events = BufReader::new(response);
+
+    let (id, feed) = read_server_event(&mut events);
+    assert_eq!(id, "1");
+    assert_eq!(feed.height, Height(1));
+    assert_eq!(feed.transactions.len(), 2);
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let (id, feed) = read_server_event(&mut events);
+    assert_eq!(id, "2");
+    assert_eq!(feed.height, Height(2));
+    assert_eq!(feed.transactions.len(), 1);
+    assert_eq!(feed.transactions[0].tx_hash, tx.hash());
+
+    // Reconnecting clients resume after the last received event.
+    let response = api.get_response("v1/transactions/feed/stream", &[("Last-Event-ID", "1")]);
+    let (id, feed) = read_server_event(&mut BufReader::new(response));
+    assert_eq!(id, "2");
+    assert_eq!(feed.transactions[0].tx_hash, tx.hash());
+}
+
+/// Check that the faucet credits a capped amount and rate limits the requests of a key.
+#[test]
+fn test_faucet() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        faucet_limit: 50,
+        faucet_interval: 3,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let alice = tx_alice.author();
+
+    assert!(api.request_faucet(alice, 60).is_none());
+    let response = api.request_faucet(alice, 30).unwrap();
+    testkit.create_block();
+    api.assert_tx_status(response.tx_hash, &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 130);
+    assert!(api.request_faucet(alice, 30).is_none());
+
+    // Only service keys of the validators can sign faucet credits.
+    let tx = FaucetIssue::sign(&alice, &alice, 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 105, "description": "Not a faucet key" }),
+    );
+}
+
+/// Check that the submissions above the rate limits are rejected with `429 Too Many Requests`.
+#[test]
+fn test_submission_rate_limits() {
+    let (_testkit, api) = create_testkit_with_node_config(NodeConfig {
+        ip_rate_limit: 3,
+        key_rate_limit: 1,
+        ..NodeConfig::default()
+    });
+    let submit = |tx: &Signed<RawTransaction>| {
+        let body = json!({ "tx_body": messages::to_hex_string(tx) });
+        api.post_response("v1/transactions", &body, &[])
+    };
+    let (pk_alice, key_alice) = crypto::gen_keypair();
+    let (pk_bob, key_bob) = crypto::gen_keypair();
+
+    let response = submit(&CreateWallet::sign(ALICE_NAME, &pk_alice, &key_alice));
+    assert_eq!(response.status(), 200);
+    let response = submit(&Transfer::sign(&pk_alice, &pk_bob, 10, 0, &key_alice));
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers()[reqwest::header::RETRY_AFTER], "60");
+
+    // Other keys are limited by the address they come from.
+    let response = submit(&CreateWallet::sign(BOB_NAME, &pk_bob, &key_bob));
+    assert_eq!(response.status(), 200);
+    let (pk_carol, key_carol) = crypto::gen_keypair();
+    let response = submit(&CreateWallet::sign(CAROL_NAME, &pk_carol, &key_carol));
+    assert_eq!(response.status(), 200);
+    let (pk_shared, key_shared) = crypto::gen_keypair();
+    let response = submit(&CreateWallet::sign(SHARED_NAME, &pk_shared, &key_shared));
+    assert_eq!(response.status(), 429);
+
+    assert_eq!(
+        api.get_rate_limit_stats(),
+        RateLimitStats {
+            accepted: 3,
+            rejected_by_ip: 1,
+            rejected_by_key: 1,
+        }
+    );
+}
+
+/// Check that the retried submissions with the same idempotency key get the cached response.
+#[test]
+fn test_idempotency_key() {
+    let (_testkit, api) = create_testkit_with_config(Config {
+        faucet_limit: 50,
+        ..Config::default()
+    });
+    let (pk_alice, _) = crypto::gen_keypair();
+    let body = json!({ "pub_key": pk_alice, "amount": 10 });
+    let key = [("Idempotency-Key", "faucet-1")];
+
+    let mut response = api.post_response("v1/faucet", &body, &key);
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("idempotent-replayed").is_none());
+    let first: FaucetResponse = response.json().unwrap();
+
+    // Without the key the second request would be rejected as too frequent.
+    let mut response = api.post_response("v1/faucet", &body, &key);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["idempotent-replayed"], "true");
+    let replayed: FaucetResponse = response.json().unwrap();
+    assert_eq!(replayed.tx_hash, first.tx_hash);
+
+    let other = json!({ "pub_key": pk_alice, "amount": 20 });
+    let response = api.post_response("v1/faucet", &other, &key);
+    assert_eq!(response.status(), 422);
+}
+
+/// Check that the privileged endpoints require an API key while the others stay public.
+#[test]
+fn test_api_key_authentication() {
+    let config = Config {
+        faucet_limit: 50,
+        ..Config::default()
+    };
+    let service = Service::new(config).with_api_keys(vec!["secret".to_owned()]);
+    let testkit = TestKitBuilder::validator().with_service(service).create();
+    let api = CryptocurrencyApi {
+        inner: testkit.api(),
+    };
+    let valid = [("X-API-Key", "secret")];
+
+    let response = api.get_response("v1/stats", &[]);
+    assert_eq!(response.status(), 401);
+    let response = api.get_response("v1/stats", &[("X-API-Key", "wrong")]);
+    assert_eq!(response.status(), 401);
+    assert_eq!(api.get_response("v1/stats", &valid).status(), 200);
+    assert_eq!(api.get_response("v1/blacklist", &[]).status(), 401);
+    assert_eq!(api.get_response("v1/blacklist", &valid).status(), 200);
+
+    let (pk_alice, _) = crypto::gen_keypair();
+    let body = json!({ "pub_key": pk_alice, "amount": 10 });
+    let response = api.post_response("v1/faucet", &body, &[]);
+    assert_eq!(response.status(), 401);
+    let response = api.post_response("v1/faucet", &body, &valid);
+    assert_eq!(response.status(), 200);
+
+    // Read endpoints stay public.
+    assert_eq!(api.get_response("v1/supply", &[]).status(), 200);
+    assert_eq!(api.get_response("v1/wallets", &[]).status(), 200);
+}
+
+/// Check that the public endpoints answer the allowed origins with the CORS headers.
+#[test]
+fn test_cors_policy() {
+    let (_testkit, api) = create_testkit_with_node_config(NodeConfig {
+        cors_allowed_origins: vec!["https://wallet.example".to_owned()],
+        ..NodeConfig::default()
+    });
+
+    let response = api.get_response("v1/supply", &[("Origin", "https://wallet.example")]);
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://wallet.example"
+    );
+    let response = api.get_response("v1/supply", &[("Origin", "https://evil.example")]);
+    assert!(response
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+
+    // Errors carry the headers as well, so that browsers let the pages read them.
+    let response = api.get_response("v1/wallets/info", &[("Origin", "https://wallet.example")]);
+    assert_eq!(response.status(), 400);
+    assert!(response
+        .headers()
+        .get("access-control-allow-origin")
+        .is_some());
+
+    let response = api.send_request(
+        reqwest::Method::OPTIONS,
+        "v1/transactions",
+        &[
+            ("Origin", "https://wallet.example"),
+            ("Access-Control-Request-Method", "POST"),
+        ],
+    );
+    assert_eq!(response.status(), 204);
+    assert_eq!(
+        response.headers()["access-control-allow-methods"],
+        "GET, POST"
+    );
+    assert_eq!(
+        response.headers()["access-control-allow-headers"],
+        "Content-Type"
+    );
+}
+
+/// Check that the probes report the schema and the height of the last committed block.
+#[test]
+fn test_health_probes() {
+    let (mut testkit, api) = create_testkit();
+    testkit.create_block();
+
+    for probe in &["healthz", "readyz"] {
+        let mut response = api.get_response(probe, &[]);
+        assert_eq!(response.status(), 200);
+        let report: HealthReport = response.json().unwrap();
+        assert!(report.schema_accessible);
+        assert!(report.ready);
+        assert_eq!(report.height, Some(Height(1)));
+        assert_eq!(report.lag_secs, 0);
+    }
+}
+
+/// Check that the requests get correlation IDs and the executed transactions are logged with them.
+#[test]
+fn test_request_tracing() {
+    let (mut testkit, api) = create_testkit();
+
+    let response = api.get_response("v1/supply", &[("X-Request-Id", "trace-1")]);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-request-id"], "trace-1");
+    let response = api.get_response("v1/supply", &[]);
+    assert_eq!(response.headers()["x-request-id"].len(), 16);
+
+    let tracer = Tracer::new();
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    tracer.register_transaction(tx_alice.hash(), "trace-2".to_owned());
+    testkit.create_block();
+
+    let mut records = tracer.block_records(&*testkit.snapshot());
+    records.sort_by_key(|record| record.correlation_id != "trace-2");
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].correlation_id, "trace-2");
+    assert_eq!(records[0].tx_hash, tx_alice.hash());
+    assert_eq!(records[0].author, tx_alice.author());
+    assert_eq!(records[0].variant, "CreateWallet");
+    assert_eq!(
+        records[0].outcome,
+        TransactionStatus::Success { height: Height(1) }
+    );
+    // Transactions submitted elsewhere are logged with their hashes.
+    assert_eq!(records[1].correlation_id, hex::encode(tx_bob.hash().as_ref()));
+}
+
+/// Check that the statistics count wallets, transfers and transactions per block.
+#[test]
+fn test_stats() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let stats = api.get_stats();
+    assert_eq!(stats.wallets, 2);
+    assert_eq!(stats.total_supply, 200);
+    assert_eq!(stats.transfers, 1);
+    assert_eq!(stats.transfer_volume, 10);
+    assert!((stats.transactions_per_block - 1.5).abs() < std::f64::EPSILON);
+}
+
+/// Check that the wallets are found by name prefix in the order of their names.
+#[test]
+fn test_search_wallets() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alicia, _) = api.create_wallet("Alicia");
+    let (tx_alice, _) = api.create_wallet("Alice");
+    api.create_wallet("Bob");
+    testkit.create_block();
+
+    let wallets = api.search_wallets("Ali");
+    let keys = wallets.iter().map(|w| w.pub_key).collect::<Vec<_>>();
+    assert_eq!(keys, vec![tx_alice.author(), tx_alicia.author()]);
+    assert_eq!(wallets[0].balance, 100);
+
+    assert!(api.search_wallets("Carol").is_empty());
+}
+
+/// Check that the top wallets are sorted by balance and capped by the limit.
+#[test]
+fn test_top_wallets() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let top = api.get_top_wallets(2);
+    let balances = top.iter().map(|w| w.balance).collect::<Vec<_>>();
+    assert_eq!(balances, vec![130, 100]);
+    assert_eq!(top[0].pub_key, tx_bob.author());
+    assert_eq!(top[1].pub_key, tx_carol.author());
+}
+
+/// Check that the wallet endpoints answer with protobuf messages to the clients accepting them.
+#[test]
+fn test_protobuf_content_negotiation() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let bytes = api.get_protobuf("v1/wallets/top?limit=2");
+    let wallets = protobuf::parse_from_bytes::<proto::WalletList>(&bytes)
+        .unwrap()
+        .take_wallets()
+        .into_iter()
+        .map(|wallet| Wallet::from_pb(wallet).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(wallets.len(), 2);
+    assert_eq!(wallets[0].pub_key, tx_bob.author());
+    assert_eq!(wallets[0].balance, 130);
+
+    let bytes = api.get_protobuf("v1/wallets?limit=10");
+    let page = protobuf::parse_from_bytes::<proto::WalletsPage>(&bytes).unwrap();
+    assert_eq!(page.get_total(), 2);
+    assert_eq!(page.get_wallets().len(), 2);
+
+    let query = format!(
+        "v1/wallets/history?pub_key={}",
+        hex::encode(tx_alice.author().as_ref())
+    );
+    let bytes = api.get_protobuf(&query);
+    let page = protobuf::parse_from_bytes::<proto::WalletHistoryPage>(&bytes).unwrap();
+    assert_eq!(page.get_total(), 2);
+    assert_eq!(page.get_transactions().len(), 2);
+
+    // Clients not accepting protobuf still get JSON.
+    assert_eq!(api.get_top_wallets(2).len(), 2);
+}
+
+/// Check that the amounts are serialized as decimal strings on request.
+#[test]
+fn test_amounts_as_strings() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 30, 7, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let top = api.get_json(
+        "v1/wallets/top",
+        &json!({ "limit": 1, "amounts_as_strings": true }),
+    );
+    assert_eq!(top[0]["balance"], json!("130"));
+    assert_eq!(top[0]["pending_balance"], json!(130));
+
+    let alice = hex::encode(tx_alice.author().as_ref());
+    let history = api.get_json(
+        "v1/wallets/history",
+        &json!({ "pub_key": alice, "amounts_as_strings": true }),
+    );
+    let transfer = &history["transactions"][1]["debug"];
+    assert_eq!(transfer["amount"], json!("30"));
+    assert_eq!(transfer["seed"], json!("7"));
+
+    // Numbers are kept by default.
+    let top = api.get_json("v1/wallets/top", &json!({ "limit": 1 }));
+    assert_eq!(top[0]["balance"], json!(130));
+}
+
+/// Check that the second API version serializes amounts as strings by default
+/// and that the replaced endpoints of the first one are marked as deprecated.
+#[test]
+fn test_api_v2() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let top = api.get_json("v2/wallets/top", &json!({ "limit": 1 }));
+    assert_eq!(top[0]["balance"], json!("100"));
+    let top = api.get_json(
+        "v2/wallets/top",
+        &json!({ "limit": 1, "amounts_as_strings": false }),
+    );
+    assert_eq!(top[0]["balance"], json!(100));
+
+    let alice = hex::encode(tx_alice.author().as_ref());
+    let info = api.get_json("v2/wallets/info", &json!({ "pub_key": alice }));
+    assert!(info["block_proof"].is_object());
+
+    let response = api.get_response("v1/wallets/top?limit=1", &[]);
+    assert_eq!(response.headers()["deprecation"], "true");
+    assert_eq!(
+        response.headers()[reqwest::header::LINK],
+        "</api/services/multisign_cryptocurrency/v2/wallets/top>; rel=\"successor-version\""
+    );
+    let response = api.get_response("v2/wallets/top?limit=1", &[]);
+    assert!(response.headers().get("deprecation").is_none());
+    let response = api.get_response("v1/stats", &[]);
+    assert!(response.headers().get("deprecation").is_none());
+}
+
+/// Check that the wallet endpoints answer the requests for the unchanged wallet with
+/// `304 Not Modified`.
+#[test]
+fn test_wallet_etag() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let query = format!(
+        "v1/wallets/info?pub_key={}",
+        hex::encode(tx_alice.author().as_ref())
+    );
+    let response = api.get_response(&query, &[]);
+    assert_eq!(response.status(), 200);
+    let etag = response.headers()[reqwest::header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    assert!(etag.starts_with("W/\""));
+
+    let response = api.get_response(&query, &[("If-None-Match", etag.as_str())]);
+    assert_eq!(response.status(), 304);
+    assert_eq!(response.headers()[reqwest::header::ETAG], etag.as_str());
+
+    // Protobuf responses are tagged separately.
+    let history = query.replace("info", "history");
+    let response = api.get_response(
+        &history,
+        &[("If-None-Match", etag.as_str()), ("Accept", "application/x-protobuf")],
+    );
+    assert_eq!(response.status(), 200);
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let response = api.get_response(&query, &[("If-None-Match", etag.as_str())]);
+    assert_eq!(response.status(), 200);
+    assert_ne!(response.headers()[reqwest::header::ETAG], etag.as_str());
+}
+
+/// Check that the wallet history is returned page by page in both orders.
+#[test]
+fn test_wallet_history_pagination() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_first = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx_first);
+    testkit.create_block();
+    let tx_second = Transfer::sign(&alice, &bob, 10, 1, &key_alice);
+    api.transfer(&tx_second);
+    testkit.create_block();
+
+    let page = api.get_wallet_history(alice, 1, 1, false);
+    assert_eq!(page.total, 3);
+    assert!(page.proof.is_some());
+    assert_eq!(page.transactions.len(), 1);
+    assert_eq!(
+        page.transactions[0].signed_message().hash(),
+        tx_first.hash()
+    );
+
+    let page = api.get_wallet_history(alice, 0, 2, true);
+    let hashes = page
+        .transactions
+        .iter()
+        .map(|tx| tx.signed_message().hash())
+        .collect::<Vec<_>>();
+    assert_eq!(hashes, vec![tx_second.hash(), tx_first.hash()]);
+
+    let page = api.get_wallet_history(alice, 3, 2, false);
+    assert!(page.proof.is_none());
+    assert!(page.transactions.is_empty());
+}
+
+/// Check that the wallet history is paged through by the cursors in both orders.
+#[test]
+fn test_wallet_history_cursor() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let mut expected = vec![tx_alice.hash()];
+    for seed in 0..2 {
+        let tx = Transfer::sign(&alice, &bob, 10, seed, &key_alice);
+        api.transfer(&tx);
+        testkit.create_block();
+        expected.push(tx.hash());
+    }
+
+    for &newest_first in &[false, true] {
+        let mut query = WalletHistoryQuery {
+            pub_key: alice,
+            from: None,
+            cursor: None,
+            limit: Some(2),
+            newest_first: Some(newest_first),
+            tx_type: None,
+            counterparty: None,
+            from_height: None,
+            to_height: None,
+        };
+        let mut hashes = Vec::new();
+        loop {
+            let page = api.query_wallet_history(&query);
+            hashes.extend(page.transactions.iter().map(|tx| tx.signed_message().hash()));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        let mut expected = expected.clone();
+        if newest_first {
+            expected.reverse();
+        }
+        assert_eq!(hashes, expected);
+    }
+
+    let response = api.get_response(
+        &format!("v1/wallets/history?pub_key={}&cursor=invalid", hex::encode(alice.as_ref())),
+        &[],
+    );
+    assert_eq!(response.status(), 400);
+}
+
+/// Check that the history records older than the retention period are pruned
+/// and the retained ones stay provable.
+#[test]
+fn test_history_pruning() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        history_retention: 50,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let tx_received = Transfer::sign(&bob, &alice, 10, 0, &key_bob);
+    api.transfer(&tx_received);
+    testkit.create_block();
+
+    testkit.create_blocks_until(Height(59));
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    testkit.create_blocks_until(Height(99));
+    assert_eq!(api.get_wallet(alice).unwrap().history_len, 3);
+
+    testkit.create_block();
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.history_len, 1);
+    assert_eq!(wallet.balance, 100);
+
+    let page = api.get_wallet_history(alice, 0, 10, false);
+    assert_eq!(page.total, 1);
+    let records = page
+        .proof
+        .unwrap()
+        .validate(wallet.history_hash, wallet.history_len)
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(*records[0].1, tx.hash());
+
+    // Transfers pruned from the history can still be refunded.
+    let tx = Refund::sign(&alice, &tx_received.hash(), 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 90);
+}
+
+/// Check that the wallet history is filtered by type, counterparty and height.
+#[test]
+fn test_wallet_history_filters() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx_to_bob = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx_to_bob);
+    testkit.create_block();
+    let tx_to_carol = Transfer::sign(&alice, &carol, 10, 0, &key_alice);
+    api.transfer(&tx_to_carol);
+    testkit.create_block();
+
+    let query = WalletHistoryQuery {
+        pub_key: alice,
+        from: None,
+        cursor: None,
+        limit: None,
+        newest_first: None,
+        tx_type: Some(HistoryTxType::Transfer),
+        counterparty: None,
+        from_height: None,
+        to_height: None,
+    };
+    let hashes = |page: WalletHistoryPage| {
+        page.transactions
+            .iter()
+            .map(|tx| tx.signed_message().hash())
+            .collect::<Vec<_>>()
+    };
+
+    let page = api.query_wallet_history(&query);
+    assert_eq!(page.total, 3);
+    assert!(page.proof.is_some());
+    assert_eq!(hashes(page), vec![tx_to_bob.hash(), tx_to_carol.hash()]);
+
+    let page = api.query_wallet_history(&WalletHistoryQuery {
+        counterparty: Some(bob),
+        ..query.clone()
+    });
+    assert_eq!(hashes(page), vec![tx_to_bob.hash()]);
+
+    let page = api.query_wallet_history(&WalletHistoryQuery {
+        from_height: Some(3),
+        ..query.clone()
+    });
+    assert_eq!(hashes(page), vec![tx_to_carol.hash()]);
+
+    let page = api.query_wallet_history(&WalletHistoryQuery {
+        tx_type: Some(HistoryTxType::Issue),
+        ..query
+    });
+    assert!(page.transactions.is_empty());
+}
+
+/// Check that the exported wallet history is decoded with balances after each record.
+#[test]
+fn test_export_wallet_history() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let records = match api.export_wallet_history(alice, ExportFormat::Json) {
+        HistoryExport::Json(records) => records,
+        export => panic!("Unexpected export {:?}", export),
+    };
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].tx_type, "CreateWallet");
+    assert_eq!(records[0].height, 1);
+    assert_eq!(records[0].balance_after, Some(100));
+    assert_eq!(records[1].tx_hash, tx.hash());
+    assert_eq!(records[1].tx_type, "Transfer");
+    assert_eq!(records[1].height, 2);
+    assert_eq!(records[1].counterparty, Some(bob));
+    assert_eq!(records[1].amount, Some(10));
+    assert_eq!(records[1].balance_after, Some(90));
+
+    let csv = match api.export_wallet_history(bob, ExportFormat::Csv) {
+        HistoryExport::Csv(csv) => csv,
+        export => panic!("Unexpected export {:?}", export),
+    };
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "height,tx_hash,type,counterparty,amount,balance_after"
+    );
+    assert_eq!(lines.len(), 3);
+    let expected = format!(",Transfer,{},10,110", hex::encode(alice.as_ref()));
+    assert!(lines[2].starts_with("2,"));
+    assert!(lines[2].ends_with(&expected));
+}
+
+/// Check that the subscribers are notified about the wallet changes made by blocks.
+#[test]
+fn test_subscribe_wallet() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    // The first request subscribes to the wallet and times out without changes.
+    assert!(api.subscribe_wallet(alice, 1, 100).is_empty());
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    // Blocks not touching the wallet are not reported.
+    testkit.create_block();
+
+    let events = api.subscribe_wallet(alice, 1, 100);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].height, Height(2));
+    assert_eq!(events[0].wallet.balance, 90);
+    assert!(api.subscribe_wallet(alice, 2, 100).is_empty());
+}
+
+/// Check that the WebSocket subscription streams the changes of the wallet as they are committed.
+#[test]
+fn test_subscribe_wallet_websocket() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let response = api.get_response(
+        &format!(
+            "v1/wallets/subscribe/ws?pub_key={}",
+            hex::encode(alice.as_ref())
+        ),
+        &[],
+    );
+    assert_eq!(response.status(), 400);
+
+    let mut socket = api.open_wallet_socket(alice, 1);
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let event: WalletEvent = serde_json::from_str(&read_text_frame(&mut socket)).unwrap();
+    assert_eq!(event.height, Height(2));
+    assert_eq!(event.wallet.balance, 90);
+}
+
+/// Check that the wallets are listed page by page in a stable order.
+#[test]
+fn test_wallets_pagination() {
+    let (mut testkit, api) = create_testkit();
+    api.create_wallet(ALICE_NAME);
+    api.create_wallet(BOB_NAME);
+    api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let all = api.get_wallets(None, None);
+    assert_eq!(all.total, 3);
+    assert_eq!(all.wallets.len(), 3);
+
+    let first = api.get_wallets(Some(0), Some(2));
+    let second = api.get_wallets(Some(2), Some(2));
+    assert_eq!(first.total, 3);
+    assert_eq!(first.wallets.len(), 2);
+    assert_eq!(second.wallets.len(), 1);
+
+    let keys = first
+        .wallets
+        .iter()
+        .chain(&second.wallets)
+        .map(|wallet| wallet.pub_key)
+        .collect::<Vec<_>>();
+    let all_keys = all
+        .wallets
+        .iter()
+        .map(|wallet| wallet.pub_key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, all_keys);
+}
+
+/// Check that the encrypted transfer memo is stored and returned with the wallet history.
+#[test]
+fn test_transfer_with_encrypted_memo() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let ciphertext = vec![7; 64];
+    let tx = Transfer::sign_with_encrypted_memo(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transferred amount
+        0,  // seed
+        &ciphertext,
+        &key_alice,
+    );
+    let tx_too_long = Transfer::sign_with_encrypted_memo(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transferred amount
+        1,  // seed
+        &[7; 305],
+        &key_alice,
+    );
+    api.transfer(&tx);
+    api.transfer(&tx_too_long);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_too_long.hash(),
+        &json!({ "type": "error", "code": 17, "description": "Memo is too long" }),
+    );
+
+    let wallet_info = api.get_wallet_info(tx_bob.author());
+    let history = wallet_info.wallet_history.unwrap();
+    assert_eq!(history.encrypted_memos.len(), 1);
+    assert_eq!(history.encrypted_memos[&tx.hash()], ciphertext);
+}
+
+/// Check that the receiver refunds the exact amount of a transfer once.
+#[test]
+fn test_refund() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_transfer = Transfer::sign(&alice, &bob, 30, 0, &key_alice);
+    api.transfer(&tx_transfer);
+    testkit.create_block();
+
+    let tx = Refund::sign(&alice, &tx_transfer.hash(), 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 75, "description": "Transfer not found" }),
+    );
+
+    let tx_refund = Refund::sign(&bob, &tx_transfer.hash(), 0, &key_bob);
+    api.transfer(&tx_refund);
+    testkit.create_block();
+    api.assert_tx_status(tx_refund.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 100);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 100);
+
+    let history = api.get_wallet_info(alice).wallet_history.unwrap();
+    assert_eq!(history.refunds[&tx_transfer.hash()], tx_refund.hash());
+
+    let tx = Refund::sign(&bob, &tx_transfer.hash(), 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 76, "description": "Transfer is already refunded" }),
+    );
+}
+
+/// Check that the arbitrator claws back a transfer disputed within the dispute window.
+#[test]
+fn test_dispute() {
+    let (arbitrator_pk, arbitrator_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        arbitrators: vec![arbitrator_pk],
+        dispute_window: 3,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_old = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx_old);
+    testkit.create_block();
+    let tx_disputed = Transfer::sign(&alice, &bob, 30, 1, &key_alice);
+    api.transfer(&tx_disputed);
+    testkit.create_blocks_until(Height(5));
+
+    let tx = OpenDispute::sign(&alice, &tx_old.hash(), &key_alice);
+    let tx_open = OpenDispute::sign(&alice, &tx_disputed.hash(), &key_alice);
+    api.transfer(&tx);
+    api.transfer(&tx_open);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 77, "description": "Dispute window has passed" }),
+    );
+    api.assert_tx_status(tx_open.hash(), &json!({ "type": "success" }));
+
+    let tx = ResolveDispute::sign(&bob, &tx_disputed.hash(), false, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 31, "description": "Not an arbitrator" }),
+    );
+
+    let tx = ResolveDispute::sign(&arbitrator_pk, &tx_disputed.hash(), true, 0, &arbitrator_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 90);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+
+    let tx = ResolveDispute::sign(&arbitrator_pk, &tx_disputed.hash(), true, 1, &arbitrator_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 79, "description": "Dispute not found" }),
+    );
+}
+
+/// Check that an accepted swap exchanges both assets, and a failed leg reverts the other one.
+#[test]
+fn test_atomic_swap() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = DefineAsset::sign(&bob, "GOLD", 0, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = Issue::sign_with_asset(&bob, "GOLD", 20, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx_too_much = ProposeSwap::sign(&alice, &bob, "", 30, "GOLD", 25, 20, 0, &key_alice);
+    let tx_swap = ProposeSwap::sign(&alice, &bob, "", 30, "GOLD", 10, 20, 1, &key_alice);
+    api.transfer(&tx_too_much);
+    api.transfer(&tx_swap);
+    testkit.create_block();
+    api.assert_tx_status(tx_swap.hash(), &json!({ "type": "success" }));
+
+    let tx = AcceptSwap::sign(&bob, &tx_too_much.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 100);
+
+    let tx = AcceptSwap::sign(&alice, &tx_swap.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 81, "description": "Not a counterparty of the swap" }),
+    );
+
+    let tx = AcceptSwap::sign(&bob, &tx_swap.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+    assert_eq!(api.get_wallet_info(alice).asset_balances["GOLD"], 10);
+}
+
+/// Check that an order trades with the best resting orders at their prices and rests its remainder.
+#[test]
+fn test_order_book() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = DefineAsset::sign(&bob, "GOLD", 0, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = Issue::sign_with_asset(&bob, "GOLD", 20, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx = PlaceOrder::sign(&bob, "GOLD", "GOLD", false, 1, 1, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({
+            "type": "error",
+            "code": 84,
+            "description": "Market must trade two different assets"
+        }),
+    );
+
+    let tx_expensive = PlaceOrder::sign(&bob, "GOLD", "", false, 3, 10, 1, &key_bob);
+    let tx_cheap = PlaceOrder::sign(&bob, "GOLD", "", false, 2, 5, 2, &key_bob);
+    api.transfer(&tx_expensive);
+    api.transfer(&tx_cheap);
+    testkit.create_block();
+    assert_eq!(api.get_wallet_info(bob).asset_balances["GOLD"], 5);
+
+    let tx = PlaceOrder::sign(&alice, "GOLD", "", true, 3, 12, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 69);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 131);
+    assert_eq!(api.get_wallet_info(alice).asset_balances["GOLD"], 12);
+
+    let tx = CancelOrder::sign(&alice, &tx_expensive.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 7, "description": "Not allowed to cancel the transfer" }),
+    );
+
+    let tx = CancelOrder::sign(&bob, &tx_expensive.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet_info(bob).asset_balances["GOLD"], 8);
+
+    let tx = CancelOrder::sign(&bob, &tx_cheap.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 85, "description": "Order not found" }),
+    );
+}
+
+/// Check that the pool keeps the product of its reserves and pays out shares proportionally.
+#[test]
+fn test_liquidity_pool() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = AddLiquidity::sign(&bob, "GOLD", 50, 50, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 24, "description": "Asset not found" }),
+    );
+
+    let tx = DefineAsset::sign(&bob, "GOLD", 0, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = Issue::sign_with_asset(&bob, "GOLD", 100, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx = AddLiquidity::sign(&bob, "GOLD", 50, 50, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = SwapViaPool::sign(&alice, "GOLD", true, 50, 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 89, "description": "Output is below the minimum" }),
+    );
+
+    let tx = SwapViaPool::sign(&alice, "GOLD", true, 50, 25, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 50);
+    assert_eq!(api.get_wallet_info(alice).asset_balances["GOLD"], 25);
+
+    let tx = RemoveLiquidity::sign(&bob, "GOLD", 60, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 88, "description": "Insufficient pool shares" }),
+    );
+
+    let tx = RemoveLiquidity::sign(&bob, "GOLD", 50, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 150);
+    assert_eq!(api.get_wallet_info(bob).asset_balances["GOLD"], 75);
+}
+
+/// Check that stakes earn block rewards and validators get the commission of delegated ones.
+#[test]
+fn test_staking_rewards() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        staking_reward: 10,
+        delegation_commission: 20,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = DelegateStake::sign(&bob, &alice, 50, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 91, "description": "Not a validator" }),
+    );
+
+    // The stake earns the whole reward of the block it's committed in.
+    let tx = Stake::sign(&alice, 50, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 60);
+
+    let tx = DelegateStake::sign(&bob, &alice, 50, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 66);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 54);
+
+    // Rewards are listed apart from the wallet history.
+    assert_eq!(api.get_wallet(alice).unwrap().history_len, 2);
+    let staking_payouts = api.get_wallet_info(alice).staking_payouts;
+    assert_eq!(staking_payouts.get(&3), Some(&10));
+    assert_eq!(staking_payouts.get(&4), Some(&6));
+
+    let tx_stake = Stake::sign(&bob, 10, 0, &key_bob);
+    let tx_unstake = Unstake::sign(&bob, 60, 0, &key_bob);
+    api.transfer(&tx_stake);
+    api.transfer(&tx_unstake);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_stake.hash(),
+        &json!({
+            "type": "error",
+            "code": 90,
+            "description": "Stake is delegated to another validator"
+        }),
+    );
+    api.assert_tx_status(
+        tx_unstake.hash(),
+        &json!({ "type": "error", "code": 92, "description": "Insufficient staked amount" }),
+    );
+
+    let tx = Unstake::sign(&bob, 50, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 82);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 108);
+}
+
+/// Check that the savings interest accrues per block and is credited on withdrawal.
+#[test]
+fn test_savings() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        savings_interest_rate: 100,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let alice = tx_alice.author();
+
+    let tx = DepositSavings::sign(&alice, 100, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 0);
+
+    // One block of interest is accrued so far.
+    let tx = WithdrawSavings::sign(&alice, 102, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 93, "description": "Insufficient savings" }),
+    );
+
+    let tx = WithdrawSavings::sign(&alice, 102, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 102);
+}
+
+/// Check that a loan requires enough collateral and is liquidated once its price drops.
+#[test]
+fn test_collateralized_loan() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        collateral_ratio: 150,
+        liquidation_ratio: 120,
+        max_supply: 20,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = DefineAsset::sign(&bob, "GOLD", 0, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = Issue::sign_with_asset(&bob, "GOLD", 100, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = AddLiquidity::sign(&bob, "GOLD", 50, 50, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx_too_much = OpenLoan::sign(&bob, "GOLD", 30, 25, 0, &key_bob);
+    let tx_loan = OpenLoan::sign(&bob, "GOLD", 30, 20, 0, &key_bob);
+    api.transfer(&tx_too_much);
+    api.transfer(&tx_loan);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_too_much.hash(),
+        &json!({
+            "type": "error",
+            "code": 95,
+            "description": "Collateral is insufficient for the loan"
+        }),
+    );
+    api.assert_tx_status(tx_loan.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 70);
+    assert_eq!(api.get_wallet_info(bob).asset_balances["GOLD"], 20);
+
+    // Borrowed currency counts towards the maximum supply.
+    let tx = OpenLoan::sign(&bob, "GOLD", 2, 1, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 22, "description": "Supply cap exceeded" }),
+    );
+
+    let tx_repay = RepayLoan::sign(&alice, &tx_loan.hash(), &key_alice);
+    let tx_liquidate = Liquidate::sign(&alice, &tx_loan.hash(), &key_alice);
+    api.transfer(&tx_repay);
+    api.transfer(&tx_liquidate);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_repay.hash(),
+        &json!({ "type": "error", "code": 97, "description": "Not a borrower of the loan" }),
+    );
+    api.assert_tx_status(
+        tx_liquidate.hash(),
+        &json!({
+            "type": "error",
+            "code": 98,
+            "description": "Loan is sufficiently collateralized"
+        }),
+    );
+
+    // Selling the collateral to the pool drops its price, but only from the next block.
+    let tx = SwapViaPool::sign(&bob, "GOLD", false, 20, 0, 0, &key_bob);
+    let tx_liquidate = Liquidate::sign(&alice, &tx_loan.hash(), &key_alice);
+    api.transfer(&tx);
+    api.transfer(&tx_liquidate);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_liquidate.hash(),
+        &json!({
+            "type": "error",
+            "code": 98,
+            "description": "Loan is sufficiently collateralized"
+        }),
+    );
+
+    let tx = Liquidate::sign(&alice, &tx_loan.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 80);
+    assert_eq!(api.get_wallet_info(alice).asset_balances["GOLD"], 30);
+
+    let tx = RepayLoan::sign(&bob, &tx_loan.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 96, "description": "Loan not found" }),
+    );
+}
+
+/// Check that transfers earn loyalty points which are redeemed for the currency.
+#[test]
+fn test_loyalty_points() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        loyalty_rate: 10,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = Transfer::sign(&alice, &bob, 50, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet_info(alice).loyalty_points, 5);
+    assert_eq!(api.get_wallet_info(bob).loyalty_points, 0);
+
+    let tx = RedeemPoints::sign(&alice, 6, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 99, "description": "Insufficient loyalty points" }),
+    );
+
+    let tx = RedeemPoints::sign(&alice, 5, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 55);
+    assert_eq!(api.get_wallet_info(alice).loyalty_points, 0);
+}
+
+/// Check that a parameter change is applied once the votes reach the quorum.
+#[test]
+fn test_param_change_vote() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        governance_quorum: 50,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx_unknown = ProposeParamChange::sign(&alice, "admins", 0, 0, &key_alice);
+    let tx_proposal = ProposeParamChange::sign(&alice, "transfer_fee", 5, 1, &key_alice);
+    api.transfer(&tx_unknown);
+    api.transfer(&tx_proposal);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_unknown.hash(),
+        &json!({ "type": "error", "code": 101, "description": "Unknown parameter" }),
+    );
+    api.assert_tx_status(tx_proposal.hash(), &json!({ "type": "success" }));
+
+    let tx = Vote::sign(&alice, &tx_proposal.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Vote::sign(&alice, &tx_proposal.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 103, "description": "Already voted for the proposal" }),
+    );
+
+    // Two of three equal balances reach the quorum of a half of the total supply.
+    let tx = Vote::sign(&bob, &tx_proposal.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Vote::sign(&carol, &tx_proposal.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 102, "description": "Proposal not found" }),
+    );
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 85);
+}
+
+/// Check that votes are weighted by the balances at the moment of the proposal.
+#[test]
+fn test_param_change_vote_weights() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        governance_quorum: 70,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx_proposal = ProposeParamChange::sign(&alice, "transfer_fee", 5, 0, &key_alice);
+    api.transfer(&tx_proposal);
+    testkit.create_block();
+    api.assert_tx_status(tx_proposal.hash(), &json!({ "type": "success" }));
+
+    let (tx_dave, key_dave) = api.create_wallet("Dave");
+    let tx = Vote::sign(&alice, &tx_proposal.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // Coins moved after the vote don't add to the weight of the receiver.
+    let tx = Transfer::sign(&alice, &bob, 100, 0, &key_alice);
+    api.transfer(&tx);
+    let tx_dave_vote = Vote::sign(&tx_dave.author(), &tx_proposal.hash(), &key_dave);
+    api.transfer(&tx_dave_vote);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_dave_vote.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+
+    let tx = Vote::sign(&bob, &tx_proposal.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // 200 of 300 don't reach the quorum, so the proposal is still open.
+    let tx = Vote::sign(&carol, &tx_proposal.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&bob, &carol, 10, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 185);
+}
+
+/// Check that proposals are paid for, limited to one open proposal per wallet
+/// and can't be voted after they expire.
+#[test]
+fn test_param_change_expiry() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        governance_quorum: 50,
+        proposal_fee: 10,
+        proposal_period: 2,
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_proposal = ProposeParamChange::sign(&alice, "transfer_fee", 5, 0, &key_alice);
+    api.transfer(&tx_proposal);
+    testkit.create_block();
+    api.assert_tx_status(tx_proposal.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 90);
+
+    let tx = ProposeParamChange::sign(&alice, "loyalty_rate", 1, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 111, "description": "Another proposal of the wallet is open" }),
+    );
+
+    testkit.create_block();
+    let tx = Vote::sign(&bob, &tx_proposal.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 102, "description": "Proposal not found" }),
+    );
+
+    // The expired proposal no longer blocks the next one.
+    let tx = ProposeParamChange::sign(&alice, "transfer_fee", 5, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 80);
+}
+
+/// Check that transactions fail while the service is halted until an administrator resumes it.
+#[test]
+fn test_halt_service() {
+    let (admin_pk, admin_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        admins: vec![admin_pk],
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = HaltService::sign(&alice, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 12, "description": "Not an administrator" }),
+    );
+
+    let tx = HaltService::sign(&admin_pk, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 104, "description": "Service is halted" }),
+    );
+
+    let tx = ResumeService::sign(&admin_pk, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&alice, &bob, 10, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+}
+
+/// Check that a transfer scheduled for a height passed while the service was halted
+/// is executed once the service is resumed.
+#[test]
+fn test_scheduled_transfer_after_halt() {
+    let (admin_pk, admin_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        admins: vec![admin_pk],
+        ..Config::default()
+    });
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = ScheduleTransfer::sign(&alice, &bob, 30, 5, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = HaltService::sign(&admin_pk, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(6));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 100);
+
+    let tx = ResumeService::sign(&admin_pk, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+}
+
+/// Check that the transfer by name resolves the receiver by its wallet name.
+#[test]
+fn test_transfer_by_name() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = TransferByName::sign(&tx_alice.author(), BOB_NAME, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 110);
+
+    let tx = TransferByName::sign(&tx_alice.author(), CAROL_NAME, 10, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 2, "description": "Receiver doesn't exist" }),
+    );
+}
+
+/// Check that a transfer from a non-existing wallet fails as expected.
+#[test]
+fn test_transfer_from_nonexisting_wallet() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    // Do not commit Alice's transaction, so Alice's wallet does not exist
+    // when a transfer occurs.
+    testkit.create_block_with_tx_hashes(&[tx_bob.hash()]);
+
+    api.assert_no_wallet(tx_alice.author());
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+
+    let tx = Transfer::sign(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block_with_tx_hashes(&[tx.hash()]);
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 1, "description": "Sender doesn't exist" }),
+    );
+
+    // Check that Bob's balance doesn't change.
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that a transfer to a non-existing wallet fails as expected.
+#[test]
+fn test_transfer_to_nonexisting_wallet() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    // Do not commit Bob's transaction, so Bob's wallet does not exist
+    // when a transfer occurs.
+    testkit.create_block_with_tx_hashes(&[tx_alice.hash()]);
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    api.assert_no_wallet(tx_bob.author());
+
+    let tx = Transfer::sign(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block_with_tx_hashes(&[tx.hash()]);
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 2, "description": "Receiver doesn't exist" }),
+    );
+
+    // Check that Alice's balance doesn't change.
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that an overcharge does not lead to changes in sender's and receiver's balances.
+#[test]
+fn test_transfer_overcharge() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    // Transfer funds. The transfer amount (110) is more than Alice has (100).
+    let tx = Transfer::sign(
+        &tx_alice.author(),
+        &tx_bob.author(),
+        110, // transfer amount
+        0,   // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that the batch transfer credits all the receivers.
+#[test]
+fn test_batch_transfer() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let payments = vec![
+        Payment {
+            to: tx_bob.author(),
+            amount: 10,
+        },
+        Payment {
+            to: tx_carol.author(),
+            amount: 20,
+        },
+    ];
+    let tx = BatchTransfer::sign(&tx_alice.author(), &payments, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 70);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 110);
+    let wallet = api.get_wallet(tx_carol.author()).unwrap();
+    assert_eq!(wallet.balance, 120);
+}
+
+/// Check that the batch transfer fails as a whole if one of the receivers doesn't exist.
+#[test]
+fn test_batch_transfer_is_atomic() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (unknown, _) = crypto::gen_keypair();
+    let payments = vec![
+        Payment {
+            to: tx_bob.author(),
+            amount: 10,
+        },
+        Payment {
+            to: unknown,
+            amount: 20,
+        },
+    ];
+    let tx = BatchTransfer::sign(&tx_alice.author(), &payments, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 2, "description": "Receiver doesn't exist" }),
+    );
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that a transfer to the sender's own wallet fails with a distinct error code.
+#[test]
+fn test_transfer_to_self() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_alice.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 20, "description": "Sender is the same as receiver" }),
+    );
+}
+
+/// Check that zero-amount transfers and issues are rejected.
+#[test]
+fn test_zero_amount() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let transfer = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 0, 0, &key_alice);
+    let issue = Issue::sign(&tx_alice.author(), 0, 0, &key_alice);
+    api.transfer(&transfer);
+    api.transfer(&issue);
+    testkit.create_block();
+
+    let error = json!({ "type": "error", "code": 19, "description": "Amount must be positive" });
+    api.assert_tx_status(transfer.hash(), &error);
+    api.assert_tx_status(issue.hash(), &error);
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.history_len, 1);
+}
+
+/// Check that a minter can issue currency to its wallet.
+#[test]
+fn test_issue() {
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        minters: vec![minter_pk],
+        ..Config::default()
+    });
+
+    api.create_wallet_with_keypair(ALICE_NAME, minter_pk, &minter_sk);
+    testkit.create_block();
+
+    let tx = Issue::sign(&minter_pk, 50, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(minter_pk).unwrap();
+    assert_eq!(wallet.balance, 150);
+}
+
+/// Check that an issue from a key outside the minter set fails as expected.
+#[test]
+fn test_issue_by_non_minter() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+
+    let tx = Issue::sign(&tx_alice.author(), 50, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 21, "description": "Not authorized to issue currency" }),
+    );
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that an airdrop credits the listed wallets once each, or all wallets if none are listed.
+#[test]
+fn test_airdrop() {
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        minters: vec![minter_pk],
+        ..Config::default()
+    });
+
+    api.create_wallet_with_keypair(ALICE_NAME, minter_pk, &minter_sk);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (bob, carol) = (tx_bob.author(), tx_carol.author());
+    let supply = api.get_supply();
+
+    let tx = Airdrop::sign(&minter_pk, &[bob, bob], 10, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 100);
+
+    let tx = Airdrop::sign(&minter_pk, &[], 5, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(minter_pk).unwrap().balance, 105);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 115);
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 105);
+    assert_eq!(api.get_supply(), supply + 25);
+
+    let tx = Airdrop::sign(&bob, &[carol], 5, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 21, "description": "Not authorized to issue currency" }),
+    );
+}
+
+/// Check that a dividend is split proportionally to balances with shares rounded down.
+#[test]
+fn test_distribute_dividend() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx = Transfer::sign(&bob, &carol, 50, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    // Bob gets 10 * 50 / 200 = 2.5 and Carol gets 10 * 150 / 200 = 7.5, both rounded down.
+    let tx = DistributeDividend::sign(&alice, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 91);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 52);
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 157);
+
+    let tx = DistributeDividend::sign(&alice, 200, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+}
+
+/// Check that transfer fees are charged from the sender and paid out to the fee recipients.
+#[test]
+fn test_transfer_fee() {
+    let (carol, key_carol) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        transfer_fee: 2,
+        fee_recipients: vec![carol],
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    api.create_wallet_with_keypair(CAROL_NAME, carol, &key_carol);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 88);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 102);
+    assert_eq!(api.get_wallet(carol).unwrap().history_len, 1);
+    assert_eq!(api.get_wallet_info(carol).fee_payouts.get(&2), Some(&2));
+
+    // The transfer itself is covered, but the fee isn't.
+    let tx = Transfer::sign(&alice, &bob, 88, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 88);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+
+    // Other transfers are charged as well, a batch once for all its payments.
+    let tx = TransferByName::sign(&alice, BOB_NAME, 10, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 76);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 120);
+
+    let payments = vec![
+        Payment { to: bob, amount: 5 },
+        Payment { to: bob, amount: 5 },
+    ];
+    let tx = BatchTransfer::sign(&alice, &payments, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 64);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+}
+
+/// Check that collected fees are burned when the service is configured to do so.
+#[test]
+fn test_transfer_fee_burned() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        transfer_fee: 2,
+        burn_fees: true,
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let supply = api.get_supply();
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 88);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
+    assert_eq!(api.get_supply(), supply - 2);
+}
+
+/// Check that transfers above the spending limit are rejected unless co-signed.
+#[test]
+fn test_spending_limit() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let (co_signer, co_signer_sk) = crypto::gen_keypair();
+
+    let tx = SetSpendingLimit::sign(&alice, 20, &co_signer, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // A batch is limited by the sum of its payments, an escrow like a transfer.
+    let payments = vec![
+        Payment {
+            to: bob,
+            amount: 15,
+        },
+        Payment {
+            to: bob,
+            amount: 15,
+        },
+    ];
+    let tx_transfer = Transfer::sign(&alice, &bob, 30, 0, &key_alice);
+    let tx_batch = BatchTransfer::sign(&alice, &payments, 0, &key_alice);
+    let tx_escrow = CreateEscrow::sign(&alice, &bob, &co_signer, 30, 0, &key_alice);
+    api.transfer(&tx_transfer);
+    api.transfer(&tx_batch);
+    api.transfer(&tx_escrow);
+    testkit.create_block();
+    for tx in &[tx_transfer, tx_batch, tx_escrow] {
+        api.assert_tx_status(
+            tx.hash(),
+            &json!({ "type": "error", "code": 46, "description": "Spending limit exceeded" }),
+        );
+    }
+
+    let co_signature = CoSignedTransfer::co_sign(&alice, &bob, 30, 0, &key_alice);
+    let tx = CoSignedTransfer::sign(&alice, &bob, 30, 0, &co_signature, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 47, "description": "Invalid co-signature" }),
+    );
+
+    let co_signature = CoSignedTransfer::co_sign(&alice, &bob, 30, 1, &co_signer_sk);
+    let tx = CoSignedTransfer::sign(&alice, &bob, 30, 1, &co_signature, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+}
+
+/// Check that the amount sent within the velocity window is limited.
+#[test]
+fn test_velocity_limit() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        velocity_limit: 50,
+        velocity_window: 3,
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = Transfer::sign(&alice, &bob, 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&alice, &bob, 30, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 48, "description": "Velocity limit exceeded" }),
+    );
+
+    // The first transfer was made in block 2, so it leaves the window in block 5.
+    testkit.create_blocks_until(Height(4));
+    let tx = Transfer::sign(&alice, &bob, 30, 2, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // Other payments from the wallet are limited as well.
+    let tx_escrow = CreateEscrow::sign(&alice, &bob, &bob, 30, 0, &key_alice);
+    let tx_scheduled = ScheduleTransfer::sign(&alice, &bob, 30, 7, 0, &key_alice);
+    api.transfer(&tx_escrow);
+    api.transfer(&tx_scheduled);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_escrow.hash(),
+        &json!({ "type": "error", "code": 48, "description": "Velocity limit exceeded" }),
+    );
+    api.assert_tx_status(tx_scheduled.hash(), &json!({ "type": "success" }));
+
+    // The scheduled transfer is dropped in block 7.
+    testkit.create_block();
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 40);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 160);
+}
+
+/// Check that balances in assets other than the default one are transferred separately.
+#[test]
+fn test_transfer_asset() {
+    let (mut testkit, api) = create_testkit();
+
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    api.create_wallet_with_keypair(ALICE_NAME, minter_pk, &minter_sk);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = DefineAsset::sign(&minter_pk, "GOLD", 2, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Issue::sign_with_asset(&minter_pk, "GOLD", 50, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign_with_asset(&minter_pk, &tx_bob.author(), "GOLD", 20, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign_with_asset(&minter_pk, &tx_bob.author(), "GOLD", 40, 1, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+
+    let alice_info = api.get_wallet_info(minter_pk);
+    assert_eq!(alice_info.asset_balances["GOLD"], 30);
+    let bob_info = api.get_wallet_info(tx_bob.author());
+    assert_eq!(bob_info.asset_balances["GOLD"], 20);
+
+    // The default coin balances are not affected.
+    assert_eq!(api.get_wallet(minter_pk).unwrap().balance, 100);
+    assert_eq!(api.get_wallet(tx_bob.author()).unwrap().balance, 100);
+    assert_eq!(api.get_supply(), 200);
+}
+
+/// Check that only the issuer of a defined asset can issue it within its maximum supply.
+#[test]
+fn test_define_asset() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = DefineAsset::sign(&tx_bob.author(), "GOLD", 2, 100, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = DefineAsset::sign(&tx_alice.author(), "GOLD", 0, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 23, "description": "Asset already exists" }),
+    );
+
+    for code in &["", "GOLD/SILVER"] {
+        let tx = DefineAsset::sign(&tx_alice.author(), code, 0, 0, &key_alice);
+        api.transfer(&tx);
+        testkit.create_block();
+        api.assert_tx_status(
+            tx.hash(),
+            &json!({ "type": "error", "code": 108, "description": "Invalid asset code" }),
+        );
+    }
+
+    let tx = Issue::sign_with_asset(&tx_alice.author(), "GOLD", 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 21, "description": "Not authorized to issue currency" }),
+    );
+
+    let tx = Issue::sign_with_asset(&tx_alice.author(), "SILVER", 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 24, "description": "Asset not found" }),
+    );
+
+    let tx = Issue::sign_with_asset(&tx_bob.author(), "GOLD", 60, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Issue::sign_with_asset(&tx_bob.author(), "GOLD", 50, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 22, "description": "Supply cap exceeded" }),
+    );
+
+    let bob_info = api.get_wallet_info(tx_bob.author());
+    assert_eq!(bob_info.asset_balances["GOLD"], 60);
+    assert_eq!(api.get_supply(), 200);
+}
+
+/// Check that a spender can transfer funds from the owner's wallet up to the allowance.
+#[test]
+fn test_transfer_from() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let tx = Approve::sign(&tx_alice.author(), &tx_bob.author(), 50, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = TransferFrom::sign(
+        &tx_bob.author(),
+        &tx_alice.author(),
+        &tx_carol.author(),
+        30,
+        0,
+        &key_bob,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = TransferFrom::sign(
+        &tx_bob.author(),
+        &tx_alice.author(),
+        &tx_carol.author(),
+        30,
+        1,
+        &key_bob,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 27, "description": "Insufficient allowance" }),
+    );
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert_eq!(wallet.balance, 70);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(tx_carol.author()).unwrap();
+    assert_eq!(wallet.balance, 130);
+}
+
+/// Check that a transfer permit signed by the owner can be relayed once by another wallet.
+#[test]
+fn test_permit_transfer() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    // Permit signed with a key other than the owner's is rejected.
+    let signature = PermitTransfer::sign_permit(&alice, &carol, 40, 0, &key_bob);
+    let tx = PermitTransfer::sign(&bob, &alice, &carol, 40, 0, &signature, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 28, "description": "Invalid permit signature" }),
+    );
+
+    let signature = PermitTransfer::sign_permit(&alice, &carol, 40, 0, &key_alice);
+    let tx = PermitTransfer::sign(&bob, &alice, &carol, 40, 0, &signature, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // The same permit can't be relayed again.
+    let tx = PermitTransfer::sign(&carol, &alice, &carol, 40, 0, &signature, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 29, "description": "Permit already used" }),
+    );
+
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.balance, 60);
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.balance, 100);
+    let wallet = api.get_wallet(carol).unwrap();
+    assert_eq!(wallet.balance, 140);
+}
+
+/// Check that escrowed funds are locked until the arbitrator releases or refunds them.
+#[test]
+fn test_escrow() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx_release = CreateEscrow::sign(&alice, &bob, &carol, 30, 0, &key_alice);
+    let tx_refund = CreateEscrow::sign(&alice, &bob, &carol, 20, 1, &key_alice);
+    api.transfer(&tx_release);
+    api.transfer(&tx_refund);
+    testkit.create_block();
+    api.assert_tx_status(tx_release.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_refund.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.balance, 100);
+    assert_eq!(wallet.pending_balance, 50);
+
+    // Only the arbitrator can release the funds.
+    let tx = ReleaseEscrow::sign(&bob, &tx_release.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 31, "description": "Not an arbitrator" }),
+    );
+
+    let tx = ReleaseEscrow::sign(&carol, &tx_release.hash(), &key_carol);
+    api.transfer(&tx);
+    let tx = RefundEscrow::sign(&carol, &tx_refund.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx = RefundEscrow::sign(&carol, &tx_release.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 30, "description": "Escrow not found" }),
+    );
+
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.balance, 70);
+    assert_eq!(wallet.pending_balance, 70);
+    assert!(api.get_wallet_info(alice).pending_txs.is_empty());
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.balance, 130);
+    let wallet = api.get_wallet(carol).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that hash-locked funds are claimed with the preimage or refunded after the timeout.
+#[test]
+fn test_hash_lock() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let claimed_lock = crypto::hash(b"secret");
+    let refunded_lock = crypto::hash(b"other secret");
+
+    // The locks are executed in block 2, so the second one is refundable from block 4.
+    let tx = LockWithHash::sign(&alice, &bob, 30, &claimed_lock, 10, &key_alice);
+    api.transfer(&tx);
+    let tx = LockWithHash::sign(&alice, &bob, 20, &refunded_lock, 4, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.pending_balance, 50);
+
+    let tx = ClaimWithPreimage::sign(&bob, b"secret", &key_bob);
+    api.transfer(&tx);
+    let tx_refund = RefundHashLock::sign(&alice, &refunded_lock, &key_alice);
+    api.transfer(&tx_refund);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_refund.hash(),
+        &json!({ "type": "error", "code": 35, "description": "Hash lock not expired" }),
+    );
+
+    let tx = ClaimWithPreimage::sign(&alice, b"secret", &key_alice);
+    let tx_refund = RefundHashLock::sign(&bob, &refunded_lock, &key_bob);
+    api.transfer(&tx_refund);
+    testkit.create_block();
+    api.assert_tx_status(tx_refund.hash(), &json!({ "type": "success" }));
+
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 33, "description": "Hash lock not found" }),
+    );
+
+    let wallet = api.get_wallet(alice).unwrap();
+    assert_eq!(wallet.balance, 70);
+    assert_eq!(wallet.pending_balance, 70);
+    assert!(api.get_wallet_info(alice).pending_txs.is_empty());
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.balance, 130);
+}
+
+/// Check that time-locked funds can't be spent by the receiver before the unlock height.
+#[test]
+fn test_time_locked_transfer() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = Transfer::sign_with_unlock(&alice, &bob, 40, 4, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.balance, 140);
+    assert_eq!(wallet.pending_balance, 100);
+
+    let tx = Transfer::sign(&bob, &alice, 120, 0, &key_bob);
+    let tx_close = CloseWallet::sign(&bob, &alice, &key_bob);
+    api.transfer(&tx);
+    api.transfer(&tx_close);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+    );
+    api.assert_tx_status(
+        tx_close.hash(),
+        &json!({ "type": "error", "code": 109, "description": "Wallet has time-locked funds" }),
+    );
+
+    // The funds are unlocked at the end of block 4.
+    testkit.create_block();
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.pending_balance, 140);
+    assert!(api.get_wallet_info(bob).pending_txs.is_empty());
+
+    let tx = Transfer::sign(&bob, &alice, 120, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(bob).unwrap();
+    assert_eq!(wallet.balance, 20);
+}
+
+/// Check that vested funds are released linearly after the cliff.
+#[test]
+fn test_vesting() {
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        minters: vec![minter_pk],
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    testkit.create_block();
+    let alice = tx_alice.author();
+
+    // 100 coins vest from block 2 to block 12 with nothing released before block 6.
+    let tx_vesting = CreateVesting::sign(&minter_pk, &alice, 100, 2, 4, 10, 0, &minter_sk);
+    api.transfer(&tx_vesting);
+    testkit.create_block();
+    api.assert_tx_status(tx_vesting.hash(), &json!({ "type": "success" }));
+
+    let tx = ClaimVesting::sign(&alice, &tx_vesting.hash(), 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 38, "description": "Nothing to claim" }),
+    );
+
+    testkit.create_blocks_until(Height(6));
+    let tx = ClaimVesting::sign(&alice, &tx_vesting.hash(), 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    // Claimed in block 7, i.e. 5 of 10 blocks since the start.
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 150);
+
+    testkit.create_blocks_until(Height(15));
+    let tx = ClaimVesting::sign(&alice, &tx_vesting.hash(), 2, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 200);
+    assert_eq!(api.get_supply(), 200);
+}
+
+/// Check that a standing order pays every interval until it is cancelled.
+#[test]
+fn test_standing_order() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    // The order is created in block 2, so the payments are made in blocks 4, 6, ...
+    let tx_order = CreateStandingOrder::sign(&alice, &bob, 10, 2, 0, &key_alice);
+    api.transfer(&tx_order);
+    testkit.create_block();
+    api.assert_tx_status(tx_order.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(6));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 80);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 120);
+
+    let tx = CancelStandingOrder::sign(&alice, &tx_order.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(10));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 80);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 120);
+
+    let tx = CreateStandingOrder::sign(&alice, &bob, 10, u64::max_value(), 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 40, "description": "Invalid interval" }),
+    );
+}
+
+/// Check that a standing order is dropped once the sender can't pay it.
+#[test]
+fn test_standing_order_underfunded() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_order = CreateStandingOrder::sign(&alice, &bob, 40, 1, 0, &key_alice);
+    api.transfer(&tx_order);
+    testkit.create_blocks_until(Height(6));
+
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 20);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 180);
+
+    let tx = CancelStandingOrder::sign(&alice, &tx_order.hash(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 41, "description": "Standing order not found" }),
+    );
+}
+
+/// Check that a payment stream pays every block until the receiver closes it.
+#[test]
+fn test_stream() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    // The stream is opened in block 2, so the payments are made in blocks 3, 4, ...
+    let tx_stream = OpenStream::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx_stream);
+    testkit.create_block();
+    api.assert_tx_status(tx_stream.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 100);
+
+    testkit.create_blocks_until(Height(6));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 60);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 140);
+
+    let tx = CloseStream::sign(&tx_carol.author(), &tx_stream.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 7, "description": "Not allowed to cancel the transfer" }),
+    );
+
+    let tx = CloseStream::sign(&bob, &tx_stream.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(10));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 50);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 150);
+}
+
+/// Check that a scheduled transfer is executed at its height and dropped if it can't be made.
+#[test]
+fn test_schedule_transfer() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_scheduled = ScheduleTransfer::sign(&alice, &bob, 30, 5, 0, &key_alice);
+    let tx_underfunded = ScheduleTransfer::sign(&alice, &bob, 100, 6, 0, &key_alice);
+    let tx_passed = ScheduleTransfer::sign(&alice, &bob, 10, 2, 0, &key_alice);
+    api.transfer(&tx_scheduled);
+    api.transfer(&tx_underfunded);
+    api.transfer(&tx_passed);
+    testkit.create_block();
+    api.assert_tx_status(tx_scheduled.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_underfunded.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_passed.hash(),
+        &json!({
+            "type": "error",
+            "code": 43,
+            "description": "Scheduled height has already passed"
+        }),
+    );
+
+    testkit.create_blocks_until(Height(4));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 100);
+
+    testkit.create_block();
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+
+    testkit.create_blocks_until(Height(8));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 70);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 130);
+}
+
+/// Check that a non-fungible token can be minted and transferred only by its owner.
+#[test]
+fn test_nft() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx_mint = MintNft::sign(&tx_alice.author(), "Painting", 0, &key_alice);
+    api.transfer(&tx_mint);
+    testkit.create_block();
+    api.assert_tx_status(tx_mint.hash(), &json!({ "type": "success" }));
+    let token = tx_mint.hash();
+    assert_eq!(api.get_nfts(tx_alice.author()), vec![token]);
+
+    let tx = TransferNft::sign(&tx_bob.author(), &token, &tx_bob.author(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 26, "description": "Not a token owner" }),
+    );
+
+    let tx = TransferNft::sign(&tx_alice.author(), &token, &tx_bob.author(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert!(api.get_nfts(tx_alice.author()).is_empty());
+    assert_eq!(api.get_nfts(tx_bob.author()), vec![token]);
+
+    let tx = TransferNft::sign(
+        &tx_bob.author(),
+        &Hash::zero(),
+        &tx_alice.author(),
+        &key_bob,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 25, "description": "Token not found" }),
+    );
+}
+
+/// Check that the total supply follows wallet creation and burning.
+#[test]
+fn test_total_supply() {
+    let (mut testkit, api) = create_testkit();
+    assert_eq!(api.get_supply(), 0);
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    api.create_wallet(BOB_NAME);
+    testkit.create_block();
+    assert_eq!(api.get_supply(), 200);
+
+    let tx = Burn::sign(&tx_alice.author(), 30, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_supply(), 170);
+}
+
+/// Check that issuing beyond the maximum supply fails as expected.
+#[test]
+fn test_issue_supply_cap() {
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        minters: vec![minter_pk],
+        max_supply: 100,
+        ..Config::default()
+    });
+
+    api.create_wallet_with_keypair(ALICE_NAME, minter_pk, &minter_sk);
+    testkit.create_block();
+
+    let tx = Issue::sign(&minter_pk, 60, 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Issue::sign(&minter_pk, 50, 1, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 22, "description": "Supply cap exceeded" }),
+    );
+
+    let wallet = api.get_wallet(minter_pk).unwrap();
+    assert_eq!(wallet.balance, 160);
+}
+
+/// Check that an issue overflowing the balance fails as expected.
+#[test]
+fn test_issue_overflow() {
+    let (minter_pk, minter_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        minters: vec![minter_pk],
+        ..Config::default()
+    });
+
+    api.create_wallet_with_keypair(ALICE_NAME, minter_pk, &minter_sk);
+    testkit.create_block();
+
+    let tx = Issue::sign(&minter_pk, u64::max_value(), 0, &minter_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 18, "description": "Balance overflow" }),
+    );
+
+    let wallet = api.get_wallet(minter_pk).unwrap();
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that a closed wallet sweeps its balance and rejects incoming transfers.
+#[test]
+fn test_close_wallet() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let tx = CloseWallet::sign(&tx_alice.author(), &tx_bob.author(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_alice.author()).unwrap();
+    assert!(wallet.closed);
+    assert_eq!(wallet.balance, 0);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 200);
+
+    let tx = Transfer::sign(&tx_bob.author(), &tx_alice.author(), 10, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 15, "description": "Wallet is closed" }),
+    );
+}
+
+/// Check that a wallet with staked or saved funds can't be closed and a closed wallet
+/// can't withdraw them.
+#[test]
+fn test_close_wallet_with_open_positions() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx_stake = Stake::sign(&alice, 30, 0, &key_alice);
+    let tx_deposit = DepositSavings::sign(&alice, 20, 0, &key_alice);
+    api.transfer(&tx_stake);
+    api.transfer(&tx_deposit);
+    testkit.create_block();
+    api.assert_tx_status(tx_stake.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_deposit.hash(), &json!({ "type": "success" }));
+
+    let tx = CloseWallet::sign(&alice, &bob, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 110, "description": "Wallet has open positions" }),
+    );
+
+    let tx_unstake = Unstake::sign(&alice, 30, 0, &key_alice);
+    let tx_withdraw = WithdrawSavings::sign(&alice, 20, 0, &key_alice);
+    api.transfer(&tx_unstake);
+    api.transfer(&tx_withdraw);
+    testkit.create_block();
+    api.assert_tx_status(tx_unstake.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_withdraw.hash(), &json!({ "type": "success" }));
+
+    // `CloseWallet` has no seed, so the wallet is closed to another receiver.
+    let tx = CloseWallet::sign(&alice, &carol, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 200);
+
+    let tx = Unstake::sign(&alice, 30, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 15, "description": "Wallet is closed" }),
+    );
+}
+
+/// Check that the multisign wallet creation transaction works when invoked via API.
+#[test]
+fn test_create_multisig_wallet() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx.author()).unwrap();
+    assert_eq!(wallet.name, SHARED_NAME);
+    assert_eq!(wallet.balance, 100);
+}
+
+/// Check that a multisign wallet cannot require more approvals than it has owners.
+#[test]
+fn test_create_multisig_wallet_invalid_quorum() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, _) = api.create_wallet(ALICE_NAME);
+    let owners = vec![tx_alice.author()];
+    let (tx, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 4, "description": "Invalid quorum" }),
+    );
+    api.assert_no_wallet(tx.author());
+}
+
+/// Check that an owner can link its wallet to the multisign wallet.
+#[test]
+fn test_link_wallet() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let owners = vec![tx_alice.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 1);
+    testkit.create_block();
+
+    let tx = LinkWallet::sign(&tx_alice.author(), &tx_shared.author(), &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet_info = api.get_wallet_info(tx_alice.author());
+    assert_eq!(wallet_info.linked_multisig_wallets, vec![tx_shared.author()]);
+    let wallet_info = api.get_wallet_info(tx_bob.author());
+    assert!(wallet_info.linked_multisig_wallets.is_empty());
+}
+
+/// Check that a multisign transfer is executed only after the quorum is reached.
+#[test]
+fn test_transfer_multisign_quorum() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author(), tx_carol.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let tx = TransferMultisign::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &tx_carol.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    // Only the initiator has approved the transfer so far.
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    assert_eq!(api.get_wallet_info(tx_shared.author()).pending_txs, vec![tx.hash()]);
+
+    let accept = AcceptMultisign::sign(
+        &tx_bob.author(),
+        &tx.hash(),
+        0, // seed
+        &key_bob,
+    );
+    api.transfer(&accept);
+    testkit.create_block();
+    api.assert_tx_status(accept.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.balance, 90);
+    assert!(api.get_wallet_info(tx_shared.author()).pending_txs.is_empty());
+    let wallet = api.get_wallet(tx_carol.author()).unwrap();
+    assert_eq!(wallet.balance, 110);
+}
+
+/// Check that the transaction status returns the events emitted by the execution.
+#[test]
+fn test_transaction_receipts() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author(), tx_carol.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+    let shared = tx_shared.author();
+    let balance_changed = |wallet, previous_balance, balance| ExecutionEvent::BalanceChanged {
+        wallet,
+        asset: String::new(),
+        previous_balance,
+        balance,
+    };
+
+    let receipt = api.get_transaction_receipt(tx_alice.hash());
+    assert_eq!(receipt.status, TransactionStatus::Success { height: Height(1) });
+    assert_eq!(receipt.events, vec![balance_changed(alice, 0, 100)]);
+
+    let tx = TransferMultisign::sign(&alice, &shared, &carol, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    assert_eq!(
+        api.get_transaction_receipt(tx.hash()).events,
+        vec![
+            ExecutionEvent::PendingTransferCreated {
+                transfer: tx.hash(),
+                wallet: shared,
+                amount: 10,
+            },
+            ExecutionEvent::TransferApproved {
+                transfer: tx.hash(),
+                approver: alice,
+            },
+        ]
+    );
+
+    let accept = AcceptMultisign::sign(&bob, &tx.hash(), 0, &key_bob);
+    api.transfer(&accept);
+    testkit.create_block();
+    assert_eq!(
+        api.get_transaction_receipt(accept.hash()).events,
+        vec![
+            ExecutionEvent::TransferApproved {
+                transfer: tx.hash(),
+                approver: bob,
+            },
+            balance_changed(shared, 100, 90),
+            balance_changed(carol, 100, 110),
+        ]
+    );
+
+    // Failed transactions emit no events.
+    let tx = Transfer::sign(&alice, &bob, 1000, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    let receipt = api.get_transaction_receipt(tx.hash());
+    match receipt.status {
+        TransactionStatus::Error { code, .. } => assert_eq!(code, 3),
+        status => panic!("Unexpected status {:?}", status),
+    }
+    assert!(receipt.events.is_empty());
+}
+
+/// Check that the event log is queried by type, wallet and height range page by page.
+#[test]
+fn test_event_index() {
+    let (mut testkit, api) = create_testkit();
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let query = EventsQuery {
+        event_type: None,
+        wallet: None,
+        from_height: None,
+        to_height: None,
+        from: None,
+        limit: None,
+    };
+    let page = api.get_events(&query);
+    assert_eq!(page.total, 4);
+    assert_eq!(page.events.len(), 4);
+    assert!(page.next.is_none());
+    let positions = page.events.iter().map(|event| event.position).collect::<Vec<_>>();
+    assert_eq!(positions, vec![0, 1, 2, 3]);
+
+    let page = api.get_events(&EventsQuery {
+        wallet: Some(bob),
+        ..query
+    });
+    let heights = page.events.iter().map(|event| event.height).collect::<Vec<_>>();
+    assert_eq!(heights, vec![Height(1), Height(2)]);
+    assert_eq!(
+        page.events[1].event,
+        ExecutionEvent::BalanceChanged {
+            wallet: bob,
+            asset: String::new(),
+            previous_balance: 100,
+            balance: 110,
+        }
+    );
+
+    let page = api.get_events(&EventsQuery {
+        from_height: Some(2),
+        ..query
+    });
+    assert!(page.events.iter().all(|event| event.tx_hash == tx.hash()));
+    assert_eq!(page.events.len(), 2);
+    let page = api.get_events(&EventsQuery {
+        to_height: Some(1),
+        ..query
+    });
+    assert_eq!(page.events.len(), 2);
+    let page = api.get_events(&EventsQuery {
+        event_type: Some(EventType::TransferApproved),
+        ..query
+    });
+    assert!(page.events.is_empty());
+
+    let page = api.get_events(&EventsQuery {
+        limit: Some(3),
+        ..query
+    });
+    assert_eq!(page.events.len(), 3);
+    assert_eq!(page.next, Some(3));
+    let page = api.get_events(&EventsQuery {
+        from: page.next,
+        ..query
+    });
+    assert_eq!(page.events.len(), 1);
+    assert_eq!(page.events[0].position, 3);
+}
+
+/// Check that a spender can initiate transfers from a wallet but can't manage its roles.
+#[test]
+fn test_wallet_roles() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 1);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+    let shared = tx_shared.author();
+
+    // Spender role is granted by one of the owners of the multisign wallet.
+    let tx_grant_shared = GrantRole::sign(&alice, &shared, &carol, 2, &key_alice);
+    let tx_grant_bob = GrantRole::sign(&bob, &bob, &carol, 2, &key_bob);
+    api.transfer(&tx_grant_shared);
+    api.transfer(&tx_grant_bob);
+    testkit.create_block();
+    api.assert_tx_status(tx_grant_shared.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_grant_bob.hash(), &json!({ "type": "success" }));
+
+    let tx = DelegatedTransfer::sign(&carol, &bob, &alice, 10, 0, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 90);
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 110);
+
+    // Transfer initiated by the spender waits for an approval of the owners.
+    let tx = TransferMultisign::sign(&carol, &shared, &alice, 10, 0, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet_info(shared).pending_txs, vec![tx.hash()]);
+
+    let accept = AcceptMultisign::sign(&bob, &tx.hash(), 0, &key_bob);
+    api.transfer(&accept);
+    testkit.create_block();
+    api.assert_tx_status(accept.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(shared).unwrap().balance, 90);
+
+    let tx = GrantRole::sign(&carol, &shared, &carol, 3, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({
+            "type": "error",
+            "code": 55,
+            "description": "Not allowed to manage roles of the wallet"
+        }),
+    );
+}
+
+/// Check that a delegated spender is limited by the cumulative cap and the expiry.
+#[test]
+fn test_delegate() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx = Delegate::sign(&alice, &carol, 30, 6, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = DelegatedTransfer::sign(&carol, &alice, &bob, 20, 0, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = DelegatedTransfer::sign(&carol, &alice, &bob, 20, 1, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 57, "description": "Delegation cap exceeded" }),
+    );
+
+    // The delegation expires starting from block 6.
+    testkit.create_blocks_until(Height(5));
+    let tx = DelegatedTransfer::sign(&carol, &alice, &bob, 10, 2, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 58, "description": "Delegation expired" }),
+    );
+
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 80);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 120);
+}
+
+/// Check that each accepted multisign transfer moves exactly its own amount.
+#[test]
+fn test_accept_multisign_with_several_pending_transfers() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let first = TransferMultisign::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &tx_bob.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    let second = TransferMultisign::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &tx_bob.author(),
+        30, // transfer amount
+        1,  // seed
+        &key_alice,
+    );
+    api.transfer(&first);
+    api.transfer(&second);
+    testkit.create_block();
+
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    assert_eq!(wallet.pending_balance, 60);
+
+    let accept = AcceptMultisign::sign(
+        &tx_bob.author(),
+        &second.hash(),
+        0, // seed
+        &key_bob,
+    );
+    api.transfer(&accept);
+    testkit.create_block();
+    api.assert_tx_status(accept.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.balance, 70);
+    assert_eq!(wallet.pending_balance, 60);
+    assert_eq!(api.get_wallet_info(tx_shared.author()).pending_txs, vec![first.hash()]);
+    let wallet = api.get_wallet(tx_bob.author()).unwrap();
+    assert_eq!(wallet.balance, 130);
+}
+
+/// Check that the initiator can cancel a pending multisign transfer.
+#[test]
+fn test_cancel_multisign() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let tx = TransferMultisign::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &tx_bob.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let cancel = CancelMultisign::sign(
+        &tx_alice.author(),
+        &tx.hash(),
+        0, // seed
+        &key_alice,
+    );
+    api.transfer(&cancel);
+    testkit.create_block();
+    api.assert_tx_status(cancel.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.balance, 100);
+    assert_eq!(wallet.pending_balance, 100);
+    assert!(api.get_wallet_info(tx_shared.author()).pending_txs.is_empty());
+}
+
+/// Check that owners and quorum of the multisign wallet change only after the quorum approval.
+#[test]
+fn test_change_approvers() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let new_owners = vec![tx_alice.author(), tx_bob.author(), tx_carol.author()];
+    let change = ChangeApprovers::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &new_owners,
+        1, // quorum
+        0, // seed
+        &key_alice,
+    );
+    api.transfer(&change);
+    testkit.create_block();
+    api.assert_tx_status(change.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.history_len, 1);
+
+    let accept = AcceptMultisign::sign(
+        &tx_bob.author(),
+        &change.hash(),
+        0, // seed
+        &key_bob,
+    );
+    api.transfer(&accept);
+    testkit.create_block();
+    api.assert_tx_status(accept.hash(), &json!({ "type": "success" }));
+
+    // The change is recorded in the wallet history.
+    let wallet = api.get_wallet(tx_shared.author()).unwrap();
+    assert_eq!(wallet.history_len, 2);
+
+    // A single approval is now enough to execute a transfer.
+    let tx = TransferMultisign::sign(
+        &tx_alice.author(),
+        &tx_shared.author(),
+        &tx_carol.author(),
+        10, // transfer amount
+        0,  // seed
+        &key_alice,
+    );
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let wallet = api.get_wallet(tx_carol.author()).unwrap();
+    assert_eq!(wallet.balance, 110);
+}
+
+/// Check that a frozen wallet cannot send transfers until it is unfrozen by an administrator.
+#[test]
+fn test_freeze_wallet() {
+    let (admin_pk, admin_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        admins: vec![admin_pk],
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let freeze = FreezeWallet::sign(&admin_pk, &tx_alice.author(), 0, &admin_sk);
+    api.transfer(&freeze);
+    testkit.create_block();
+    api.assert_tx_status(freeze.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 11, "description": "Wallet is frozen" }),
+    );
+
+    let unfreeze = UnfreezeWallet::sign(&admin_pk, &tx_alice.author(), 0, &admin_sk);
+    api.transfer(&unfreeze);
+    testkit.create_block();
+    api.assert_tx_status(unfreeze.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&tx_alice.author(), &tx_bob.author(), 10, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+}
+
+/// Check that only administrators can freeze wallets.
+#[test]
+fn test_freeze_wallet_by_non_admin() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let freeze = FreezeWallet::sign(&tx_alice.author(), &tx_bob.author(), 0, &key_alice);
+    api.transfer(&freeze);
+    testkit.create_block();
+    api.assert_tx_status(
+        freeze.hash(),
+        &json!({ "type": "error", "code": 12, "description": "Not an administrator" }),
+    );
+}
+
+/// Check that transfers to and from a blacklisted key fail until it is removed from the list.
+#[test]
+fn test_blacklist() {
+    let (admin_pk, admin_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        admins: vec![admin_pk],
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx = BlacklistKey::sign(&admin_pk, &bob, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_blacklist(), vec![bob]);
+
+    let error = json!({ "type": "error", "code": 49, "description": "Key is blacklisted" });
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &error);
+
+    let tx = Transfer::sign(&bob, &alice, 10, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &error);
+
+    let tx = UnblacklistKey::sign(&admin_pk, &bob, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert!(api.get_blacklist().is_empty());
+
+    let tx = Transfer::sign(&alice, &bob, 10, 1, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+}
+
+/// Check that payouts skip blacklisted wallets and the payments from them never credit
+/// the receiver.
+#[test]
+fn test_blacklist_payouts() {
+    let (admin_pk, admin_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        admins: vec![admin_pk],
+        minters: vec![admin_pk],
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx = OpenStream::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = BlacklistKey::sign(&admin_pk, &alice, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    testkit.create_block();
+
+    // The stream is closed without paying Bob anything the blacklisted Alice didn't pay.
+    let (alice_balance, bob_balance) = (
+        api.get_wallet(alice).unwrap().balance,
+        api.get_wallet(bob).unwrap().balance,
+    );
+    assert_eq!(alice_balance + bob_balance, 200);
+    testkit.create_block();
+    assert_eq!(api.get_wallet(alice).unwrap().balance, alice_balance);
+
+    let tx = DistributeDividend::sign(&carol, 10, 0, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, alice_balance);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, bob_balance + 10);
+
+    // Listed blacklisted recipients are skipped as well.
+    let tx = Airdrop::sign(&admin_pk, &[alice, bob], 5, 0, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, alice_balance);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, bob_balance + 15);
+
+    let tx = Airdrop::sign(&admin_pk, &[alice], 5, 1, &admin_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 44, "description": "Airdrop has no recipients" }),
+    );
+}
+
+/// Check that a wallet with a whitelist accepts transfers only from the listed senders.
+#[test]
+fn test_whitelist() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx = SetWhitelist::sign(&bob, &[alice], 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx_alice_to_bob = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    let tx_carol_to_bob = Transfer::sign(&carol, &bob, 10, 0, &key_carol);
+    api.transfer(&tx_alice_to_bob);
+    api.transfer(&tx_carol_to_bob);
+    testkit.create_block();
+    api.assert_tx_status(tx_alice_to_bob.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_carol_to_bob.hash(),
+        &json!({
+            "type": "error",
+            "code": 51,
+            "description": "Sender is not whitelisted by the receiver"
+        }),
+    );
+
+    let tx = SetWhitelist::sign(&bob, &[], 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = Transfer::sign(&carol, &bob, 10, 1, &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 120);
+}
+
+/// Check that transfers above the KYC threshold require both parties to be verified.
+#[test]
+fn test_kyc_threshold() {
+    let (officer_pk, officer_sk) = crypto::gen_keypair();
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        compliance_officers: vec![officer_pk],
+        kyc_threshold: 20,
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    testkit.create_block();
+
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_small = Transfer::sign(&alice, &bob, 20, 0, &key_alice);
+    let tx_large = Transfer::sign(&alice, &bob, 30, 0, &key_alice);
+    api.transfer(&tx_small);
+    api.transfer(&tx_large);
+    testkit.create_block();
+    api.assert_tx_status(tx_small.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_large.hash(),
+        &json!({
+            "type": "error",
+            "code": 53,
+            "description": "Both parties must pass KYC for the transfer"
+        }),
+    );
+
+    let tx = SetKycStatus::sign(&alice, &alice, true, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 52, "description": "Not a compliance officer" }),
+    );
+
+    let tx = SetKycStatus::sign(&officer_pk, &alice, true, 0, &officer_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    assert!(api.get_wallet(alice).unwrap().kyc_verified);
+
+    // The escrow can't be released to the receiver until it is verified as well.
+    let tx_escrow = CreateEscrow::sign(&alice, &bob, &officer_pk, 30, 0, &key_alice);
+    api.transfer(&tx_escrow);
+    testkit.create_block();
+    api.assert_tx_status(tx_escrow.hash(), &json!({ "type": "success" }));
+
+    let tx = ReleaseEscrow::sign(&officer_pk, &tx_escrow.hash(), &officer_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({
+            "type": "error",
+            "code": 53,
+            "description": "Both parties must pass KYC for the transfer"
+        }),
+    );
+
+    let tx = SetKycStatus::sign(&officer_pk, &bob, true, 0, &officer_sk);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx = RefundEscrow::sign(&officer_pk, &tx_escrow.hash(), &officer_sk);
+    let tx_transfer = Transfer::sign(&alice, &bob, 30, 1, &key_alice);
+    api.transfer(&tx);
+    api.transfer(&tx_transfer);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(tx_transfer.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 50);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 150);
+}
+
+/// Check that a rotated wallet keeps its history and reserved funds under the new key.
+#[test]
+fn test_rotate_key() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+    let (new_alice, new_key_alice) = crypto::gen_keypair();
+
+    let tx_escrow = CreateEscrow::sign(&alice, &bob, &carol, 30, 0, &key_alice);
+    api.transfer(&tx_escrow);
+    testkit.create_block();
+
+    let tx = RotateKey::sign(&alice, &new_alice, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    api.assert_no_wallet(alice);
+    let wallet = api.get_wallet(new_alice).unwrap();
+    assert_eq!(wallet.balance, 100);
+    assert_eq!(wallet.pending_balance, 70);
+    assert_eq!(wallet.history_len, 2);
+
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 1, "description": "Sender doesn't exist" }),
+    );
+
+    let tx = RefundEscrow::sign(&carol, &tx_escrow.hash(), &key_carol);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(new_alice).unwrap().pending_balance, 100);
+
+    let tx = Transfer::sign(&new_alice, &bob, 10, 0, &new_key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(new_alice).unwrap().balance, 90);
+
+    let tx = CreateWallet::sign(ALICE_NAME, &alice, &key_alice);
+    let tx_multisig = CreateMultisigWallet::sign(SHARED_NAME, &[bob, carol], 2, &alice, &key_alice);
+    api.transfer(&tx);
+    api.transfer(&tx_multisig);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 0, "description": "Wallet already exists" }),
+    );
+    api.assert_tx_status(
+        tx_multisig.hash(),
+        &json!({ "type": "error", "code": 0, "description": "Wallet already exists" }),
+    );
+}
+
+/// Check that key rotation moves tokens, velocity history and multisign ownership
+/// of the wallet to the new key.
+#[test]
+fn test_rotate_key_moves_wallet_state() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        velocity_limit: 50,
+        velocity_window: 10,
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, _) = api.create_wallet(CAROL_NAME);
+    let owners = vec![tx_alice.author(), tx_bob.author()];
+    let (tx_shared, _) = api.create_multisig_wallet(SHARED_NAME, &owners, 2);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+    let shared = tx_shared.author();
+    let (new_alice, new_key_alice) = crypto::gen_keypair();
+
+    let tx_mint = MintNft::sign(&alice, "Painting", 0, &key_alice);
+    let tx_transfer = Transfer::sign(&alice, &bob, 40, 0, &key_alice);
+    api.transfer(&tx_mint);
+    api.transfer(&tx_transfer);
+    testkit.create_block();
+    api.assert_tx_status(tx_transfer.hash(), &json!({ "type": "success" }));
+    let token = tx_mint.hash();
+
+    let tx = RotateKey::sign(&alice, &new_alice, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    assert!(api.get_nfts(alice).is_empty());
+    assert_eq!(api.get_nfts(new_alice), vec![token]);
+
+    // The transfer made before the rotation still counts towards the velocity limit.
+    let tx = Transfer::sign(&new_alice, &bob, 20, 0, &new_key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 48, "description": "Velocity limit exceeded" }),
+    );
+
+    let tx = TransferNft::sign(&new_alice, &token, &carol, &new_key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_nfts(carol), vec![token]);
+
+    // The old key is no longer an owner of the multisign wallet, while the new one is.
+    let tx_old = TransferMultisign::sign(&alice, &shared, &carol, 10, 0, &key_alice);
+    let tx_new = TransferMultisign::sign(&new_alice, &shared, &carol, 10, 1, &new_key_alice);
+    api.transfer(&tx_old);
+    api.transfer(&tx_new);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_old.hash(),
+        &json!({ "type": "error", "code": 1, "description": "Sender doesn't exist" }),
+    );
+    api.assert_tx_status(tx_new.hash(), &json!({ "type": "success" }));
+
+    let accept = AcceptMultisign::sign(&bob, &tx_new.hash(), 0, &key_bob);
+    api.transfer(&accept);
+    testkit.create_block();
+    api.assert_tx_status(accept.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(carol).unwrap().balance, 110);
+}
+
+/// Check that guardians can recover a wallet unless the owner cancels the recovery.
+#[test]
+fn test_recover_wallet() {
+    let (mut testkit, api) = create_testkit_with_config(Config {
+        recovery_challenge_period: 2,
+        ..Config::default()
+    });
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+    let (new_alice, new_key_alice) = crypto::gen_keypair();
+
+    let tx = SetGuardians::sign(&alice, &[bob, carol], 3, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 4, "description": "Invalid quorum" }),
+    );
+
+    let tx = SetGuardians::sign(&alice, &[bob, carol], 2, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = RecoverWallet::sign(&alice, &alice, &new_alice, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 61, "description": "Not a guardian of the wallet" }),
+    );
+
+    let tx_bob_approve = RecoverWallet::sign(&bob, &alice, &new_alice, 0, &key_bob);
+    let tx_carol_approve = RecoverWallet::sign(&carol, &alice, &new_alice, 0, &key_carol);
+    api.transfer(&tx_bob_approve);
+    api.transfer(&tx_carol_approve);
+    testkit.create_block();
+    api.assert_tx_status(tx_carol_approve.hash(), &json!({ "type": "success" }));
+
+    let tx = CancelRecovery::sign(&alice, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    testkit.create_blocks_until(Height(8));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 100);
+
+    let tx = RecoverWallet::sign(&bob, &alice, &new_alice, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx_bob_approve = RecoverWallet::sign(&bob, &alice, &new_alice, 2, &key_bob);
+    let tx_carol_approve = RecoverWallet::sign(&carol, &alice, &new_alice, 1, &key_carol);
+    api.transfer(&tx_bob_approve);
+    api.transfer(&tx_carol_approve);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx_bob_approve.hash(),
+        &json!({
+            "type": "error",
+            "code": 5,
+            "description": "Transfer is already approved by this approver"
+        }),
+    );
+    api.assert_tx_status(tx_carol_approve.hash(), &json!({ "type": "success" }));
+    testkit.create_blocks_until(Height(13));
+
+    api.assert_no_wallet(alice);
+    assert_eq!(api.get_wallet(new_alice).unwrap().balance, 100);
+
+    let tx = Transfer::sign(&new_alice, &bob, 10, 0, &new_key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+}
+
+/// Check that the beneficiary can claim the balance of the wallet only after its inactivity period.
+#[test]
+fn test_claim_inheritance() {
+    let (mut testkit, api) = create_testkit();
+
+    let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
+    let (tx_carol, key_carol) = api.create_wallet(CAROL_NAME);
+    testkit.create_block();
+
+    let (alice, bob, carol) = (tx_alice.author(), tx_bob.author(), tx_carol.author());
+
+    let tx = SetBeneficiary::sign(&alice, &bob, 5, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(5));
+    let tx = Transfer::sign(&alice, &carol, 10, 0, &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+
+    let tx = ClaimInheritance::sign(&carol, &alice, 0, &key_carol);
+    api.transfer(&tx);
+    testkit.create_blocks_until(Height(8));
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 64, "description": "Not a beneficiary of the wallet" }),
+    );
+
+    let tx = ClaimInheritance::sign(&bob, &alice, 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 65, "description": "Wallet is still active" }),
+    );
+
+    // Any transaction signed by the owner restarts the period, not only a transfer.
+    testkit.create_blocks_until(Height(10));
+    let tx = SetMetadata::sign(&alice, "contact", "alice@example.com", &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    testkit.create_blocks_until(Height(12));
+    let tx = ClaimInheritance::sign(&bob, &alice, 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 65, "description": "Wallet is still active" }),
+    );
+
+    // The claim is a regular payment and respects the whitelist of the beneficiary.
+    testkit.create_blocks_until(Height(16));
+    let tx = SetWhitelist::sign(&bob, &[carol], 0, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = ClaimInheritance::sign(&bob, &alice, 2, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({
+            "type": "error",
+            "code": 51,
+            "description": "Sender is not whitelisted by the receiver"
+        }),
+    );
+
+    let tx = SetWhitelist::sign(&bob, &[], 1, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    let tx = ClaimInheritance::sign(&bob, &alice, 3, &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 0);
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 190);
+}
+
+/// Check that a session key transfers small amounts from the wallet until it expires.
 #[test]
-fn test_transfer() {
-    // Create 2 wallets.
+fn test_session_transfer() {
     let (mut testkit, api) = create_testkit();
+
     let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
     let (tx_bob, _) = api.create_wallet(BOB_NAME);
     testkit.create_block();
-    api.assert_tx_status(tx_alice.hash(), &json!({ "type": "success" }));
-    api.assert_tx_status(tx_bob.hash(), &json!({ "type": "success" }));
 
-    // Check that the initial Alice's and Bob's balances persisted by the service.
-    let wallet = api.get_wallet(tx_alice.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
-    let wallet = api.get_wallet(tx_bob.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let (session, session_key) = crypto::gen_keypair();
 
-    // Transfer funds by invoking the corresponding API method.
-    let tx = Transfer::sign(
-        &tx_alice.author(),
-        &tx_bob.author(),
-        10, // transferred amount
-        0,  // seed
-        &key_alice,
-    );
+    let tx = AddSessionKey::sign(&alice, &session, 10, 5, &key_alice);
     api.transfer(&tx);
     testkit.create_block();
     api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
 
-    // After the transfer transaction is included into a block, we may check new wallet
-    // balances.
-    let wallet = api.get_wallet(tx_alice.author()).unwrap();
-    assert_eq!(wallet.balance, 90);
-    let wallet = api.get_wallet(tx_bob.author()).unwrap();
-    assert_eq!(wallet.balance, 110);
+    let tx_small = SessionTransfer::sign(&session, &bob, 10, 0, &session_key);
+    let tx_large = SessionTransfer::sign(&session, &bob, 11, 0, &session_key);
+    api.transfer(&tx_small);
+    api.transfer(&tx_large);
+    testkit.create_block();
+    api.assert_tx_status(tx_small.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_large.hash(),
+        &json!({
+            "type": "error",
+            "code": 68,
+            "description": "Amount exceeds the session key limit"
+        }),
+    );
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 90);
+
+    testkit.create_blocks_until(Height(5));
+    let tx = SessionTransfer::sign(&session, &bob, 10, 1, &session_key);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 66, "description": "Session key not found" }),
+    );
+    assert_eq!(api.get_wallet(bob).unwrap().balance, 110);
 }
 
-/// Check that a transfer from a non-existing wallet fails as expected.
+/// Check that the API aggregates the wallets watched by a key.
 #[test]
-fn test_transfer_from_nonexisting_wallet() {
+fn test_watched_wallets() {
     let (mut testkit, api) = create_testkit();
 
     let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
     let (tx_bob, _) = api.create_wallet(BOB_NAME);
-    // Do not commit Alice's transaction, so Alice's wallet does not exist
-    // when a transfer occurs.
-    testkit.create_block_with_tx_hashes(&[tx_bob.hash()]);
+    testkit.create_block();
 
-    api.assert_no_wallet(tx_alice.author());
-    let wallet = api.get_wallet(tx_bob.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+    let (watcher, watcher_key) = crypto::gen_keypair();
+    let (unknown, _) = crypto::gen_keypair();
 
-    let tx = Transfer::sign(
-        &tx_alice.author(),
-        &tx_bob.author(),
-        10, // transfer amount
-        0,  // seed
-        &key_alice,
-    );
+    let tx = Transfer::sign(&alice, &bob, 10, 0, &key_alice);
     api.transfer(&tx);
-    testkit.create_block_with_tx_hashes(&[tx.hash()]);
-    api.assert_tx_status(
-        tx.hash(),
-        &json!({ "type": "error", "code": 1, "description": "Sender doesn't exist" }),
-    );
+    testkit.create_block();
 
-    // Check that Bob's balance doesn't change.
-    let wallet = api.get_wallet(tx_bob.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
+    let tx = SetWatchList::sign(&watcher, &[alice, bob, unknown], 0, &watcher_key);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let info = api.get_watched_wallets(watcher);
+    assert_eq!(info.total_balance, 200);
+    assert_eq!(info.wallets.len(), 3);
+    assert_eq!(info.wallets[0].wallet.as_ref().unwrap().balance, 90);
+    assert_eq!(info.wallets[1].history.len(), 2);
+    assert!(info.wallets[2].wallet.is_none());
+
+    let tx = SetWatchList::sign(&watcher, &[], 1, &watcher_key);
+    api.transfer(&tx);
+    testkit.create_block();
+    assert!(api.get_watched_wallets(watcher).wallets.is_empty());
 }
 
-/// Check that a transfer to a non-existing wallet fails as expected.
+/// Check that wallets can set and delete metadata entries within the size limits.
 #[test]
-fn test_transfer_to_nonexisting_wallet() {
+fn test_wallet_metadata() {
     let (mut testkit, api) = create_testkit();
 
     let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
-    let (tx_bob, _) = api.create_wallet(BOB_NAME);
-    // Do not commit Bob's transaction, so Bob's wallet does not exist
-    // when a transfer occurs.
-    testkit.create_block_with_tx_hashes(&[tx_alice.hash()]);
+    testkit.create_block();
 
-    let wallet = api.get_wallet(tx_alice.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
-    api.assert_no_wallet(tx_bob.author());
+    let alice = tx_alice.author();
 
-    let tx = Transfer::sign(
-        &tx_alice.author(),
-        &tx_bob.author(),
-        10, // transfer amount
-        0,  // seed
-        &key_alice,
+    let tx_avatar = SetMetadata::sign(&alice, "avatar", "a1b2c3", &key_alice);
+    let tx_contact = SetMetadata::sign(&alice, "contact", "alice@example.com", &key_alice);
+    let tx_large = SetMetadata::sign(&alice, "bio", &"x".repeat(1025), &key_alice);
+    api.transfer(&tx_avatar);
+    api.transfer(&tx_contact);
+    api.transfer(&tx_large);
+    testkit.create_block();
+    api.assert_tx_status(tx_contact.hash(), &json!({ "type": "success" }));
+    api.assert_tx_status(
+        tx_large.hash(),
+        &json!({ "type": "error", "code": 70, "description": "Metadata is too large" }),
     );
+
+    let metadata = api.get_wallet_metadata(alice);
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(metadata["avatar"], "a1b2c3");
+
+    let tx = DeleteMetadata::sign(&alice, "avatar", &key_alice);
     api.transfer(&tx);
-    testkit.create_block_with_tx_hashes(&[tx.hash()]);
+    testkit.create_block();
+    api.assert_tx_status(tx.hash(), &json!({ "type": "success" }));
+
+    let tx = DeleteMetadata::sign(&alice, "bio", &key_alice);
+    api.transfer(&tx);
+    testkit.create_block();
     api.assert_tx_status(
         tx.hash(),
-        &json!({ "type": "error", "code": 2, "description": "Receiver doesn't exist" }),
+        &json!({ "type": "error", "code": 71, "description": "Metadata entry not found" }),
     );
-
-    // Check that Alice's balance doesn't change.
-    let wallet = api.get_wallet(tx_alice.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
+    assert!(!api.get_wallet_metadata(alice).contains_key("avatar"));
 }
 
-/// Check that an overcharge does not lead to changes in sender's and receiver's balances.
+/// Check that the payer pays the invoice once and the payment is reconciled by the invoice ID.
 #[test]
-fn test_transfer_overcharge() {
+fn test_pay_invoice() {
     let (mut testkit, api) = create_testkit();
 
     let (tx_alice, key_alice) = api.create_wallet(ALICE_NAME);
-    let (tx_bob, _) = api.create_wallet(BOB_NAME);
+    let (tx_bob, key_bob) = api.create_wallet(BOB_NAME);
     testkit.create_block();
 
-    // Transfer funds. The transfer amount (110) is more than Alice has (100).
-    let tx = Transfer::sign(
-        &tx_alice.author(),
-        &tx_bob.author(),
-        110, // transfer amount
-        0,   // seed
-        &key_alice,
-    );
+    let (alice, bob) = (tx_alice.author(), tx_bob.author());
+
+    let tx_invoice = CreateInvoice::sign(&alice, 30, &bob, "Order #1", 0, &key_alice);
+    api.transfer(&tx_invoice);
+    testkit.create_block();
+    api.assert_tx_status(tx_invoice.hash(), &json!({ "type": "success" }));
+    assert!(!api.get_invoice(tx_invoice.hash()).is_paid());
+
+    let tx = PayInvoice::sign(&alice, &tx_invoice.hash(), &key_alice);
     api.transfer(&tx);
     testkit.create_block();
     api.assert_tx_status(
         tx.hash(),
-        &json!({ "type": "error", "code": 3, "description": "Insufficient currency amount" }),
+        &json!({ "type": "error", "code": 73, "description": "Not a payer of the invoice" }),
     );
 
-    let wallet = api.get_wallet(tx_alice.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
-    let wallet = api.get_wallet(tx_bob.author()).unwrap();
-    assert_eq!(wallet.balance, 100);
+    let tx_pay = PayInvoice::sign(&bob, &tx_invoice.hash(), &key_bob);
+    api.transfer(&tx_pay);
+    testkit.create_block();
+    api.assert_tx_status(tx_pay.hash(), &json!({ "type": "success" }));
+    assert_eq!(api.get_wallet(alice).unwrap().balance, 130);
+    assert_eq!(api.get_invoice(tx_invoice.hash()).payment, tx_pay.hash());
+
+    let tx_invoice_2 = CreateInvoice::sign(&alice, 30, &bob, "Order #1", 1, &key_alice);
+    let tx = PayInvoice::sign(&bob, &tx_invoice_2.hash(), &key_bob);
+    api.transfer(&tx);
+    testkit.create_block();
+    api.assert_tx_status(
+        tx.hash(),
+        &json!({ "type": "error", "code": 72, "description": "Invoice not found" }),
+    );
 }
 
 #[test]
@@ -219,8 +4858,19 @@ impl CryptocurrencyApi {
     /// to the pool of unconfirmed transactions.
     fn create_wallet(&self, name: &str) -> (Signed<RawTransaction>, SecretKey) {
         let (pubkey, key) = crypto::gen_keypair();
+        let tx = self.create_wallet_with_keypair(name, pubkey, &key);
+        (tx, key)
+    }
+
+    /// Sends a wallet creation transaction signed with the given key pair.
+    fn create_wallet_with_keypair(
+        &self,
+        name: &str,
+        pubkey: PublicKey,
+        key: &SecretKey,
+    ) -> Signed<RawTransaction> {
         // Create a pre-signed transaction
-        let tx = CreateWallet::sign(name, &pubkey, &key);
+        let tx = CreateWallet::sign(name, &pubkey, key);
 
         let data = messages::to_hex_string(&tx);
         let tx_info: TransactionResponse = self
@@ -230,13 +4880,405 @@ impl CryptocurrencyApi {
             .post("v1/transactions")
             .unwrap();
         assert_eq!(tx_info.tx_hash, tx.hash());
+        tx
+    }
+
+    /// Generates a multisign wallet creation transaction with a random key pair, sends it
+    /// over HTTP, and checks the synchronous result.
+    fn create_multisig_wallet(
+        &self,
+        name: &str,
+        owners: &[PublicKey],
+        quorum: u32,
+    ) -> (Signed<RawTransaction>, SecretKey) {
+        let (pubkey, key) = crypto::gen_keypair();
+        let tx = CreateMultisigWallet::sign(name, owners, quorum, &pubkey, &key);
+        self.transfer(&tx);
         (tx, key)
     }
 
+    fn get_wallet_info(&self, pub_key: PublicKey) -> WalletInfo {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&WalletQuery { pub_key })
+            .get("v1/wallets/info")
+            .unwrap()
+    }
+
+    fn get_nfts(&self, pub_key: PublicKey) -> Vec<Hash> {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&WalletQuery { pub_key })
+            .get("v1/wallets/nfts")
+            .unwrap()
+    }
+
+    fn submit_transaction(
+        &self,
+        tx_body: String,
+        encoding: TransactionEncoding,
+    ) -> Option<SubmitTransactionResponse> {
+        self.post_transaction(&SubmitTransactionQuery {
+            tx_body,
+            encoding: Some(encoding),
+            wait: None,
+            timeout: None,
+        })
+    }
+
+    fn submit_transaction_and_wait(
+        &self,
+        tx: &Signed<RawTransaction>,
+        timeout: u64,
+    ) -> SubmitTransactionResponse {
+        self.post_transaction(&SubmitTransactionQuery {
+            tx_body: messages::to_hex_string(tx),
+            encoding: None,
+            wait: Some(true),
+            timeout: Some(timeout),
+        })
+        .unwrap()
+    }
+
+    fn validate_transaction(&self, tx: &Signed<RawTransaction>) -> ValidateTransactionResponse {
+        let query = ValidateTransactionQuery {
+            tx_body: messages::to_hex_string(tx),
+            encoding: None,
+        };
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&query)
+            .post("v1/transactions/validate")
+            .unwrap()
+    }
+
+    fn post_transaction(
+        &self,
+        query: &SubmitTransactionQuery,
+    ) -> Option<SubmitTransactionResponse> {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(query)
+            .post("v1/transactions")
+            .ok()
+    }
+
+    fn get_transaction_feed(&self, since: u64, timeout: u64) -> TransactionFeed {
+        let query = TransactionFeedQuery {
+            since: Some(since),
+            timeout: Some(timeout),
+        };
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&query)
+            .get("v1/transactions/feed")
+            .unwrap()
+    }
+
+    fn get_response(&self, endpoint: &str, headers: &[(&str, &str)]) -> reqwest::Response {
+        self.send_request(reqwest::Method::GET, endpoint, headers)
+    }
+
+    fn send_request(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+    ) -> reqwest::Response {
+        let url = self.inner.public_url(&format!(
+            "api/services/multisign_cryptocurrency/{}",
+            endpoint
+        ));
+        let mut request = reqwest::Client::new().request(method, &url);
+        for &(name, value) in headers {
+            request = request.header(name, value);
+        }
+        request.send().unwrap()
+    }
+
+    fn get_transaction_status(&self, hash: Hash) -> TransactionStatus {
+        self.get_transaction_receipt(hash).status
+    }
+
+    fn get_events(&self, query: &EventsQuery) -> EventsPage {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(query)
+            .get("v1/events")
+            .unwrap()
+    }
+
+    fn get_transaction_receipt(&self, hash: Hash) -> TransactionReceipt {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&TransactionStatusQuery { hash })
+            .get("v1/transactions/status")
+            .unwrap()
+    }
+
+    fn get_supply(&self) -> u64 {
+        self.get_supply_info().total_supply
+    }
+
+    fn get_supply_info(&self) -> SupplyInfo {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/supply")
+            .unwrap()
+    }
+
+    fn get_stats(&self) -> ServiceStats {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/stats")
+            .unwrap()
+    }
+
+    fn get_blacklist(&self) -> Vec<PublicKey> {
+        let blacklist_info: BlacklistInfo = self
+            .inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/blacklist")
+            .unwrap();
+        blacklist_info.blacklist
+    }
+
+    fn get_watched_wallets(&self, pub_key: PublicKey) -> WatchedWalletsInfo {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&WalletQuery { pub_key })
+            .get("v1/wallets/watched")
+            .unwrap()
+    }
+
+    fn get_wallet_metadata(&self, pub_key: PublicKey) -> BTreeMap<String, String> {
+        let wallet_info = self
+            .inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&WalletQuery { pub_key })
+            .get::<WalletInfo>("v1/wallets/info")
+            .unwrap();
+        wallet_info.metadata
+    }
+
+    fn get_wallet_history(
+        &self,
+        pub_key: PublicKey,
+        from: u64,
+        limit: u64,
+        newest_first: bool,
+    ) -> WalletHistoryPage {
+        self.query_wallet_history(&WalletHistoryQuery {
+            pub_key,
+            from: Some(from),
+            cursor: None,
+            limit: Some(limit),
+            newest_first: Some(newest_first),
+            tx_type: None,
+            counterparty: None,
+            from_height: None,
+            to_height: None,
+        })
+    }
+
+    fn query_wallet_history(&self, query: &WalletHistoryQuery) -> WalletHistoryPage {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(query)
+            .get("v1/wallets/history")
+            .unwrap()
+    }
+
+    fn export_wallet_history(&self, pub_key: PublicKey, format: ExportFormat) -> HistoryExport {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&HistoryExportQuery {
+                pub_key,
+                format: Some(format),
+            })
+            .get("v1/wallets/history/export")
+            .unwrap()
+    }
+
+    fn subscribe_wallet(&self, pub_key: PublicKey, since: u64, timeout: u64) -> Vec<WalletEvent> {
+        let query = WalletSubscribeQuery {
+            pub_key,
+            since: Some(since),
+            timeout: Some(timeout),
+        };
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&query)
+            .get("v1/wallets/subscribe")
+            .unwrap()
+    }
+
+    /// Opens a WebSocket connection to the wallet subscription, returning the stream
+    /// positioned after the handshake response.
+    fn open_wallet_socket(&self, pub_key: PublicKey, since: u64) -> TcpStream {
+        let url = self.inner.public_url(&format!(
+            "api/services/multisign_cryptocurrency/v1/wallets/subscribe/ws?pub_key={}&since={}",
+            hex::encode(pub_key.as_ref()),
+            since
+        ));
+        let url = url.trim_start_matches("http://");
+        let (host, path) = url.split_at(url.find('/').unwrap());
+
+        let mut socket = TcpStream::connect(host).unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        write!(
+            socket,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            path, host
+        )
+        .unwrap();
+
+        let mut reader = BufReader::new(socket.try_clone().unwrap());
+        let mut status = String::new();
+        reader.read_line(&mut status).unwrap();
+        assert!(status.starts_with("HTTP/1.1 101"), "{}", status);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+        }
+        socket
+    }
+
+    fn get_wallets(&self, offset: Option<u64>, limit: Option<u64>) -> WalletsPage {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&WalletsQuery { offset, limit })
+            .get("v1/wallets")
+            .unwrap()
+    }
+
+    fn search_wallets(&self, name_prefix: &str) -> Vec<Wallet> {
+        let query = WalletSearchQuery {
+            name_prefix: name_prefix.to_owned(),
+            limit: None,
+        };
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&query)
+            .get("v1/wallets/search")
+            .unwrap()
+    }
+
+    fn get_top_wallets(&self, limit: u64) -> Vec<Wallet> {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&TopWalletsQuery { limit: Some(limit) })
+            .get("v1/wallets/top")
+            .unwrap()
+    }
+
+    fn register_webhook(&self, webhook: &Webhook) -> Vec<Webhook> {
+        self.inner
+            .private(ApiKind::Service("multisign_cryptocurrency"))
+            .query(webhook)
+            .post("v1/webhooks/register")
+            .unwrap()
+    }
+
+    fn unregister_webhook(&self, webhook: &Webhook) -> Vec<Webhook> {
+        self.inner
+            .private(ApiKind::Service("multisign_cryptocurrency"))
+            .query(webhook)
+            .post("v1/webhooks/unregister")
+            .unwrap()
+    }
+
+    fn get_snapshot(&self) -> StateSnapshot {
+        self.inner
+            .private(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/snapshot")
+            .unwrap()
+    }
+
+    fn graphql(&self, query: &str) -> serde_json::Value {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&json!({ "query": query }))
+            .post("v1/graphql")
+            .unwrap()
+    }
+
+    fn get_openapi(&self) -> serde_json::Value {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/openapi")
+            .unwrap()
+    }
+
+    fn get_json(&self, endpoint: &str, query: &serde_json::Value) -> serde_json::Value {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(query)
+            .get(endpoint)
+            .unwrap()
+    }
+
+    fn get_protobuf(&self, endpoint: &str) -> Vec<u8> {
+        let mut response = self.get_response(endpoint, &[("Accept", "application/x-protobuf")]);
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_TYPE],
+            "application/x-protobuf"
+        );
+        let mut bytes = Vec::new();
+        response.copy_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn post_response(
+        &self,
+        endpoint: &str,
+        body: &serde_json::Value,
+        headers: &[(&str, &str)],
+    ) -> reqwest::Response {
+        let url = self.inner.public_url(&format!(
+            "api/services/multisign_cryptocurrency/{}",
+            endpoint
+        ));
+        let mut request = reqwest::Client::new().post(&url).json(body);
+        for &(name, value) in headers {
+            request = request.header(name, value);
+        }
+        request.send().unwrap()
+    }
+
+    fn get_rate_limit_stats(&self) -> RateLimitStats {
+        self.inner
+            .private(ApiKind::Service("multisign_cryptocurrency"))
+            .get("v1/rate_limits")
+            .unwrap()
+    }
+
+    fn request_faucet(&self, pub_key: PublicKey, amount: u64) -> Option<FaucetResponse> {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&FaucetQuery { pub_key, amount })
+            .post("v1/faucet")
+            .ok()
+    }
+
+    fn get_invoice(&self, invoice: Hash) -> Invoice {
+        self.inner
+            .public(ApiKind::Service("multisign_cryptocurrency"))
+            .query(&InvoiceQuery { invoice })
+            .get("v1/invoices")
+            .unwrap()
+    }
+
     fn get_wallet(&self, pub_key: PublicKey) -> Option<Wallet> {
         let wallet_info = self
             .inner
-            .public(ApiKind::Service("cryptocurrency"))
+            .public(ApiKind::Service("multisign_cryptocurrency"))
             .query(&WalletQuery { pub_key })
             .get::<WalletInfo>("v1/wallets/info")
             .unwrap();
@@ -266,7 +5308,7 @@ impl CryptocurrencyApi {
     fn assert_no_wallet(&self, pub_key: PublicKey) {
         let wallet_info: WalletInfo = self
             .inner
-            .public(ApiKind::Service("cryptocurrency"))
+            .public(ApiKind::Service("multisign_cryptocurrency"))
             .query(&WalletQuery { pub_key })
             .get("v1/wallets/info")
             .unwrap();
@@ -295,9 +5337,148 @@ impl CryptocurrencyApi {
 
 /// Creates a testkit together with the API wrapper defined above.
 fn create_testkit() -> (TestKit, CryptocurrencyApi) {
-    let testkit = TestKitBuilder::validator().with_service(Service).create();
+    create_testkit_with_config(Config::default())
+}
+
+/// Creates a testkit with the given service configuration together with the API wrapper.
+fn create_testkit_with_config(config: Config) -> (TestKit, CryptocurrencyApi) {
+    let testkit = TestKitBuilder::validator()
+        .with_service(Service::new(config))
+        .create();
     let api = CryptocurrencyApi {
         inner: testkit.api(),
     };
     (testkit, api)
 }
+
+/// Creates a testkit with the given node-local settings together with the API wrapper.
+fn create_testkit_with_node_config(node_config: NodeConfig) -> (TestKit, CryptocurrencyApi) {
+    let service = Service::new(Config::default()).with_node_config(node_config);
+    let testkit = TestKitBuilder::validator().with_service(service).create();
+    let api = CryptocurrencyApi {
+        inner: testkit.api(),
+    };
+    (testkit, api)
+}
+
+/// Reads an unmasked text frame sent by the server over the WebSocket connection.
+fn read_text_frame(socket: &mut TcpStream) -> String {
+    let mut header = [0; 2];
+    socket.read_exact(&mut header).unwrap();
+    assert_eq!(header[0], 0x81, "Expected a final text frame");
+    let length = match header[1] & 0x7f {
+        126 => {
+            let mut length = [0; 2];
+            socket.read_exact(&mut length).unwrap();
+            u64::from(u16::from_be_bytes(length))
+        }
+        127 => {
+            let mut length = [0; 8];
+            socket.read_exact(&mut length).unwrap();
+            u64::from_be_bytes(length)
+        }
+        length => u64::from(length),
+    };
+    let mut payload = vec![0; length as usize];
+    socket.read_exact(&mut payload).unwrap();
+    String::from_utf8(payload).unwrap()
+}
+
+/// Reads a `transactions` server-sent event, returning its id and the transaction feed.
+fn read_server_event<R: BufRead>(events: &mut R) -> (String, TransactionFeed) {
+    let (mut id, mut data) = (String::new(), String::new());
+    loop {
+        let mut line = String::new();
+        events.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with("id: ") {
+            id = line["id: ".len()..].to_owned();
+        } else if line.starts_with("data: ") {
+            data = line["data: ".len()..].to_owned();
+        } else {
+            assert_eq!(line, "event: transactions");
+        }
+    }
+    (id, serde_json::from_str(&data).unwrap())
+}
+
+/// Accepts a single webhook request on the listener, replies with `200 OK`
+/// and returns the request body.
+fn receive_webhook(listener: &TcpListener) -> Vec<u8> {
+    let mut stream = accept(listener);
+
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    let body_start = loop {
+        let len = stream.read(&mut buf).unwrap();
+        assert_ne!(len, 0, "Webhook request is incomplete");
+        request.extend_from_slice(&buf[..len]);
+        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+    let content_length: usize = headers
+        .lines()
+        .find(|line| line.starts_with("content-length:"))
+        .and_then(|line| line["content-length:".len()..].trim().parse().ok())
+        .unwrap();
+    while request.len() < body_start + content_length {
+        let len = stream.read(&mut buf).unwrap();
+        assert_ne!(len, 0, "Webhook request is incomplete");
+        request.extend_from_slice(&buf[..len]);
+    }
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    request.split_off(body_start)
+}
+
+/// Accepts a single NATS client connection on the listener, answers its `PING`
+/// and returns the subjects and payloads of the messages published before it.
+fn receive_nats_messages(listener: &TcpListener) -> Vec<(String, Vec<u8>)> {
+    let mut stream = accept(listener);
+    stream.write_all(b"INFO {}\r\n").unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut messages = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let len = reader.read_line(&mut line).unwrap();
+        assert_ne!(len, 0, "NATS client disconnected");
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("PUB") => {
+                let subject = words.next().unwrap().to_owned();
+                let len: usize = words.next().unwrap().parse().unwrap();
+                let mut payload = vec![0; len + 2];
+                reader.read_exact(&mut payload).unwrap();
+                payload.truncate(len);
+                messages.push((subject, payload));
+            }
+            Some("PING") => break,
+            _ => {}
+        }
+    }
+    stream.write_all(b"PONG\r\n").unwrap();
+    messages
+}
+
+/// Waits for a connection to the listener for up to 10 seconds.
+fn accept(listener: &TcpListener) -> TcpStream {
+    listener.set_nonblocking(true).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let stream = loop {
+        match listener.accept() {
+            Ok((stream, _)) => break stream,
+            Err(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Err(e) => panic!("No connection was accepted: {}", e),
+        }
+    };
+    stream.set_nonblocking(false).unwrap();
+    stream
+}
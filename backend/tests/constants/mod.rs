@@ -18,3 +18,7 @@
 pub const ALICE_NAME: &str = "Alice";
 /// Bob's wallet name.
 pub const BOB_NAME: &str = "Bob";
+/// Carol's wallet name.
+pub const CAROL_NAME: &str = "Carol";
+/// Shared multisign wallet name.
+pub const SHARED_NAME: &str = "Shared";
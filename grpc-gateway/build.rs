@@ -0,0 +1,12 @@
+/// Copy of the Exonum protobuf files imported by the service messages.
+const EXONUM_PROTOS: &str = "proto/exonum";
+
+fn main() {
+    // The client is used by the tests only.
+    tonic_build::configure()
+        .compile(
+            &["proto/gateway.proto"],
+            &["proto", "../backend/src/proto", EXONUM_PROTOS],
+        )
+        .unwrap();
+}
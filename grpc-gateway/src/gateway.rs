@@ -0,0 +1,310 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Implementation of the gRPC service on top of the REST API.
+
+use prost::Message;
+use reqwest::{header, Client, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status};
+
+use crate::proto::exonum::{Hash, PublicKey};
+use crate::proto::exonum_test_task::gateway::{
+    gateway_server::Gateway, ListWalletsRequest, SearchWalletsRequest, SubmitTransactionReply,
+    SubmitTransactionRequest, TopWalletsRequest, WalletEvent, WalletHistoryRequest,
+    WatchWalletRequest,
+};
+use crate::proto::exonum_test_task::{Wallet, WalletHistoryPage, WalletList, WalletsPage};
+
+/// Path of the service API relative to the API root.
+const SERVICE_PATH: &str = "api/services/multisign_cryptocurrency";
+/// Content type of the protobuf responses of the REST API.
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+/// Timeout of a single long polling request of the wallet changes, the maximum of the API.
+const WATCH_TIMEOUT_MS: u64 = 30_000;
+/// Number of the wallet changes buffered for a slow client.
+const WATCH_BUFFER: usize = 32;
+/// Length of the public keys and hashes in bytes.
+const KEY_LENGTH: usize = 32;
+
+/// Gateway translating the gRPC calls into requests to the REST API of a node.
+#[derive(Debug, Clone)]
+pub struct RestGateway {
+    client: Client,
+    api_root: String,
+}
+
+impl RestGateway {
+    /// Creates a gateway to the REST API with the given root, e.g. `http://127.0.0.1:8200`.
+    pub fn new(api_root: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_root: api_root.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{}", self.api_root, SERVICE_PATH, path)
+    }
+
+    /// Requests the endpoint answering with the protobuf encoding and decodes the response.
+    async fn get_protobuf<M>(&self, path: &str, query: &[(&str, String)]) -> Result<M, Status>
+    where
+        M: Message + Default,
+    {
+        let response = self
+            .client
+            .get(&self.url(path))
+            .query(query)
+            .header(header::ACCEPT, PROTOBUF_CONTENT_TYPE)
+            .send()
+            .await
+            .map_err(unavailable)?;
+        let body = check_status(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(unavailable)?;
+        M::decode(body).map_err(|e| Status::internal(e.to_string()))
+    }
+
+    /// Waits for the changes of the wallet made by the blocks above `since`.
+    async fn poll_wallet(&self, pub_key: &str, since: u64) -> Result<Vec<WalletEvent>, Status> {
+        let query = [
+            ("pub_key", pub_key.to_owned()),
+            ("since", since.to_string()),
+            ("timeout", WATCH_TIMEOUT_MS.to_string()),
+        ];
+        let response = self
+            .client
+            .get(&self.url("v1/wallets/subscribe"))
+            .query(&query)
+            .send()
+            .await
+            .map_err(unavailable)?;
+        let events: Vec<JsonWalletEvent> = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        events
+            .into_iter()
+            .map(JsonWalletEvent::into_proto)
+            .collect()
+    }
+}
+
+#[tonic::async_trait]
+impl Gateway for RestGateway {
+    async fn list_wallets(
+        &self,
+        request: Request<ListWalletsRequest>,
+    ) -> Result<Response<WalletsPage>, Status> {
+        let request = request.into_inner();
+        let mut query = vec![("offset", request.offset.to_string())];
+        push_limit(&mut query, request.limit);
+        let page = self.get_protobuf("v1/wallets", &query).await?;
+        Ok(Response::new(page))
+    }
+
+    async fn top_wallets(
+        &self,
+        request: Request<TopWalletsRequest>,
+    ) -> Result<Response<WalletList>, Status> {
+        let mut query = Vec::new();
+        push_limit(&mut query, request.into_inner().limit);
+        let wallets = self.get_protobuf("v1/wallets/top", &query).await?;
+        Ok(Response::new(wallets))
+    }
+
+    async fn search_wallets(
+        &self,
+        request: Request<SearchWalletsRequest>,
+    ) -> Result<Response<WalletList>, Status> {
+        let request = request.into_inner();
+        let mut query = vec![("name_prefix", request.name_prefix)];
+        push_limit(&mut query, request.limit);
+        let wallets = self.get_protobuf("v1/wallets/search", &query).await?;
+        Ok(Response::new(wallets))
+    }
+
+    async fn wallet_history(
+        &self,
+        request: Request<WalletHistoryRequest>,
+    ) -> Result<Response<WalletHistoryPage>, Status> {
+        let request = request.into_inner();
+        let mut query = vec![
+            ("pub_key", encode_key(request.pub_key.as_ref())?),
+            ("from", request.from.to_string()),
+            ("newest_first", request.newest_first.to_string()),
+        ];
+        push_limit(&mut query, request.limit);
+        let page = self.get_protobuf("v1/wallets/history", &query).await?;
+        Ok(Response::new(page))
+    }
+
+    async fn submit_transaction(
+        &self,
+        request: Request<SubmitTransactionRequest>,
+    ) -> Result<Response<SubmitTransactionReply>, Status> {
+        let tx_body = hex::encode(request.into_inner().signed_transaction);
+        let response = self
+            .client
+            .post(&self.url("v1/transactions"))
+            .json(&json!({ "tx_body": tx_body }))
+            .send()
+            .await
+            .map_err(unavailable)?;
+        let submitted: JsonSubmitted = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(SubmitTransactionReply {
+            tx_hash: Some(Hash {
+                data: decode_key(&submitted.tx_hash)?,
+            }),
+        }))
+    }
+
+    type WatchWalletStream = mpsc::Receiver<Result<WalletEvent, Status>>;
+
+    async fn watch_wallet(
+        &self,
+        request: Request<WatchWalletRequest>,
+    ) -> Result<Response<Self::WatchWalletStream>, Status> {
+        let request = request.into_inner();
+        let pub_key = encode_key(request.pub_key.as_ref())?;
+        let gateway = self.clone();
+        let (mut sender, receiver) = mpsc::channel(WATCH_BUFFER);
+
+        // Polls the wallet changes until the client disconnects or the node fails.
+        tokio::spawn(async move {
+            let mut since = request.since;
+            loop {
+                let events = match gateway.poll_wallet(&pub_key, since).await {
+                    Ok(events) => events,
+                    Err(status) => {
+                        let _ = sender.send(Err(status)).await;
+                        return;
+                    }
+                };
+                for event in events {
+                    since = since.max(event.height);
+                    if sender.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(receiver))
+    }
+}
+
+/// Response of `POST v1/transactions`.
+#[derive(Debug, Deserialize)]
+struct JsonSubmitted {
+    tx_hash: String,
+}
+
+/// Wallet change returned by `GET v1/wallets/subscribe`.
+#[derive(Debug, Deserialize)]
+struct JsonWalletEvent {
+    height: u64,
+    wallet: JsonWallet,
+}
+
+/// JSON representation of `exonum_test_task.Wallet` with the keys and hashes in hex.
+#[derive(Debug, Deserialize)]
+struct JsonWallet {
+    pub_key: String,
+    name: String,
+    balance: u64,
+    pending_balance: u64,
+    history_len: u64,
+    history_hash: String,
+    assets_hash: String,
+    frozen: bool,
+    closed: bool,
+    kyc_verified: bool,
+}
+
+impl JsonWalletEvent {
+    fn into_proto(self) -> Result<WalletEvent, Status> {
+        let wallet = self.wallet;
+        Ok(WalletEvent {
+            height: self.height,
+            wallet: Some(Wallet {
+                pub_key: Some(PublicKey {
+                    data: decode_key(&wallet.pub_key)?,
+                }),
+                name: wallet.name,
+                balance: wallet.balance,
+                pending_balance: wallet.pending_balance,
+                history_len: wallet.history_len,
+                history_hash: Some(Hash {
+                    data: decode_key(&wallet.history_hash)?,
+                }),
+                frozen: wallet.frozen,
+                closed: wallet.closed,
+                assets_hash: Some(Hash {
+                    data: decode_key(&wallet.assets_hash)?,
+                }),
+                kyc_verified: wallet.kyc_verified,
+            }),
+        })
+    }
+}
+
+/// Adds the `limit` query parameter unless it is `0`, which stands for the API default.
+fn push_limit(query: &mut Vec<(&str, String)>, limit: u64) {
+    if limit != 0 {
+        query.push(("limit", limit.to_string()));
+    }
+}
+
+/// Encodes the public key as hex for the query of the REST API.
+fn encode_key(key: Option<&PublicKey>) -> Result<String, Status> {
+    match key {
+        Some(key) if key.data.len() == KEY_LENGTH => Ok(hex::encode(&key.data)),
+        _ => Err(Status::invalid_argument("pub_key must be 32 bytes long")),
+    }
+}
+
+/// Decodes a hex public key or hash returned by the REST API.
+fn decode_key(value: &str) -> Result<Vec<u8>, Status> {
+    hex::decode(value).map_err(|e| Status::internal(e.to_string()))
+}
+
+/// Maps the unsuccessful responses of the REST API to the gRPC status codes.
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, Status> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let message = response.text().await.unwrap_or_default();
+    Err(match status {
+        StatusCode::BAD_REQUEST => Status::invalid_argument(message),
+        StatusCode::NOT_FOUND => Status::not_found(message),
+        StatusCode::TOO_MANY_REQUESTS => Status::resource_exhausted(message),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Status::permission_denied(message),
+        _ => Status::unavailable(message),
+    })
+}
+
+fn unavailable(error: reqwest::Error) -> Status {
+    Status::unavailable(error.to_string())
+}
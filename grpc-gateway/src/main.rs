@@ -0,0 +1,57 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! gRPC gateway to the REST API of the cryptocurrency service.
+//!
+//! Exonum 0.10 runs the service API on actix, which can't host a tonic server, so the gateway
+//! runs as a separate process and translates the gRPC calls into requests to the REST API
+//! of a node.
+//!
+//! Usage: `exonum-test-task-grpc-gateway [--listen ADDR] [--api-root URL]`.
+
+use std::{env, error::Error, net::SocketAddr};
+
+use tonic::transport::Server;
+
+use exonum_test_task_grpc_gateway::gateway::RestGateway;
+use exonum_test_task_grpc_gateway::proto::exonum_test_task::gateway::gateway_server::GatewayServer;
+
+/// Address the gRPC server listens on by default.
+const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:50051";
+/// Root of the public REST API of the node used by default.
+const DEFAULT_API_ROOT: &str = "http://127.0.0.1:8200";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let mut listen = DEFAULT_LISTEN_ADDRESS.to_owned();
+    let mut api_root = DEFAULT_API_ROOT.to_owned();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} requires a value", arg))?;
+        match arg.as_str() {
+            "--listen" => listen = value,
+            "--api-root" => api_root = value,
+            _ => return Err(format!("unknown argument {}", arg).into()),
+        }
+    }
+
+    let address: SocketAddr = listen.parse()?;
+    Server::builder()
+        .add_service(GatewayServer::new(RestGateway::new(&api_root)))
+        .serve(address)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Rust types generated from the protobuf files.
+//!
+//! The modules follow the protobuf packages, so the generated code can refer to the types
+//! of the imported packages.
+
+pub mod exonum {
+    tonic::include_proto!("exonum");
+}
+
+pub mod exonum_test_task {
+    tonic::include_proto!("exonum_test_task");
+
+    pub mod gateway {
+        tonic::include_proto!("exonum_test_task.gateway");
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2018 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of the gateway serving the gRPC calls with a stub of the REST API of a node.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use prost::Message;
+use tonic::{
+    transport::{Channel, Server},
+    Code,
+};
+
+use exonum_test_task_grpc_gateway::gateway::RestGateway;
+use exonum_test_task_grpc_gateway::proto::exonum_test_task::gateway::{
+    gateway_client::GatewayClient, gateway_server::GatewayServer, ListWalletsRequest,
+    SubmitTransactionRequest,
+};
+use exonum_test_task_grpc_gateway::proto::exonum_test_task::{Wallet, WalletsPage};
+
+/// Request received by the REST API stub.
+#[derive(Debug)]
+struct RecordedRequest {
+    /// Request line and headers.
+    head: String,
+    body: Vec<u8>,
+}
+
+/// Response of the REST API stub: status code, content type and body.
+type StubResponse = (u16, &'static str, Vec<u8>);
+
+/// Starts the REST API stub answering the requests with the given responses in turn.
+///
+/// Returns the API root and the receiver of the requests the stub got.
+fn serve_rest_api(responses: Vec<StubResponse>) -> (String, mpsc::Receiver<RecordedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let api_root = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for (status, content_type, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                let lowercase = line.to_lowercase();
+                if let Some(length) = lowercase.strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                head.push_str(&lowercase);
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 {} Stub\r\nContent-Type: {}\r\nContent-Length: {}\r\n                 Connection: close\r\n\r\n",
+                status,
+                content_type,
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+            sender
+                .send(RecordedRequest {
+                    head,
+                    body: request_body,
+                })
+                .unwrap();
+        }
+    });
+    (api_root, receiver)
+}
+
+/// Serves the gateway to the given REST API root and connects a gRPC client to it.
+async fn connect_gateway(api_root: &str) -> GatewayClient<Channel> {
+    let address: SocketAddr = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let server = Server::builder()
+        .add_service(GatewayServer::new(RestGateway::new(api_root)))
+        .serve(address);
+    tokio::spawn(server);
+
+    for _ in 0..50 {
+        if let Ok(client) = GatewayClient::connect(format!("http://{}", address)).await {
+            return client;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("Gateway didn't start");
+}
+
+/// Check that a wallets page requested over gRPC is fetched from the REST API in the protobuf
+/// encoding and returned as is.
+#[tokio::test(threaded_scheduler)]
+async fn test_list_wallets() {
+    let page = WalletsPage {
+        total: 3,
+        wallets: vec![Wallet {
+            name: "Alice".to_owned(),
+            balance: 100,
+            pending_balance: 100,
+            ..Wallet::default()
+        }],
+    };
+    let mut body = Vec::new();
+    page.encode(&mut body).unwrap();
+    let (api_root, requests) = serve_rest_api(vec![(200, "application/x-protobuf", body)]);
+    let mut client = connect_gateway(&api_root).await;
+
+    let request = ListWalletsRequest {
+        offset: 2,
+        limit: 1,
+    };
+    let response = client.list_wallets(request).await.unwrap();
+    assert_eq!(response.into_inner(), page);
+
+    let request = requests.recv().unwrap();
+    assert!(request.head.starts_with(
+        "get /api/services/multisign_cryptocurrency/v1/wallets?offset=2&limit=1 http/1.1"
+    ));
+    assert!(request.head.contains("accept: application/x-protobuf"));
+}
+
+/// Check that the submitted transactions are passed to the REST API in hex and its errors
+/// are mapped to the gRPC status codes.
+#[tokio::test(threaded_scheduler)]
+async fn test_submit_transaction() {
+    let tx_hash = [0xab; 32];
+    let reply = format!(r#"{{ "tx_hash": "{}" }}"#, hex::encode(&tx_hash));
+    let (api_root, requests) = serve_rest_api(vec![
+        (200, "application/json", reply.into_bytes()),
+        (400, "text/plain", b"Invalid transaction".to_vec()),
+    ]);
+    let mut client = connect_gateway(&api_root).await;
+
+    let request = SubmitTransactionRequest {
+        signed_transaction: vec![1, 2, 3],
+    };
+    let reply = client.submit_transaction(request).await.unwrap();
+    assert_eq!(reply.into_inner().tx_hash.unwrap().data, tx_hash.to_vec());
+
+    let request = requests.recv().unwrap();
+    assert!(request
+        .head
+        .starts_with("post /api/services/multisign_cryptocurrency/v1/transactions http/1.1"));
+    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+    assert_eq!(body, serde_json::json!({ "tx_body": "010203" }));
+
+    let request = SubmitTransactionRequest {
+        signed_transaction: vec![4, 5, 6],
+    };
+    let status = client.submit_transaction(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "Invalid transaction");
+}